@@ -1,6 +1,10 @@
 //! Cryptographic signature keys storage API
 
+use std::collections::BTreeSet;
+
 use namada_core::storage;
+use namada_core::token::Amount;
+use namada_storage::collections::lazy_map::{NestedSubKey, SubKey};
 use namada_storage::{Result, StorageRead, StorageWrite};
 
 use super::*;
@@ -119,3 +123,148 @@ where
     }
     Ok(())
 }
+
+/// Get the spending limit of an account for the given token
+pub fn spending_limit<S>(
+    storage: &S,
+    owner: &Address,
+    token: &Address,
+) -> Result<Option<SpendingLimit>>
+where
+    S: StorageRead,
+{
+    spending_limits_handle(owner).get(storage, token)
+}
+
+/// Check if an account is allowed to send tokens to the given recipient. Any
+/// recipient is allowed if the account's allowlist is empty.
+pub fn is_allowed_recipient<S>(
+    storage: &S,
+    owner: &Address,
+    recipient: &Address,
+) -> Result<bool>
+where
+    S: StorageRead,
+{
+    let allowed_recipients = allowed_recipients_handle(owner);
+    Ok(allowed_recipients.is_empty(storage)?
+        || allowed_recipients.contains(storage, recipient)?)
+}
+
+/// Get the amount of a token that a delegate key is allowed to spend from an
+/// account
+pub fn allowance<S>(
+    storage: &S,
+    owner: &Address,
+    delegate: &common::PublicKey,
+    token: &Address,
+) -> Result<Amount>
+where
+    S: StorageRead,
+{
+    Ok(allowances_handle(owner)
+        .at(delegate)
+        .get(storage, token)?
+        .unwrap_or_default())
+}
+
+/// Get the delegate keys that are allowed to spend the given token from an
+/// account together with their allowances
+pub fn token_allowances<S>(
+    storage: &S,
+    owner: &Address,
+    token: &Address,
+) -> Result<Vec<(common::PublicKey, Amount)>>
+where
+    S: StorageRead,
+{
+    let mut allowances = vec![];
+    for allowance in allowances_handle(owner).iter(storage)? {
+        let (
+            NestedSubKey::Data {
+                key: delegate,
+                nested_sub_key: SubKey::Data(allowed_token),
+            },
+            amount,
+        ) = allowance?;
+        if &allowed_token == token {
+            allowances.push((delegate, amount));
+        }
+    }
+    Ok(allowances)
+}
+
+/// Get the tokens that are subject to the spending policy of an account, i.e.
+/// the tokens with a spending limit or an allowance of a delegate key
+pub fn spending_policy_tokens<S>(
+    storage: &S,
+    owner: &Address,
+) -> Result<BTreeSet<Address>>
+where
+    S: StorageRead,
+{
+    let mut tokens = BTreeSet::new();
+    for limit in spending_limits_handle(owner).iter(storage)? {
+        let (token, _) = limit?;
+        tokens.insert(token);
+    }
+    for allowance in allowances_handle(owner).iter(storage)? {
+        let (
+            NestedSubKey::Data {
+                key: _,
+                nested_sub_key: SubKey::Data(token),
+            },
+            _,
+        ) = allowance?;
+        tokens.insert(token);
+    }
+    Ok(tokens)
+}
+
+/// Apply an update of the spending policy of an account
+pub fn update_spending_policy<S>(
+    storage: &mut S,
+    update: &UpdateSpendingPolicy,
+) -> Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    let owner = &update.addr;
+    for (token, limit) in &update.limits {
+        match limit {
+            Some(limit) => {
+                spending_limits_handle(owner).insert(
+                    storage,
+                    token.clone(),
+                    *limit,
+                )?;
+            }
+            None => {
+                spending_limits_handle(owner).remove(storage, token)?;
+                spending_records_handle(owner).remove(storage, token)?;
+            }
+        }
+    }
+    for recipient in &update.disallowed_recipients {
+        allowed_recipients_handle(owner).remove(storage, recipient)?;
+    }
+    for recipient in &update.allowed_recipients {
+        allowed_recipients_handle(owner).insert(storage, recipient.clone())?;
+    }
+    for (delegate, allowances) in &update.allowances {
+        for (token, amount) in allowances {
+            if amount.is_zero() {
+                allowances_handle(owner)
+                    .at(delegate)
+                    .remove(storage, token)?;
+            } else {
+                allowances_handle(owner).at(delegate).insert(
+                    storage,
+                    token.clone(),
+                    *amount,
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
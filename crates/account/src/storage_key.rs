@@ -1,9 +1,14 @@
+use std::str::FromStr;
+
 use namada_core::address::Address;
 use namada_core::key::common;
 use namada_core::storage::{self, DbKeySeg};
+use namada_core::token::Amount;
 use namada_macros::StorageKeys;
-use namada_storage::collections::lazy_map::LazyMap;
-use namada_storage::collections::{lazy_map, LazyCollection};
+use namada_storage::collections::lazy_map::{LazyMap, NestedMap};
+use namada_storage::collections::{lazy_map, LazyCollection, LazySet};
+
+use crate::{SpendingLimit, SpendingRecord};
 
 /// Storage keys for account.
 #[derive(StorageKeys)]
@@ -11,6 +16,10 @@ struct Keys {
     public_keys: &'static str,
     threshold: &'static str,
     protocol_public_keys: &'static str,
+    spending_limits: &'static str,
+    spending_records: &'static str,
+    allowed_recipients: &'static str,
+    allowances: &'static str,
 }
 
 /// Obtain a storage key for user's public key.
@@ -89,3 +98,103 @@ pub fn is_protocol_pk_key(key: &storage::Key) -> Option<&Address> {
         _ => None,
     }
 }
+
+/// Obtain the storage key prefix of a sub-space of a user's account.
+fn account_subspace_prefix(owner: &Address, name: &str) -> storage::Key {
+    storage::Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(owner.to_owned()),
+            DbKeySeg::StringSeg(name.to_string()),
+        ],
+    }
+}
+
+/// LazyMap handler for the user's spending limits, per token
+pub fn spending_limits_handle(
+    owner: &Address,
+) -> LazyMap<Address, SpendingLimit> {
+    LazyMap::open(account_subspace_prefix(owner, Keys::VALUES.spending_limits))
+}
+
+/// LazyMap handler for the user's spending records, per token
+pub fn spending_records_handle(
+    owner: &Address,
+) -> LazyMap<Address, SpendingRecord> {
+    LazyMap::open(account_subspace_prefix(
+        owner,
+        Keys::VALUES.spending_records,
+    ))
+}
+
+/// LazySet handler for the recipients the user is allowed to send tokens to
+pub fn allowed_recipients_handle(owner: &Address) -> LazySet<Address> {
+    LazySet::open(account_subspace_prefix(
+        owner,
+        Keys::VALUES.allowed_recipients,
+    ))
+}
+
+/// NestedMap handler for the user's allowances of delegate keys, per token
+pub fn allowances_handle(
+    owner: &Address,
+) -> NestedMap<common::PublicKey, LazyMap<Address, Amount>> {
+    NestedMap::open(account_subspace_prefix(owner, Keys::VALUES.allowances))
+}
+
+/// Check if the given storage key is a spending limit or an allowed recipient
+/// key. If it is, returns the owner.
+pub fn is_spending_policy_key(key: &storage::Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(prefix), ..]
+            if prefix.as_str() == Keys::VALUES.spending_limits
+                || prefix.as_str() == Keys::VALUES.allowed_recipients =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+/// Check if the given storage key is a spending record key. If it is, returns
+/// the owner and the token.
+pub fn is_spending_record_key(
+    key: &storage::Key,
+) -> Option<(&Address, &Address)> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(owner),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(data),
+            DbKeySeg::AddressSeg(token),
+        ] if prefix.as_str() == Keys::VALUES.spending_records
+            && data.as_str() == lazy_map::DATA_SUBKEY =>
+        {
+            Some((owner, token))
+        }
+        _ => None,
+    }
+}
+
+/// Check if the given storage key is an allowance key. If it is, returns the
+/// owner, the delegate key and the token.
+pub fn is_allowance_key(
+    key: &storage::Key,
+) -> Option<(&Address, common::PublicKey, &Address)> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(owner),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(data),
+            DbKeySeg::StringSeg(delegate),
+            DbKeySeg::StringSeg(nested_data),
+            DbKeySeg::AddressSeg(token),
+        ] if prefix.as_str() == Keys::VALUES.allowances
+            && data.as_str() == lazy_map::DATA_SUBKEY
+            && nested_data.as_str() == lazy_map::DATA_SUBKEY =>
+        {
+            let delegate = common::PublicKey::from_str(delegate).ok()?;
+            Some((owner, delegate, token))
+        }
+        _ => None,
+    }
+}
@@ -1,7 +1,11 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use namada_core::address::Address;
 use namada_core::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_core::hash::Hash;
 use namada_core::key::common;
+use namada_core::storage::Epoch;
+use namada_core::token::Amount;
 use serde::{Deserialize, Serialize};
 
 /// A tx data type to initialize a new established account
@@ -50,6 +54,100 @@ pub struct UpdateAccount {
     pub threshold: Option<u8>,
 }
 
+/// A limit on the amount of a token that an account can spend within a window
+/// of epochs
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct SpendingLimit {
+    /// The maximum amount that can be spent within a window
+    pub amount: Amount,
+    /// The length of a window in epochs
+    pub window_epochs: u64,
+}
+
+/// The amount of a token spent by an account within the current window of
+/// its spending limit
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct SpendingRecord {
+    /// The first epoch of the current window
+    pub window_start: Epoch,
+    /// The amount spent since the start of the window
+    pub spent: Amount,
+}
+
+impl SpendingRecord {
+    /// Add the given amount to the previous record of a token's spending.
+    /// A new window is started if the previous one has elapsed at the
+    /// current epoch. Returns `None` on overflow.
+    pub fn spend(
+        prev: Option<Self>,
+        limit: &SpendingLimit,
+        current_epoch: Epoch,
+        amount: Amount,
+    ) -> Option<Self> {
+        // Only keep the previous record if its window hasn't elapsed yet
+        let prev = prev.filter(|record| {
+            let window_end =
+                record.window_start.0.saturating_add(limit.window_epochs);
+            current_epoch.0 < window_end
+        });
+        let (window_start, spent) = prev
+            .map(|record| (record.window_start, record.spent))
+            .unwrap_or((current_epoch, Amount::zero()));
+        Some(Self {
+            window_start,
+            spent: spent.checked_add(amount)?,
+        })
+    }
+}
+
+/// A tx data type to update the spending policy of an account
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct UpdateSpendingPolicy {
+    /// An address of the account
+    pub addr: Address,
+    /// The spending limits to set per token. A `None` limit removes the
+    /// limit of the token.
+    pub limits: BTreeMap<Address, Option<SpendingLimit>>,
+    /// The recipients to add to the account's allowlist
+    pub allowed_recipients: BTreeSet<Address>,
+    /// The recipients to remove from the account's allowlist
+    pub disallowed_recipients: BTreeSet<Address>,
+    /// The allowances of delegate keys to set per token. A zero amount
+    /// removes the allowance.
+    pub allowances: BTreeMap<common::PublicKey, BTreeMap<Address, Amount>>,
+}
+
 #[cfg(any(test, feature = "testing"))]
 /// Tests and strategies for accounts
 pub mod tests {
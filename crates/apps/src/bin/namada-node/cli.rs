@@ -5,6 +5,7 @@ use namada::core::time::{DateTimeUtc, Utc};
 use namada_apps::cli::{self, cmds};
 use namada_apps::config::ValidatorLocalConfig;
 use namada_apps::node::ledger;
+use namada_apps::node::ledger::frost::service as frost;
 
 pub fn main() -> Result<()> {
     let (cmd, mut ctx) = cli::namada_node_cli()?;
@@ -76,6 +77,35 @@ pub fn main() -> Result<()> {
                 std::fs::write(config_path, updated_config).unwrap();
             }
         },
+        cmds::NamadaNode::Frost(sub) => match sub {
+            cmds::Frost::ShareHolder(cmds::FrostShareHolder(args)) => {
+                frost::run_share_holder(&args.socket, &args.share_file)
+                    .wrap_err("Failed to run the key share holder")?;
+            }
+            cmds::Frost::Dkg(cmds::FrostDkg(args)) => {
+                // Default to a majority of the share holders
+                let threshold = args
+                    .threshold
+                    .map(u16::from)
+                    .unwrap_or_else(|| (args.sockets.len() / 2 + 1) as u16);
+                let public = frost::run_dkg(&args.sockets, threshold)
+                    .wrap_err("Failed to generate the threshold key")?;
+                let public_key = public
+                    .public_key()
+                    .wrap_err("Invalid threshold public key")?;
+                println!(
+                    "Generated a {}-of-{} threshold protocol key {}.",
+                    public.threshold,
+                    public.max_signers(),
+                    public_key
+                );
+                println!(
+                    "Register it as the validator's protocol key and add the \
+                     share holders' sockets to `protocol_key_shares` in the \
+                     validator_local_config.toml file of the chain directory."
+                );
+            }
+        },
     }
     Ok(())
 }
@@ -12,6 +12,7 @@ use std::sync::Once;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use borsh_ext::BorshSerializeExt;
+use masp_primitives::memo::MemoBytes;
 use masp_primitives::transaction::Transaction;
 use masp_primitives::zip32::ExtendedFullViewingKey;
 use masp_proofs::prover::LocalTxProver;
@@ -76,7 +77,8 @@ use namada::tx::{Code, Data, Section, Signature, Tx};
 use namada::vm::wasm::run;
 use namada::{proof_of_stake, tendermint};
 use namada_sdk::masp::{
-    self, ContextSyncStatus, ShieldedContext, ShieldedTransfer, ShieldedUtils,
+    self, ContextSyncStatus, NoteSelection, ShieldedContext, ShieldedTransfer,
+    ShieldedUtils,
 };
 pub use namada_sdk::tx::{
     TX_BECOME_VALIDATOR_WASM, TX_BOND_WASM, TX_BRIDGE_POOL_WASM,
@@ -918,6 +920,7 @@ impl Default for BenchShieldedCtx {
             chain_id: Some(shell.inner.chain_id.clone()),
             base_dir,
             wasm_dir: Some(WASM_DIR.into()),
+            shielded_context: None,
         })
         .unwrap();
 
@@ -1020,6 +1023,8 @@ impl BenchShieldedCtx {
                     &target,
                     &address::testing::nam(),
                     denominated_amount,
+                    MemoBytes::empty(),
+                    NoteSelection::default(),
                     true,
                 ),
             )
@@ -123,17 +123,21 @@ pub mod cmds {
     pub enum NamadaNode {
         Ledger(Ledger),
         Config(Config),
+        Frost(Frost),
     }
 
     impl Cmd for NamadaNode {
         fn add_sub(app: App) -> App {
-            app.subcommand(Ledger::def()).subcommand(Config::def())
+            app.subcommand(Ledger::def())
+                .subcommand(Config::def())
+                .subcommand(Frost::def())
         }
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             let ledger = SubCmd::parse(matches).map(Self::Ledger);
             let config = SubCmd::parse(matches).map(Self::Config);
-            ledger.or(config)
+            let frost = SubCmd::parse(matches).map(Self::Frost);
+            ledger.or(config).or(frost)
         }
     }
     impl SubCmd for NamadaNode {
@@ -215,6 +219,7 @@ pub mod cmds {
                 // Simple transactions
                 .subcommand(TxCustom::def().display_order(1))
                 .subcommand(TxTransfer::def().display_order(1))
+                .subcommand(TxConsolidateNotes::def().display_order(1))
                 .subcommand(TxIbcTransfer::def().display_order(1))
                 .subcommand(TxUpdateAccount::def().display_order(1))
                 .subcommand(TxInitAccount::def().display_order(1))
@@ -233,6 +238,9 @@ pub mod cmds {
                 .subcommand(Withdraw::def().display_order(2))
                 .subcommand(Redelegate::def().display_order(2))
                 .subcommand(ClaimRewards::def().display_order(2))
+                .subcommand(Restake::def().display_order(2))
+                .subcommand(LiquidBond::def().display_order(2))
+                .subcommand(RedeemShares::def().display_order(2))
                 .subcommand(TxCommissionRateChange::def().display_order(2))
                 .subcommand(TxChangeConsensusKey::def().display_order(2))
                 .subcommand(TxMetadataChange::def().display_order(2))
@@ -247,14 +255,19 @@ pub mod cmds {
                 .subcommand(QueryStatus::def().display_order(5))
                 .subcommand(QueryAccount::def().display_order(5))
                 .subcommand(QueryTransfers::def().display_order(5))
+                .subcommand(ExportShieldedHistory::def().display_order(5))
                 .subcommand(QueryConversions::def().display_order(5))
                 .subcommand(QueryMaspRewardTokens::def().display_order(5))
+                .subcommand(QueryMaspRewards::def().display_order(5))
                 .subcommand(QueryBlock::def().display_order(5))
                 .subcommand(QueryBalance::def().display_order(5))
                 .subcommand(QueryBonds::def().display_order(5))
                 .subcommand(QueryBondedStake::def().display_order(5))
                 .subcommand(QuerySlashes::def().display_order(5))
                 .subcommand(QueryDelegations::def().display_order(5))
+                .subcommand(QueryPendingUnbonds::def().display_order(5))
+                .subcommand(QuerySlashReport::def().display_order(5))
+                .subcommand(SimulateValidatorSets::def().display_order(5))
                 .subcommand(QueryFindValidator::def().display_order(5))
                 .subcommand(QueryResult::def().display_order(5))
                 .subcommand(QueryRawBytes::def().display_order(5))
@@ -264,12 +277,17 @@ pub mod cmds {
                 .subcommand(QueryProtocolParameters::def().display_order(5))
                 .subcommand(QueryPgf::def().display_order(5))
                 .subcommand(QueryValidatorState::def().display_order(5))
+                .subcommand(QueryConsensusKey::def().display_order(5))
                 .subcommand(QueryCommissionRate::def().display_order(5))
                 .subcommand(QueryRewards::def().display_order(5))
+                .subcommand(QueryRewardsProjection::def().display_order(5))
                 .subcommand(QueryMetaData::def().display_order(5))
+                .subcommand(QueryValidatorHistory::def().display_order(5))
                 // Actions
                 .subcommand(SignTx::def().display_order(6))
                 .subcommand(ShieldedSync::def().display_order(6))
+                .subcommand(ShieldedIndex::def().display_order(6))
+                .subcommand(ShieldedContexts::def().display_order(6))
                 .subcommand(GenIbcShieldedTransfer::def().display_order(6))
                 // Utils
                 .subcommand(Utils::def().display_order(7))
@@ -279,6 +297,8 @@ pub mod cmds {
             use NamadaClientWithContext::*;
             let tx_custom = Self::parse_with_ctx(matches, TxCustom);
             let tx_transfer = Self::parse_with_ctx(matches, TxTransfer);
+            let tx_consolidate_notes =
+                Self::parse_with_ctx(matches, TxConsolidateNotes);
             let tx_ibc_transfer = Self::parse_with_ctx(matches, TxIbcTransfer);
             let tx_update_account =
                 Self::parse_with_ctx(matches, TxUpdateAccount);
@@ -313,16 +333,23 @@ pub mod cmds {
             let withdraw = Self::parse_with_ctx(matches, Withdraw);
             let redelegate = Self::parse_with_ctx(matches, Redelegate);
             let claim_rewards = Self::parse_with_ctx(matches, ClaimRewards);
+            let restake = Self::parse_with_ctx(matches, Restake);
+            let liquid_bond = Self::parse_with_ctx(matches, LiquidBond);
+            let redeem_shares = Self::parse_with_ctx(matches, RedeemShares);
             let query_epoch = Self::parse_with_ctx(matches, QueryEpoch);
             let query_next_epoch_info =
                 Self::parse_with_ctx(matches, QueryNextEpochInfo);
             let query_status = Self::parse_with_ctx(matches, QueryStatus);
             let query_account = Self::parse_with_ctx(matches, QueryAccount);
             let query_transfers = Self::parse_with_ctx(matches, QueryTransfers);
+            let export_shielded_history =
+                Self::parse_with_ctx(matches, ExportShieldedHistory);
             let query_conversions =
                 Self::parse_with_ctx(matches, QueryConversions);
             let query_masp_reward_tokens =
                 Self::parse_with_ctx(matches, QueryMaspRewardTokens);
+            let query_masp_rewards =
+                Self::parse_with_ctx(matches, QueryMaspRewards);
             let query_block = Self::parse_with_ctx(matches, QueryBlock);
             let query_balance = Self::parse_with_ctx(matches, QueryBalance);
             let query_bonds = Self::parse_with_ctx(matches, QueryBonds);
@@ -330,8 +357,16 @@ pub mod cmds {
                 Self::parse_with_ctx(matches, QueryBondedStake);
             let query_slashes = Self::parse_with_ctx(matches, QuerySlashes);
             let query_rewards = Self::parse_with_ctx(matches, QueryRewards);
+            let query_rewards_projection =
+                Self::parse_with_ctx(matches, QueryRewardsProjection);
             let query_delegations =
                 Self::parse_with_ctx(matches, QueryDelegations);
+            let query_pending_unbonds =
+                Self::parse_with_ctx(matches, QueryPendingUnbonds);
+            let query_slash_report =
+                Self::parse_with_ctx(matches, QuerySlashReport);
+            let simulate_validator_sets =
+                Self::parse_with_ctx(matches, SimulateValidatorSets);
             let query_find_validator =
                 Self::parse_with_ctx(matches, QueryFindValidator);
             let query_result = Self::parse_with_ctx(matches, QueryResult);
@@ -346,18 +381,26 @@ pub mod cmds {
             let query_pgf = Self::parse_with_ctx(matches, QueryPgf);
             let query_validator_state =
                 Self::parse_with_ctx(matches, QueryValidatorState);
+            let query_consensus_key =
+                Self::parse_with_ctx(matches, QueryConsensusKey);
             let query_commission =
                 Self::parse_with_ctx(matches, QueryCommissionRate);
             let query_metadata = Self::parse_with_ctx(matches, QueryMetaData);
+            let query_validator_history =
+                Self::parse_with_ctx(matches, QueryValidatorHistory);
             let add_to_eth_bridge_pool =
                 Self::parse_with_ctx(matches, AddToEthBridgePool);
             let sign_tx = Self::parse_with_ctx(matches, SignTx);
             let shielded_sync = Self::parse_with_ctx(matches, ShieldedSync);
+            let shielded_index = Self::parse_with_ctx(matches, ShieldedIndex);
+            let shielded_contexts =
+                Self::parse_with_ctx(matches, ShieldedContexts);
             let gen_ibc_shielded =
                 Self::parse_with_ctx(matches, GenIbcShieldedTransfer);
             let utils = SubCmd::parse(matches).map(Self::WithoutContext);
             tx_custom
                 .or(tx_transfer)
+                .or(tx_consolidate_notes)
                 .or(tx_ibc_transfer)
                 .or(tx_update_account)
                 .or(tx_init_account)
@@ -377,6 +420,9 @@ pub mod cmds {
                 .or(withdraw)
                 .or(redelegate)
                 .or(claim_rewards)
+                .or(restake)
+                .or(liquid_bond)
+                .or(redeem_shares)
                 .or(add_to_eth_bridge_pool)
                 .or(tx_update_steward_commission)
                 .or(tx_resign_steward)
@@ -384,15 +430,21 @@ pub mod cmds {
                 .or(query_next_epoch_info)
                 .or(query_status)
                 .or(query_transfers)
+                .or(export_shielded_history)
                 .or(query_conversions)
                 .or(query_masp_reward_tokens)
+                .or(query_masp_rewards)
                 .or(query_block)
                 .or(query_balance)
                 .or(query_bonds)
                 .or(query_bonded_stake)
                 .or(query_slashes)
                 .or(query_rewards)
+                .or(query_rewards_projection)
                 .or(query_delegations)
+                .or(query_pending_unbonds)
+                .or(query_slash_report)
+                .or(simulate_validator_sets)
                 .or(query_find_validator)
                 .or(query_result)
                 .or(query_raw_bytes)
@@ -402,11 +454,15 @@ pub mod cmds {
                 .or(query_protocol_parameters)
                 .or(query_pgf)
                 .or(query_validator_state)
+                .or(query_consensus_key)
                 .or(query_commission)
                 .or(query_metadata)
+                .or(query_validator_history)
                 .or(query_account)
                 .or(sign_tx)
                 .or(shielded_sync)
+                .or(shielded_index)
+                .or(shielded_contexts)
                 .or(gen_ibc_shielded)
                 .or(utils)
         }
@@ -447,6 +503,7 @@ pub mod cmds {
         // Ledger cmds
         TxCustom(TxCustom),
         TxTransfer(TxTransfer),
+        TxConsolidateNotes(TxConsolidateNotes),
         TxIbcTransfer(TxIbcTransfer),
         QueryResult(QueryResult),
         TxUpdateAccount(TxUpdateAccount),
@@ -466,6 +523,9 @@ pub mod cmds {
         Unbond(Unbond),
         Withdraw(Withdraw),
         ClaimRewards(ClaimRewards),
+        Restake(Restake),
+        LiquidBond(LiquidBond),
+        RedeemShares(RedeemShares),
         Redelegate(Redelegate),
         AddToEthBridgePool(AddToEthBridgePool),
         TxUpdateStewardCommission(TxUpdateStewardCommission),
@@ -475,16 +535,22 @@ pub mod cmds {
         QueryStatus(QueryStatus),
         QueryAccount(QueryAccount),
         QueryTransfers(QueryTransfers),
+        ExportShieldedHistory(ExportShieldedHistory),
         QueryConversions(QueryConversions),
         QueryMaspRewardTokens(QueryMaspRewardTokens),
+        QueryMaspRewards(QueryMaspRewards),
         QueryBlock(QueryBlock),
         QueryBalance(QueryBalance),
         QueryBonds(QueryBonds),
         QueryBondedStake(QueryBondedStake),
         QueryCommissionRate(QueryCommissionRate),
         QueryMetaData(QueryMetaData),
+        QueryValidatorHistory(QueryValidatorHistory),
         QuerySlashes(QuerySlashes),
         QueryDelegations(QueryDelegations),
+        QueryPendingUnbonds(QueryPendingUnbonds),
+        QuerySlashReport(QuerySlashReport),
+        SimulateValidatorSets(SimulateValidatorSets),
         QueryFindValidator(QueryFindValidator),
         QueryRawBytes(QueryRawBytes),
         QueryProposal(QueryProposal),
@@ -493,9 +559,13 @@ pub mod cmds {
         QueryProtocolParameters(QueryProtocolParameters),
         QueryPgf(QueryPgf),
         QueryValidatorState(QueryValidatorState),
+        QueryConsensusKey(QueryConsensusKey),
         QueryRewards(QueryRewards),
+        QueryRewardsProjection(QueryRewardsProjection),
         SignTx(SignTx),
         ShieldedSync(ShieldedSync),
+        ShieldedIndex(ShieldedIndex),
+        ShieldedContexts(ShieldedContexts),
         GenIbcShieldedTransfer(GenIbcShieldedTransfer),
     }
 
@@ -522,6 +592,14 @@ pub mod cmds {
         KeyAddrAdd(WalletAddKeyAddress),
         /// Key / address remove
         KeyAddrRemove(WalletRemoveKeyAddress),
+        /// Address book contact metadata
+        ContactSet(WalletSetContact),
+        /// Address book export
+        AddressBookExport(WalletExportAddressBook),
+        /// Address book import
+        AddressBookImport(WalletImportAddressBook),
+        /// Wallet agent
+        Agent(WalletAgent),
     }
 
     impl Cmd for NamadaWallet {
@@ -536,6 +614,10 @@ pub mod cmds {
                 .subcommand(WalletImportKey::def())
                 .subcommand(WalletAddKeyAddress::def())
                 .subcommand(WalletRemoveKeyAddress::def())
+                .subcommand(WalletSetContact::def())
+                .subcommand(WalletExportAddressBook::def())
+                .subcommand(WalletImportAddressBook::def())
+                .subcommand(WalletAgent::def())
         }
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
@@ -550,6 +632,12 @@ pub mod cmds {
             let key_addr_add = SubCmd::parse(matches).map(Self::KeyAddrAdd);
             let key_addr_remove =
                 SubCmd::parse(matches).map(Self::KeyAddrRemove);
+            let contact_set = SubCmd::parse(matches).map(Self::ContactSet);
+            let address_book_export =
+                SubCmd::parse(matches).map(Self::AddressBookExport);
+            let address_book_import =
+                SubCmd::parse(matches).map(Self::AddressBookImport);
+            let agent = SubCmd::parse(matches).map(Self::Agent);
             gen.or(derive)
                 .or(pay_addr_gen)
                 .or(key_addr_list)
@@ -559,6 +647,10 @@ pub mod cmds {
                 .or(import)
                 .or(key_addr_add)
                 .or(key_addr_remove)
+                .or(contact_set)
+                .or(address_book_export)
+                .or(address_book_import)
+                .or(agent)
         }
     }
 
@@ -693,7 +785,9 @@ pub mod cmds {
                      hash. Looks up an alias of the given address.\nIn the \
                      shielded setting, searches for a spending / viewing key \
                      and payment address by a given alias. Looks up an alias \
-                     of the given payment address.",
+                     of the given payment address.\nWith a search query, \
+                     lists the address book entries whose alias, address, \
+                     tags or notes contain the query.",
                 )
                 .add_args::<args::KeyAddressFind>()
         }
@@ -811,6 +905,203 @@ pub mod cmds {
         }
     }
 
+    /// Set the address book metadata of an address
+    #[derive(Clone, Debug)]
+    pub struct WalletSetContact(pub args::ContactSet);
+
+    impl SubCmd for WalletSetContact {
+        const CMD: &'static str = "contact";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::ContactSet::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Sets the address book metadata of a known address or \
+                     payment address.",
+                )
+                .long_about(
+                    "Sets the notes, tags, default token and memo and the \
+                     payment address of a contact in the address book. The \
+                     given values replace the existing metadata of the \
+                     contact.",
+                )
+                .add_args::<args::ContactSet>()
+        }
+    }
+
+    /// Export the address book to a file
+    #[derive(Clone, Debug)]
+    pub struct WalletExportAddressBook(pub args::AddressBookExport);
+
+    impl SubCmd for WalletExportAddressBook {
+        const CMD: &'static str = "export-address-book";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::AddressBookExport::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Exports the known addresses and payment addresses with \
+                     their metadata in JSON or CSV format.",
+                )
+                .add_args::<args::AddressBookExport>()
+        }
+    }
+
+    /// Import an address book from a file
+    #[derive(Clone, Debug)]
+    pub struct WalletImportAddressBook(pub args::AddressBookImport);
+
+    impl SubCmd for WalletImportAddressBook {
+        const CMD: &'static str = "import-address-book";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::AddressBookImport::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Imports addresses and payment addresses with their \
+                     metadata from a JSON or CSV file.",
+                )
+                .add_args::<args::AddressBookImport>()
+        }
+    }
+
+    /// Wallet agent sub-commands
+    #[derive(Clone, Debug)]
+    pub enum WalletAgent {
+        Run(WalletAgentRun),
+        Unlock(WalletAgentUnlock),
+        Lock(WalletAgentLock),
+        List(WalletAgentList),
+    }
+
+    impl SubCmd for WalletAgent {
+        const CMD: &'static str = "agent";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).and_then(|matches| {
+                let run = SubCmd::parse(matches).map(Self::Run);
+                let unlock = SubCmd::parse(matches).map(Self::Unlock);
+                let lock = SubCmd::parse(matches).map(Self::Lock);
+                let list = SubCmd::parse(matches).map(Self::List);
+                run.or(unlock).or(lock).or(list)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .about(
+                    "Wallet agent sub-commands. The agent keeps unlocked keys \
+                     for a limited time, so that commands don't prompt for \
+                     their passwords.",
+                )
+                .subcommand(WalletAgentRun::def())
+                .subcommand(WalletAgentUnlock::def())
+                .subcommand(WalletAgentLock::def())
+                .subcommand(WalletAgentList::def())
+        }
+    }
+
+    /// Run the wallet agent
+    #[derive(Clone, Debug)]
+    pub struct WalletAgentRun(pub args::AgentRun);
+
+    impl SubCmd for WalletAgentRun {
+        const CMD: &'static str = "run";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::AgentRun::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Runs the wallet agent in the foreground.")
+                .long_about(
+                    "Runs the wallet agent in the foreground. Set the \
+                     NAMADA_WALLET_AGENT_SOCKET environment variable to the \
+                     agent's socket for the other commands to use it.",
+                )
+                .add_args::<args::AgentRun>()
+        }
+    }
+
+    /// Unlock keys in the wallet agent
+    #[derive(Clone, Debug)]
+    pub struct WalletAgentUnlock(pub args::AgentUnlock);
+
+    impl SubCmd for WalletAgentUnlock {
+        const CMD: &'static str = "unlock";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::AgentUnlock::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Decrypts the given keys and hands them to the wallet \
+                     agent for a limited time.",
+                )
+                .add_args::<args::AgentUnlock>()
+        }
+    }
+
+    /// Lock all the keys of the wallet agent
+    #[derive(Clone, Debug)]
+    pub struct WalletAgentLock;
+
+    impl SubCmd for WalletAgentLock {
+        const CMD: &'static str = "lock";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|_matches| Self)
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Locks all the keys held by the wallet agent.")
+        }
+    }
+
+    /// List the keys of the wallet agent
+    #[derive(Clone, Debug)]
+    pub struct WalletAgentList;
+
+    impl SubCmd for WalletAgentList {
+        const CMD: &'static str = "list";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|_matches| Self)
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD).about(
+                "Lists the keys held by the wallet agent and when they're \
+                 locked again.",
+            )
+        }
+    }
+
     /// Generate a payment address from a viewing key or payment address
     #[derive(Clone, Debug)]
     pub struct WalletGenPaymentAddress(pub args::PayAddressGen<args::CliTypes>);
@@ -1038,6 +1329,81 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub enum Frost {
+        ShareHolder(FrostShareHolder),
+        Dkg(FrostDkg),
+    }
+
+    impl SubCmd for Frost {
+        const CMD: &'static str = "frost";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).and_then(|matches| {
+                let share_holder =
+                    SubCmd::parse(matches).map(Self::ShareHolder);
+                let dkg = SubCmd::parse(matches).map(Self::Dkg);
+                share_holder.or(dkg)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .about(
+                    "Threshold signing sub-commands for the validator's \
+                     protocol key.",
+                )
+                .subcommand(FrostShareHolder::def())
+                .subcommand(FrostDkg::def())
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct FrostShareHolder(pub args::FrostShareHolder);
+
+    impl SubCmd for FrostShareHolder {
+        const CMD: &'static str = "share-holder";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::FrostShareHolder::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Run a holder of a protocol key share, listening for key \
+                     generation and signing requests on a local socket.",
+                )
+                .add_args::<args::FrostShareHolder>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct FrostDkg(pub args::FrostDkg);
+
+    impl SubCmd for FrostDkg {
+        const CMD: &'static str = "dkg";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::FrostDkg::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Generate a threshold protocol key between the running \
+                     share holders.",
+                )
+                .add_args::<args::FrostDkg>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryResult(pub args::QueryResult<args::CliTypes>);
 
@@ -1212,6 +1578,30 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct TxConsolidateNotes(
+        pub args::TxConsolidateNotes<args::CliTypes>,
+    );
+
+    impl SubCmd for TxConsolidateNotes {
+        const CMD: &'static str = "consolidate-notes";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxConsolidateNotes(args::TxConsolidateNotes::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Merge the smallest notes of a token held by a spending \
+                     key into a single note.",
+                )
+                .add_args::<args::TxConsolidateNotes<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct TxIbcTransfer(pub args::TxIbcTransfer<args::CliTypes>);
 
@@ -1409,6 +1799,54 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct ShieldedIndex(pub args::ShieldedIndex<args::CliTypes>);
+
+    impl SubCmd for ShieldedIndex {
+        const CMD: &'static str = "shielded-index";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                ShieldedIndex(args::ShieldedIndex::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Index the MASP transactions of a node in a local file, \
+                     up to an optional specified block height. The file is \
+                     updated from its last indexed block and can be used by \
+                     the shielded-sync command instead of the node.",
+                )
+                .add_args::<args::ShieldedIndex<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct ShieldedContexts(pub args::ShieldedContexts);
+
+    impl SubCmd for ShieldedContexts {
+        const CMD: &'static str = "shielded-contexts";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                ShieldedContexts(args::ShieldedContexts::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "List the named shielded contexts of the chain, merge one \
+                     into the context selected with --shielded-context, or \
+                     delete one. Each context has its own keys, notes and \
+                     sync state.",
+                )
+                .add_args::<args::ShieldedContexts>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct Bond(pub args::Bond<args::CliTypes>);
 
@@ -1428,6 +1866,50 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct LiquidBond(pub args::LiquidBond<args::CliTypes>);
+
+    impl SubCmd for LiquidBond {
+        const CMD: &'static str = "liquid-bond";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| LiquidBond(args::LiquidBond::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Bond tokens in PoS system in exchange for the \
+                     transferable share tokens of the validator.",
+                )
+                .add_args::<args::LiquidBond<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct RedeemShares(pub args::RedeemShares<args::CliTypes>);
+
+    impl SubCmd for RedeemShares {
+        const CMD: &'static str = "redeem-shares";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| RedeemShares(args::RedeemShares::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Redeem the share tokens of a validator for an unbond of \
+                     their value.",
+                )
+                .add_args::<args::RedeemShares<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct Unbond(pub args::Unbond<args::CliTypes>);
 
@@ -1488,6 +1970,28 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct Restake(pub args::Restake<args::CliTypes>);
+
+    impl SubCmd for Restake {
+        const CMD: &'static str = "restake";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Restake(args::Restake::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Enable or disable the automatic restaking of the rewards \
+                     of a bond at a regular interval of epochs.",
+                )
+                .add_args::<args::Restake<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct Redelegate(pub args::Redelegate<args::CliTypes>);
 
@@ -1632,6 +2136,28 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct QueryMaspRewards(pub args::QueryMaspRewards<args::CliTypes>);
+
+    impl SubCmd for QueryMaspRewards {
+        const CMD: &'static str = "masp-rewards";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryMaspRewards(args::QueryMaspRewards::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Estimate the MASP rewards of a token in the current \
+                     epoch and project them over the next epochs.",
+                )
+                .add_args::<args::QueryMaspRewards<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryBlock(pub args::Query<args::CliTypes>);
 
@@ -1743,11 +2269,36 @@ pub mod cmds {
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Query the state of a PoS validator.")
+                .about(
+                    "Query the state of a PoS validator, its liveness in the \
+                     current window of blocks and its jail history.",
+                )
                 .add_args::<args::QueryValidatorState<args::CliTypes>>()
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct QueryConsensusKey(pub args::QueryConsensusKey<args::CliTypes>);
+
+    impl SubCmd for QueryConsensusKey {
+        const CMD: &'static str = "consensus-key";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryConsensusKey(args::QueryConsensusKey::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Query the consensus key of a PoS validator and the epoch \
+                     in which its pending change takes effect, if any.",
+                )
+                .add_args::<args::QueryConsensusKey<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryTransfers(pub args::QueryTransfers<args::CliTypes>);
 
@@ -1767,6 +2318,32 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct ExportShieldedHistory(
+        pub args::ExportShieldedHistory<args::CliTypes>,
+    );
+
+    impl SubCmd for ExportShieldedHistory {
+        const CMD: &'static str = "export-shielded-history";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                ExportShieldedHistory(args::ExportShieldedHistory::parse(
+                    matches,
+                ))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Export the shielded history of a viewing key to a CSV or \
+                     JSON file. The shielded context must be synced first.",
+                )
+                .add_args::<args::ExportShieldedHistory<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryCommissionRate(
         pub args::QueryCommissionRate<args::CliTypes>,
@@ -1807,6 +2384,32 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct QueryValidatorHistory(
+        pub args::QueryValidatorHistory<args::CliTypes>,
+    );
+
+    impl SubCmd for QueryValidatorHistory {
+        const CMD: &'static str = "validator-history";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryValidatorHistory(args::QueryValidatorHistory::parse(
+                    matches,
+                ))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Query a validator's commission rate, stake and state in \
+                     a range of epochs, and its slashes in these epochs.",
+                )
+                .add_args::<args::QueryValidatorHistory<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QuerySlashes(pub args::QuerySlashes<args::CliTypes>);
 
@@ -1851,6 +2454,32 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct QueryRewardsProjection(
+        pub args::QueryRewardsProjection<args::CliTypes>,
+    );
+
+    impl SubCmd for QueryRewardsProjection {
+        const CMD: &'static str = "rewards-projection";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryRewardsProjection(args::QueryRewardsProjection::parse(
+                    matches,
+                ))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Estimate the rewards and the APR of bonding the given \
+                     amount to a validator.",
+                )
+                .add_args::<args::QueryRewardsProjection<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryDelegations(pub args::QueryDelegations<args::CliTypes>);
 
@@ -1873,6 +2502,84 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct QueryPendingUnbonds(
+        pub args::QueryPendingUnbonds<args::CliTypes>,
+    );
+
+    impl SubCmd for QueryPendingUnbonds {
+        const CMD: &'static str = "pending-unbonds";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryPendingUnbonds(args::QueryPendingUnbonds::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Query the unbonds of the given owner that have not been \
+                     withdrawn yet, with their withdrawable amounts and \
+                     epochs, and the redelegations that can still be slashed \
+                     for the source validator's infractions.",
+                )
+                .add_args::<args::QueryPendingUnbonds<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QuerySlashReport(pub args::QuerySlashReport<args::CliTypes>);
+
+    impl SubCmd for QuerySlashReport {
+        const CMD: &'static str = "slash-report";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QuerySlashReport(args::QuerySlashReport::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Query the slashes that affected the bonds and unbonds of \
+                     the given owner, with the amount lost by each bond, \
+                     unbond or redelegated part of them.",
+                )
+                .add_args::<args::QuerySlashReport<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct SimulateValidatorSets(
+        pub args::SimulateValidatorSets<args::CliTypes>,
+    );
+
+    impl SubCmd for SimulateValidatorSets {
+        const CMD: &'static str = "simulate-validator-sets";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                SimulateValidatorSets(args::SimulateValidatorSets::parse(
+                    matches,
+                ))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Simulate the validator sets over the next epochs with \
+                     changed PoS parameters, starting from the current \
+                     validators or from an exported snapshot of them, and \
+                     report the membership of the validator sets and the \
+                     concentration of the voting power.",
+                )
+                .add_args::<args::SimulateValidatorSets<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryFindValidator(pub args::QueryFindValidator<args::CliTypes>);
 
@@ -2175,6 +2882,7 @@ pub mod cmds {
         ValidateGenesisTemplates(ValidateGenesisTemplates),
         TestGenesis(TestGenesis),
         SignGenesisTxs(SignGenesisTxs),
+        MaspParams(MaspParams),
     }
 
     impl SubCmd for Utils {
@@ -2208,6 +2916,7 @@ pub mod cmds {
                     SubCmd::parse(matches).map(Self::SignGenesisTxs);
                 let test_genesis =
                     SubCmd::parse(matches).map(Self::TestGenesis);
+                let masp_params = SubCmd::parse(matches).map(Self::MaspParams);
                 join_network
                     .or(fetch_wasms)
                     .or(validate_wasm)
@@ -2222,6 +2931,7 @@ pub mod cmds {
                     .or(validate_genesis_templates)
                     .or(test_genesis)
                     .or(genesis_tx)
+                    .or(masp_params)
             })
         }
 
@@ -2242,6 +2952,7 @@ pub mod cmds {
                 .subcommand(ValidateGenesisTemplates::def())
                 .subcommand(TestGenesis::def())
                 .subcommand(SignGenesisTxs::def())
+                .subcommand(MaspParams::def())
                 .subcommand_required(true)
                 .arg_required_else_help(true)
         }
@@ -2922,6 +3633,29 @@ pub mod cmds {
                 .add_args::<args::DefaultBaseDir>()
         }
     }
+
+    #[derive(Clone, Debug)]
+    pub struct MaspParams(pub args::MaspParams);
+
+    impl SubCmd for MaspParams {
+        const CMD: &'static str = "masp-params";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::MaspParams::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Verify the MASP parameter files against their pinned \
+                     hashes and report the missing ones. The parameters can \
+                     be installed from a local bundle first.",
+                )
+                .add_args::<args::MaspParams>()
+        }
+    }
 }
 
 pub mod args {
@@ -2937,7 +3671,7 @@ pub mod args {
     use namada::core::ethereum_events::EthAddress;
     use namada::core::keccak::KeccakHash;
     use namada::core::key::*;
-    use namada::core::masp::PaymentAddress;
+    use namada::core::masp::{PaymentAddress, PaymentRequest};
     use namada::core::storage::{self, BlockHeight, Epoch};
     use namada::core::time::DateTimeUtc;
     use namada::core::token;
@@ -2945,17 +3679,22 @@ pub mod args {
     use namada::ibc::core::host::types::identifiers::{ChannelId, PortId};
     use namada::tx::data::GasLimit;
     pub use namada_sdk::args::*;
+    use namada_sdk::masp::{
+        NoteSelection, ShieldedHistoryFormat, DEFAULT_MAX_CONSOLIDATED_NOTES,
+    };
     pub use namada_sdk::tx::{
         TX_BECOME_VALIDATOR_WASM, TX_BOND_WASM, TX_BRIDGE_POOL_WASM,
         TX_CHANGE_COMMISSION_WASM, TX_CHANGE_CONSENSUS_KEY_WASM,
         TX_CHANGE_METADATA_WASM, TX_CLAIM_REWARDS_WASM,
         TX_DEACTIVATE_VALIDATOR_WASM, TX_IBC_WASM, TX_INIT_ACCOUNT_WASM,
-        TX_INIT_PROPOSAL, TX_REACTIVATE_VALIDATOR_WASM, TX_REDELEGATE_WASM,
-        TX_RESIGN_STEWARD, TX_REVEAL_PK, TX_TRANSFER_WASM, TX_UNBOND_WASM,
-        TX_UNJAIL_VALIDATOR_WASM, TX_UPDATE_ACCOUNT_WASM,
-        TX_UPDATE_STEWARD_COMMISSION, TX_VOTE_PROPOSAL, TX_WITHDRAW_WASM,
-        VP_USER_WASM,
+        TX_INIT_PROPOSAL, TX_LIQUID_BOND_WASM, TX_REACTIVATE_VALIDATOR_WASM,
+        TX_REDEEM_SHARES_WASM, TX_REDELEGATE_WASM, TX_RESIGN_STEWARD,
+        TX_REVEAL_PK, TX_SCHEDULE_COMMISSION_WASM, TX_SET_RESTAKE_WASM,
+        TX_TRANSFER_WASM, TX_UNBOND_WASM, TX_UNJAIL_VALIDATOR_WASM,
+        TX_UPDATE_ACCOUNT_WASM, TX_UPDATE_STEWARD_COMMISSION, TX_VOTE_PROPOSAL,
+        TX_WITHDRAW_WASM, VP_USER_WASM,
     };
+    use namada_sdk::wallet::address_book::AddressBookFormat;
 
     use super::context::*;
     use super::utils::*;
@@ -2967,12 +3706,15 @@ pub mod args {
     use crate::facade::tendermint_rpc::Url;
 
     pub const ADDRESS: Arg<WalletAddress> = arg("address");
+    pub const ADDRESS_BOOK_FORMAT: ArgDefault<AddressBookFormat> =
+        arg_default("format", DefaultFn(|| AddressBookFormat::Json));
     pub const ALIAS_OPT: ArgOpt<String> = ALIAS.opt();
     pub const ALIAS: Arg<String> = arg("alias");
     pub const ALIAS_FORCE: ArgFlag = flag("alias-force");
     pub const ALIAS_MANY: ArgMulti<String, GlobPlus> = arg_multi("aliases");
     pub const ALLOW_DUPLICATE_IP: ArgFlag = flag("allow-duplicate-ip");
     pub const AMOUNT: Arg<token::DenominatedAmount> = arg("amount");
+    pub const AMOUNT_OPT: ArgOpt<token::DenominatedAmount> = AMOUNT.opt();
     pub const ARCHIVE_DIR: ArgOpt<PathBuf> = arg_opt("archive-dir");
     pub const AVATAR_OPT: ArgOpt<String> = arg_opt("avatar");
     pub const BALANCE_OWNER: ArgOpt<WalletBalanceOwner> = arg_opt("owner");
@@ -2985,6 +3727,7 @@ pub mod args {
     );
     pub const BATCH_SIZE_OPT: ArgDefault<u64> =
         arg_default("batch-size", DefaultFn(|| 1));
+    pub const BIRTHDAY_OPT: ArgOpt<BlockHeight> = arg_opt("birthday");
     pub const BLOCK_HEIGHT: Arg<BlockHeight> = arg("block-height");
     pub const BLOCK_HEIGHT_OPT: ArgOpt<BlockHeight> = arg_opt("height");
     pub const BRIDGE_POOL_GAS_AMOUNT: ArgDefault<token::DenominatedAmount> =
@@ -3027,18 +3770,26 @@ pub mod args {
     pub const DATA_PATH_OPT: ArgOpt<PathBuf> = arg_opt("data-path");
     pub const DATA_PATH: Arg<PathBuf> = arg("data-path");
     pub const DECRYPT: ArgFlag = flag("decrypt");
+    pub const DEFAULT_MEMO_OPT: ArgOpt<String> = arg_opt("default-memo");
+    pub const DEFAULT_TOKEN_OPT: ArgOpt<String> = arg_opt("default-token");
     pub const DESCRIPTION_OPT: ArgOpt<String> = arg_opt("description");
     pub const DISPOSABLE_SIGNING_KEY: ArgFlag = flag("disposable-gas-payer");
     pub const DESTINATION_VALIDATOR: Arg<WalletAddress> =
         arg("destination-validator");
+    pub const DISABLE_RESTAKE: ArgFlag = flag("disable");
     pub const DISCORD_OPT: ArgOpt<String> = arg_opt("discord-handle");
     pub const DO_IT: ArgFlag = flag("do-it");
     pub const DONT_ARCHIVE: ArgFlag = flag("dont-archive");
+    pub const DELETE_CONTEXT: ArgOpt<String> = arg_opt("delete");
     pub const DONT_PREFETCH_WASM: ArgFlag = flag("dont-prefetch-wasm");
     pub const DRY_RUN_TX: ArgFlag = flag("dry-run");
     pub const DRY_RUN_WRAPPER_TX: ArgFlag = flag("dry-run-wrapper");
     pub const DUMP_TX: ArgFlag = flag("dump-tx");
+    pub const DUST_THRESHOLD: ArgOpt<token::DenominatedAmount> =
+        arg_opt("dust-threshold");
+    pub const EFFECTIVE_EPOCH: ArgOpt<Epoch> = arg_opt("effective-epoch");
     pub const EPOCH: ArgOpt<Epoch> = arg_opt("epoch");
+    pub const EPOCHS: ArgDefault<u64> = arg_default("epochs", DefaultFn(|| 10));
     pub const ERC20: Arg<EthAddress> = arg("erc20");
     pub const ETH_CONFIRMATIONS: Arg<u64> = arg("confirmations");
     pub const ETH_GAS: ArgOpt<u64> = arg_opt("eth-gas");
@@ -3051,8 +3802,10 @@ pub mod args {
     );
     pub const ETH_SYNC: ArgFlag = flag("sync");
     pub const EXPIRATION_OPT: ArgOpt<DateTimeUtc> = arg_opt("expiration");
+    pub const EXPIRY_OPT: ArgOpt<DateTimeUtc> = arg_opt("expiry");
     pub const EMAIL: Arg<String> = arg("email");
     pub const EMAIL_OPT: ArgOpt<String> = EMAIL.opt();
+    pub const END_EPOCH: ArgOpt<Epoch> = arg_opt("end-epoch");
     pub const FEE_UNSHIELD_SPENDING_KEY: ArgOpt<WalletTransferSource> =
         arg_opt("gas-spending-key");
     pub const FEE_AMOUNT_OPT: ArgOpt<token::DenominatedAmount> =
@@ -3091,6 +3844,7 @@ pub mod args {
     pub const HISTORIC: ArgFlag = flag("historic");
     pub const IBC_TRANSFER_MEMO_PATH: ArgOpt<PathBuf> = arg_opt("memo-path");
     pub const INPUT_OPT: ArgOpt<PathBuf> = arg_opt("input");
+    pub const INSTALL_PARAMS: ArgOpt<PathBuf> = arg_opt("install");
     pub const LEDGER_ADDRESS_ABOUT: &str =
         "Address of a ledger node as \"{scheme}://{host}:{port}\". If the \
          scheme is not supplied, it is assumed to be TCP.";
@@ -3101,17 +3855,32 @@ pub mod args {
             let raw = "http://127.0.0.1:26657";
             Url::from_str(raw).unwrap()
         }));
+    pub const LIFETIME: ArgDefault<Duration> = arg_default(
+        "lifetime",
+        DefaultFn(|| Duration::from_str("1h").unwrap()),
+    );
+    pub const LIFETIME_OPT: ArgOpt<Duration> = arg_opt("lifetime");
+    pub const LIVENESS_THRESHOLD: ArgOpt<Dec> = arg_opt("liveness-threshold");
     pub const LIST_FIND_ADDRESSES_ONLY: ArgFlag = flag("addr");
     pub const LIST_FIND_KEYS_ONLY: ArgFlag = flag("keys");
     pub const LOCALHOST: ArgFlag = flag("localhost");
+    pub const MASP_TXS_FILE: Arg<PathBuf> = arg("masp-txs-file");
+    pub const MASP_TXS_FILE_OPT: ArgOpt<PathBuf> = MASP_TXS_FILE.opt();
     pub const MAX_COMMISSION_RATE_CHANGE: Arg<Dec> =
         arg("max-commission-rate-change");
     pub const MAX_ETH_GAS: ArgOpt<u64> = arg_opt("max_eth-gas");
+    pub const MAX_VALIDATOR_SLOTS: ArgOpt<u64> = arg_opt("max-validator-slots");
+    pub const MAX_NOTES: ArgDefault<usize> =
+        arg_default("max-notes", DefaultFn(|| DEFAULT_MAX_CONSOLIDATED_NOTES));
     pub const MEMO_OPT: ArgOpt<String> = arg_opt("memo");
+    pub const MERGE_CONTEXT: ArgOpt<String> = arg_opt("merge");
     pub const MODE: ArgOpt<String> = arg_opt("mode");
     pub const NET_ADDRESS: Arg<SocketAddr> = arg("net-address");
     pub const NAMADA_START_TIME: ArgOpt<DateTimeUtc> = arg_opt("time");
     pub const NO_CONVERSIONS: ArgFlag = flag("no-conversions");
+    pub const NOTE_SELECTION: ArgDefault<NoteSelection> =
+        arg_default("note-selection", DefaultFn(NoteSelection::default));
+    pub const NOTE_OPT: ArgOpt<String> = arg_opt("note");
     pub const NUT: ArgFlag = flag("nut");
     pub const OUT_FILE_PATH_OPT: ArgOpt<PathBuf> = arg_opt("out-file-path");
     pub const OUTPUT: ArgOpt<PathBuf> = arg_opt("output");
@@ -3120,6 +3889,7 @@ pub mod args {
     pub const OWNER: Arg<WalletAddress> = arg("owner");
     pub const OWNER_OPT: ArgOpt<WalletAddress> = OWNER.opt();
     pub const PATH: Arg<PathBuf> = arg("path");
+    pub const PAYMENT_REQUEST_OPT: ArgOpt<PaymentRequest> = arg_opt("request");
     pub const PIN: ArgFlag = flag("pin");
     pub const PORT_ID: ArgDefault<PortId> = arg_default(
         "port-id",
@@ -3155,25 +3925,38 @@ pub mod args {
         RAW_PUBLIC_KEY_HASH.opt();
     pub const RECEIVER: Arg<String> = arg("receiver");
     pub const RELAYER: Arg<Address> = arg("relayer");
+    pub const RESTAKE_INTERVAL: ArgOpt<u64> = arg_opt("interval");
     pub const SAFE_MODE: ArgFlag = flag("safe-mode");
     pub const SCHEME: ArgDefault<SchemeType> =
         arg_default("scheme", DefaultFn(|| SchemeType::Ed25519));
+    pub const SEARCH_OPT: ArgOpt<String> = arg_opt("search");
     pub const SELF_BOND_AMOUNT: Arg<token::DenominatedAmount> =
         arg("self-bond-amount");
     pub const SENDER: Arg<String> = arg("sender");
+    pub const SHARE_FILE: Arg<PathBuf> = arg("share-file");
+    pub const SHARES: Arg<token::DenominatedAmount> = arg("shares");
     pub const SHIELDED: ArgFlag = flag("shielded");
+    pub const SHIELDED_CONTEXT: ArgOpt<String> = arg_opt("shielded-context");
+    pub const SHIELDED_HISTORY_FORMAT: ArgDefault<ShieldedHistoryFormat> =
+        arg_default("format", DefaultFn(|| ShieldedHistoryFormat::Csv));
     pub const SIGNER: ArgOpt<WalletAddress> = arg_opt("signer");
     pub const SIGNING_KEYS: ArgMulti<WalletPublicKey, GlobStar> =
         arg_multi("signing-keys");
     pub const SIGNATURES: ArgMulti<PathBuf, GlobStar> = arg_multi("signatures");
+    pub const SOCKET: Arg<PathBuf> = arg("socket");
+    pub const SOCKET_OPT: ArgOpt<PathBuf> = SOCKET.opt();
+    pub const SOCKETS: ArgMulti<PathBuf, GlobPlus> = arg_multi("sockets");
     pub const SOURCE: Arg<WalletAddress> = arg("source");
     pub const SOURCE_OPT: ArgOpt<WalletAddress> = SOURCE.opt();
+    pub const SOURCE_SPENDING_KEY: Arg<WalletSpendingKey> = arg("source");
     pub const SPENDING_KEYS: ArgMulti<WalletSpendingKey, GlobStar> =
         arg_multi("spending-keys");
+    pub const START_EPOCH: Arg<Epoch> = arg("start-epoch");
     pub const STEWARD: Arg<WalletAddress> = arg("steward");
     pub const SOURCE_VALIDATOR: Arg<WalletAddress> = arg("source-validator");
     pub const STORAGE_KEY: Arg<storage::Key> = arg("storage-key");
     pub const SUSPEND_ACTION: ArgFlag = flag("suspend");
+    pub const TAGS: ArgMulti<String, GlobStar> = arg_multi("tags");
     pub const TEMPLATES_PATH: Arg<PathBuf> = arg("templates-path");
     pub const TIMEOUT_HEIGHT: ArgOpt<u64> = arg_opt("timeout-height");
     pub const TIMEOUT_SEC_OFFSET: ArgOpt<u64> = arg_opt("timeout-sec-offset");
@@ -3181,8 +3964,11 @@ pub mod args {
     pub const TOKEN_OPT: ArgOpt<WalletAddress> = TOKEN.opt();
     pub const TOKEN: Arg<WalletAddress> = arg("token");
     pub const TOKEN_STR: Arg<String> = arg("token");
+    pub const TOKEN_STR_OPT: ArgOpt<String> = TOKEN_STR.opt();
     pub const TRANSFER_SOURCE: Arg<WalletTransferSource> = arg("source");
     pub const TRANSFER_TARGET: Arg<WalletTransferTarget> = arg("target");
+    pub const TRANSFER_TARGET_OPT: ArgOpt<WalletTransferTarget> =
+        TRANSFER_TARGET.opt();
     pub const TRANSPARENT: ArgFlag = flag("transparent");
     pub const TX_HASH: Arg<String> = arg("tx-hash");
     pub const THRESHOLD: ArgOpt<u8> = arg_opt("threshold");
@@ -3203,6 +3989,8 @@ pub mod args {
         arg_opt("eth-cold-key");
     pub const VALIDATOR_ETH_HOT_KEY: ArgOpt<WalletPublicKey> =
         arg_opt("eth-hot-key");
+    pub const VALIDATOR_STAKE_THRESHOLD: ArgOpt<token::DenominatedAmount> =
+        arg_opt("validator-stake-threshold");
     pub const VALUE: Arg<String> = arg("value");
     pub const VOTER_OPT: ArgOpt<WalletAddress> = arg_opt("voter");
     pub const VIEWING_KEY: Arg<WalletViewingKey> = arg("key");
@@ -3223,6 +4011,7 @@ pub mod args {
         pub chain_id: Option<ChainId>,
         pub base_dir: PathBuf,
         pub wasm_dir: Option<PathBuf>,
+        pub shielded_context: Option<String>,
     }
 
     impl Global {
@@ -3232,11 +4021,13 @@ pub mod args {
             let chain_id = CHAIN_ID_OPT.parse(matches);
             let base_dir = BASE_DIR.parse(matches);
             let wasm_dir = WASM_DIR.parse(matches);
+            let shielded_context = SHIELDED_CONTEXT.parse(matches);
             Global {
                 is_pre_genesis,
                 chain_id,
                 base_dir,
                 wasm_dir,
+                shielded_context,
             }
         }
 
@@ -3266,6 +4057,11 @@ pub mod args {
                         .global(true)
                         .help("Dispatch pre-genesis specific logic."),
                 )
+                .arg(SHIELDED_CONTEXT.def().global(true).help(
+                    "The name of the shielded context to use. Every named \
+                     context has its own keys, notes and sync state. Defaults \
+                     to the context stored in the chain directory.",
+                ))
         }
     }
 
@@ -3394,6 +4190,57 @@ pub mod args {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct FrostShareHolder {
+        pub socket: PathBuf,
+        pub share_file: PathBuf,
+    }
+
+    impl Args for FrostShareHolder {
+        fn parse(matches: &ArgMatches) -> Self {
+            let socket = SOCKET.parse(matches);
+            let share_file = SHARE_FILE.parse(matches);
+            Self { socket, share_file }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(
+                SOCKET
+                    .def()
+                    .help("The path of the Unix socket to listen on."),
+            )
+            .arg(SHARE_FILE.def().help(
+                "The path of the key share file. It's created by the key \
+                 generation if it doesn't exist yet.",
+            ))
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct FrostDkg {
+        pub sockets: Vec<PathBuf>,
+        pub threshold: Option<u8>,
+    }
+
+    impl Args for FrostDkg {
+        fn parse(matches: &ArgMatches) -> Self {
+            let sockets = SOCKETS.parse(matches);
+            let threshold = THRESHOLD.parse(matches);
+            Self { sockets, threshold }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(SOCKETS.def().help(
+                "The sockets of the share holders, separated by commas. The \
+                 share holders are identified in this order.",
+            ))
+            .arg(THRESHOLD.def().help(
+                "The number of share holders required to sign. Defaults to a \
+                 majority of the share holders.",
+            ))
+        }
+    }
+
     /// Convert CLI args to SDK args, with contextual data.
     pub trait CliToSdk<SDK>: Args {
         /// Convert CLI args to SDK args, with contextual data.
@@ -3987,66 +4834,166 @@ pub mod args {
                         .conflicts_with(TX_PATH_OPT.name),
                 )
                 .arg(
-                    TX_PATH_OPT
+                    TX_PATH_OPT
+                        .def()
+                        .help("The path to a serialized transaction.")
+                        .conflicts_with_all([
+                            CODE_PATH_OPT.name,
+                            DATA_PATH_OPT.name,
+                        ]),
+                )
+                .arg(OWNER.def().help(
+                    "The address corresponding to the signatures or signing \
+                     keys.",
+                ))
+        }
+    }
+
+    impl CliToSdk<TxTransfer<SdkTypes>> for TxTransfer<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> TxTransfer<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_mut_chain_or_exit();
+            TxTransfer::<SdkTypes> {
+                tx,
+                source: chain_ctx.get_cached(&self.source),
+                target: chain_ctx.get(&self.target),
+                token: chain_ctx.get(&self.token),
+                amount: self.amount,
+                payment_request: self.payment_request,
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for TxTransfer<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let source = TRANSFER_SOURCE.parse(matches);
+            let payment_request = PAYMENT_REQUEST_OPT.parse(matches);
+            // The payment request prefills the arguments that aren't given.
+            // The transfer is checked against the request when built.
+            let request = payment_request.as_ref();
+            let target =
+                TRANSFER_TARGET_OPT.parse(matches).unwrap_or_else(|| {
+                    let request = request
+                        .expect("the target is required without a request");
+                    WalletTransferTarget::new(request.address.to_string())
+                });
+            let token = TOKEN_OPT
+                .parse(matches)
+                .or_else(|| {
+                    request
+                        .and_then(|request| request.token.as_ref())
+                        .map(|token| WalletAddress::new(token.encode()))
+                })
+                .unwrap_or_else(|| {
+                    println!("The payment request doesn't specify a token.");
+                    safe_exit(1)
+                });
+            let amount = AMOUNT_OPT
+                .parse(matches)
+                .or_else(|| request.and_then(|request| request.amount))
+                .unwrap_or_else(|| {
+                    println!("The payment request doesn't specify an amount.");
+                    safe_exit(1)
+                });
+            let tx_code_path = PathBuf::from(TX_TRANSFER_WASM);
+            Self {
+                tx,
+                source,
+                target,
+                token,
+                amount: InputAmount::Unvalidated(amount),
+                payment_request,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(TRANSFER_SOURCE.def().help(
+                    "The source account address. The source's key may be used \
+                     to produce the signature.",
+                ))
+                .arg(
+                    TRANSFER_TARGET_OPT
+                        .def()
+                        .required_unless_present(PAYMENT_REQUEST_OPT.name)
+                        .help(
+                            "The target account address. The target's key may \
+                             be used to produce the signature.",
+                        ),
+                )
+                .arg(
+                    TOKEN_OPT
+                        .def()
+                        .required_unless_present(PAYMENT_REQUEST_OPT.name)
+                        .help("The transfer token."),
+                )
+                .arg(
+                    AMOUNT_OPT
                         .def()
-                        .help("The path to a serialized transaction.")
-                        .conflicts_with_all([
-                            CODE_PATH_OPT.name,
-                            DATA_PATH_OPT.name,
-                        ]),
+                        .required_unless_present(PAYMENT_REQUEST_OPT.name)
+                        .help("The amount to transfer in decimal."),
                 )
-                .arg(OWNER.def().help(
-                    "The address corresponding to the signatures or signing \
-                     keys.",
+                .arg(PAYMENT_REQUEST_OPT.def().help(
+                    "A payment request URI to pay. It gives the target and \
+                     may give the token, amount and memo of the transfer.",
                 ))
         }
     }
 
-    impl CliToSdk<TxTransfer<SdkTypes>> for TxTransfer<CliTypes> {
-        fn to_sdk(self, ctx: &mut Context) -> TxTransfer<SdkTypes> {
+    impl CliToSdk<TxConsolidateNotes<SdkTypes>> for TxConsolidateNotes<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> TxConsolidateNotes<SdkTypes> {
             let tx = self.tx.to_sdk(ctx);
             let chain_ctx = ctx.borrow_mut_chain_or_exit();
-            TxTransfer::<SdkTypes> {
+            TxConsolidateNotes::<SdkTypes> {
                 tx,
                 source: chain_ctx.get_cached(&self.source),
-                target: chain_ctx.get(&self.target),
                 token: chain_ctx.get(&self.token),
-                amount: self.amount,
+                max_notes: self.max_notes,
+                dust_threshold: self.dust_threshold,
                 tx_code_path: self.tx_code_path.to_path_buf(),
             }
         }
     }
 
-    impl Args for TxTransfer<CliTypes> {
+    impl Args for TxConsolidateNotes<CliTypes> {
         fn parse(matches: &ArgMatches) -> Self {
             let tx = Tx::parse(matches);
-            let source = TRANSFER_SOURCE.parse(matches);
-            let target = TRANSFER_TARGET.parse(matches);
+            let source = SOURCE_SPENDING_KEY.parse(matches);
             let token = TOKEN.parse(matches);
-            let amount = InputAmount::Unvalidated(AMOUNT.parse(matches));
+            let max_notes = MAX_NOTES.parse(matches);
+            let dust_threshold =
+                DUST_THRESHOLD.parse(matches).map(InputAmount::Unvalidated);
             let tx_code_path = PathBuf::from(TX_TRANSFER_WASM);
             Self {
                 tx,
                 source,
-                target,
                 token,
-                amount,
+                max_notes,
+                dust_threshold,
                 tx_code_path,
             }
         }
 
         fn def(app: App) -> App {
             app.add_args::<Tx<CliTypes>>()
-                .arg(TRANSFER_SOURCE.def().help(
-                    "The source account address. The source's key may be used \
-                     to produce the signature.",
-                ))
-                .arg(TRANSFER_TARGET.def().help(
-                    "The target account address. The target's key may be used \
-                     to produce the signature.",
+                .arg(
+                    SOURCE_SPENDING_KEY
+                        .def()
+                        .help("The spending key whose notes to merge."),
+                )
+                .arg(TOKEN.def().help("The token of the notes to merge."))
+                .arg(MAX_NOTES.def().help(
+                    "The maximum number of notes to merge, smallest first. \
+                     More notes make a larger proof.",
                 ))
-                .arg(TOKEN.def().help("The transfer token."))
-                .arg(AMOUNT.def().help("The amount to transfer in decimal."))
+                .arg(
+                    DUST_THRESHOLD
+                        .def()
+                        .help("Only merge the notes of at most this amount."),
+                )
         }
     }
 
@@ -4561,6 +5508,108 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<LiquidBond<SdkTypes>> for LiquidBond<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> LiquidBond<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_chain_or_exit();
+            LiquidBond::<SdkTypes> {
+                tx,
+                validator: chain_ctx.get(&self.validator),
+                amount: self.amount,
+                source: chain_ctx.get(&self.source),
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for LiquidBond<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            let amount = amount
+                .canonical()
+                .increase_precision(NATIVE_MAX_DECIMAL_PLACES.into())
+                .unwrap_or_else(|e| {
+                    println!("Could not parse bond amount: {:?}", e);
+                    safe_exit(1);
+                })
+                .amount();
+            let source = SOURCE.parse(matches);
+            let tx_code_path = PathBuf::from(TX_LIQUID_BOND_WASM);
+            Self {
+                tx,
+                validator,
+                amount,
+                source,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(VALIDATOR.def().help("Validator address."))
+                .arg(
+                    AMOUNT
+                        .def()
+                        .help("Amount of tokens to stake in a liquid bond."),
+                )
+                .arg(SOURCE.def().help(
+                    "Source address of the tokens, to which the share tokens \
+                     are minted.",
+                ))
+        }
+    }
+
+    impl CliToSdk<RedeemShares<SdkTypes>> for RedeemShares<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> RedeemShares<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_chain_or_exit();
+            RedeemShares::<SdkTypes> {
+                tx,
+                validator: chain_ctx.get(&self.validator),
+                shares: self.shares,
+                owner: chain_ctx.get(&self.owner),
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for RedeemShares<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let shares = SHARES.parse(matches);
+            let shares = shares
+                .canonical()
+                .increase_precision(NATIVE_MAX_DECIMAL_PLACES.into())
+                .unwrap_or_else(|e| {
+                    println!("Could not parse shares amount: {:?}", e);
+                    safe_exit(1);
+                })
+                .amount();
+            let owner = OWNER.parse(matches);
+            let tx_code_path = PathBuf::from(TX_REDEEM_SHARES_WASM);
+            Self {
+                tx,
+                validator,
+                shares,
+                owner,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(VALIDATOR.def().help("Validator address."))
+                .arg(SHARES.def().help("Amount of share tokens to redeem."))
+                .arg(OWNER.def().help(
+                    "Owner of the share tokens, for whom their value is \
+                     unbonded.",
+                ))
+        }
+    }
+
     impl CliToSdk<Unbond<SdkTypes>> for Unbond<CliTypes> {
         fn to_sdk(self, ctx: &mut Context) -> Unbond<SdkTypes> {
             let tx = self.tx.to_sdk(ctx);
@@ -5171,6 +6220,62 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<Restake<SdkTypes>> for Restake<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> Restake<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_chain_or_exit();
+            Restake::<SdkTypes> {
+                tx,
+                validator: chain_ctx.get(&self.validator),
+                source: self.source.map(|x| chain_ctx.get(&x)),
+                interval: self.interval,
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for Restake<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let source = SOURCE_OPT.parse(matches);
+            // The interval is absent iff the restaking is disabled
+            let interval = RESTAKE_INTERVAL.parse(matches);
+            let tx_code_path = PathBuf::from(TX_SET_RESTAKE_WASM);
+            Self {
+                tx,
+                validator,
+                source,
+                interval,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(VALIDATOR.def().help("Validator address."))
+                .arg(SOURCE_OPT.def().help(
+                    "Source address of the bond. For self-bonds, the \
+                     validator is also the source.",
+                ))
+                .arg(RESTAKE_INTERVAL.def().help(
+                    "Number of epochs between two restakes of the rewards. \
+                     The restaked rewards are bonded at the pipeline offset, \
+                     like any other bond.",
+                ))
+                .arg(
+                    DISABLE_RESTAKE
+                        .def()
+                        .help("Disable the restaking of the rewards."),
+                )
+                .group(
+                    ArgGroup::new("restake_flags")
+                        .args([RESTAKE_INTERVAL.name, DISABLE_RESTAKE.name])
+                        .required(true),
+                )
+        }
+    }
+
     impl CliToSdk<QueryConversions<SdkTypes>> for QueryConversions<CliTypes> {
         fn to_sdk(self, ctx: &mut Context) -> QueryConversions<SdkTypes> {
             QueryConversions::<SdkTypes> {
@@ -5316,6 +6421,98 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<ExportShieldedHistory<SdkTypes>>
+        for ExportShieldedHistory<CliTypes>
+    {
+        fn to_sdk(self, ctx: &mut Context) -> ExportShieldedHistory<SdkTypes> {
+            let query = self.query.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_mut_chain_or_exit();
+            ExportShieldedHistory::<SdkTypes> {
+                query,
+                viewing_key: chain_ctx.get_cached(&self.viewing_key),
+                format: self.format,
+                output: self.output,
+            }
+        }
+    }
+
+    impl Args for ExportShieldedHistory<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let viewing_key = VIEWING_KEY.parse(matches);
+            let format = SHIELDED_HISTORY_FORMAT.parse(matches);
+            let output = OUTPUT.parse(matches);
+            Self {
+                query,
+                viewing_key,
+                format,
+                output,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>()
+                .arg(
+                    VIEWING_KEY
+                        .def()
+                        .help("The viewing key whose history is exported."),
+                )
+                .arg(
+                    SHIELDED_HISTORY_FORMAT
+                        .def()
+                        .help("The format of the history: json or csv."),
+                )
+                .arg(OUTPUT.def().help(
+                    "The file to write the history to. It is printed if not \
+                     given.",
+                ))
+        }
+    }
+
+    impl CliToSdk<QueryMaspRewards<SdkTypes>> for QueryMaspRewards<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> QueryMaspRewards<SdkTypes> {
+            let query = self.query.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_mut_chain_or_exit();
+            QueryMaspRewards::<SdkTypes> {
+                query,
+                token: chain_ctx.get(&self.token),
+                amount: self.amount,
+                epochs: self.epochs,
+            }
+        }
+    }
+
+    impl Args for QueryMaspRewards<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount =
+                AMOUNT_OPT.parse(matches).map(InputAmount::Unvalidated);
+            let epochs = EPOCHS.parse(matches);
+            Self {
+                query,
+                token,
+                amount,
+                epochs,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>()
+                .arg(TOKEN.def().help("The token held in the shielded pool."))
+                .arg(AMOUNT_OPT.def().help(
+                    "The amount of the token held in the shielded pool to \
+                     estimate the rewards of.",
+                ))
+                .arg(EPOCHS.def().help(
+                    "The number of epochs after the current one to project \
+                     the rewards over, at most a year of epochs. The \
+                     projection assumes that the amounts locked in the \
+                     shielded pool stay the same.",
+                ))
+        }
+    }
+
     impl CliToSdk<QueryBonds<SdkTypes>> for QueryBonds<CliTypes> {
         fn to_sdk(self, ctx: &mut Context) -> QueryBonds<SdkTypes> {
             let query = self.query.to_sdk(ctx);
@@ -5427,6 +6624,33 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<QueryConsensusKey<SdkTypes>>
+        for QueryConsensusKey<CliTypes>
+    {
+        fn to_sdk(self, ctx: &mut Context) -> QueryConsensusKey<SdkTypes> {
+            QueryConsensusKey::<SdkTypes> {
+                query: self.query.to_sdk(ctx),
+                validator: ctx.borrow_chain_or_exit().get(&self.validator),
+            }
+        }
+    }
+
+    impl Args for QueryConsensusKey<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            Self { query, validator }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>().arg(
+                VALIDATOR.def().help(
+                    "The validator's address whose consensus key to query.",
+                ),
+            )
+        }
+    }
+
     impl CliToSdk<CommissionRateChange<SdkTypes>>
         for CommissionRateChange<CliTypes>
     {
@@ -5435,6 +6659,7 @@ pub mod args {
                 tx: self.tx.to_sdk(ctx),
                 validator: ctx.borrow_chain_or_exit().get(&self.validator),
                 rate: self.rate,
+                effective_epoch: self.effective_epoch,
                 tx_code_path: self.tx_code_path.to_path_buf(),
             }
         }
@@ -5445,11 +6670,17 @@ pub mod args {
             let tx = Tx::parse(matches);
             let validator = VALIDATOR.parse(matches);
             let rate = COMMISSION_RATE.parse(matches);
-            let tx_code_path = PathBuf::from(TX_CHANGE_COMMISSION_WASM);
+            let effective_epoch = EFFECTIVE_EPOCH.parse(matches);
+            let tx_code_path = PathBuf::from(if effective_epoch.is_some() {
+                TX_SCHEDULE_COMMISSION_WASM
+            } else {
+                TX_CHANGE_COMMISSION_WASM
+            });
             Self {
                 tx,
                 validator,
                 rate,
+                effective_epoch,
                 tx_code_path,
             }
         }
@@ -5464,6 +6695,11 @@ pub mod args {
                         .def()
                         .help("The desired new commission rate."),
                 )
+                .arg(EFFECTIVE_EPOCH.def().help(
+                    "The epoch from which the new commission rate takes \
+                     effect. Defaults to the pipeline epoch. A later epoch \
+                     gives the delegators more notice of the change.",
+                ))
         }
     }
 
@@ -5725,35 +6961,86 @@ pub mod args {
             let last_query_height = BLOCK_HEIGHT_OPT.parse(matches);
             let spending_keys = SPENDING_KEYS.parse(matches);
             let viewing_keys = VIEWING_KEYS.parse(matches);
+            let masp_txs_file = MASP_TXS_FILE_OPT.parse(matches);
             Self {
                 ledger_address,
                 batch_size,
                 last_query_height,
                 spending_keys,
                 viewing_keys,
+                masp_txs_file,
             }
         }
 
         fn def(app: App) -> App {
             app.arg(LEDGER_ADDRESS.def().help(LEDGER_ADDRESS_ABOUT))
                 .arg(BATCH_SIZE_OPT.def().help(
-                    "Optional batch size which determines how many txs to \
+                    "Optional batch size which determines how many blocks to \
                      fetch before caching locally. Default is 1.",
                 ))
                 .arg(BLOCK_HEIGHT_OPT.def().help(
-                    "Option block height to sync up to. Default is latest.",
-                ))
-                .arg(SPENDING_KEYS.def().help(
-                    "List of new spending keys with which to check note \
-                     ownership. These will be added to the shielded context.",
-                ))
-                .arg(VIEWING_KEYS.def().help(
-                    "List of new viewing keys with which to check note \
-                     ownership. These will be added to the shielded context.",
+                    "Option block height to sync up to. Default is latest.",
+                ))
+                .arg(SPENDING_KEYS.def().help(
+                    "List of new spending keys with which to check note \
+                     ownership. These will be added to the shielded context.",
+                ))
+                .arg(VIEWING_KEYS.def().help(
+                    "List of new viewing keys with which to check note \
+                     ownership. These will be added to the shielded context.",
+                ))
+                .arg(MASP_TXS_FILE_OPT.def().help(
+                    "Optional file of MASP transactions indexed by the \
+                     shielded-index command, to sync from instead of the node.",
+                ))
+        }
+    }
+
+    impl Args for ShieldedIndex<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let ledger_address = LEDGER_ADDRESS.parse(matches);
+            let masp_txs_file = MASP_TXS_FILE.parse(matches);
+            let last_query_height = BLOCK_HEIGHT_OPT.parse(matches);
+            Self {
+                ledger_address,
+                masp_txs_file,
+                last_query_height,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(LEDGER_ADDRESS.def().help(LEDGER_ADDRESS_ABOUT))
+                .arg(MASP_TXS_FILE.def().help(
+                    "The file of the indexed MASP transactions. It's created \
+                     if it doesn't exist.",
+                ))
+                .arg(BLOCK_HEIGHT_OPT.def().help(
+                    "Option block height to index up to. Default is latest.",
                 ))
         }
     }
 
+    impl Args for ShieldedContexts {
+        fn parse(matches: &ArgMatches) -> Self {
+            let merge = MERGE_CONTEXT.parse(matches);
+            let delete = DELETE_CONTEXT.parse(matches);
+            Self { merge, delete }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(MERGE_CONTEXT.def().help(
+                "The name of a shielded context to merge into the selected \
+                 one. Both contexts must be synced to the same height.",
+            ))
+            .arg(
+                DELETE_CONTEXT
+                    .def()
+                    .help("The name of a shielded context to delete.")
+                    .conflicts_with(MERGE_CONTEXT.name),
+            )
+        }
+    }
+
     impl CliToSdk<ShieldedSync<SdkTypes>> for ShieldedSync<CliTypes> {
         fn to_sdk(self, ctx: &mut Context) -> ShieldedSync<SdkTypes> {
             let chain_ctx = ctx.borrow_mut_chain_or_exit();
@@ -5771,6 +7058,17 @@ pub mod args {
                     .iter()
                     .map(|vk| chain_ctx.get_cached(vk))
                     .collect(),
+                masp_txs_file: self.masp_txs_file,
+            }
+        }
+    }
+
+    impl CliToSdk<ShieldedIndex<SdkTypes>> for ShieldedIndex<CliTypes> {
+        fn to_sdk(self, _ctx: &mut Context) -> ShieldedIndex<SdkTypes> {
+            ShieldedIndex {
+                ledger_address: self.ledger_address,
+                masp_txs_file: self.masp_txs_file,
+                last_query_height: self.last_query_height,
             }
         }
     }
@@ -5893,6 +7191,52 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<QueryValidatorHistory<SdkTypes>>
+        for QueryValidatorHistory<CliTypes>
+    {
+        fn to_sdk(self, ctx: &mut Context) -> QueryValidatorHistory<SdkTypes> {
+            QueryValidatorHistory::<SdkTypes> {
+                query: self.query.to_sdk(ctx),
+                validator: ctx.borrow_chain_or_exit().get(&self.validator),
+                start_epoch: self.start_epoch,
+                end_epoch: self.end_epoch,
+            }
+        }
+    }
+
+    impl Args for QueryValidatorHistory<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let start_epoch = START_EPOCH.parse(matches);
+            let end_epoch = END_EPOCH.parse(matches);
+            Self {
+                query,
+                validator,
+                start_epoch,
+                end_epoch,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>()
+                .arg(
+                    VALIDATOR.def().help(
+                        "The validator's address whose history to query.",
+                    ),
+                )
+                .arg(
+                    START_EPOCH
+                        .def()
+                        .help("The first epoch of the range to query."),
+                )
+                .arg(END_EPOCH.def().help(
+                    "The last epoch of the range to query (corresponding to \
+                     the last committed block, if not specified).",
+                ))
+        }
+    }
+
     impl CliToSdk<QuerySlashes<SdkTypes>> for QuerySlashes<CliTypes> {
         fn to_sdk(self, ctx: &mut Context) -> QuerySlashes<SdkTypes> {
             QuerySlashes::<SdkTypes> {
@@ -5956,6 +7300,41 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<QueryRewardsProjection<SdkTypes>>
+        for QueryRewardsProjection<CliTypes>
+    {
+        fn to_sdk(self, ctx: &mut Context) -> QueryRewardsProjection<SdkTypes> {
+            QueryRewardsProjection::<SdkTypes> {
+                query: self.query.to_sdk(ctx),
+                validator: ctx.borrow_chain_or_exit().get(&self.validator),
+                amount: self.amount,
+            }
+        }
+    }
+
+    impl Args for QueryRewardsProjection<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            Self {
+                query,
+                validator,
+                amount,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>()
+                .arg(
+                    VALIDATOR
+                        .def()
+                        .help("Validator address for the rewards projection."),
+                )
+                .arg(AMOUNT.def().help("Amount of tokens to bond."))
+        }
+    }
+
     impl Args for QueryDelegations<CliTypes> {
         fn parse(matches: &ArgMatches) -> Self {
             let query = Query::parse(matches);
@@ -5981,6 +7360,138 @@ pub mod args {
         }
     }
 
+    impl Args for QueryPendingUnbonds<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let owner = OWNER.parse(matches);
+            Self { query, owner }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>().arg(OWNER.def().help(
+                "The address of the owner of the unbonds and redelegations.",
+            ))
+        }
+    }
+
+    impl CliToSdk<QueryPendingUnbonds<SdkTypes>>
+        for QueryPendingUnbonds<CliTypes>
+    {
+        fn to_sdk(self, ctx: &mut Context) -> QueryPendingUnbonds<SdkTypes> {
+            QueryPendingUnbonds::<SdkTypes> {
+                query: self.query.to_sdk(ctx),
+                owner: ctx.borrow_chain_or_exit().get(&self.owner),
+            }
+        }
+    }
+
+    impl Args for QuerySlashReport<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let owner = OWNER.parse(matches);
+            Self { query, owner }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>().arg(
+                OWNER
+                    .def()
+                    .help("The address of the owner of the bonds and unbonds."),
+            )
+        }
+    }
+
+    impl CliToSdk<QuerySlashReport<SdkTypes>> for QuerySlashReport<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> QuerySlashReport<SdkTypes> {
+            QuerySlashReport::<SdkTypes> {
+                query: self.query.to_sdk(ctx),
+                owner: ctx.borrow_chain_or_exit().get(&self.owner),
+            }
+        }
+    }
+
+    impl Args for SimulateValidatorSets<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let epochs = EPOCHS.parse(matches);
+            let max_validator_slots = MAX_VALIDATOR_SLOTS.parse(matches);
+            let validator_stake_threshold =
+                VALIDATOR_STAKE_THRESHOLD.parse(matches).map(|amount| {
+                    amount
+                        .canonical()
+                        .increase_precision(NATIVE_MAX_DECIMAL_PLACES.into())
+                        .unwrap_or_else(|e| {
+                            println!(
+                                "Could not parse validator stake threshold: \
+                                 {:?}",
+                                e
+                            );
+                            safe_exit(1);
+                        })
+                        .amount()
+                });
+            let liveness_threshold = LIVENESS_THRESHOLD.parse(matches);
+            let input = INPUT_OPT.parse(matches);
+            let output = OUTPUT.parse(matches);
+            Self {
+                query,
+                epochs,
+                max_validator_slots,
+                validator_stake_threshold,
+                liveness_threshold,
+                input,
+                output,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>()
+                .arg(EPOCHS.def().help(
+                    "The number of epochs to simulate, starting from the \
+                     current one.",
+                ))
+                .arg(MAX_VALIDATOR_SLOTS.def().help(
+                    "The simulated maximum number of consensus validators. \
+                     Defaults to the current parameter.",
+                ))
+                .arg(VALIDATOR_STAKE_THRESHOLD.def().help(
+                    "The simulated minimum stake of the consensus and \
+                     below-capacity validators. Defaults to the current \
+                     parameter.",
+                ))
+                .arg(LIVENESS_THRESHOLD.def().help(
+                    "The simulated minimum fraction of the blocks in the \
+                     liveness window that the consensus validators have to \
+                     vote on to not be jailed. Defaults to the current \
+                     parameter.",
+                ))
+                .arg(INPUT_OPT.def().help(
+                    "A JSON export of a validator set snapshot to simulate \
+                     from, instead of the current validators of the node.",
+                ))
+                .arg(OUTPUT.def().help(
+                    "The file to export the JSON validator set snapshot that \
+                     the simulation starts from to.",
+                ))
+        }
+    }
+
+    impl CliToSdk<SimulateValidatorSets<SdkTypes>>
+        for SimulateValidatorSets<CliTypes>
+    {
+        fn to_sdk(self, ctx: &mut Context) -> SimulateValidatorSets<SdkTypes> {
+            SimulateValidatorSets::<SdkTypes> {
+                query: self.query.to_sdk(ctx),
+                epochs: self.epochs,
+                max_validator_slots: self.max_validator_slots,
+                validator_stake_threshold: self.validator_stake_threshold,
+                liveness_threshold: self.liveness_threshold,
+                input: self.input,
+                output: self.output,
+            }
+        }
+    }
+
     impl Args for QueryFindValidator<CliTypes> {
         fn parse(matches: &ArgMatches) -> Self {
             let query = Query::parse(matches);
@@ -6102,6 +7613,7 @@ pub mod args {
                 wrapper_fee_payer: self.wrapper_fee_payer.map(|x| ctx.get(&x)),
                 memo: self.memo,
                 use_device: self.use_device,
+                note_selection: self.note_selection,
             }
         }
     }
@@ -6220,6 +7732,11 @@ pub mod args {
                     .def()
                     .help("Attach a plaintext memo to the transaction."),
             )
+            .arg(NOTE_SELECTION.def().help(
+                "The strategy to select the notes that fund a shielded \
+                 transfer: oldest-first, fewest-notes, smallest-first, random \
+                 or avoid-conversions.",
+            ))
         }
 
         fn parse(matches: &ArgMatches) -> Self {
@@ -6248,6 +7765,7 @@ pub mod args {
             let wrapper_fee_payer = FEE_PAYER_OPT.parse(matches);
             let output_folder = OUTPUT_FOLDER_PATH.parse(matches);
             let use_device = USE_DEVICE.parse(matches);
+            let note_selection = NOTE_SELECTION.parse(matches);
             Self {
                 dry_run,
                 dry_run_wrapper,
@@ -6272,6 +7790,7 @@ pub mod args {
                 output_folder,
                 memo,
                 use_device,
+                note_selection,
             }
         }
     }
@@ -6357,6 +7876,10 @@ pub mod args {
                 alias_force: self.alias_force,
                 viewing_key,
                 pin: self.pin,
+                amount: self.amount,
+                token: self.token,
+                memo: self.memo,
+                expiry: self.expiry,
             }
         }
     }
@@ -6367,11 +7890,19 @@ pub mod args {
             let alias_force = ALIAS_FORCE.parse(matches);
             let viewing_key = VIEWING_KEY.parse(matches);
             let pin = PIN.parse(matches);
+            let amount = AMOUNT_OPT.parse(matches);
+            let token = TOKEN_STR_OPT.parse(matches);
+            let memo = MEMO_OPT.parse(matches);
+            let expiry = EXPIRY_OPT.parse(matches);
             Self {
                 alias,
                 alias_force,
                 viewing_key,
                 pin,
+                amount,
+                token,
+                memo,
+                expiry,
             }
         }
 
@@ -6389,6 +7920,22 @@ pub mod args {
                 "Require that the single transaction to this address be \
                  pinned.",
             ))
+            .arg(AMOUNT_OPT.def().help(
+                "Print a payment request URI for the address that requests \
+                 this amount.",
+            ))
+            .arg(TOKEN_STR_OPT.def().help(
+                "Print a payment request URI for the address that requests \
+                 this token.",
+            ))
+            .arg(MEMO_OPT.def().help(
+                "Print a payment request URI for the address that requests \
+                 this memo.",
+            ))
+            .arg(EXPIRY_OPT.def().help(
+                "Print a payment request URI for the address that expires at \
+                 this time.",
+            ))
         }
     }
 
@@ -6405,6 +7952,7 @@ pub mod args {
                 HD_ALLOW_NON_COMPLIANT_DERIVATION_PATH.parse(matches);
             let prompt_bip39_passphrase =
                 HD_PROMPT_BIP39_PASSPHRASE.parse(matches);
+            let birthday = BIRTHDAY_OPT.parse(matches);
             Self {
                 scheme,
                 shielded,
@@ -6415,6 +7963,7 @@ pub mod args {
                 derivation_path,
                 allow_non_compliant,
                 prompt_bip39_passphrase,
+                birthday,
             }
         }
 
@@ -6472,6 +8021,11 @@ pub mod args {
                     "Use an additional passphrase for HD-key generation.",
                 ),
             )
+            .arg(BIRTHDAY_OPT.def().requires(SHIELDED.name).help(
+                "The height of the first block that may contain notes of the \
+                 shielded key. Blocks before it are skipped when syncing the \
+                 key.",
+            ))
         }
     }
 
@@ -6488,6 +8042,8 @@ pub mod args {
                 HD_ALLOW_NON_COMPLIANT_DERIVATION_PATH.parse(matches);
             let prompt_bip39_passphrase =
                 HD_PROMPT_BIP39_PASSPHRASE.parse(matches);
+            let birthday = BIRTHDAY_OPT.parse(matches);
+            let ledger_address = LEDGER_ADDRESS.parse(matches);
             Self {
                 scheme,
                 shielded,
@@ -6498,6 +8054,8 @@ pub mod args {
                 derivation_path,
                 allow_non_compliant,
                 prompt_bip39_passphrase,
+                birthday,
+                ledger_address,
             }
         }
 
@@ -6558,6 +8116,17 @@ pub mod args {
                     "Use an additional passphrase for HD-key generation.",
                 ),
             )
+            .arg(BIRTHDAY_OPT.def().requires(SHIELDED.name).help(
+                "The height of the first block that may contain notes of the \
+                 shielded key. Blocks before it are skipped when syncing the \
+                 key. Defaults to the last committed block height of the \
+                 node.",
+            ))
+            .arg(LEDGER_ADDRESS.def().help(
+                "Address of a ledger node as \"{scheme}://{host}:{port}\", \
+                 queried for the default birthday of a shielded key. If the \
+                 scheme is not supplied, it is assumed to be TCP.",
+            ))
         }
     }
 
@@ -6616,6 +8185,7 @@ pub mod args {
             let public_key = RAW_PUBLIC_KEY_OPT.parse(matches);
             let public_key_hash = RAW_PUBLIC_KEY_HASH_OPT.parse(matches);
             let payment_address = RAW_PAYMENT_ADDRESS_OPT.parse(matches);
+            let search = SEARCH_OPT.parse(matches);
             let keys_only = LIST_FIND_KEYS_ONLY.parse(matches);
             let addresses_only = LIST_FIND_ADDRESSES_ONLY.parse(matches);
             let decrypt = DECRYPT.parse(matches);
@@ -6626,6 +8196,7 @@ pub mod args {
                 public_key,
                 public_key_hash,
                 payment_address,
+                search,
                 keys_only,
                 addresses_only,
                 decrypt,
@@ -6655,6 +8226,10 @@ pub mod args {
             .arg(RAW_PAYMENT_ADDRESS_OPT.def().help(
                 "The bech32m encoded string of a shielded payment address.",
             ))
+            .arg(SEARCH_OPT.def().help(
+                "Search the address book for contacts whose alias, address, \
+                 tags or notes contain the given text.",
+            ))
             .group(
                 ArgGroup::new("addr_find_args")
                     .args([
@@ -6663,6 +8238,7 @@ pub mod args {
                         RAW_PUBLIC_KEY_OPT.name,
                         RAW_PUBLIC_KEY_HASH_OPT.name,
                         RAW_PAYMENT_ADDRESS_OPT.name,
+                        SEARCH_OPT.name,
                     ])
                     .required(true),
             )
@@ -6691,11 +8267,13 @@ pub mod args {
             let alias_force = ALIAS_FORCE.parse(matches);
             let value = VALUE.parse(matches);
             let unsafe_dont_encrypt = UNSAFE_DONT_ENCRYPT.parse(matches);
+            let birthday = BIRTHDAY_OPT.parse(matches);
             Self {
                 alias,
                 alias_force,
                 value,
                 unsafe_dont_encrypt,
+                birthday,
             }
         }
 
@@ -6718,6 +8296,139 @@ pub mod args {
                 "UNSAFE: Do not encrypt the added keys. Do not use this for \
                  keys used in a live network.",
             ))
+            .arg(BIRTHDAY_OPT.def().help(
+                "The height of the first block that may contain notes of the \
+                 shielded key. Blocks before it are skipped when syncing the \
+                 key.",
+            ))
+        }
+    }
+
+    impl Args for ContactSet {
+        fn parse(matches: &ArgMatches) -> Self {
+            let alias = ALIAS.parse(matches);
+            let note = NOTE_OPT.parse(matches);
+            let tags = TAGS.parse(matches);
+            let default_token = DEFAULT_TOKEN_OPT.parse(matches);
+            let default_memo = DEFAULT_MEMO_OPT.parse(matches);
+            let payment_address = RAW_PAYMENT_ADDRESS_OPT.parse(matches);
+            Self {
+                alias,
+                note,
+                tags,
+                default_token,
+                default_memo,
+                payment_address,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(ALIAS.def().help(
+                "The alias of a known address or payment address to describe.",
+            ))
+            .arg(NOTE_OPT.def().help("Free-form notes about the contact."))
+            .arg(TAGS.def().help(
+                "Comma-separated tags of the contact, e.g. exchange, \
+                 validator or personal.",
+            ))
+            .arg(DEFAULT_TOKEN_OPT.def().help(
+                "The alias or address of the token sent to the contact by \
+                 default.",
+            ))
+            .arg(DEFAULT_MEMO_OPT.def().help(
+                "The memo attached to transfers to the contact by default.",
+            ))
+            .arg(RAW_PAYMENT_ADDRESS_OPT.def().help(
+                "The bech32m encoded string of the contact's shielded payment \
+                 address.",
+            ))
+        }
+    }
+
+    impl Args for AddressBookExport {
+        fn parse(matches: &ArgMatches) -> Self {
+            let format = ADDRESS_BOOK_FORMAT.parse(matches);
+            let output = OUTPUT.parse(matches);
+            Self { format, output }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(
+                ADDRESS_BOOK_FORMAT
+                    .def()
+                    .help("The format of the address book: json or csv."),
+            )
+            .arg(OUTPUT.def().help(
+                "The file to write the address book to. It is printed if not \
+                 given.",
+            ))
+        }
+    }
+
+    impl Args for AddressBookImport {
+        fn parse(matches: &ArgMatches) -> Self {
+            let file_path = FILE_PATH.parse(matches);
+            let format = ADDRESS_BOOK_FORMAT.parse(matches);
+            let alias_force = ALIAS_FORCE.parse(matches);
+            Self {
+                file_path,
+                format,
+                alias_force,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(FILE_PATH.def().help(
+                "Path to the file containing the address book to import.",
+            ))
+            .arg(
+                ADDRESS_BOOK_FORMAT
+                    .def()
+                    .help("The format of the address book: json or csv."),
+            )
+            .arg(
+                ALIAS_FORCE
+                    .def()
+                    .help("Override the existing aliases of imported entries."),
+            )
+        }
+    }
+
+    impl Args for AgentRun {
+        fn parse(matches: &ArgMatches) -> Self {
+            let socket = SOCKET_OPT.parse(matches);
+            let lifetime = LIFETIME.parse(matches);
+            Self { socket, lifetime }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(SOCKET_OPT.def().help(
+                "The path of the Unix socket to listen on. Defaults to \
+                 \"wallet-agent.sock\" in the base directory.",
+            ))
+            .arg(LIFETIME.def().help(
+                "For how long the keys stay unlocked by default, e.g. \"30m\" \
+                 or \"2h\". Defaults to one hour.",
+            ))
+        }
+    }
+
+    impl Args for AgentUnlock {
+        fn parse(matches: &ArgMatches) -> Self {
+            let aliases = ALIAS_MANY.parse(matches);
+            let lifetime = LIFETIME_OPT.parse(matches);
+            Self { aliases, lifetime }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(ALIAS_MANY.def().help(
+                "The aliases of the keys or spending keys to unlock, \
+                 separated by commas.",
+            ))
+            .arg(LIFETIME_OPT.def().help(
+                "For how long the keys stay unlocked, e.g. \"30m\". Defaults \
+                 to the agent's lifetime.",
+            ))
         }
     }
 
@@ -6766,11 +8477,13 @@ pub mod args {
             let alias = ALIAS.parse(matches);
             let alias_force = ALIAS_FORCE.parse(matches);
             let unsafe_dont_encrypt = UNSAFE_DONT_ENCRYPT.parse(matches);
+            let birthday = BIRTHDAY_OPT.parse(matches);
             Self {
                 alias,
                 alias_force,
                 file_path,
                 unsafe_dont_encrypt,
+                birthday,
             }
         }
 
@@ -6788,6 +8501,11 @@ pub mod args {
                 "UNSAFE: Do not encrypt the imported keys. Do not use this \
                  for keys used in a live network.",
             ))
+            .arg(BIRTHDAY_OPT.def().help(
+                "The height of the first block that may contain notes of the \
+                 shielded key. Blocks before it are skipped when syncing the \
+                 key.",
+            ))
         }
     }
 
@@ -6863,6 +8581,26 @@ pub mod args {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct MaspParams {
+        /// The directory of a bundle of parameter files to install
+        pub install: Option<PathBuf>,
+    }
+
+    impl Args for MaspParams {
+        fn parse(matches: &ArgMatches) -> Self {
+            let install = INSTALL_PARAMS.parse(matches);
+            Self { install }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(INSTALL_PARAMS.def().help(
+                "The directory of a bundle of MASP parameter files to verify \
+                 and install into the MASP parameters directory.",
+            ))
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct FetchWasms {
         pub chain_id: ChainId,
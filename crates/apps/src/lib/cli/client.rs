@@ -1,6 +1,8 @@
 use color_eyre::eyre::Result;
 use masp_primitives::zip32::ExtendedFullViewingKey;
 use namada::io::Io;
+use namada_sdk::masp::fs::DEFAULT_CONTEXT_NAME;
+use namada_sdk::masp::{LedgerMaspTxSource, MaspTxIndex};
 use namada_sdk::{Namada, NamadaImpl};
 
 use crate::cli;
@@ -63,6 +65,18 @@ impl CliApi {
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_transfer(&namada, args).await?;
                     }
+                    Sub::TxConsolidateNotes(TxConsolidateNotes(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.tx.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_consolidate_notes(&namada, args).await?;
+                    }
                     Sub::TxIbcTransfer(TxIbcTransfer(args)) => {
                         let chain_ctx = ctx.borrow_mut_chain_or_exit();
                         let ledger_address =
@@ -247,6 +261,42 @@ impl CliApi {
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_claim_rewards(&namada, args).await?;
                     }
+                    Sub::Restake(Restake(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.tx.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_restake(&namada, args).await?;
+                    }
+                    Sub::LiquidBond(LiquidBond(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.tx.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_liquid_bond(&namada, args).await?;
+                    }
+                    Sub::RedeemShares(RedeemShares(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.tx.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_redeem_shares(&namada, args).await?;
+                    }
                     Sub::Redelegate(Redelegate(args)) => {
                         let chain_ctx = ctx.borrow_mut_chain_or_exit();
                         let ledger_address =
@@ -323,14 +373,19 @@ impl CliApi {
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let chain_ctx = ctx.take_chain_or_exit();
-                        let vks = chain_ctx
-                            .wallet
+                        let wallet = &chain_ctx.wallet;
+                        // Wallet keys skip the blocks before their birthday
+                        let vks = wallet
                             .get_viewing_keys()
-                            .values()
-                            .copied()
-                            .map(|vk| ExtendedFullViewingKey::from(vk).fvk.vk)
+                            .into_iter()
+                            .map(|(alias, vk)| {
+                                (
+                                    ExtendedFullViewingKey::from(vk).fvk.vk,
+                                    wallet.find_birthday(alias),
+                                )
+                            })
                             .chain(args.viewing_keys.into_iter().map(|vk| {
-                                ExtendedFullViewingKey::from(vk).fvk.vk
+                                (ExtendedFullViewingKey::from(vk).fvk.vk, None)
                             }))
                             .collect::<Vec<_>>();
                         let sks = args
@@ -338,14 +393,62 @@ impl CliApi {
                             .into_iter()
                             .map(|sk| sk.into())
                             .collect::<Vec<_>>();
-                        crate::client::masp::syncing(
+                        match &args.masp_txs_file {
+                            Some(masp_txs_file) => {
+                                let source =
+                                    MaspTxIndex::read_file(masp_txs_file)?;
+                                crate::client::masp::syncing(
+                                    chain_ctx.shielded,
+                                    &source,
+                                    &io,
+                                    args.batch_size,
+                                    args.last_query_height,
+                                    &sks,
+                                    &vks,
+                                )
+                                .await?;
+                            }
+                            None => {
+                                crate::client::masp::syncing(
+                                    chain_ctx.shielded,
+                                    &LedgerMaspTxSource::new(&client),
+                                    &io,
+                                    args.batch_size,
+                                    args.last_query_height,
+                                    &sks,
+                                    &vks,
+                                )
+                                .await?;
+                            }
+                        }
+                    }
+                    Sub::ShieldedIndex(ShieldedIndex(args)) => {
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&args.ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        crate::client::masp::indexing(&client, &io, args)
+                            .await?;
+                    }
+                    Sub::ShieldedContexts(ShieldedContexts(args)) => {
+                        let base_dir = ctx.global_args.base_dir.clone();
+                        let selected = ctx
+                            .global_args
+                            .shielded_context
+                            .clone()
+                            .unwrap_or_else(|| {
+                                DEFAULT_CONTEXT_NAME.to_string()
+                            });
+                        let chain_ctx = ctx.take_chain_or_exit();
+                        let chain_dir = base_dir
+                            .join(chain_ctx.config.ledger.chain_id.as_str());
+                        crate::client::masp::managing_contexts(
                             chain_ctx.shielded,
-                            &client,
                             &io,
-                            args.batch_size,
-                            args.last_query_height,
-                            &sks,
-                            &vks,
+                            &chain_dir,
+                            &selected,
+                            args,
                         )
                         .await?;
                     }
@@ -472,6 +575,18 @@ impl CliApi {
                         rpc::query_and_print_validator_state(&namada, args)
                             .await;
                     }
+                    Sub::QueryConsensusKey(QueryConsensusKey(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.query.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::query_and_print_consensus_key(&namada, args).await;
+                    }
                     Sub::QueryTransfers(QueryTransfers(args)) => {
                         let chain_ctx = ctx.borrow_mut_chain_or_exit();
                         let ledger_address =
@@ -484,6 +599,18 @@ impl CliApi {
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_transfers(&namada, args).await;
                     }
+                    Sub::ExportShieldedHistory(ExportShieldedHistory(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.query.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::export_shielded_history(&namada, args).await;
+                    }
                     Sub::QueryConversions(QueryConversions(args)) => {
                         let chain_ctx = ctx.borrow_mut_chain_or_exit();
                         let ledger_address =
@@ -507,6 +634,18 @@ impl CliApi {
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_masp_reward_tokens(&namada).await;
                     }
+                    Sub::QueryMaspRewards(QueryMaspRewards(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.query.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::query_masp_rewards(&namada, args).await;
+                    }
                     Sub::QueryBlock(QueryBlock(args)) => {
                         let chain_ctx = ctx.borrow_mut_chain_or_exit();
                         let ledger_address =
@@ -581,6 +720,19 @@ impl CliApi {
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_and_print_metadata(&namada, args).await;
                     }
+                    Sub::QueryValidatorHistory(QueryValidatorHistory(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.query.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::query_and_print_validator_history(&namada, args)
+                            .await;
+                    }
                     Sub::QuerySlashes(QuerySlashes(args)) => {
                         let chain_ctx = ctx.borrow_mut_chain_or_exit();
                         let ledger_address =
@@ -605,6 +757,21 @@ impl CliApi {
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_and_print_rewards(&namada, args).await;
                     }
+                    Sub::QueryRewardsProjection(QueryRewardsProjection(
+                        args,
+                    )) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.query.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::query_and_print_rewards_projection(&namada, args)
+                            .await;
+                    }
                     Sub::QueryDelegations(QueryDelegations(args)) => {
                         let chain_ctx = ctx.borrow_mut_chain_or_exit();
                         let ledger_address =
@@ -617,6 +784,46 @@ impl CliApi {
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_delegations(&namada, args).await;
                     }
+                    Sub::QueryPendingUnbonds(QueryPendingUnbonds(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.query.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::query_and_print_pending_unbonds(&namada, args)
+                            .await;
+                    }
+                    Sub::QuerySlashReport(QuerySlashReport(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.query.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::query_and_print_slash_report(&namada, args).await;
+                    }
+                    Sub::SimulateValidatorSets(SimulateValidatorSets(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.query.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        // The node is not needed to simulate from an export
+                        if args.input.is_none() {
+                            client.wait_until_node_is_synced(&io).await?;
+                        }
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::simulate_validator_sets(&namada, args).await;
+                    }
                     Sub::QueryFindValidator(QueryFindValidator(args)) => {
                         let chain_ctx = ctx.borrow_mut_chain_or_exit();
                         let ledger_address =
@@ -807,6 +1014,7 @@ impl CliApi {
                 Utils::SignGenesisTxs(SignGenesisTxs(args)) => {
                     utils::sign_genesis_tx(global_args, args).await
                 }
+                Utils::MaspParams(MaspParams(args)) => utils::masp_params(args),
             },
         }
         Ok(())
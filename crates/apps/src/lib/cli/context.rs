@@ -166,10 +166,21 @@ impl Context {
                         }
                     }
                 }
+                let shielded = match &global_args.shielded_context {
+                    Some(name) => FsShieldedUtils::new_named(&chain_dir, name)
+                        .unwrap_or_else(|err| {
+                            eprintln!(
+                                "Failed to open the shielded context {name}: \
+                                 {err}"
+                            );
+                            utils::safe_exit(1)
+                        }),
+                    None => FsShieldedUtils::new(chain_dir),
+                };
                 Some(ChainContext {
                     wallet,
                     config,
-                    shielded: FsShieldedUtils::new(chain_dir),
+                    shielded,
                     native_token,
                 })
             }
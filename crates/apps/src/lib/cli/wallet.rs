@@ -4,7 +4,7 @@ use std::fs::File;
 use std::io::{self, Write};
 use std::str::FromStr;
 
-use borsh::BorshDeserialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 use borsh_ext::BorshSerializeExt;
 use color_eyre::eyre::Result;
 use itertools::sorted;
@@ -14,17 +14,26 @@ use ledger_transport_hid::TransportNativeHID;
 use masp_primitives::zip32::ExtendedFullViewingKey;
 use namada::core::address::{Address, DecodeError};
 use namada::core::key::*;
-use namada::core::masp::{ExtendedSpendingKey, MaspValue, PaymentAddress};
+use namada::core::masp::{
+    ExtendedSpendingKey, MaspValue, PaymentAddress, PaymentRequest,
+};
+use namada::core::storage::BlockHeight;
 use namada::io::Io;
+use namada::tendermint_rpc::HttpClient;
 use namada_sdk::masp::find_valid_diversifier;
+use namada_sdk::rpc;
+use namada_sdk::wallet::address_book::Contact;
+#[cfg(unix)]
+use namada_sdk::wallet::agent;
 use namada_sdk::wallet::{
-    DecryptionError, DerivationPath, DerivationPathError, FindKeyError, Wallet,
+    DecryptionError, DerivationPath, DerivationPathError, FindKeyError,
+    StoredKeypair, Wallet, WalletIo,
 };
 use namada_sdk::{display_line, edisplay_line};
 use rand_core::OsRng;
 
 use crate::cli;
-use crate::cli::api::CliApi;
+use crate::cli::api::{CliApi, CliClient};
 use crate::cli::args::CliToSdk;
 use crate::cli::{args, cmds, Context};
 use crate::client::utils::PRE_GENESIS_DIR;
@@ -41,7 +50,7 @@ impl CliApi {
     ) -> Result<()> {
         match cmd {
             cmds::NamadaWallet::KeyGen(cmds::WalletGen(args)) => {
-                key_gen(ctx, io, args)
+                key_gen(ctx, io, args).await
             }
             cmds::NamadaWallet::KeyDerive(cmds::WalletDerive(args)) => {
                 key_derive(ctx, io, args).await
@@ -67,6 +76,16 @@ impl CliApi {
             cmds::NamadaWallet::KeyAddrRemove(
                 cmds::WalletRemoveKeyAddress(args),
             ) => key_address_remove(ctx, io, args),
+            cmds::NamadaWallet::ContactSet(cmds::WalletSetContact(args)) => {
+                contact_set(ctx, io, args)
+            }
+            cmds::NamadaWallet::AddressBookExport(
+                cmds::WalletExportAddressBook(args),
+            ) => address_book_export(ctx, io, args),
+            cmds::NamadaWallet::AddressBookImport(
+                cmds::WalletImportAddressBook(args),
+            ) => address_book_import(ctx, io, args),
+            cmds::NamadaWallet::Agent(sub) => wallet_agent(ctx, io, sub),
             cmds::NamadaWallet::PayAddrGen(cmds::WalletGenPaymentAddress(
                 args,
             )) => {
@@ -112,6 +131,10 @@ fn shielded_keys_list(
             display_line!(io, &mut w_lock; "  Alias \"{}\" ({}):", alias, encrypted_status).unwrap();
             // Always print the corresponding viewing key
             display_line!(io, &mut w_lock; "    Viewing Key: {}", key).unwrap();
+            if let Some(birthday) = wallet.find_birthday(&alias) {
+                display_line!(io, &mut w_lock; "    Birthday: {}", birthday)
+                    .unwrap();
+            }
             // A subset of viewing keys will have corresponding spending keys.
             // Print those too if they are available and requested.
             if let Some(spending_key) = spending_key_opt {
@@ -194,6 +217,7 @@ fn shielded_key_derive(
         allow_non_compliant,
         prompt_bip39_passphrase,
         use_device,
+        birthday,
         ..
     }: args::KeyDerive,
 ) {
@@ -233,6 +257,9 @@ fn shielded_key_derive(
         display_line!(io, "No changes are persisted. Exiting.");
         cli::safe_exit(1)
     };
+    if let Some(birthday) = birthday {
+        set_birthday(io, &mut wallet, &alias, birthday);
+    }
     wallet
         .save()
         .unwrap_or_else(|err| edisplay_line!(io, "{}", err));
@@ -243,8 +270,9 @@ fn shielded_key_derive(
     );
 }
 
-/// Generate a spending key.
-fn shielded_key_gen(
+/// Generate a spending key. Unless a birthday is given, the last committed
+/// block height of the node becomes the birthday of the new key.
+async fn shielded_key_gen(
     ctx: Context,
     io: &impl Io,
     args::KeyGen {
@@ -255,9 +283,15 @@ fn shielded_key_gen(
         derivation_path,
         allow_non_compliant,
         prompt_bip39_passphrase,
+        birthday,
+        ledger_address,
         ..
     }: args::KeyGen,
 ) {
+    let birthday = match birthday {
+        Some(birthday) => Some(birthday),
+        None => query_birthday(io, &ledger_address).await,
+    };
     let mut wallet = load_wallet(ctx);
     let alias = alias.to_lowercase();
     let password = read_and_confirm_encryption_password(unsafe_dont_encrypt);
@@ -296,6 +330,9 @@ fn shielded_key_gen(
         println!("No changes are persisted. Exiting.");
         cli::safe_exit(1);
     });
+    if let Some(birthday) = birthday {
+        set_birthday(io, &mut wallet, &alias, birthday);
+    }
 
     wallet
         .save()
@@ -316,7 +353,10 @@ fn payment_address_gen(
         alias_force,
         viewing_key,
         pin,
-        ..
+        amount,
+        token,
+        memo,
+        expiry,
     }: args::PayAddressGen,
 ) {
     let mut wallet = load_wallet(ctx);
@@ -327,6 +367,16 @@ fn payment_address_gen(
         .to_payment_address(div)
         .expect("a PaymentAddress");
     let payment_addr = PaymentAddress::from(masp_payment_addr).pinned(pin);
+    let token = token.map(|token| {
+        wallet
+            .find_address(&token)
+            .map(|addr| addr.into_owned())
+            .or_else(|| Address::decode(&token).ok())
+            .unwrap_or_else(|| {
+                edisplay_line!(io, "Unknown token \"{}\".", token);
+                cli::safe_exit(1)
+            })
+    });
     let alias = wallet
         .insert_payment_addr(alias, payment_addr, alias_force)
         .unwrap_or_else(|| {
@@ -340,9 +390,21 @@ fn payment_address_gen(
         payment_addr,
         alias,
     );
+    if amount.is_some() || token.is_some() || memo.is_some() || expiry.is_some()
+    {
+        let request = PaymentRequest {
+            amount,
+            token,
+            memo,
+            expiry,
+            ..PaymentRequest::new(payment_addr)
+        };
+        display_line!(io, "Payment request: {}", request);
+    }
 }
 
-/// Add a viewing key, spending key, or payment address to wallet.
+/// Add a viewing key, spending key, or payment address to wallet. The
+/// birthday height only applies to keys.
 fn shielded_key_address_add(
     ctx: Context,
     io: &impl Io,
@@ -350,6 +412,7 @@ fn shielded_key_address_add(
     alias_force: bool,
     masp_value: MaspValue,
     unsafe_dont_encrypt: bool,
+    birthday: Option<BlockHeight>,
 ) {
     let alias = alias.to_lowercase();
     let mut wallet = load_wallet(ctx);
@@ -390,6 +453,9 @@ fn shielded_key_address_add(
             (alias, "payment address")
         }
     };
+    if let Some(birthday) = birthday {
+        set_birthday(io, &mut wallet, &alias, birthday);
+    }
     wallet.save().unwrap_or_else(|err| eprintln!("{}", err));
     display_line!(
         io,
@@ -399,8 +465,55 @@ fn shielded_key_address_add(
     );
 }
 
+/// Query the last committed block height of the node, to be used as the
+/// birthday of a newly generated shielded key. Without a birthday, the key is
+/// synced from the first block.
+async fn query_birthday(
+    io: &impl Io,
+    ledger_address: &tendermint_rpc::Url,
+) -> Option<BlockHeight> {
+    let client = HttpClient::from_tendermint_address(ledger_address);
+    match rpc::query_block(&client).await {
+        Ok(last_block) => {
+            let birthday = last_block.map(|block| block.height);
+            if let Some(birthday) = birthday {
+                display_line!(io, "Using birthday height {}", birthday);
+            }
+            birthday
+        }
+        Err(err) => {
+            edisplay_line!(
+                io,
+                "Failed to query the last block height from {}: {}. The key \
+                 has no birthday and will be synced from the first block.",
+                ledger_address,
+                err
+            );
+            None
+        }
+    }
+}
+
+/// Record the birthday height of a shielded key, telling the user if the
+/// alias doesn't belong to a key and the birthday is dropped.
+fn set_birthday(
+    io: &impl Io,
+    wallet: &mut Wallet<CliWalletUtils>,
+    alias: &str,
+    birthday: BlockHeight,
+) {
+    if !wallet.set_birthday(alias, birthday) {
+        edisplay_line!(
+            io,
+            "The birthday height {} is not recorded, as \"{}\" is not a \
+             viewing or spending key.",
+            birthday,
+            alias
+        );
+    }
+}
+
 /// Decode the derivation path from the given string unless it is "default",
-/// in which case use the default derivation path for the given transparent
 /// scheme.
 pub fn decode_transparent_derivation_path(
     scheme: SchemeType,
@@ -616,11 +729,11 @@ fn transparent_key_and_address_gen(
 }
 
 /// Key generation
-fn key_gen(ctx: Context, io: &impl Io, args_key_gen: args::KeyGen) {
+async fn key_gen(ctx: Context, io: &impl Io, args_key_gen: args::KeyGen) {
     if !args_key_gen.shielded {
         transparent_key_and_address_gen(ctx, io, args_key_gen)
     } else {
-        shielded_key_gen(ctx, io, args_key_gen)
+        shielded_key_gen(ctx, io, args_key_gen).await
     }
 }
 
@@ -696,6 +809,7 @@ fn key_address_find(
         public_key,
         public_key_hash,
         payment_address,
+        search,
         keys_only,
         addresses_only,
         decrypt,
@@ -742,6 +856,52 @@ fn key_address_find(
     } else if payment_address.is_some() {
         // Search alias by MASP payment address
         payment_address_or_alias_find(ctx, io, None, payment_address)
+    } else if let Some(query) = search {
+        // Search the address book
+        contacts_find(ctx, io, query)
+    }
+}
+
+/// List the address book entries matching the search query.
+fn contacts_find(ctx: Context, io: &impl Io, query: String) {
+    let wallet = load_wallet(ctx);
+    let aliases = wallet.search_contacts(&query);
+    if aliases.is_empty() {
+        display_line!(io, "No contacts matching \"{}\" found.", query);
+        return;
+    }
+    let mut w_lock = io::stdout().lock();
+    display_line!(io, &mut w_lock; "Contacts matching \"{}\":", query).unwrap();
+    for alias in aliases {
+        display_line!(io, &mut w_lock; "  \"{}\":", alias).unwrap();
+        if let Some(address) = wallet.find_address(&alias) {
+            display_line!(io, &mut w_lock; "    Address: {}", address).unwrap();
+        }
+        let contact = wallet.find_contact(&alias);
+        let payment_address = wallet
+            .find_payment_addr(&alias)
+            .or_else(|| contact.and_then(|c| c.payment_address.as_ref()));
+        if let Some(payment_address) = payment_address {
+            display_line!(io, &mut w_lock; "    Payment address: {}", payment_address).unwrap();
+        }
+        let Some(contact) = contact else {
+            continue;
+        };
+        if !contact.tags.is_empty() {
+            let tags = contact.tags.iter().cloned().collect::<Vec<_>>();
+            display_line!(io, &mut w_lock; "    Tags: {}", tags.join(", "))
+                .unwrap();
+        }
+        if let Some(token) = &contact.default_token {
+            display_line!(io, &mut w_lock; "    Default token: {}", wallet.lookup_alias(token)).unwrap();
+        }
+        if let Some(memo) = &contact.default_memo {
+            display_line!(io, &mut w_lock; "    Default memo: {}", memo)
+                .unwrap();
+        }
+        if let Some(note) = &contact.note {
+            display_line!(io, &mut w_lock; "    Note: {}", note).unwrap();
+        }
     }
 }
 
@@ -799,6 +959,7 @@ fn add_key_or_address(
     alias_force: bool,
     value: KeyAddrAddValue,
     unsafe_dont_encrypt: bool,
+    birthday: Option<BlockHeight>,
 ) {
     match value {
         KeyAddrAddValue::TranspValue(TransparentValue::TranspSecretKey(sk)) => {
@@ -824,6 +985,7 @@ fn add_key_or_address(
             alias_force,
             masp_value,
             unsafe_dont_encrypt,
+            birthday,
         ),
     }
 }
@@ -837,6 +999,7 @@ fn key_address_add(
         alias_force,
         value,
         unsafe_dont_encrypt,
+        birthday,
         ..
     }: args::KeyAddressAdd,
 ) {
@@ -845,7 +1008,15 @@ fn key_address_add(
         display_line!(io, "No changes are persisted. Exiting.");
         cli::safe_exit(1)
     });
-    add_key_or_address(ctx, io, alias, alias_force, value, unsafe_dont_encrypt)
+    add_key_or_address(
+        ctx,
+        io,
+        alias,
+        alias_force,
+        value,
+        unsafe_dont_encrypt,
+        birthday,
+    )
 }
 
 /// Remove keys and addresses
@@ -863,6 +1034,257 @@ fn key_address_remove(
     display_line!(io, "Successfully removed alias: \"{}\"", alias);
 }
 
+/// Set the address book metadata of an address or a payment address.
+fn contact_set(
+    ctx: Context,
+    io: &impl Io,
+    args::ContactSet {
+        alias,
+        note,
+        tags,
+        default_token,
+        default_memo,
+        payment_address,
+    }: args::ContactSet,
+) {
+    let alias = alias.to_lowercase();
+    let mut wallet = load_wallet(ctx);
+    let default_token = default_token.map(|token| {
+        wallet
+            .find_address(&token)
+            .map(|addr| addr.into_owned())
+            .or_else(|| Address::decode(&token).ok())
+            .unwrap_or_else(|| {
+                edisplay_line!(io, "Unknown token \"{}\".", token);
+                cli::safe_exit(1)
+            })
+    });
+    let mut contact = Contact {
+        note,
+        default_token,
+        default_memo,
+        payment_address,
+        ..Contact::default()
+    };
+    for tag in tags {
+        contact.add_tag(tag);
+    }
+    if !wallet.set_contact(&alias, contact) {
+        edisplay_line!(
+            io,
+            "Alias \"{}\" not found. Add the address or payment address to \
+             the wallet first.",
+            alias
+        );
+        cli::safe_exit(1)
+    }
+    wallet
+        .save()
+        .unwrap_or_else(|err| edisplay_line!(io, "{}", err));
+    display_line!(io, "Successfully updated contact: \"{}\"", alias);
+}
+
+/// Export the address book to a file or print it.
+fn address_book_export(
+    ctx: Context,
+    io: &impl Io,
+    args::AddressBookExport { format, output }: args::AddressBookExport,
+) {
+    let wallet = load_wallet(ctx);
+    let data = wallet.export_address_book(format).unwrap_or_else(|err| {
+        edisplay_line!(io, "{}", err);
+        cli::safe_exit(1)
+    });
+    match output {
+        Some(path) => {
+            std::fs::write(&path, data).unwrap_or_else(|err| {
+                edisplay_line!(io, "{}", err);
+                cli::safe_exit(1)
+            });
+            display_line!(io, "Exported to file {}", path.to_string_lossy());
+        }
+        None => display_line!(io, "{}", data),
+    }
+}
+
+/// Import an address book from a file.
+fn address_book_import(
+    ctx: Context,
+    io: &impl Io,
+    args::AddressBookImport {
+        file_path,
+        format,
+        alias_force,
+    }: args::AddressBookImport,
+) {
+    let data = std::fs::read_to_string(file_path).unwrap_or_else(|err| {
+        edisplay_line!(io, "{}", err);
+        display_line!(io, "No changes are persisted. Exiting.");
+        cli::safe_exit(1)
+    });
+    let mut wallet = load_wallet(ctx);
+    let aliases = wallet
+        .import_address_book(&data, format, alias_force)
+        .unwrap_or_else(|err| {
+            edisplay_line!(io, "{}", err);
+            display_line!(io, "No changes are persisted. Exiting.");
+            cli::safe_exit(1)
+        });
+    wallet
+        .save()
+        .unwrap_or_else(|err| edisplay_line!(io, "{}", err));
+    display_line!(
+        io,
+        "Successfully imported {} address book entries: {}",
+        aliases.len(),
+        aliases.join(", ")
+    );
+}
+
+/// Run the wallet agent or query it.
+#[cfg(unix)]
+fn wallet_agent(ctx: Context, io: &impl Io, cmd: cmds::WalletAgent) {
+    match cmd {
+        cmds::WalletAgent::Run(cmds::WalletAgentRun(args)) => {
+            agent_run(ctx, io, args)
+        }
+        cmds::WalletAgent::Unlock(cmds::WalletAgentUnlock(args)) => {
+            agent_unlock(ctx, io, args)
+        }
+        cmds::WalletAgent::Lock(cmds::WalletAgentLock) => {
+            agent_client(io).lock().unwrap_or_else(|err| {
+                edisplay_line!(io, "{}", err);
+                cli::safe_exit(1)
+            });
+            display_line!(io, "Locked all the keys of the wallet agent.");
+        }
+        cmds::WalletAgent::List(cmds::WalletAgentList) => {
+            let keys = agent_client(io).list().unwrap_or_else(|err| {
+                edisplay_line!(io, "{}", err);
+                cli::safe_exit(1)
+            });
+            if keys.is_empty() {
+                display_line!(io, "The wallet agent holds no unlocked keys.");
+            }
+            for key in keys {
+                display_line!(
+                    io,
+                    "  \"{}\": locked in {}s",
+                    key.alias,
+                    key.expires_in.as_secs()
+                );
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn wallet_agent(_ctx: Context, io: &impl Io, _cmd: cmds::WalletAgent) {
+    edisplay_line!(io, "The wallet agent requires Unix domain sockets.");
+    cli::safe_exit(1)
+}
+
+/// Run the wallet agent in the foreground.
+#[cfg(unix)]
+fn agent_run(
+    ctx: Context,
+    io: &impl Io,
+    args::AgentRun { socket, lifetime }: args::AgentRun,
+) {
+    let socket = socket
+        .unwrap_or_else(|| ctx.global_args.base_dir.join("wallet-agent.sock"));
+    display_line!(
+        io,
+        "Wallet agent listening on {}. To use it, run:\n  export {}={}",
+        socket.to_string_lossy(),
+        agent::AGENT_SOCKET_ENV,
+        socket.to_string_lossy()
+    );
+    if let Err(err) = wallet::agent::run_agent(&socket, lifetime.0) {
+        edisplay_line!(io, "{}", err);
+        cli::safe_exit(1)
+    }
+}
+
+/// Decrypt the given keys and hand them to the wallet agent.
+#[cfg(unix)]
+fn agent_unlock(
+    ctx: Context,
+    io: &impl Io,
+    args::AgentUnlock { aliases, lifetime }: args::AgentUnlock,
+) {
+    let client = agent_client(io);
+    let wallet = load_wallet(ctx);
+    let mut keys = vec![];
+    for alias in aliases {
+        let store = wallet.store();
+        let unlocked = if let Some(stored) = store.find_secret_key(&alias) {
+            decrypt_for_agent(io, &alias, stored)
+        } else if let Some(stored) = store.find_spending_key(&alias) {
+            decrypt_for_agent(io, &alias, stored)
+        } else {
+            edisplay_line!(io, "No key with alias {} found.", alias);
+            cli::safe_exit(1)
+        };
+        match unlocked {
+            Some((encrypted, decrypted)) => keys.push(agent::UnlockedKey {
+                alias,
+                encrypted,
+                decrypted: decrypted.to_string(),
+            }),
+            None => display_line!(
+                io,
+                "The key {} is not encrypted, skipping it.",
+                alias
+            ),
+        }
+    }
+    let count = keys.len();
+    client
+        .unlock(keys, lifetime.map(|lifetime| lifetime.0))
+        .unwrap_or_else(|err| {
+            edisplay_line!(io, "{}", err);
+            cli::safe_exit(1)
+        });
+    display_line!(io, "Unlocked {} keys in the wallet agent.", count);
+}
+
+/// Decrypt a stored key with a password read from the user.
+#[cfg(unix)]
+fn decrypt_for_agent<T>(
+    io: &impl Io,
+    alias: &str,
+    stored: &StoredKeypair<T>,
+) -> Option<(String, zeroize::Zeroizing<String>)>
+where
+    T: BorshSerialize + BorshDeserialize + std::fmt::Display + FromStr,
+    <T as FromStr>::Err: std::fmt::Display,
+{
+    if !stored.is_encrypted() {
+        return None;
+    }
+    display_line!(io, "Unlocking the key {}.", alias);
+    let password = CliWalletUtils::read_password(false);
+    stored.decrypt_for_agent(password).unwrap_or_else(|err| {
+        edisplay_line!(io, "Failed to decrypt the key {}: {}", alias, err);
+        cli::safe_exit(1)
+    })
+}
+
+/// Get a client of the wallet agent given by the environment.
+#[cfg(unix)]
+fn agent_client(io: &impl Io) -> agent::AgentClient {
+    agent::AgentClient::from_env().unwrap_or_else(|| {
+        edisplay_line!(
+            io,
+            "The wallet agent's socket is not set. Run `agent run` and set \
+             the {} environment variable.",
+            agent::AGENT_SOCKET_ENV
+        );
+        cli::safe_exit(1)
+    })
+}
+
 /// Find a keypair in the wallet store.
 fn transparent_key_find(
     ctx: Context,
@@ -1277,6 +1699,7 @@ fn key_import(
         alias,
         alias_force,
         unsafe_dont_encrypt,
+        birthday,
     }: args::KeyImport,
 ) {
     let file_data = std::fs::read(file_path).unwrap_or_else(|err| {
@@ -1304,6 +1727,7 @@ fn key_import(
             alias_force,
             masp_value,
             unsafe_dont_encrypt,
+            birthday,
         );
     } else {
         display_line!(io, "Could not parse the data.");
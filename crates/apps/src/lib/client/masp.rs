@@ -1,30 +1,32 @@
 use std::fmt::Debug;
+use std::path::Path;
 
 use color_eyre::owo_colors::OwoColorize;
 use masp_primitives::sapling::ViewingKey;
 use masp_primitives::zip32::ExtendedSpendingKey;
 use namada_sdk::error::Error;
 use namada_sdk::io::Io;
+use namada_sdk::masp::fs::{self, FsShieldedUtils};
 use namada_sdk::masp::{
-    IndexedNoteEntry, ProgressLogger, ProgressType, ShieldedContext,
-    ShieldedUtils,
+    IndexedNoteEntry, LedgerMaspTxSource, MaspTxIndex, MaspTxSource,
+    ProgressLogger, ProgressType, ShieldedContext, ShieldedUtils,
 };
 use namada_sdk::queries::Client;
 use namada_sdk::storage::BlockHeight;
-use namada_sdk::{display, display_line, MaybeSend, MaybeSync};
+use namada_sdk::{args, display, display_line, MaybeSend, MaybeSync};
 
 pub async fn syncing<
     U: ShieldedUtils + MaybeSend + MaybeSync,
-    C: Client + Sync,
+    S: MaspTxSource + Sync,
     IO: Io,
 >(
     mut shielded: ShieldedContext<U>,
-    client: &C,
+    source: &S,
     io: &IO,
     batch_size: u64,
     last_query_height: Option<BlockHeight>,
     sks: &[ExtendedSpendingKey],
-    fvks: &[ViewingKey],
+    fvks: &[(ViewingKey, Option<BlockHeight>)],
 ) -> Result<ShieldedContext<U>, Error> {
     let shutdown_signal = async {
         let (tx, rx) = tokio::sync::oneshot::channel();
@@ -37,7 +39,7 @@ pub async fn syncing<
     let logger = CliLogger::new(io);
     let sync = async move {
         shielded
-            .fetch(client, &logger, last_query_height, batch_size, sks, fvks)
+            .fetch(source, &logger, last_query_height, batch_size, sks, fvks)
             .await
             .map(|_| shielded)
     };
@@ -55,6 +57,87 @@ pub async fn syncing<
     }
 }
 
+/// List, merge or delete the named shielded contexts stored in the given chain
+/// directory. The given shielded context is the selected one, into which
+/// another context is merged.
+pub async fn managing_contexts<IO: Io>(
+    mut shielded: ShieldedContext<FsShieldedUtils>,
+    io: &IO,
+    chain_dir: &Path,
+    selected: &str,
+    args: args::ShieldedContexts,
+) -> Result<(), Error> {
+    let to_error = |err: std::io::Error| Error::Other(err.to_string());
+    let names = fs::list_named_contexts(chain_dir).map_err(to_error)?;
+    let check_other = |name: &str| {
+        if name == selected {
+            Err(Error::Other(format!(
+                "The shielded context {name} is the selected one"
+            )))
+        } else if !names.iter().any(|known| known == name) {
+            Err(Error::Other(format!("No shielded context named {name}")))
+        } else {
+            Ok(())
+        }
+    };
+    if let Some(name) = args.delete {
+        check_other(&name)?;
+        fs::delete_named_context(chain_dir, &name).map_err(to_error)?;
+        display_line!(io, "Deleted the shielded context {}.", name);
+    } else if let Some(name) = args.merge {
+        check_other(&name)?;
+        let mut other =
+            FsShieldedUtils::new_named(chain_dir, &name).map_err(to_error)?;
+        other.load_confirmed().await.map_err(to_error)?;
+        // The selected context may not have been saved yet
+        let _ = shielded.load_confirmed().await;
+        shielded.merge(other)?;
+        shielded.save().await.map_err(to_error)?;
+        display_line!(
+            io,
+            "Merged the shielded context {} into {}.",
+            name,
+            selected
+        );
+    } else {
+        for name in names {
+            let marker = if name == selected { "*" } else { " " };
+            display_line!(io, "{} {}", marker, name);
+        }
+    }
+    Ok(())
+}
+
+/// Index the MASP txs of the node in the given file, from the last block
+/// already indexed in it
+pub async fn indexing<C: Client + Sync, IO: Io>(
+    client: &C,
+    io: &IO,
+    args: args::ShieldedIndex,
+) -> Result<(), Error> {
+    let mut index = if args.masp_txs_file.exists() {
+        MaspTxIndex::read_file(&args.masp_txs_file)
+            .map_err(|e| Error::Other(e.to_string()))?
+    } else {
+        MaspTxIndex::default()
+    };
+    display_line!(io, "{}", "==== Shielded indexing started ====".on_white());
+    display_line!(io, "\n\n");
+    let logger = CliLogger::new(io);
+    index
+        .index(
+            &LedgerMaspTxSource::new(client),
+            &logger,
+            args.last_query_height,
+        )
+        .await?;
+    index
+        .write_file(&args.masp_txs_file)
+        .map_err(|e| Error::Other(e.to_string()))?;
+    display!(io, "Indexing finished\n");
+    Ok(())
+}
+
 pub struct CliLogging<'io, T, IO: Io> {
     items: Vec<T>,
     index: usize,
@@ -37,7 +37,7 @@ use namada::governance::storage::proposal::{
 use namada::governance::utils::{
     compute_proposal_result, ProposalVotes, TallyType, TallyVote, VotePower,
 };
-use namada::io::Io;
+use namada::io::{Io, NullIo};
 use namada::ledger::events::Event;
 use namada::ledger::ibc::storage::{
     ibc_denom_key, ibc_denom_key_prefix, is_ibc_denom_key,
@@ -46,12 +46,21 @@ use namada::ledger::parameters::{storage as param_storage, EpochDuration};
 use namada::ledger::pos::types::{CommissionPair, Slash};
 use namada::ledger::pos::PosParams;
 use namada::ledger::queries::RPC;
-use namada::proof_of_stake::types::{ValidatorState, WeightedValidator};
+use namada::proof_of_stake::simulation::{
+    simulate_validator_sets, ValidatorSetSnapshot,
+};
+use namada::proof_of_stake::types::{
+    BondRewardsProjection, ConsensusKeyRotation, DelegatorSlashLoss,
+    PendingUnbondsAndRedelegations, ValidatorHistoryDetails,
+    ValidatorLivenessDetails, ValidatorState, WeightedValidator,
+};
 use namada::{state as storage, token};
 use namada_sdk::error::{
     is_pinned_error, Error, PinnedBalanceError, QueryError,
 };
-use namada_sdk::masp::{Conversions, MaspChange, MaspTokenRewardData};
+use namada_sdk::masp::{
+    encode_shielded_history, Conversions, MaspChange, MaspTokenRewardData,
+};
 use namada_sdk::proof_of_stake::types::ValidatorMetaData;
 use namada_sdk::queries::Client;
 use namada_sdk::rpc::{
@@ -317,6 +326,48 @@ pub async fn query_transfers(
     }
 }
 
+/// Export the shielded history of a viewing key
+pub async fn export_shielded_history(
+    context: &impl Namada,
+    args: args::ExportShieldedHistory,
+) {
+    let vk = ExtendedFullViewingKey::from(args.viewing_key).fvk.vk;
+    let mut shielded = context.shielded_mut().await;
+    let _ = shielded.load().await;
+    // Precompute asset types to increase chances of success in decoding
+    let token_map = query_tokens(context, None, None).await;
+    let tokens = token_map.values().collect();
+    let _ = shielded
+        .precompute_asset_types(context.client(), tokens)
+        .await;
+    // Keep the messages of the conversions out of a printed history
+    let history = shielded
+        .history(context.client(), &NullIo, &vk)
+        .await
+        .and_then(|history| encode_shielded_history(&history, args.format));
+    let data = match history {
+        Ok(data) => data,
+        Err(err) => {
+            edisplay_line!(context.io(), "{}", err);
+            cli::safe_exit(1)
+        }
+    };
+    match args.output {
+        Some(path) => {
+            fs::write(&path, data).unwrap_or_else(|err| {
+                edisplay_line!(context.io(), "{}", err);
+                cli::safe_exit(1)
+            });
+            display_line!(
+                context.io(),
+                "Exported to file {}",
+                path.to_string_lossy()
+            );
+        }
+        None => display_line!(context.io(), "{}", data),
+    }
+}
+
 /// Query the raw bytes of given storage key
 pub async fn query_raw_bytes<N: Namada>(
     context: &N,
@@ -1608,6 +1659,12 @@ pub async fn query_protocol_parameters(
         "",
         pos_params.unbonding_len
     );
+    display_line!(
+        context.io(),
+        "{:4}Max commission schedule length: {}",
+        "",
+        pos_params.max_commission_schedule_len
+    );
     display_line!(
         context.io(),
         "{:4}Cubic slashing window length: {}",
@@ -1736,6 +1793,57 @@ pub async fn query_consensus_keys<C: namada::ledger::queries::Client + Sync>(
     )
 }
 
+/// Query the consensus key of a validator and its pending change
+pub async fn query_consensus_key_rotation<
+    C: namada::ledger::queries::Client + Sync,
+>(
+    client: &C,
+    validator: &Address,
+) -> ConsensusKeyRotation {
+    unwrap_client_response::<C, ConsensusKeyRotation>(
+        RPC.vp()
+            .pos()
+            .consensus_key_rotation(client, validator)
+            .await,
+    )
+}
+
+/// Query and print the consensus key of a validator and its pending change
+pub async fn query_and_print_consensus_key<N: Namada>(
+    context: &N,
+    args: args::QueryConsensusKey,
+) {
+    let validator = args.validator;
+    let current_epoch = query_epoch(context.client()).await.unwrap();
+    let rotation =
+        query_consensus_key_rotation(context.client(), &validator).await;
+    match rotation.current_key {
+        Some(key) => display_line!(
+            context.io(),
+            "Consensus key of validator {validator} in epoch {current_epoch}: \
+             {key}"
+        ),
+        None => display_line!(
+            context.io(),
+            "Validator {validator} has no consensus key in epoch \
+             {current_epoch}"
+        ),
+    }
+    match rotation.pending {
+        Some((switch_epoch, new_key)) => display_line!(
+            context.io(),
+            "The consensus key will be replaced by {new_key} in epoch \
+             {switch_epoch}. Give CometBFT the new key once the epoch begins \
+             and make sure that the old key is not used by any node after \
+             that."
+        ),
+        None => display_line!(
+            context.io(),
+            "No change of the consensus key is pending"
+        ),
+    }
+}
+
 pub async fn query_pgf_stewards<C: namada::ledger::queries::Client + Sync>(
     client: &C,
 ) -> Vec<StewardDetail> {
@@ -2096,8 +2204,8 @@ pub async fn query_rewards<C: namada::ledger::queries::Client + Sync>(
 }
 
 /// Query a validator's state information
-pub async fn query_and_print_validator_state(
-    context: &impl Namada,
+pub async fn query_and_print_validator_state<N: Namada>(
+    context: &N,
     args: args::QueryValidatorState,
 ) {
     let validator = args.validator;
@@ -2138,6 +2246,51 @@ pub async fn query_and_print_validator_state(
              state information is no longer stored)"
         ),
     }
+
+    let liveness: ValidatorLivenessDetails =
+        unwrap_client_response::<N::Client, _>(
+            RPC.vp()
+                .pos()
+                .validator_liveness(context.client(), &validator)
+                .await,
+        );
+    match liveness.window {
+        Some(window) => display_line!(
+            context.io(),
+            "Liveness in the last {} blocks: signed {}, missed {}. The \
+             validator can miss {} more votes in the window before it gets \
+             jailed (jailed at {} missed votes).",
+            window.num_blocks,
+            window.signed_votes,
+            window.missed_votes,
+            window.missed_votes_until_jail(),
+            window.missed_votes_threshold,
+        ),
+        None => display_line!(
+            context.io(),
+            "No liveness data for validator {validator}, it is not tracked \
+             outside of the consensus set"
+        ),
+    }
+    if liveness.jail_history.is_empty() {
+        display_line!(context.io(), "Validator {validator} was never jailed");
+    } else {
+        display_line!(context.io(), "Jail history:");
+        for (jail_epoch, record) in liveness.jail_history {
+            match record.unjail_epoch {
+                Some(unjail_epoch) => display_line!(
+                    context.io(),
+                    "Jailed from epoch {jail_epoch} to {unjail_epoch} for {}",
+                    record.reason
+                ),
+                None => display_line!(
+                    context.io(),
+                    "Jailed from epoch {jail_epoch} for {}",
+                    record.reason
+                ),
+            }
+        }
+    }
 }
 
 /// Query PoS validator's commission rate information
@@ -2256,6 +2409,60 @@ pub async fn query_and_print_metadata(
     }
 }
 
+/// Query PoS validator's history
+pub async fn query_and_print_validator_history<N: Namada>(
+    context: &N,
+    args: args::QueryValidatorHistory,
+) {
+    let validator = args.validator;
+    let response = RPC
+        .vp()
+        .pos()
+        .validator_history(
+            context.client(),
+            &validator,
+            &args.start_epoch,
+            &args.end_epoch,
+        )
+        .await;
+    let history: ValidatorHistoryDetails =
+        unwrap_client_response::<N::Client, _>(response);
+    if history.records.is_empty() {
+        display_line!(
+            context.io(),
+            "No history found for {} in the given epochs",
+            validator.encode()
+        );
+        return;
+    }
+    display_line!(context.io(), "Validator {} history:", validator.encode());
+    for (epoch, record) in history.records {
+        display_line!(
+            context.io(),
+            "Epoch {}: commission rate {}, stake {}, state {:?}",
+            epoch,
+            record.commission_rate,
+            record.stake.to_string_native(),
+            record.state
+        );
+    }
+    if history.slashes.is_empty() {
+        display_line!(context.io(), "No slashes in the given epochs");
+    } else {
+        display_line!(context.io(), "Slashes:");
+        for slash in history.slashes {
+            display_line!(
+                context.io(),
+                "Infraction epoch {}, block height {}, type {}, rate {}",
+                slash.epoch,
+                slash.block_height,
+                slash.r#type,
+                slash.rate
+            );
+        }
+    }
+}
+
 /// Query PoS slashes
 pub async fn query_slashes<N: Namada>(context: &N, args: args::QuerySlashes) {
     match args.validator {
@@ -2422,6 +2629,318 @@ pub async fn query_and_print_rewards<N: Namada>(
     );
 }
 
+/// Query and print the projected rewards of a new bond
+pub async fn query_and_print_rewards_projection<N: Namada>(
+    context: &N,
+    args: args::QueryRewardsProjection,
+) {
+    let validator = args.validator;
+    let response = RPC
+        .vp()
+        .pos()
+        .rewards_projection(context.client(), &validator, &args.amount)
+        .await;
+    let projection: BondRewardsProjection =
+        unwrap_client_response::<N::Client, _>(response);
+    if !projection.is_consensus {
+        display_line!(
+            context.io(),
+            "Validator {validator} would not be in the consensus set in epoch \
+             {} and would not earn any rewards",
+            projection.start_epoch
+        );
+        return;
+    }
+    display_line!(
+        context.io(),
+        "Projected rewards from epoch {}: {} NAM per epoch",
+        projection.start_epoch,
+        projection.rewards_per_epoch.to_string_native()
+    );
+    display_line!(
+        context.io(),
+        "Nominal APR: {}, real APR: {}",
+        projection.nominal_apr,
+        projection.real_apr
+    );
+    display_line!(
+        context.io(),
+        "Based on a PoS inflation of {} NAM per epoch, a staked ratio of {}, \
+         a commission rate of {} and an uptime of {}",
+        projection.inflation_per_epoch.to_string_native(),
+        projection.staked_ratio,
+        projection.commission_rate,
+        projection.uptime
+    );
+}
+
+/// Query and print the pending unbonds and redelegations of a delegator
+pub async fn query_and_print_pending_unbonds<N: Namada>(
+    context: &N,
+    args: args::QueryPendingUnbonds,
+) {
+    let owner = args.owner;
+    let current_epoch = query_epoch(context.client()).await.unwrap();
+    let response = RPC
+        .vp()
+        .pos()
+        .pending_unbonds_and_redelegations(context.client(), &owner)
+        .await;
+    let pending: PendingUnbondsAndRedelegations =
+        unwrap_client_response::<N::Client, _>(response);
+
+    if pending.unbonds.is_empty() {
+        display_line!(context.io(), "No pending unbonds found");
+    } else {
+        display_line!(context.io(), "Pending unbonds:");
+        for unbond in pending.unbonds {
+            let withdrawable = if unbond.withdraw_epoch <= current_epoch {
+                "withdrawable now".to_string()
+            } else {
+                format!("withdrawable from epoch {}", unbond.withdraw_epoch)
+            };
+            display_line!(
+                context.io(),
+                "  From {}, bonded from epoch {}: {} NAM, {withdrawable} as \
+                 {} NAM",
+                unbond.validator,
+                unbond.start_epoch,
+                unbond.amount.to_string_native(),
+                unbond.amount_after_slashing.to_string_native(),
+            );
+            display_line!(
+                context.io(),
+                "    Slashable for infractions before epoch {}",
+                unbond.slashable_until
+            );
+            for (src_validator, amount) in unbond.redelegated {
+                display_line!(
+                    context.io(),
+                    "    {} NAM redelegated from {src_validator}",
+                    amount.to_string_native()
+                );
+            }
+        }
+    }
+
+    if pending.redelegations.is_empty() {
+        display_line!(context.io(), "No pending redelegations found");
+    } else {
+        display_line!(context.io(), "Pending redelegations:");
+        for redelegation in pending.redelegations {
+            display_line!(
+                context.io(),
+                "  From {} to {} in epoch {}: {} NAM, {} NAM after slashing",
+                redelegation.src_validator,
+                redelegation.dest_validator,
+                redelegation.start_epoch,
+                redelegation.amount.to_string_native(),
+                redelegation.amount_after_slashing.to_string_native(),
+            );
+            display_line!(
+                context.io(),
+                "    Slashable for infractions of {} before epoch {} until \
+                 epoch {}, when it can be redelegated again",
+                redelegation.src_validator,
+                redelegation.slashable_until,
+                redelegation.mature_epoch
+            );
+        }
+    }
+}
+
+/// Query and print the losses of a delegator to the slashes of their
+/// validators
+pub async fn query_and_print_slash_report<N: Namada>(
+    context: &N,
+    args: args::QuerySlashReport,
+) {
+    let owner = args.owner;
+    let response = RPC
+        .vp()
+        .pos()
+        .delegator_slash_report(context.client(), &owner)
+        .await;
+    let losses: Vec<DelegatorSlashLoss> =
+        unwrap_client_response::<N::Client, _>(response);
+    if losses.is_empty() {
+        display_line!(context.io(), "No slashes affected the bonds of {owner}");
+        return;
+    }
+
+    let mut total_lost = token::Amount::zero();
+    for loss in losses {
+        let holding = &loss.holding;
+        let mut kind = match holding.withdraw {
+            Some(withdraw) => format!(
+                "Unbond from {} bonded from epoch {}, withdrawable from epoch \
+                 {withdraw}",
+                holding.validator, holding.start
+            ),
+            None => format!(
+                "Bond to {} from epoch {}",
+                holding.validator, holding.start
+            ),
+        };
+        if let Some((src_validator, bond_start)) = &holding.redelegated_from {
+            kind.push_str(&format!(
+                ", redelegated from {src_validator} bonded from epoch \
+                 {bond_start}"
+            ));
+        }
+        display_line!(
+            context.io(),
+            "Slash of {} for {} in epoch {} (block height {}), rate {}:",
+            loss.slashed_validator,
+            loss.slash.r#type,
+            loss.slash.epoch,
+            loss.slash.block_height,
+            loss.slash.rate
+        );
+        display_line!(
+            context.io(),
+            "  {kind}: lost {} NAM",
+            loss.amount.to_string_native()
+        );
+        total_lost += loss.amount;
+    }
+    display_line!(
+        context.io(),
+        "Total lost to slashes: {} NAM",
+        total_lost.to_string_native()
+    );
+}
+
+/// Simulate the validator sets with changed PoS parameters and print the
+/// membership of the sets and the concentration of the voting power
+pub async fn simulate_validator_sets<N: Namada>(
+    context: &N,
+    args: args::SimulateValidatorSets,
+) {
+    let snapshot: ValidatorSetSnapshot = match &args.input {
+        Some(path) => {
+            let data = fs::read_to_string(path).unwrap_or_else(|err| {
+                edisplay_line!(context.io(), "{}", err);
+                cli::safe_exit(1)
+            });
+            serde_json::from_str(&data).unwrap_or_else(|err| {
+                edisplay_line!(
+                    context.io(),
+                    "Couldn't parse the validator set snapshot: {}",
+                    err
+                );
+                cli::safe_exit(1)
+            })
+        }
+        None => unwrap_client_response::<N::Client, _>(
+            RPC.vp()
+                .pos()
+                .validator_set_snapshot(context.client())
+                .await,
+        ),
+    };
+    if let Some(path) = &args.output {
+        let data = serde_json::to_string_pretty(&snapshot)
+            .expect("Validator set snapshot should be serializable");
+        fs::write(path, data).unwrap_or_else(|err| {
+            edisplay_line!(context.io(), "{}", err);
+            cli::safe_exit(1)
+        });
+        display_line!(
+            context.io(),
+            "Exported the validator set snapshot to file {}",
+            path.to_string_lossy()
+        );
+    }
+
+    let mut params = snapshot.params.clone();
+    if let Some(max_validator_slots) = args.max_validator_slots {
+        params.max_validator_slots = max_validator_slots;
+    }
+    if let Some(threshold) = args.validator_stake_threshold {
+        params.validator_stake_threshold = threshold;
+    }
+    if let Some(threshold) = args.liveness_threshold {
+        params.liveness_threshold = threshold;
+    }
+    display_line!(
+        context.io(),
+        "Simulating {} epochs from epoch {} with at most {} consensus \
+         validators, a stake threshold of {} NAM and a liveness threshold of \
+         {}",
+        args.epochs,
+        snapshot.epoch,
+        params.max_validator_slots,
+        params.validator_stake_threshold.to_string_native(),
+        params.liveness_threshold
+    );
+
+    let simulated = simulate_validator_sets(&snapshot, &params, args.epochs);
+    for sets in &simulated {
+        let concentration = &sets.concentration;
+        display_line!(context.io(), "Epoch {}:", sets.epoch);
+        display_line!(
+            context.io(),
+            "  Consensus: {} validators with {} NAM, below capacity: {}, \
+             below threshold: {}, jailed: {}",
+            sets.consensus.len(),
+            concentration.total_stake.to_string_native(),
+            sets.below_capacity.len(),
+            sets.below_threshold.len(),
+            sets.jailed.len()
+        );
+        display_line!(
+            context.io(),
+            "  Largest validator share: {}, validators to halt the chain: {}, \
+             validators to commit blocks: {}",
+            concentration.largest_share,
+            concentration.halting_validators,
+            concentration.committing_validators
+        );
+        if let (Some((_, smallest)), Some((_, largest))) =
+            (sets.consensus.last(), sets.below_capacity.first())
+        {
+            display_line!(
+                context.io(),
+                "  Smallest consensus stake: {} NAM, largest below-capacity \
+                 stake: {} NAM",
+                smallest.to_string_native(),
+                largest.to_string_native()
+            );
+        }
+    }
+
+    // Compare the sets in the last simulated epoch with the ones with the
+    // current parameters
+    let baseline =
+        simulate_validator_sets(&snapshot, &snapshot.params, args.epochs);
+    if let (Some(baseline), Some(simulated)) =
+        (baseline.last(), simulated.last())
+    {
+        let addresses = |set: &[(Address, token::Amount)]| {
+            set.iter()
+                .map(|(address, _)| address.clone())
+                .collect::<BTreeSet<_>>()
+        };
+        let baseline_consensus = addresses(&baseline.consensus);
+        let simulated_consensus = addresses(&simulated.consensus);
+        display_line!(
+            context.io(),
+            "Compared to the current parameters in epoch {}:",
+            simulated.epoch
+        );
+        for address in simulated_consensus.difference(&baseline_consensus) {
+            display_line!(context.io(), "  {address} joins the consensus set");
+        }
+        for address in baseline_consensus.difference(&simulated_consensus) {
+            display_line!(context.io(), "  {address} leaves the consensus set");
+        }
+        for address in simulated.jailed.difference(&baseline.jailed) {
+            display_line!(context.io(), "  {address} is jailed for liveness");
+        }
+    }
+}
+
 pub async fn query_delegations<N: Namada>(
     context: &N,
     args: args::QueryDelegations,
@@ -2677,6 +3196,77 @@ pub async fn query_masp_reward_tokens(context: &impl Namada) {
     }
 }
 
+/// Estimate the MASP rewards of a token in the current epoch and project them
+/// over the next epochs.
+pub async fn query_masp_rewards(
+    context: &impl Namada,
+    args: args::QueryMaspRewards,
+) {
+    let estimates = namada_sdk::rpc::query_masp_reward_estimates(
+        context.client(),
+        &args.token,
+        args.epochs,
+    )
+    .await
+    .unwrap_or_else(|err| {
+        edisplay_line!(
+            context.io(),
+            "Failed to estimate the MASP rewards: {}",
+            err
+        );
+        cli::safe_exit(1)
+    });
+    let amount = match args.amount {
+        Some(amount) => Some(
+            rpc::validate_amount(context, amount, &args.token, false)
+                .await
+                .unwrap_or_else(|err| {
+                    edisplay_line!(context.io(), "Invalid amount: {}", err);
+                    cli::safe_exit(1)
+                })
+                .amount(),
+        ),
+        None => None,
+    };
+    display_line!(
+        context.io(),
+        "Estimated MASP rewards of {}, in the native token:",
+        args.token
+    );
+    let mut total_reward = token::Amount::zero();
+    for estimate in estimates {
+        let rate = estimate
+            .reward_rate()
+            .map_or_else(|| "unknown".to_string(), |rate| rate.to_string());
+        display_line!(
+            context.io(),
+            "Epoch {}: reward rate {} per epoch, {} raw units for every {} \
+             raw units of the token, pool inflation {}",
+            estimate.epoch,
+            rate,
+            estimate.reward,
+            estimate.precision,
+            estimate.inflation.to_string_native()
+        );
+        if let Some(amount) = amount {
+            let reward = estimate.reward_for(amount);
+            total_reward = total_reward.checked_add(reward).unwrap_or_default();
+            display_line!(
+                context.io(),
+                "  Reward of the amount: {}",
+                reward.to_string_native()
+            );
+        }
+    }
+    if amount.is_some() {
+        display_line!(
+            context.io(),
+            "Total estimated reward: {}",
+            total_reward.to_string_native()
+        );
+    }
+}
+
 /// Query a wasm code hash
 pub async fn query_wasm_code_hash(
     context: &impl Namada,
@@ -444,19 +444,57 @@ pub async fn submit_change_consensus_key(
 
         if !tx_args.dry_run {
             if resp.is_applied_and_valid().is_some() {
-                namada.wallet_mut().await.save().unwrap_or_else(|err| {
+                let mut wallet = namada.wallet_mut().await;
+                wallet.save().unwrap_or_else(|err| {
                     edisplay_line!(namada.io(), "{}", err)
                 });
 
+                // Prepare the new key for CometBFT, to be swapped in at the
+                // switch epoch
+                let next_key_path =
+                    wallet.find_key_by_pk(&new_key, None).ok().and_then(|sk| {
+                        tendermint_node::write_next_validator_key(
+                            config.ledger.cometbft_dir(),
+                            &sk,
+                        )
+                        .map_err(|err| edisplay_line!(namada.io(), "{}", err))
+                        .ok()
+                    });
+                // To avoid wallet deadlocks in following operations
+                drop(wallet);
+
+                let switch_epoch = rpc::query_consensus_key_rotation(
+                    namada.client(),
+                    &validator,
+                )
+                .await
+                .pending
+                .map(|(switch_epoch, _key)| switch_epoch.to_string())
+                .unwrap_or_else(|| "the pipeline epoch".to_string());
                 display_line!(
                     namada.io(),
                     "New consensus key stored with alias \
                      \"{consensus_key_alias}\". It will become active \
-                     {EPOCH_SWITCH_BLOCKS_DELAY} blocks before pipeline \
-                     offset from the current epoch, at which point you'll \
-                     need to give the new key to CometBFT in order to be able \
-                     to sign with it in consensus.",
+                     {EPOCH_SWITCH_BLOCKS_DELAY} blocks before the start of \
+                     epoch {switch_epoch}, at which point you'll need to give \
+                     the new key to CometBFT in order to be able to sign with \
+                     it in consensus. The old key must not be used by any \
+                     node after that.",
                 );
+                match next_key_path {
+                    Some(path) => display_line!(
+                        namada.io(),
+                        "The new key for CometBFT has been written to {}. \
+                         Replace the CometBFT validator key with it at the \
+                         switch epoch.",
+                        path.to_string_lossy()
+                    ),
+                    None => display_line!(
+                        namada.io(),
+                        "Convert the new consensus key for CometBFT with \
+                         `namadaw convert` before the switch epoch."
+                    ),
+                }
             }
         } else {
             display_line!(
@@ -984,6 +1022,23 @@ pub async fn submit_transfer(
     Ok(())
 }
 
+pub async fn submit_consolidate_notes(
+    namada: &impl Namada,
+    args: args::TxConsolidateNotes,
+) -> Result<(), error::Error> {
+    let (mut tx, signing_data, _tx_epoch) = args.clone().build(namada).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
 pub async fn submit_ibc_transfer<N: Namada>(
     namada: &N,
     args: args::TxIbcTransfer,
@@ -1418,6 +1473,66 @@ where
     Ok(())
 }
 
+pub async fn submit_restake<N: Namada>(
+    namada: &N,
+    args: args::Restake,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data) = args.build(namada).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn submit_liquid_bond<N: Namada>(
+    namada: &N,
+    args: args::LiquidBond,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data) = args.build(namada).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn submit_redeem_shares<N: Namada>(
+    namada: &N,
+    args: args::RedeemShares,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data) = args.build(namada).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
 pub async fn submit_redelegate<N: Namada>(
     namada: &N,
     args: args::Redelegate,
@@ -17,6 +17,7 @@ use namada::core::string_encoding::StringEncoded;
 use namada::core::token;
 use namada::core::uint::Uint;
 use namada::vm::validate_untrusted_wasm;
+use namada_sdk::masp;
 use namada_sdk::wallet::{alias, Wallet};
 use prost::bytes::Bytes;
 use serde_json::json;
@@ -366,6 +367,40 @@ pub fn validate_wasm(args::ValidateWasm { code_path }: args::ValidateWasm) {
     }
 }
 
+/// Verify the MASP parameter files, after installing them from a bundle if
+/// requested
+pub fn masp_params(args::MaspParams { install }: args::MaspParams) {
+    let params_dir = masp::get_params_dir();
+    if let Some(bundle_dir) = install {
+        if let Err(err) = masp::install_params(&bundle_dir, &params_dir) {
+            eprintln!("Failed to install the MASP parameters: {err}");
+            safe_exit(1)
+        }
+        println!(
+            "Installed the MASP parameters from {} into {}.",
+            bundle_dir.display(),
+            params_dir.display()
+        );
+    }
+    let problems = masp::check_params_files(&params_dir);
+    if !problems.is_empty() {
+        for problem in problems {
+            eprintln!("{problem}");
+        }
+        safe_exit(1)
+    }
+    match masp::verify_params(&params_dir) {
+        Ok(()) => println!(
+            "The MASP parameters in {} are valid.",
+            params_dir.display()
+        ),
+        Err(err) => {
+            eprintln!("{err}");
+            safe_exit(1)
+        }
+    }
+}
+
 /// Length of a Tendermint Node ID in bytes
 const TENDERMINT_NODE_ID_LENGTH: usize = 20;
 
@@ -368,6 +368,7 @@ impl Finalized {
             liveness_threshold,
             rewards_gain_p,
             rewards_gain_d,
+            max_commission_schedule_len,
         } = self.parameters.pos_params.clone();
 
         namada::proof_of_stake::parameters::PosParams {
@@ -388,6 +389,7 @@ impl Finalized {
                 liveness_threshold,
                 rewards_gain_p,
                 rewards_gain_d,
+                max_commission_schedule_len,
             },
             max_proposal_period: self.parameters.gov_params.max_proposal_period,
         }
@@ -409,6 +409,9 @@ pub struct PosParams {
     pub rewards_gain_p: Dec,
     /// PoS gain d (read only)
     pub rewards_gain_d: Dec,
+    /// The maximum number of epochs past the pipeline epoch in which a
+    /// scheduled commission rate change can take effect
+    pub max_commission_schedule_len: u64,
 }
 
 #[derive(
@@ -91,6 +91,7 @@ fn get_tx_args(use_device: bool) -> TxArgs {
         password: None,
         memo: None,
         use_device,
+        note_selection: Default::default(),
     }
 }
 
@@ -46,6 +46,10 @@ pub struct Config {
 pub struct ValidatorLocalConfig {
     pub accepted_gas_tokens:
         HashMap<namada::core::address::Address, namada::core::token::Amount>,
+    /// The sockets of the share holders of a threshold protocol key. If
+    /// empty, the protocol keypair from the wallet is used.
+    #[serde(default)]
+    pub protocol_key_shares: Vec<PathBuf>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
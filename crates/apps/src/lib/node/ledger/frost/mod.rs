@@ -0,0 +1,278 @@
+//! Threshold signatures for the validator protocol key, using FROST over
+//! Ed25519 with SHA-512 (RFC 9591) as implemented by the `frost-ed25519`
+//! crate.
+//!
+//! The protocol key is generated with a distributed key generation, in which
+//! no single share holder ever learns the whole secret key. Any `threshold`
+//! share holders can then jointly produce an ordinary Ed25519 signature that
+//! verifies against the group public key, so that the chain can't tell a
+//! threshold signature apart from a signature made with a single key.
+
+pub mod service;
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub use frost_ed25519::keys::dkg::{
+    round1 as dkg_round1, round2 as dkg_round2,
+};
+pub use frost_ed25519::keys::{KeyPackage, PublicKeyPackage};
+pub use frost_ed25519::round1::{SigningCommitments, SigningNonces};
+pub use frost_ed25519::round2::SignatureShare;
+pub use frost_ed25519::{Identifier, SigningPackage};
+use namada::core::key::{common, ed25519};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("FROST error: {0}")]
+    Frost(#[from] frost_ed25519::Error),
+    #[error("Invalid encoding of the group public key")]
+    InvalidPublicKey,
+    #[error("The aggregated signature is invalid")]
+    InvalidSignature,
+}
+
+/// Result of a FROST operation
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The public data of a threshold key, known to all share holders and to the
+/// signing coordinator
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThresholdPublicKey {
+    /// The minimum number of share holders needed to sign
+    pub threshold: u16,
+    /// The verifying shares of the share holders and the group public key
+    pub package: PublicKeyPackage,
+}
+
+impl ThresholdPublicKey {
+    /// The group public key as an Ed25519 public key
+    pub fn public_key(&self) -> Result<common::PublicKey> {
+        let bytes = self.package.verifying_key().serialize();
+        ed25519_consensus::VerificationKey::try_from(bytes)
+            .map(|pk| common::PublicKey::Ed25519(ed25519::PublicKey(pk)))
+            .map_err(|_| Error::InvalidPublicKey)
+    }
+
+    /// The number of share holders of the key
+    pub fn max_signers(&self) -> usize {
+        self.package.verifying_shares().len()
+    }
+}
+
+/// The secret share of a threshold key held by a single share holder
+#[derive(Clone, Serialize, Deserialize)]
+pub struct KeyShare {
+    /// The secret signing share of the share holder
+    pub key_package: KeyPackage,
+    /// The public data of the threshold key
+    pub public: ThresholdPublicKey,
+}
+
+impl KeyShare {
+    /// The identifier of the share holder
+    pub fn identifier(&self) -> Identifier {
+        *self.key_package.identifier()
+    }
+}
+
+impl fmt::Debug for KeyShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyShare")
+            .field("identifier", &self.identifier())
+            .field("public", &self.public)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Get the identifier of the share holder with the given index, starting
+/// from 1
+pub fn identifier(index: u16) -> Result<Identifier> {
+    Ok(Identifier::try_from(index)?)
+}
+
+/// Derive the key share of a share holder from the completed rounds of the
+/// key generation
+pub fn dkg_key_share(
+    round2_secret: &dkg_round2::SecretPackage,
+    round1_packages: &BTreeMap<Identifier, dkg_round1::Package>,
+    round2_packages: &BTreeMap<Identifier, dkg_round2::Package>,
+) -> Result<KeyShare> {
+    let (key_package, package) = frost_ed25519::keys::dkg::part3(
+        round2_secret,
+        round1_packages,
+        round2_packages,
+    )?;
+    let public = ThresholdPublicKey {
+        threshold: *key_package.min_signers(),
+        package,
+    };
+    // Reject a group key that isn't a valid Ed25519 public key
+    public.public_key()?;
+    Ok(KeyShare {
+        key_package,
+        public,
+    })
+}
+
+/// Verify the signature shares and aggregate them into an Ed25519 signature
+/// of the group public key.
+pub fn aggregate(
+    package: &SigningPackage,
+    signature_shares: &BTreeMap<Identifier, SignatureShare>,
+    public: &ThresholdPublicKey,
+) -> Result<common::Signature> {
+    let signature =
+        frost_ed25519::aggregate(package, signature_shares, &public.package)?;
+    let signature = ed25519_consensus::Signature::from(signature.serialize());
+    // Double-check the signature with the verifier used by the chain
+    ed25519_consensus::VerificationKey::try_from(
+        public.package.verifying_key().serialize(),
+    )
+    .and_then(|pk| pk.verify(&signature, package.message()))
+    .map_err(|_| Error::InvalidSignature)?;
+    Ok(common::Signature::Ed25519(ed25519::Signature(signature)))
+}
+
+#[cfg(test)]
+mod test_frost {
+    use frost_ed25519::keys::dkg;
+    use namada::core::key::{SigScheme, SignableBytes};
+    use rand_core::OsRng;
+
+    use super::*;
+
+    /// Run the key generation between the given number of share holders
+    fn run_dkg(max_signers: u16, threshold: u16) -> Vec<KeyShare> {
+        let mut round1_secrets = BTreeMap::new();
+        let mut round1_packages = BTreeMap::new();
+        for index in 1..=max_signers {
+            let id = identifier(index).unwrap();
+            let (secret, package) =
+                dkg::part1(id, max_signers, threshold, OsRng).unwrap();
+            round1_secrets.insert(id, secret);
+            round1_packages.insert(id, package);
+        }
+        let mut round2_secrets = BTreeMap::new();
+        let mut round2_packages: BTreeMap<_, BTreeMap<_, _>> = BTreeMap::new();
+        for (id, secret) in round1_secrets {
+            let others = round1_packages
+                .iter()
+                .filter(|(sender, _)| **sender != id)
+                .map(|(sender, package)| (*sender, package.clone()))
+                .collect();
+            let (secret, packages) = dkg::part2(secret, &others).unwrap();
+            round2_secrets.insert(id, (secret, others));
+            for (receiver, package) in packages {
+                round2_packages
+                    .entry(receiver)
+                    .or_default()
+                    .insert(id, package);
+            }
+        }
+        round2_secrets
+            .into_iter()
+            .map(|(id, (secret, others))| {
+                dkg_key_share(&secret, &others, &round2_packages[&id]).unwrap()
+            })
+            .collect()
+    }
+
+    /// Sign the message with the given share holders, returning the signing
+    /// package and the signature shares
+    fn sign_with(
+        shares: &[&KeyShare],
+        message: &[u8],
+    ) -> (SigningPackage, BTreeMap<Identifier, SignatureShare>) {
+        let nonces: Vec<_> = shares
+            .iter()
+            .map(|share| {
+                frost_ed25519::round1::commit(
+                    share.key_package.signing_share(),
+                    &mut OsRng,
+                )
+            })
+            .collect();
+        let package = SigningPackage::new(
+            shares
+                .iter()
+                .zip(&nonces)
+                .map(|(share, (_, commitments))| {
+                    (share.identifier(), *commitments)
+                })
+                .collect(),
+            message,
+        );
+        let signature_shares = shares
+            .iter()
+            .zip(nonces)
+            .map(|(share, (nonces, _))| {
+                let signature_share = frost_ed25519::round2::sign(
+                    &package,
+                    &nonces,
+                    &share.key_package,
+                )
+                .unwrap();
+                (share.identifier(), signature_share)
+            })
+            .collect();
+        (package, signature_shares)
+    }
+
+    /// Test that any threshold of share holders produces an Ed25519
+    /// signature that verifies against the group public key with the
+    /// chain's signature verification.
+    #[test]
+    fn test_threshold_signature_verifies() {
+        let shares = run_dkg(3, 2);
+        let public = shares[0].public.clone();
+        assert!(shares.iter().all(|share| share.public == public));
+        let pk = public.public_key().unwrap();
+
+        let data = b"protocol tx".to_vec();
+        let message = data.signable_hash::<namada::core::hash::Sha256Hasher>();
+        for signers in [[0, 1], [0, 2], [1, 2]] {
+            let signers: Vec<_> = signers.iter().map(|i| &shares[*i]).collect();
+            let (package, signature_shares) = sign_with(&signers, &message);
+            let sig = aggregate(&package, &signature_shares, &public).unwrap();
+            common::SigScheme::verify_signature(&pk, &data, &sig).unwrap();
+        }
+    }
+
+    /// Test that signing needs at least a threshold of share holders and
+    /// that a bad signature share is rejected.
+    #[test]
+    fn test_threshold_signature_rejects_bad_shares() {
+        let shares = run_dkg(3, 2);
+        let public = shares[0].public.clone();
+
+        let (nonces, commitments) = frost_ed25519::round1::commit(
+            shares[0].key_package.signing_share(),
+            &mut OsRng,
+        );
+        let package = SigningPackage::new(
+            [(shares[0].identifier(), commitments)].into(),
+            b"message",
+        );
+        assert!(
+            frost_ed25519::round2::sign(
+                &package,
+                &nonces,
+                &shares[0].key_package
+            )
+            .is_err()
+        );
+
+        let (package, mut signature_shares) =
+            sign_with(&[&shares[0], &shares[2]], b"message");
+        let first = signature_shares[&shares[0].identifier()];
+        signature_shares.insert(shares[2].identifier(), first);
+        assert!(matches!(
+            aggregate(&package, &signature_shares, &public),
+            Err(Error::Frost(_))
+        ));
+    }
+}
@@ -0,0 +1,859 @@
+//! Local socket service of the protocol key share holders.
+//!
+//! Every share holder runs in its own process and listens on a Unix domain
+//! socket. A coordinator, either the ledger when signing or the `namadan
+//! frost dkg` command when generating the key, connects to the share
+//! holders and drives the protocol rounds with newline-delimited JSON
+//! messages.
+//!
+//! The second round packages of the key generation carry the secret shares
+//! of the share holders, so they are encrypted to their receivers with keys
+//! exchanged in the first round and the coordinator only forwards them. The
+//! share holders log the generated key, so that it can be compared with the
+//! key reported by the coordinator.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use namada::core::key::common;
+use orion::{aead, kex};
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{
+    aggregate, dkg_key_share, dkg_round1, dkg_round2, identifier, Identifier,
+    KeyShare, SignatureShare, SigningCommitments, SigningNonces,
+    SigningPackage, ThresholdPublicKey,
+};
+
+/// The maximum number of signing sessions for which a share holder keeps
+/// its nonces. Nonces of older sessions are dropped.
+const MAX_PENDING_NONCES: usize = 32;
+
+/// The time after which a share holder closes an idle connection
+const SHARE_HOLDER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The time within which the share holders must answer the coordinator
+/// outside of signing
+const COORDINATOR_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The time within which the key generation must complete
+const DKG_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The time within which a threshold signature must be made. Signing runs
+/// while the ledger extends its votes, so it must not hold up consensus.
+const SIGNING_DEADLINE: Duration = Duration::from_secs(1);
+
+#[cfg(unix)]
+type Listener = std::os::unix::net::UnixListener;
+#[cfg(unix)]
+type Stream = std::os::unix::net::UnixStream;
+// Unix domain sockets are unavailable, so no socket is ever opened
+#[cfg(not(unix))]
+type Listener = std::net::TcpListener;
+#[cfg(not(unix))]
+type Stream = std::net::TcpStream;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Share holder socket {0} error: {1}")]
+    Io(PathBuf, io::Error),
+    #[error("Invalid share holder message: {0}")]
+    Encoding(#[from] serde_json::Error),
+    #[error("{0}")]
+    Frost(#[from] super::Error),
+    #[error("Share holder {0} failed: {1}")]
+    ShareHolder(PathBuf, String),
+    #[error("Unexpected response from share holder {0}")]
+    UnexpectedResponse(PathBuf),
+    #[error("Share holder {0} didn't answer in time")]
+    Timeout(PathBuf),
+    #[error(
+        "Only {available} share holders are available, but {threshold} are \
+         needed to sign"
+    )]
+    NotEnoughShareHolders { available: usize, threshold: u16 },
+    #[error("Too many share holders: {0}")]
+    TooManyShareHolders(usize),
+    #[error("The share holders don't hold shares of the same key")]
+    InconsistentKeys,
+    #[error("Failed to access the key share file {0}: {1}")]
+    ShareFile(PathBuf, io::Error),
+}
+
+/// A request from the coordinator to a share holder
+#[derive(Debug, Serialize, Deserialize)]
+enum Request {
+    /// Get the public data of the share holder's key
+    PublicKey,
+    /// Generate nonces for a new signing session
+    Commit,
+    /// Sign with the nonces committed to in the package
+    Sign(SigningPackage),
+    /// Start the key generation as the share holder with the given index
+    DkgPart1 {
+        index: u16,
+        max_signers: u16,
+        threshold: u16,
+    },
+    /// Process the first round messages of all share holders
+    DkgPart2(BTreeMap<Identifier, DkgRound1Message>),
+    /// Process the encrypted second round packages addressed to the share
+    /// holder, by their senders
+    DkgPart3(BTreeMap<Identifier, EncryptedPackage>),
+}
+
+/// The first round message of a share holder, broadcast to all the others
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DkgRound1Message {
+    /// The first round package of the key generation
+    package: dkg_round1::Package,
+    /// The public keys of the key exchange with the share holder, by the
+    /// share holders that encrypt their second round package with them
+    exchange_keys: BTreeMap<Identifier, Vec<u8>>,
+}
+
+/// A second round package of the key generation encrypted to its receiver
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EncryptedPackage {
+    /// The public key of the sender's side of the key exchange
+    exchange_key: Vec<u8>,
+    /// The package encrypted with the exchanged key
+    ciphertext: Vec<u8>,
+}
+
+/// A response of a share holder to the coordinator
+#[derive(Debug, Serialize, Deserialize)]
+enum Response {
+    PublicKey(ThresholdPublicKey),
+    Commitments(Identifier, SigningCommitments),
+    SignatureShare(SignatureShare),
+    DkgRound1(Identifier, DkgRound1Message),
+    /// The encrypted second round packages by their receivers
+    DkgRound2(BTreeMap<Identifier, EncryptedPackage>),
+    Error(String),
+}
+
+/// The secret state of a share holder during the key generation, with its
+/// side of the key exchanges by the senders of the second round packages
+enum DkgState {
+    Round1(
+        Identifier,
+        dkg_round1::SecretPackage,
+        BTreeMap<Identifier, kex::EphemeralServerSession>,
+    ),
+    Round2(
+        dkg_round2::SecretPackage,
+        BTreeMap<Identifier, dkg_round1::Package>,
+        BTreeMap<Identifier, kex::EphemeralServerSession>,
+    ),
+}
+
+/// Encrypt a second round package to the receiver with the public key of
+/// the receiver's side of the key exchange
+fn encrypt_package(
+    package: &dkg_round2::Package,
+    receiver_key: &[u8],
+) -> Result<EncryptedPackage, String> {
+    let crypto_err = |err: orion::errors::UnknownCryptoError| err.to_string();
+    let receiver_key =
+        kex::PublicKey::from_slice(receiver_key).map_err(crypto_err)?;
+    let session = kex::EphemeralClientSession::new().map_err(crypto_err)?;
+    let exchange_key = session.public_key().as_ref().to_vec();
+    let keys = session
+        .establish_with_server(&receiver_key)
+        .map_err(crypto_err)?;
+    let data = serde_json::to_vec(package).map_err(|err| err.to_string())?;
+    let ciphertext = aead::seal(keys.transport(), &data).map_err(crypto_err)?;
+    Ok(EncryptedPackage {
+        exchange_key,
+        ciphertext,
+    })
+}
+
+/// Decrypt a second round package with the receiver's side of the key
+/// exchange with its sender
+fn decrypt_package(
+    package: &EncryptedPackage,
+    session: kex::EphemeralServerSession,
+) -> Result<dkg_round2::Package, String> {
+    let crypto_err = |err: orion::errors::UnknownCryptoError| err.to_string();
+    let sender_key = kex::PublicKey::from_slice(&package.exchange_key)
+        .map_err(crypto_err)?;
+    let keys = session
+        .establish_with_client(&sender_key)
+        .map_err(crypto_err)?;
+    let data = aead::open(keys.receiving(), &package.ciphertext)
+        .map_err(|_| "Failed to decrypt a second round package".to_string())?;
+    serde_json::from_slice(&data).map_err(|err| err.to_string())
+}
+
+/// The state of a share holder process
+struct ShareHolder {
+    share_file: PathBuf,
+    share: Option<KeyShare>,
+    nonces: VecDeque<SigningNonces>,
+    dkg: Option<DkgState>,
+}
+
+impl ShareHolder {
+    fn handle(&mut self, request: Request) -> Result<Response, String> {
+        match request {
+            Request::PublicKey => {
+                let share = self.share()?;
+                Ok(Response::PublicKey(share.public.clone()))
+            }
+            Request::Commit => {
+                let share = self.share()?;
+                let identifier = share.identifier();
+                let (nonces, commitments) = frost_ed25519::round1::commit(
+                    share.key_package.signing_share(),
+                    &mut OsRng,
+                );
+                if self.nonces.len() == MAX_PENDING_NONCES {
+                    self.nonces.pop_front();
+                }
+                self.nonces.push_back(nonces);
+                Ok(Response::Commitments(identifier, commitments))
+            }
+            Request::Sign(package) => {
+                let share = self.share.as_ref().ok_or("No key share")?;
+                let own_commitments = package
+                    .signing_commitments()
+                    .get(&share.identifier())
+                    .ok_or("The package has no commitments of this share")?;
+                // Each nonce is used at most once
+                let index = self
+                    .nonces
+                    .iter()
+                    .position(|nonces| nonces.commitments() == own_commitments)
+                    .ok_or("Unknown or already used commitments")?;
+                let nonces = self.nonces.remove(index).unwrap();
+                frost_ed25519::round2::sign(
+                    &package,
+                    &nonces,
+                    &share.key_package,
+                )
+                .map(Response::SignatureShare)
+                .map_err(|err| err.to_string())
+            }
+            Request::DkgPart1 {
+                index,
+                max_signers,
+                threshold,
+            } => {
+                if self.share.is_some() {
+                    return Err(format!(
+                        "A key share already exists in {}",
+                        self.share_file.to_string_lossy()
+                    ));
+                }
+                let identifier =
+                    identifier(index).map_err(|err| err.to_string())?;
+                let (secret, package) = frost_ed25519::keys::dkg::part1(
+                    identifier,
+                    max_signers,
+                    threshold,
+                    OsRng,
+                )
+                .map_err(|err| err.to_string())?;
+                // A separate key exchange with every other share holder
+                let mut sessions = BTreeMap::new();
+                let mut exchange_keys = BTreeMap::new();
+                for sender in (1..=max_signers).filter(|ix| *ix != index) {
+                    let sender = super::identifier(sender)
+                        .map_err(|err| err.to_string())?;
+                    let session = kex::EphemeralServerSession::new()
+                        .map_err(|err| err.to_string())?;
+                    exchange_keys
+                        .insert(sender, session.public_key().as_ref().to_vec());
+                    sessions.insert(sender, session);
+                }
+                self.dkg = Some(DkgState::Round1(identifier, secret, sessions));
+                Ok(Response::DkgRound1(
+                    identifier,
+                    DkgRound1Message {
+                        package,
+                        exchange_keys,
+                    },
+                ))
+            }
+            Request::DkgPart2(mut messages) => {
+                let Some(DkgState::Round1(identifier, secret, sessions)) =
+                    self.dkg.take()
+                else {
+                    return Err(
+                        "The key generation hasn't been started".to_string()
+                    );
+                };
+                messages.remove(&identifier);
+                let packages = messages
+                    .iter()
+                    .map(|(sender, message)| (*sender, message.package.clone()))
+                    .collect();
+                let (secret, round2_packages) =
+                    frost_ed25519::keys::dkg::part2(secret, &packages)
+                        .map_err(|err| err.to_string())?;
+                let encrypted_packages = round2_packages
+                    .iter()
+                    .map(|(receiver, package)| {
+                        let receiver_key = messages
+                            .get(receiver)
+                            .and_then(|message| {
+                                message.exchange_keys.get(&identifier)
+                            })
+                            .ok_or_else(|| {
+                                format!(
+                                    "Missing the key exchange with {receiver:?}"
+                                )
+                            })?;
+                        let package = encrypt_package(package, receiver_key)?;
+                        Ok((*receiver, package))
+                    })
+                    .collect::<Result<_, String>>()?;
+                self.dkg = Some(DkgState::Round2(secret, packages, sessions));
+                Ok(Response::DkgRound2(encrypted_packages))
+            }
+            Request::DkgPart3(encrypted_packages) => {
+                let Some(DkgState::Round2(
+                    secret,
+                    round1_packages,
+                    mut sessions,
+                )) = self.dkg.take()
+                else {
+                    return Err("The key generation's first round is missing"
+                        .to_string());
+                };
+                let packages = encrypted_packages
+                    .iter()
+                    .map(|(sender, package)| {
+                        let session =
+                            sessions.remove(sender).ok_or_else(|| {
+                                format!("Unexpected package from {sender:?}")
+                            })?;
+                        Ok((*sender, decrypt_package(package, session)?))
+                    })
+                    .collect::<Result<_, String>>()?;
+                let share = dkg_key_share(&secret, &round1_packages, &packages)
+                    .map_err(|err| err.to_string())?;
+                write_share(&self.share_file, &share)
+                    .map_err(|err| err.to_string())?;
+                let public = share.public.clone();
+                if let Ok(public_key) = public.public_key() {
+                    tracing::info!(
+                        identifier = ?share.identifier(),
+                        %public_key,
+                        "Generated a share of the protocol key"
+                    );
+                }
+                self.share = Some(share);
+                Ok(Response::PublicKey(public))
+            }
+        }
+    }
+
+    fn share(&self) -> Result<&KeyShare, String> {
+        self.share.as_ref().ok_or_else(|| {
+            "No key share, run the key generation first".to_string()
+        })
+    }
+}
+
+/// Run a share holder listening on the given socket. The key share is read
+/// from the share file, or written to it once the key generation completes.
+/// Every connection is served on its own thread, so that a stalled
+/// coordinator doesn't block the others.
+pub fn run_share_holder(socket: &Path, share_file: &Path) -> Result<(), Error> {
+    let share = if share_file.exists() {
+        let data = fs::read(share_file)
+            .map_err(|err| Error::ShareFile(share_file.to_owned(), err))?;
+        Some(serde_json::from_slice::<KeyShare>(&data)?)
+    } else {
+        None
+    };
+    match &share {
+        Some(share) => tracing::info!(
+            identifier = ?share.identifier(),
+            "Loaded the protocol key share"
+        ),
+        None => tracing::info!(
+            "No protocol key share found, waiting for the key generation"
+        ),
+    }
+    let holder = Arc::new(Mutex::new(ShareHolder {
+        share_file: share_file.to_owned(),
+        share,
+        nonces: VecDeque::new(),
+        dkg: None,
+    }));
+    let listener =
+        bind(socket).map_err(|err| Error::Io(socket.to_owned(), err))?;
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                tracing::warn!("Failed to accept a connection: {err}");
+                continue;
+            }
+        };
+        let holder = holder.clone();
+        std::thread::spawn(move || {
+            if let Err(err) = serve(&holder, stream) {
+                tracing::warn!("Share holder connection failed: {err}");
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Answer the requests of a single coordinator connection
+fn serve(holder: &Mutex<ShareHolder>, stream: Stream) -> io::Result<()> {
+    stream.set_read_timeout(Some(SHARE_HOLDER_TIMEOUT))?;
+    stream.set_write_timeout(Some(SHARE_HOLDER_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let response = match serde_json::from_str(&line?) {
+            Ok(request) => holder
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .handle(request)
+                .unwrap_or_else(|err| {
+                    tracing::warn!("Share holder request failed: {err}");
+                    Response::Error(err)
+                }),
+            Err(err) => Response::Error(err.to_string()),
+        };
+        let mut response = serde_json::to_vec(&response)?;
+        response.push(b'\n');
+        writer.write_all(&response)?;
+    }
+    Ok(())
+}
+
+/// Bind a listener on the socket, only accessible to the current user
+#[cfg(unix)]
+fn bind(socket: &Path) -> io::Result<Listener> {
+    use std::os::unix::fs::PermissionsExt;
+
+    // Remove a stale socket of a previous run
+    if socket.exists() {
+        fs::remove_file(socket)?;
+    }
+    let listener = Listener::bind(socket)?;
+    fs::set_permissions(socket, fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+#[cfg(not(unix))]
+fn bind(_socket: &Path) -> io::Result<Listener> {
+    Err(unsupported())
+}
+
+#[cfg(unix)]
+fn connect(socket: &Path) -> io::Result<Stream> {
+    Stream::connect(socket)
+}
+
+#[cfg(not(unix))]
+fn connect(_socket: &Path) -> io::Result<Stream> {
+    Err(unsupported())
+}
+
+#[cfg(not(unix))]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Threshold signing requires Unix domain sockets",
+    )
+}
+
+/// Write a new key share file, only readable by the current user
+fn write_share(path: &Path, share: &KeyShare) -> Result<(), Error> {
+    let share_err = |err| Error::ShareFile(path.to_owned(), err);
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path).map_err(share_err)?;
+    file.write_all(&serde_json::to_vec_pretty(share)?)
+        .map_err(share_err)
+}
+
+/// A coordinator's connection to a share holder
+struct Connection {
+    socket: PathBuf,
+    reader: BufReader<Stream>,
+    writer: Stream,
+    deadline: Instant,
+}
+
+impl Connection {
+    /// Connect to a share holder that must answer all the requests of this
+    /// connection before the deadline
+    fn open(socket: &Path, deadline: Instant) -> Result<Self, Error> {
+        let io_err = |err| Error::Io(socket.to_owned(), err);
+        let writer = connect(socket).map_err(io_err)?;
+        let reader = BufReader::new(writer.try_clone().map_err(io_err)?);
+        Ok(Self {
+            socket: socket.to_owned(),
+            reader,
+            writer,
+            deadline,
+        })
+    }
+
+    fn request(&mut self, request: &Request) -> Result<Response, Error> {
+        let io_err = |err| Error::Io(self.socket.clone(), err);
+        let timeout = self
+            .deadline
+            .checked_duration_since(Instant::now())
+            .filter(|timeout| !timeout.is_zero())
+            .ok_or_else(|| Error::Timeout(self.socket.clone()))?;
+        self.writer
+            .set_read_timeout(Some(timeout))
+            .map_err(io_err)?;
+        self.writer
+            .set_write_timeout(Some(timeout))
+            .map_err(io_err)?;
+        let mut message = serde_json::to_vec(request)?;
+        message.push(b'\n');
+        self.writer.write_all(&message).map_err(io_err)?;
+        let mut line = String::new();
+        self.reader.read_line(&mut line).map_err(|err| {
+            if matches!(
+                err.kind(),
+                io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+            ) {
+                Error::Timeout(self.socket.clone())
+            } else {
+                io_err(err)
+            }
+        })?;
+        match serde_json::from_str(&line)? {
+            Response::Error(err) => {
+                Err(Error::ShareHolder(self.socket.clone(), err))
+            }
+            response => Ok(response),
+        }
+    }
+
+    fn unexpected(&self) -> Error {
+        Error::UnexpectedResponse(self.socket.clone())
+    }
+}
+
+/// Apply `f` to every item on its own thread, returning the results in the
+/// order of the items. Used to talk to all the share holders at once, so
+/// that a slow share holder doesn't delay the others.
+fn concurrently<T, R>(items: Vec<T>, f: impl Fn(T) -> R + Sync) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+{
+    let f = &f;
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .into_iter()
+            .map(|item| scope.spawn(move || f(item)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle.join().expect("Share holder requests must not panic")
+            })
+            .collect()
+    })
+}
+
+/// Generate a threshold key between the share holders listening on the
+/// given sockets. The share holders are assigned identifiers in the order of
+/// their sockets. Returns the public data of the generated key.
+pub fn run_dkg(
+    sockets: &[PathBuf],
+    threshold: u16,
+) -> Result<ThresholdPublicKey, Error> {
+    let max_signers = u16::try_from(sockets.len())
+        .map_err(|_| Error::TooManyShareHolders(sockets.len()))?;
+    let deadline = Instant::now() + DKG_TIMEOUT;
+    let mut connections = sockets
+        .iter()
+        .map(|socket| Connection::open(socket, deadline))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut identifiers = vec![];
+    let mut round1_messages = BTreeMap::new();
+    for (index, connection) in (1..).zip(connections.iter_mut()) {
+        match connection.request(&Request::DkgPart1 {
+            index,
+            max_signers,
+            threshold,
+        })? {
+            Response::DkgRound1(identifier, message) => {
+                identifiers.push(identifier);
+                round1_messages.insert(identifier, message);
+            }
+            _ => return Err(connection.unexpected()),
+        }
+    }
+
+    // The second round packages are encrypted to their receivers, so the
+    // coordinator can only forward them
+    let mut round2_packages: BTreeMap<
+        Identifier,
+        BTreeMap<Identifier, EncryptedPackage>,
+    > = BTreeMap::new();
+    for (sender, connection) in identifiers.iter().zip(connections.iter_mut()) {
+        match connection.request(&Request::DkgPart2(round1_messages.clone()))? {
+            Response::DkgRound2(packages) => {
+                for (receiver, package) in packages {
+                    round2_packages
+                        .entry(receiver)
+                        .or_default()
+                        .insert(*sender, package);
+                }
+            }
+            _ => return Err(connection.unexpected()),
+        }
+    }
+
+    let mut public = None;
+    for (identifier, connection) in
+        identifiers.iter().zip(connections.iter_mut())
+    {
+        let packages = round2_packages.remove(identifier).unwrap_or_default();
+        match connection.request(&Request::DkgPart3(packages))? {
+            Response::PublicKey(package) => {
+                if public.get_or_insert_with(|| package.clone()) != &package {
+                    return Err(Error::InconsistentKeys);
+                }
+            }
+            _ => return Err(connection.unexpected()),
+        }
+    }
+    public.ok_or(Error::NotEnoughShareHolders {
+        available: 0,
+        threshold,
+    })
+}
+
+/// Signs with the protocol key by coordinating its share holders
+#[derive(Debug, Clone)]
+pub struct ThresholdSigner {
+    sockets: Vec<PathBuf>,
+    public: ThresholdPublicKey,
+}
+
+impl ThresholdSigner {
+    /// Connect to the share holders listening on the given sockets and check
+    /// that they hold shares of the same key. At least a threshold of them
+    /// must be available.
+    pub fn new(sockets: Vec<PathBuf>) -> Result<Self, Error> {
+        let deadline = Instant::now() + COORDINATOR_TIMEOUT;
+        let packages = concurrently(
+            sockets.iter().collect(),
+            |socket| -> Result<_, Error> {
+                let mut conn = Connection::open(socket, deadline)?;
+                match conn.request(&Request::PublicKey)? {
+                    Response::PublicKey(package) => Ok(package),
+                    _ => Err(conn.unexpected()),
+                }
+            },
+        );
+        let mut public: Option<ThresholdPublicKey> = None;
+        let mut available = 0;
+        for package in packages {
+            match package {
+                Ok(package) => {
+                    if public.get_or_insert_with(|| package.clone()) != &package
+                    {
+                        return Err(Error::InconsistentKeys);
+                    }
+                    available += 1;
+                }
+                Err(err) => tracing::warn!("{err}"),
+            }
+        }
+        let public = public.ok_or(Error::NotEnoughShareHolders {
+            available: 0,
+            threshold: 0,
+        })?;
+        if available < usize::from(public.threshold) {
+            return Err(Error::NotEnoughShareHolders {
+                available,
+                threshold: public.threshold,
+            });
+        }
+        Ok(Self { sockets, public })
+    }
+
+    /// The group public key that the signatures verify against
+    pub fn public_key(&self) -> common::PublicKey {
+        self.public
+            .public_key()
+            .expect("The group public key has been checked by the holders")
+    }
+
+    /// Sign the message with a threshold of the available share holders.
+    /// Fails if the share holders don't complete the signature within the
+    /// signing deadline.
+    pub fn sign(&self, message: &[u8]) -> Result<common::Signature, Error> {
+        let deadline = Instant::now() + SIGNING_DEADLINE;
+        let threshold = usize::from(self.public.threshold);
+        let committed = concurrently(
+            self.sockets.iter().collect(),
+            |socket| -> Result<_, Error> {
+                let mut conn = Connection::open(socket, deadline)?;
+                match conn.request(&Request::Commit)? {
+                    Response::Commitments(identifier, c) => {
+                        Ok((identifier, c, conn))
+                    }
+                    _ => Err(conn.unexpected()),
+                }
+            },
+        );
+        let mut signers = vec![];
+        let mut commitments = BTreeMap::new();
+        for result in committed {
+            match result {
+                // The share holders drop the nonces of extra commitments
+                // once they've committed to newer ones
+                Ok(_) if signers.len() == threshold => {}
+                Ok((identifier, c, conn)) => {
+                    if commitments.insert(identifier, c).is_some() {
+                        return Err(Error::InconsistentKeys);
+                    }
+                    signers.push((identifier, conn));
+                }
+                Err(err) => tracing::warn!("{err}"),
+            }
+        }
+        if signers.len() < threshold {
+            return Err(Error::NotEnoughShareHolders {
+                available: signers.len(),
+                threshold: self.public.threshold,
+            });
+        }
+        let package = SigningPackage::new(commitments, message);
+        let signature_shares = concurrently(
+            signers,
+            |(identifier, mut conn)| -> Result<_, Error> {
+                match conn.request(&Request::Sign(package.clone()))? {
+                    Response::SignatureShare(share) => Ok((identifier, share)),
+                    _ => Err(conn.unexpected()),
+                }
+            },
+        )
+        .into_iter()
+        .collect::<Result<BTreeMap<_, _>, Error>>()?;
+        Ok(aggregate(&package, &signature_shares, &self.public)?)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test_frost_service {
+    use namada::core::key::{SigScheme, SignableBytes};
+
+    use super::*;
+
+    /// Start the given number of share holders in the directory and wait
+    /// until they listen on their sockets
+    fn start_share_holders(dir: &Path, num: usize) -> Vec<PathBuf> {
+        let sockets: Vec<PathBuf> = (0..num)
+            .map(|ix| dir.join(format!("holder-{ix}.sock")))
+            .collect();
+        for (ix, socket) in sockets.iter().enumerate() {
+            let socket = socket.clone();
+            let share_file = dir.join(format!("holder-{ix}.json"));
+            std::thread::spawn(move || {
+                run_share_holder(&socket, &share_file).unwrap()
+            });
+        }
+        let deadline = Instant::now() + Duration::from_secs(5);
+        for socket in &sockets {
+            while connect(socket).is_err() {
+                assert!(Instant::now() < deadline, "Share holder didn't start");
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+        sockets
+    }
+
+    /// Test that the key is generated over the service and that any
+    /// threshold of the share holders then signs with it.
+    #[test]
+    fn test_dkg_and_signing_over_service() {
+        let dir = tempfile::tempdir().unwrap();
+        let sockets = start_share_holders(dir.path(), 3);
+
+        let public = run_dkg(&sockets, 2).unwrap();
+        assert_eq!(public.threshold, 2);
+        assert_eq!(public.max_signers(), 3);
+        for ix in 0..3 {
+            let share_file = dir.path().join(format!("holder-{ix}.json"));
+            let share: KeyShare =
+                serde_json::from_slice(&fs::read(share_file).unwrap()).unwrap();
+            assert_eq!(share.public, public);
+        }
+
+        let pk = public.public_key().unwrap();
+        let data = b"protocol tx".to_vec();
+        let message = data.signable_hash::<namada::core::hash::Sha256Hasher>();
+        for signers in [[0, 1], [0, 2], [1, 2]] {
+            let signer = ThresholdSigner::new(
+                signers.iter().map(|ix| sockets[*ix].clone()).collect(),
+            )
+            .unwrap();
+            assert_eq!(signer.public_key(), pk);
+            let sig = signer.sign(&message).unwrap();
+            common::SigScheme::verify_signature(&pk, &data, &sig).unwrap();
+        }
+
+        // A single share holder can't sign
+        assert!(matches!(
+            ThresholdSigner::new(vec![sockets[0].clone()]),
+            Err(Error::NotEnoughShareHolders { .. })
+        ));
+        // The existing shares are not replaced by a new key generation
+        assert!(matches!(run_dkg(&sockets, 2), Err(Error::ShareHolder(..))));
+    }
+
+    /// Test that a second round package of the key generation only opens
+    /// with the receiver's side of the key exchange with its sender.
+    #[test]
+    fn test_encrypted_round2_package() {
+        let ids = [identifier(1).unwrap(), identifier(2).unwrap()];
+        let (secret, _) =
+            frost_ed25519::keys::dkg::part1(ids[0], 2, 2, OsRng).unwrap();
+        let (_, other_package) =
+            frost_ed25519::keys::dkg::part1(ids[1], 2, 2, OsRng).unwrap();
+        let (_, packages) = frost_ed25519::keys::dkg::part2(
+            secret,
+            &[(ids[1], other_package)].into(),
+        )
+        .unwrap();
+        let package = &packages[&ids[1]];
+
+        let receiver = kex::EphemeralServerSession::new().unwrap();
+        let other = kex::EphemeralServerSession::new().unwrap();
+        let encrypted =
+            encrypt_package(package, receiver.public_key().as_ref()).unwrap();
+        let plaintext = serde_json::to_vec(package).unwrap();
+        assert!(
+            !encrypted
+                .ciphertext
+                .windows(plaintext.len())
+                .any(|window| window == plaintext)
+        );
+        assert!(decrypt_package(&encrypted, other).is_err());
+        assert_eq!(&decrypt_package(&encrypted, receiver).unwrap(), package);
+    }
+}
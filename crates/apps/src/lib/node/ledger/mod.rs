@@ -1,6 +1,7 @@
 mod abortable;
 mod broadcaster;
 pub mod ethereum_oracle;
+pub mod frost;
 pub mod shell;
 pub mod shims;
 pub mod storage;
@@ -115,6 +115,11 @@ where
         )?;
 
         if new_epoch {
+            // Warn about the changes of this validator's consensus key
+            if let Err(err) = self.check_consensus_key(current_epoch) {
+                tracing::error!("{err}");
+            }
+
             // Apply PoS and PGF inflation
             self.apply_inflation(current_epoch)?;
 
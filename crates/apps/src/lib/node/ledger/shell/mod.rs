@@ -36,7 +36,7 @@ use namada::core::address::Address;
 use namada::core::chain::ChainId;
 use namada::core::ethereum_events::EthereumEvent;
 use namada::core::key::*;
-use namada::core::storage::{BlockHeight, Key, TxIndex};
+use namada::core::storage::{BlockHeight, Epoch, Key, TxIndex};
 use namada::core::time::DateTimeUtc;
 use namada::core::{address, hints};
 use namada::ethereum_bridge::protocol::validation::bridge_pool_roots::validate_bp_roots_vext;
@@ -46,7 +46,7 @@ use namada::ledger::events::log::EventLog;
 use namada::ledger::events::Event;
 use namada::ledger::gas::{Gas, TxGasMeter};
 use namada::ledger::pos::namada_proof_of_stake::types::{
-    ConsensusValidator, ValidatorSetUpdate,
+    ConsensusKeyStatus, ConsensusValidator, ValidatorSetUpdate,
 };
 use namada::ledger::protocol::{
     apply_wasm_tx, get_fee_unshielding_transaction,
@@ -54,7 +54,9 @@ use namada::ledger::protocol::{
 };
 use namada::ledger::{parameters, protocol};
 use namada::parameters::validate_tx_bytes;
-use namada::proof_of_stake::storage::read_pos_params;
+use namada::proof_of_stake::storage::{
+    read_pos_params, validator_protocol_key_handle,
+};
 use namada::state::tx_queue::{ExpiredTx, TxInQueue};
 use namada::state::{
     DBIter, FullAccessState, Sha256Hasher, StorageHasher, StorageRead,
@@ -63,7 +65,7 @@ use namada::state::{
 use namada::token;
 pub use namada::tx::data::ResultCode;
 use namada::tx::data::{DecryptedTx, TxType, WrapperTx, WrapperTxErr};
-use namada::tx::{Section, Tx};
+use namada::tx::{Section, Signed, Tx};
 use namada::vm::wasm::{TxCache, VpCache};
 use namada::vm::{WasmCacheAccess, WasmCacheRwAccess};
 use namada::vote_ext::EthereumTxData;
@@ -73,6 +75,7 @@ use thiserror::Error;
 use tokio::sync::mpsc::{Receiver, UnboundedSender};
 
 use super::ethereum_oracle::{self as oracle, last_processed_block};
+use super::frost::service::ThresholdSigner;
 use crate::config::{self, genesis, TendermintMode, ValidatorLocalConfig};
 use crate::facade::tendermint::v0_37::abci::{request, response};
 use crate::facade::tendermint::{self, validator};
@@ -123,6 +126,12 @@ pub enum Error {
     Storage(#[from] namada::state::StorageError),
     #[error("Transaction replay attempt: {0}")]
     ReplayAttempt(String),
+    #[error(
+        "The consensus key {0} of validator {1} has been replaced by the \
+         consensus key {2}. Give CometBFT the new consensus key and make sure \
+         that the old one is not used by any node."
+    )]
+    ConsensusKeyRotatedOut(common::PublicKey, Address, common::PublicKey),
 }
 
 impl From<Error> for TxResult {
@@ -172,6 +181,11 @@ pub(super) enum ShellMode {
         broadcast_sender: UnboundedSender<Vec<u8>>,
         eth_oracle: Option<EthereumOracleChannels>,
         local_config: Option<ValidatorLocalConfig>,
+        /// Signs with a threshold protocol key in place of the protocol
+        /// keypair, if configured
+        threshold_signer: Option<ThresholdSigner>,
+        /// The consensus key that CometBFT signs with, if found
+        consensus_key: Option<common::PublicKey>,
     },
     Full,
     Seed,
@@ -279,6 +293,91 @@ impl ShellMode {
         }
     }
 
+    /// Whether this validator's protocol key is a threshold key split between
+    /// share holders
+    fn has_threshold_protocol_key(&self) -> bool {
+        matches!(
+            self,
+            ShellMode::Validator {
+                local_config: Some(config),
+                ..
+            } if !config.protocol_key_shares.is_empty()
+        )
+    }
+
+    /// Sign data with this validator's protocol key, which is either the
+    /// local protocol keypair or a threshold key split between share holders.
+    /// Returns `None` if the share holders fail to sign or are unavailable.
+    pub fn sign_protocol_data<T, S: Signable<T>>(
+        &self,
+        data: T,
+    ) -> Option<Signed<T, S>> {
+        match self {
+            ShellMode::Validator {
+                threshold_signer: Some(signer),
+                ..
+            } => {
+                let to_sign =
+                    S::as_signable(&data).signable_hash::<S::Hasher>();
+                match signer.sign(&to_sign) {
+                    Ok(sig) => Some(Signed::new_from(data, sig)),
+                    Err(err) => {
+                        tracing::error!(
+                            "Failed to sign with the threshold protocol key: \
+                             {err}"
+                        );
+                        None
+                    }
+                }
+            }
+            _ if self.has_threshold_protocol_key() => {
+                tracing::error!(
+                    "The threshold protocol key is unavailable, can't sign"
+                );
+                None
+            }
+            _ => self
+                .get_protocol_key()
+                .map(|protocol_key| Signed::new(protocol_key, data)),
+        }
+    }
+
+    /// Sign an Ethereum bridge protocol tx with this validator's protocol
+    /// key. Returns `None` if the share holders of a threshold key fail to
+    /// sign or are unavailable.
+    pub fn sign_protocol_tx(
+        &self,
+        protocol_tx: &EthereumTxData,
+        chain_id: ChainId,
+    ) -> Option<Tx> {
+        match self {
+            ShellMode::Validator {
+                threshold_signer: Some(signer),
+                ..
+            } => protocol_tx
+                .sign_with(signer.public_key(), chain_id, |hash| {
+                    signer.sign(&hash.signable_hash::<Sha256Hasher>())
+                })
+                .map_err(|err| {
+                    tracing::error!(
+                        "Failed to sign a protocol tx with the threshold \
+                         protocol key: {err}"
+                    );
+                })
+                .ok(),
+            _ if self.has_threshold_protocol_key() => {
+                tracing::error!(
+                    "The threshold protocol key is unavailable, can't sign a \
+                     protocol tx"
+                );
+                None
+            }
+            _ => self
+                .get_protocol_key()
+                .map(|protocol_key| protocol_tx.sign(protocol_key, chain_id)),
+        }
+    }
+
     /// Get the Ethereum bridge keypair for this validator.
     #[cfg_attr(not(test), allow(dead_code))]
     pub fn get_eth_bridge_keypair(&self) -> Option<&common::SecretKey> {
@@ -400,6 +499,8 @@ where
         vp_wasm_compilation_cache: u64,
         tx_wasm_compilation_cache: u64,
     ) -> Self {
+        #[cfg(not(test))]
+        let cometbft_dir = config.cometbft_dir();
         let chain_id = config.chain_id;
         let db_path = config.shell.db_dir(&chain_id);
         let base_dir = config.shell.base_dir;
@@ -471,6 +572,33 @@ where
                             None
                         };
 
+                    let threshold_signer = validator_local_config
+                        .as_ref()
+                        .filter(|config| !config.protocol_key_shares.is_empty())
+                        .and_then(|config| {
+                            ThresholdSigner::new(
+                                config.protocol_key_shares.clone(),
+                            )
+                            .map_err(|err| {
+                                tracing::error!(
+                                    "Failed to connect to the protocol key \
+                                     share holders, the validator can't sign \
+                                     with its protocol key: {err}"
+                                );
+                            })
+                            .ok()
+                        });
+
+                    let consensus_key =
+                        tendermint_node::read_validator_key(&cometbft_dir)
+                            .unwrap_or_else(|err| {
+                                tracing::warn!(
+                                    "Couldn't read the consensus key of the \
+                                     validator: {err}"
+                                );
+                                None
+                            });
+
                     wallet
                         .take_validator_data()
                         .map(|data| ShellMode::Validator {
@@ -478,6 +606,8 @@ where
                             broadcast_sender,
                             eth_oracle,
                             local_config: validator_local_config,
+                            threshold_signer,
+                            consensus_key,
                         })
                         .expect(
                             "Validator data should have been stored in the \
@@ -500,6 +630,8 @@ where
                         broadcast_sender,
                         eth_oracle,
                         local_config: None,
+                        threshold_signer: None,
+                        consensus_key: None,
                     }
                 }
             }
@@ -527,6 +659,14 @@ where
             event_log: EventLog::default(),
         };
         shell.update_eth_oracle(&Default::default());
+        // Refuse to start with a consensus key that has been rotated out, as
+        // the validator's new key may be signing on another node
+        let current_epoch = shell.state.in_mem().last_epoch;
+        if let Err(err) = shell.check_consensus_key(current_epoch) {
+            tracing::error!("{err}");
+            panic!("{err}");
+        }
+        shell.check_threshold_protocol_key(current_epoch);
         shell
     }
 
@@ -542,6 +682,125 @@ where
         &mut self.event_log
     }
 
+    /// Check the consensus key that CometBFT signs with against the changes of
+    /// the validator's consensus key. Logs a warning when the key is about to
+    /// be rotated in or out and returns an error if it has been rotated out.
+    fn check_consensus_key(&self, current_epoch: Epoch) -> Result<()> {
+        let consensus_key = match &self.mode {
+            ShellMode::Validator {
+                consensus_key: Some(consensus_key),
+                ..
+            } => consensus_key,
+            _ => return Ok(()),
+        };
+        // Nothing to check before the chain is initialized
+        if self.state.in_mem().get_state().is_none() {
+            return Ok(());
+        }
+        let status = namada::proof_of_stake::queries::consensus_key_status(
+            &self.state,
+            consensus_key,
+            current_epoch,
+        )?;
+        match status {
+            None => {
+                tracing::warn!(
+                    "The consensus key {consensus_key} of this node is not a \
+                     validator's consensus key"
+                );
+            }
+            Some((_validator, ConsensusKeyStatus::Active)) => {}
+            Some((
+                validator,
+                ConsensusKeyStatus::RotatingOut {
+                    switch_epoch,
+                    new_key,
+                },
+            )) => {
+                tracing::warn!(
+                    "The consensus key {consensus_key} of validator \
+                     {validator} will be replaced by the consensus key \
+                     {new_key} in epoch {switch_epoch}. Give CometBFT the new \
+                     key once the epoch begins and make sure that the old key \
+                     is not used by any node after that."
+                );
+            }
+            Some((
+                validator,
+                ConsensusKeyStatus::RotatingIn { switch_epoch },
+            )) => {
+                tracing::warn!(
+                    "The consensus key {consensus_key} of this node only \
+                     becomes the consensus key of validator {validator} in \
+                     epoch {switch_epoch}. The node won't sign any blocks \
+                     before then."
+                );
+            }
+            Some((validator, ConsensusKeyStatus::RotatedOut { new_key })) => {
+                return Err(Error::ConsensusKeyRotatedOut(
+                    consensus_key.clone(),
+                    validator,
+                    new_key,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the group public key of the threshold protocol key against the
+    /// validator's protocol key on chain. On a mismatch, the threshold signer
+    /// is dropped, as its signatures would be rejected.
+    fn check_threshold_protocol_key(&mut self, current_epoch: Epoch) {
+        let ShellMode::Validator {
+            data,
+            threshold_signer,
+            ..
+        } = &mut self.mode
+        else {
+            return;
+        };
+        let Some(threshold_key) =
+            threshold_signer.as_ref().map(ThresholdSigner::public_key)
+        else {
+            return;
+        };
+        // Nothing to check before the chain is initialized
+        if self.state.in_mem().get_state().is_none() {
+            return;
+        }
+        let protocol_key = read_pos_params(&self.state).and_then(|params| {
+            validator_protocol_key_handle(&data.address).get(
+                &self.state,
+                current_epoch,
+                &params,
+            )
+        });
+        match protocol_key {
+            Ok(Some(protocol_key)) if protocol_key == threshold_key => {}
+            Ok(Some(protocol_key)) => {
+                tracing::error!(
+                    "The threshold protocol key {threshold_key} doesn't match \
+                     the protocol key {protocol_key} of validator {}, the \
+                     validator can't sign with its protocol key",
+                    data.address,
+                );
+                *threshold_signer = None;
+            }
+            Ok(None) => {
+                tracing::warn!(
+                    "Validator {} has no protocol key on chain",
+                    data.address
+                );
+            }
+            Err(err) => {
+                tracing::error!(
+                    "Failed to read the protocol key of validator {}: {err}",
+                    data.address
+                );
+            }
+        }
+    }
+
     /// Iterate over the wrapper txs in order
     #[allow(dead_code)]
     fn iter_tx_queue(&mut self) -> impl Iterator<Item = &TxInQueue> {
@@ -704,15 +963,10 @@ where
 
         let ext = self.craft_extension();
 
-        let protocol_key = self
-            .mode
-            .get_protocol_key()
-            .expect("Validators should have protocol keys");
-
-        let protocol_txs = iter_protocol_txs(ext).map(|protocol_tx| {
-            protocol_tx
-                .sign(protocol_key, self.chain_id.clone())
-                .to_bytes()
+        let protocol_txs = iter_protocol_txs(ext).filter_map(|protocol_tx| {
+            self.mode
+                .sign_protocol_tx(&protocol_tx, self.chain_id.clone())
+                .map(|tx| tx.to_bytes())
         });
 
         for tx in protocol_txs {
@@ -741,18 +995,17 @@ where
             return;
         }
         if let Some(vote_extension) = self.sign_ethereum_events(eth_events) {
-            let protocol_key = self
-                .mode
-                .get_protocol_key()
-                .expect("Validators should have protocol keys");
-
-            let signed_tx = EthereumTxData::EthEventsVext(
-                namada::vote_ext::ethereum_events::SignedVext(vote_extension),
-            )
-            .sign(protocol_key, self.chain_id.clone())
-            .to_bytes();
-
-            self.mode.broadcast(signed_tx);
+            let signed_tx = self.mode.sign_protocol_tx(
+                &EthereumTxData::EthEventsVext(
+                    namada::vote_ext::ethereum_events::SignedVext(
+                        vote_extension,
+                    ),
+                ),
+                self.chain_id.clone(),
+            );
+            if let Some(signed_tx) = signed_tx {
+                self.mode.broadcast(signed_tx.to_bytes());
+            }
         }
     }
 
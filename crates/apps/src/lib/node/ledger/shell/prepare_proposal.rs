@@ -1177,6 +1177,7 @@ mod test_prepare_proposal {
                     namada::core::address::testing::nam(),
                     Amount::from(1),
                 )]),
+                protocol_key_shares: vec![],
             });
         }
 
@@ -1283,6 +1284,7 @@ mod test_prepare_proposal {
                     namada::core::address::testing::nam(),
                     Amount::from(100),
                 )]),
+                protocol_key_shares: vec![],
             });
         }
 
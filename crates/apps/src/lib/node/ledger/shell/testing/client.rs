@@ -21,6 +21,7 @@ pub fn run(
             chain_id: Some(locked.chain_id.clone()),
             base_dir: locked.base_dir.clone(),
             wasm_dir: Some(locked.wasm_dir.clone()),
+            shielded_context: None,
         }
     };
     let ctx = Context::new::<TestingIo>(global.clone())?;
@@ -4,8 +4,8 @@ pub mod bridge_pool_vext;
 pub mod eth_events;
 pub mod val_set_update;
 
-use namada::ethereum_bridge::protocol::transactions::bridge_pool_roots::sign_bridge_pool_root;
-use namada::ethereum_bridge::protocol::transactions::ethereum_events::sign_ethereum_events;
+use namada::ethereum_bridge::protocol::transactions::bridge_pool_roots::bridge_pool_root_vext;
+use namada::ethereum_bridge::protocol::transactions::ethereum_events::ethereum_events_vext;
 use namada::ethereum_bridge::protocol::transactions::validator_set_update::sign_validator_set_update;
 pub use namada::ethereum_bridge::protocol::validation::VoteExtensionError;
 use namada::tx::Signed;
@@ -57,17 +57,9 @@ where
             .mode
             .get_validator_address()
             .expect(VALIDATOR_EXPECT_MSG);
-        let protocol_key = match &self.mode {
-            ShellMode::Validator { data, .. } => &data.keys.protocol_keypair,
-            _ => unreachable!("{VALIDATOR_EXPECT_MSG}"),
-        };
-        sign_ethereum_events(
-            &self.state,
-            validator_addr,
-            protocol_key,
-            ethereum_events,
-        )
-        .map(|ethereum_events::SignedVext(ext)| ext)
+        let ext =
+            ethereum_events_vext(&self.state, validator_addr, ethereum_events)?;
+        self.mode.sign_protocol_data(ext)
     }
 
     /// Extend PreCommit votes with [`bridge_pool_roots::Vext`] instances.
@@ -82,17 +74,9 @@ where
             .mode
             .get_eth_bridge_keypair()
             .expect(VALIDATOR_EXPECT_MSG);
-        let protocol_key = match &self.mode {
-            ShellMode::Validator { data, .. } => &data.keys.protocol_keypair,
-            _ => unreachable!("{VALIDATOR_EXPECT_MSG}"),
-        };
-        sign_bridge_pool_root(
-            &self.state,
-            validator_addr,
-            eth_hot_key,
-            protocol_key,
-        )
-        .map(|bridge_pool_roots::SignedVext(ext)| ext)
+        let ext =
+            bridge_pool_root_vext(&self.state, validator_addr, eth_hot_key)?;
+        self.mode.sign_protocol_data(ext)
     }
 
     /// Extend PreCommit votes with [`validator_set_update::Vext`]
@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::str::FromStr;
 
+use borsh::BorshDeserialize;
 use borsh_ext::BorshSerializeExt;
 use namada::core::chain::ChainId;
 use namada::core::key::*;
@@ -48,6 +49,8 @@ pub enum Error {
     RollBack(String),
     #[error("Failed to convert to String: {0:?}")]
     TendermintPath(std::ffi::OsString),
+    #[error("Couldn't read {0}")]
+    CantRead(String),
     #[error("Couldn't write {0}")]
     CantWrite(String),
     #[error("Couldn't create {0}")]
@@ -305,6 +308,60 @@ pub fn write_validator_key(
     write_validator(validator_key(home_dir), KEY_DIR, KEY_FILE, key)
 }
 
+/// Write a validator's new consensus key for Tendermint next to the key in
+/// use, to replace it once the change of the consensus key takes effect.
+/// Returns the path of the written key file.
+pub fn write_next_validator_key(
+    home_dir: impl AsRef<Path>,
+    consensus_key: &common::SecretKey,
+) -> Result<PathBuf> {
+    let key = validator_key_to_json(consensus_key).unwrap();
+    let path = next_validator_key(home_dir);
+    write_validator(path.clone(), KEY_DIR, KEY_FILE, key)?;
+    Ok(path)
+}
+
+/// Read the public consensus key of the validator private key for Tendermint,
+/// if there is one
+pub fn read_validator_key(
+    home_dir: impl AsRef<Path>,
+) -> Result<Option<common::PublicKey>> {
+    let path = validator_key(home_dir);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let cant_read = |err: String| {
+        Error::CantRead(format!(
+            "{} at {}. Caused by {err}",
+            KEY_FILE,
+            path.to_string_lossy()
+        ))
+    };
+    let file =
+        std::fs::File::open(&path).map_err(|err| cant_read(err.to_string()))?;
+    let key: serde_json::Value = serde_json::from_reader(file)
+        .map_err(|err| cant_read(err.to_string()))?;
+    let pk_bytes = key["pub_key"]["value"]
+        .as_str()
+        .and_then(|value| base64::decode(value).ok())
+        .ok_or_else(|| cant_read("invalid public key value".to_string()))?;
+    let pk = match key["pub_key"]["type"].as_str() {
+        Some("tendermint/PubKeyEd25519") => {
+            ed25519::PublicKey::try_from_slice(&pk_bytes)
+                .map(common::PublicKey::Ed25519)
+        }
+        Some("tendermint/PubKeySecp256k1") => {
+            secp256k1::PublicKey::try_from_slice(&pk_bytes)
+                .map(common::PublicKey::Secp256k1)
+        }
+        _ => {
+            return Err(cant_read("unknown public key type".to_string()));
+        }
+    }
+    .map_err(|err| cant_read(err.to_string()))?;
+    Ok(Some(pk))
+}
+
 /// Initialize validator private state for Tendermint
 pub fn write_validator_state(home_dir: impl AsRef<Path>) -> Result<()> {
     let state = json!({
@@ -507,6 +564,13 @@ fn validator_key(home_dir: impl AsRef<Path>) -> PathBuf {
         .join("priv_validator_key.json")
 }
 
+fn next_validator_key(home_dir: impl AsRef<Path>) -> PathBuf {
+    home_dir
+        .as_ref()
+        .join("config")
+        .join("next_priv_validator_key.json")
+}
+
 fn validator_state(home_dir: impl AsRef<Path>) -> PathBuf {
     home_dir
         .as_ref()
@@ -0,0 +1,176 @@
+//! The wallet agent, serving the keys unlocked by the wallet for a limited
+//! time. See [`namada_sdk::wallet::agent`] for its clients.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use namada_sdk::wallet::agent::{
+    AgentError, AgentRequest, AgentResponse, UnlockedKeyInfo,
+};
+use zeroize::Zeroizing;
+
+/// The read timeout of the clients' connections
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A key held unlocked by the agent
+struct Entry {
+    alias: String,
+    decrypted: Zeroizing<String>,
+    expires_at: Instant,
+}
+
+/// The state of the agent, mapping the encrypted keys to their decrypted
+/// form
+struct Agent {
+    default_lifetime: Duration,
+    keys: HashMap<String, Entry>,
+}
+
+impl Agent {
+    fn handle(&mut self, request: AgentRequest) -> AgentResponse {
+        // Lock the expired keys first
+        let now = Instant::now();
+        self.keys.retain(|_, entry| entry.expires_at > now);
+        match request {
+            AgentRequest::Get { encrypted } => AgentResponse::Key(
+                self.keys
+                    .get(&encrypted)
+                    .map(|entry| entry.decrypted.to_string()),
+            ),
+            AgentRequest::Unlock { keys, lifetime } => {
+                let expires_at =
+                    now + lifetime.unwrap_or(self.default_lifetime);
+                for key in keys {
+                    tracing::info!(alias = key.alias, "Unlocked a key");
+                    self.keys.insert(
+                        key.encrypted.clone(),
+                        Entry {
+                            alias: key.alias.clone(),
+                            decrypted: Zeroizing::new(key.decrypted.clone()),
+                            expires_at,
+                        },
+                    );
+                }
+                AgentResponse::Done
+            }
+            AgentRequest::Lock => {
+                tracing::info!("Locked all the keys");
+                self.keys.clear();
+                AgentResponse::Done
+            }
+            AgentRequest::List => {
+                let mut keys: Vec<_> = self
+                    .keys
+                    .values()
+                    .map(|entry| UnlockedKeyInfo {
+                        alias: entry.alias.clone(),
+                        expires_in: entry.expires_at - now,
+                    })
+                    .collect();
+                keys.sort_by(|a, b| a.alias.cmp(&b.alias));
+                AgentResponse::Keys(keys)
+            }
+        }
+    }
+}
+
+/// Run the wallet agent on the given socket. Keys are unlocked for the given
+/// default time, unless a client asks for another time.
+pub fn run_agent(
+    socket: &Path,
+    default_lifetime: Duration,
+) -> Result<(), AgentError> {
+    let io_err = |err| AgentError::Io(socket.to_owned(), err);
+    // Remove a stale socket of a previous run
+    if socket.exists() {
+        fs::remove_file(socket).map_err(io_err)?;
+    }
+    let listener = UnixListener::bind(socket).map_err(io_err)?;
+    // Only the current user may talk to the agent
+    fs::set_permissions(socket, fs::Permissions::from_mode(0o600))
+        .map_err(io_err)?;
+
+    let mut agent = Agent {
+        default_lifetime,
+        keys: HashMap::new(),
+    };
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                tracing::warn!("Failed to accept a connection: {err}");
+                continue;
+            }
+        };
+        if let Err(err) = serve(&mut agent, stream) {
+            tracing::warn!("Wallet agent connection failed: {err}");
+        }
+    }
+    Ok(())
+}
+
+/// Answer the requests of a single client connection
+fn serve(agent: &mut Agent, stream: UnixStream) -> io::Result<()> {
+    // Don't let an idle client block the others
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = Zeroizing::new(line?);
+        let response = match serde_json::from_str(&line) {
+            Ok(request) => agent.handle(request),
+            Err(err) => AgentResponse::Error(err.to_string()),
+        };
+        let mut response = Zeroizing::new(serde_json::to_vec(&response)?);
+        response.push(b'\n');
+        writer.write_all(&response)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_sdk::wallet::agent::{AgentClient, UnlockedKey};
+
+    use super::*;
+
+    #[test]
+    fn test_agent_unlock_and_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("agent.sock");
+        let agent_socket = socket.clone();
+        std::thread::spawn(move || {
+            run_agent(&agent_socket, Duration::from_secs(3600))
+        });
+        // Wait for the agent to listen
+        while !socket.exists() {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let client = AgentClient::new(&socket);
+
+        let key = |alias: &str| UnlockedKey {
+            alias: alias.to_string(),
+            encrypted: format!("{alias}-encrypted"),
+            decrypted: format!("{alias}-decrypted"),
+        };
+        client.unlock(vec![key("a")], None).unwrap();
+        // Expires immediately
+        client.unlock(vec![key("b")], Some(Duration::ZERO)).unwrap();
+
+        assert_eq!(
+            client.get_key("a-encrypted").unwrap().as_deref(),
+            Some(&"a-decrypted".to_string())
+        );
+        assert!(client.get_key("b-encrypted").unwrap().is_none());
+        let keys = client.list().unwrap();
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].alias, "a");
+
+        client.lock().unwrap();
+        assert!(client.get_key("a-encrypted").unwrap().is_none());
+    }
+}
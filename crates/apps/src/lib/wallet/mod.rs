@@ -1,3 +1,5 @@
+#[cfg(unix)]
+pub mod agent;
 pub mod defaults;
 pub mod pre_genesis;
 mod store;
@@ -8,6 +10,8 @@ use std::{env, fs};
 
 use namada::bip39::{Language, Mnemonic};
 use namada::core::key::*;
+#[cfg(unix)]
+use namada_sdk::wallet::agent::AgentClient;
 pub use namada_sdk::wallet::alias::Alias;
 use namada_sdk::wallet::fs::FsWalletStorage;
 use namada_sdk::wallet::store::Store;
@@ -77,6 +81,15 @@ impl WalletIo for CliWalletUtils {
         pwd
     }
 
+    #[cfg(unix)]
+    fn read_unlocked_key(encrypted: &str) -> Option<Zeroizing<String>> {
+        let client = AgentClient::from_env()?;
+        client.get_key(encrypted).unwrap_or_else(|err| {
+            eprintln!("Failed to read a key from the wallet agent: {err}");
+            None
+        })
+    }
+
     fn read_alias(prompt_msg: &str) -> String {
         print!("Choose an alias for {}: ", prompt_msg);
         io::stdout().flush().unwrap();
@@ -125,6 +125,11 @@ impl From<raw::Address<'_, raw::Validated>> for Address {
                 InternalAddress::IbcToken(IbcTokenHash(*raw_addr.data())),
             ),
             raw::Discriminant::Masp => Address::Internal(InternalAddress::Masp),
+            raw::Discriminant::PosShareToken => Address::Internal(
+                InternalAddress::PosShareToken(EstablishedAddress {
+                    hash: *raw_addr.data(),
+                }),
+            ),
         }
     }
 }
@@ -219,6 +224,14 @@ impl<'addr> From<&'addr Address> for raw::Address<'addr, raw::Validated> {
                     .validate()
                     .expect("This raw address is valid")
             }
+            Address::Internal(InternalAddress::PosShareToken(
+                EstablishedAddress { hash },
+            )) => raw::Address::from_discriminant(
+                raw::Discriminant::PosShareToken,
+            )
+            .with_data_array_ref(hash)
+            .validate()
+            .expect("This raw address is valid"),
         }
     }
 }
@@ -543,6 +556,8 @@ pub enum InternalAddress {
     Pgf,
     /// Masp
     Masp,
+    /// Share token of liquid bonds to the validator with the given address
+    PosShareToken(EstablishedAddress),
 }
 
 impl Display for InternalAddress {
@@ -564,6 +579,10 @@ impl Display for InternalAddress {
                 Self::Multitoken => "Multitoken".to_string(),
                 Self::Pgf => "PublicGoodFundings".to_string(),
                 Self::Masp => "MASP".to_string(),
+                Self::PosShareToken(validator) => format!(
+                    "PosShareToken: {}",
+                    Address::Established(validator.clone())
+                ),
             }
         )
     }
@@ -788,6 +807,7 @@ pub mod testing {
             InternalAddress::Nut(_) => {}
             InternalAddress::Pgf => {}
             InternalAddress::Masp => {}
+            InternalAddress::PosShareToken(_) => {}
             InternalAddress::Multitoken => {} /* Add new addresses in the
                                                * `prop_oneof` below. */
         };
@@ -805,6 +825,7 @@ pub mod testing {
             Just(InternalAddress::Multitoken),
             Just(InternalAddress::Pgf),
             Just(InternalAddress::Masp),
+            arb_established_address().prop_map(InternalAddress::PosShareToken),
         ]
     }
 
@@ -65,6 +65,8 @@ pub enum Discriminant {
     IbcToken = 13,
     /// MASP raw address.
     Masp = 14,
+    /// PoS share token raw address.
+    PosShareToken = 15,
 }
 
 /// Raw address representation.
@@ -114,7 +116,8 @@ impl<S> Address<'_, S> {
                 | Discriminant::Established
                 | Discriminant::Erc20
                 | Discriminant::Nut
-                | Discriminant::IbcToken,
+                | Discriminant::IbcToken
+                | Discriminant::PosShareToken,
         )
     }
 }
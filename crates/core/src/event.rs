@@ -61,6 +61,8 @@ pub enum EventType {
     PgfPayment,
     /// Ethereum Bridge event
     EthereumBridge,
+    /// The change of a validator's commission rate
+    CommissionChange,
 }
 
 impl Display for EventType {
@@ -72,6 +74,7 @@ impl Display for EventType {
             EventType::Proposal => write!(f, "proposal"),
             EventType::PgfPayment => write!(f, "pgf_payment"),
             EventType::EthereumBridge => write!(f, "ethereum_bridge"),
+            EventType::CommissionChange => write!(f, "commission_change"),
         }?;
         Ok(())
     }
@@ -93,6 +96,7 @@ impl FromStr for EventType {
                 Ok(EventType::Ibc("write_acknowledgement".to_string()))
             }
             "ethereum_bridge" => Ok(EventType::EthereumBridge),
+            "commission_change" => Ok(EventType::CommissionChange),
             _ => Err(EventError::InvalidEventType),
         }
     }
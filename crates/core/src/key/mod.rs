@@ -443,6 +443,72 @@ impl Signable<KeccakHash> for SignableEthMessage {
     }
 }
 
+/// Tag type that indicates we should sign over the EIP-712 typed data
+/// encoding of a hash in a [`Signable`] wrapper. This is the digest
+/// Ethereum wallets sign through `eth_signTypedData_v4`.
+///
+/// The signed hash is the SHA-256 hash of the tx header, which is what the
+/// other signatures of the tx commit to as well. The typed data given to the
+/// wallet is:
+///
+/// ```json
+/// {
+///   "types": {
+///     "EIP712Domain": [
+///       { "name": "name", "type": "string" },
+///       { "name": "version", "type": "string" }
+///     ],
+///     "NamadaTx": [{ "name": "txHash", "type": "bytes32" }]
+///   },
+///   "primaryType": "NamadaTx",
+///   "domain": { "name": "Namada", "version": "1" },
+///   "message": { "txHash": "0x<hex of the tx header hash>" }
+/// }
+/// ```
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct SignableEthTypedData;
+
+impl SignableEthTypedData {
+    /// The name field of the EIP-712 domain
+    pub const DOMAIN_NAME: &'static str = "Namada";
+    /// The EIP-712 type of the domain separator
+    pub const DOMAIN_TYPE: &'static str =
+        "EIP712Domain(string name,string version)";
+    /// The version field of the EIP-712 domain
+    pub const DOMAIN_VERSION: &'static str = "1";
+    /// The EIP-712 type of the signed message
+    pub const MESSAGE_TYPE: &'static str = "NamadaTx(bytes32 txHash)";
+
+    /// Compute the EIP-712 domain separator of Namada transactions.
+    pub fn domain_separator() -> KeccakHash {
+        let mut encoded = Vec::with_capacity(3 * 32);
+        encoded.extend_from_slice(&keccak_hash(Self::DOMAIN_TYPE).0);
+        encoded.extend_from_slice(&keccak_hash(Self::DOMAIN_NAME).0);
+        encoded.extend_from_slice(&keccak_hash(Self::DOMAIN_VERSION).0);
+        keccak_hash(encoded)
+    }
+}
+
+impl Signable<crate::hash::Hash> for SignableEthTypedData {
+    type Hasher = KeccakHasher;
+    type Output = KeccakHash;
+
+    fn as_signable(hash: &crate::hash::Hash) -> KeccakHash {
+        let struct_hash = keccak_hash({
+            let mut encoded = Vec::with_capacity(2 * 32);
+            encoded.extend_from_slice(&keccak_hash(Self::MESSAGE_TYPE).0);
+            encoded.extend_from_slice(&hash.0);
+            encoded
+        });
+        keccak_hash({
+            let mut eth_message = Vec::from("\x19\x01");
+            eth_message.extend_from_slice(&Self::domain_separator().0);
+            eth_message.extend_from_slice(&struct_hash.0);
+            eth_message
+        })
+    }
+}
+
 /// Helper trait to compress arbitrary bytes to a hash value,
 /// which can be signed over.
 pub trait SignableBytes: Sized + AsRef<[u8]> {
@@ -469,6 +469,21 @@ impl Signature {
         let r = self.0.r();
         (r.to_bytes().into(), s.to_bytes().into(), v + Self::V_FIX)
     }
+
+    /// Parse a signature produced by an Ethereum wallet, encoded as the
+    /// concatenation of its `r`, `s` and `v` parameters.
+    ///
+    /// Both the legacy range [27, 28] and the range [0, 1] of `v` are
+    /// accepted.
+    pub fn try_from_eth_rsv(
+        sig: &[u8; SIGNATURE_SIZE],
+    ) -> Result<Self, ParseSignatureError> {
+        let mut sig = *sig;
+        if sig[64] >= Self::V_FIX {
+            sig[64] -= Self::V_FIX;
+        }
+        Self::try_from(&sig)
+    }
 }
 
 impl Encode<1> for Signature {
@@ -657,4 +672,23 @@ mod test {
             .expect("Test failed");
         assert_eq!(sig, signature);
     }
+
+    /// Test that a signature encoded as Ethereum `rsv` parameters
+    /// can be parsed back and verified.
+    #[test]
+    fn test_roundtrip_eth_rsv() {
+        let sk_bytes = HEXLOWER.decode(SECRET_KEY_HEX.as_bytes()).unwrap();
+        let sk = SecretKey::try_from_slice(&sk_bytes[..]).unwrap();
+        let to_sign = "test".as_bytes();
+        let signature = SigScheme::sign(&sk, to_sign);
+        let (r, s, v) = signature.into_eth_rsv();
+        let mut rsv = [0u8; SIGNATURE_SIZE];
+        rsv[..32].copy_from_slice(&r);
+        rsv[32..64].copy_from_slice(&s);
+        rsv[64] = v;
+        let sig = Signature::try_from_eth_rsv(&rsv).expect("Test failed");
+        assert!(
+            SigScheme::verify_signature(&sk.ref_to(), &to_sign, &sig).is_ok()
+        );
+    }
 }
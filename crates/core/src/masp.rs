@@ -8,6 +8,7 @@ use borsh_ext::BorshSerializeExt;
 use masp_primitives::asset_type::AssetType;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use thiserror::Error;
 
 use crate::address::{Address, DecodeError, HASH_HEX_LEN, MASP};
 use crate::impl_display_and_from_str_via_format;
@@ -16,7 +17,8 @@ use crate::string_encoding::{
     self, MASP_EXT_FULL_VIEWING_KEY_HRP, MASP_EXT_SPENDING_KEY_HRP,
     MASP_PAYMENT_ADDRESS_HRP,
 };
-use crate::token::{Denomination, MaspDigitPos};
+use crate::time::DateTimeUtc;
+use crate::token::{DenominatedAmount, Denomination, MaspDigitPos};
 
 /// The plain representation of a MASP aaset
 #[derive(
@@ -547,3 +549,239 @@ impl FromStr for MaspValue {
             })
     }
 }
+
+/// The scheme of the payment request URIs
+pub const PAYMENT_REQUEST_SCHEME: &str = "namada";
+
+/// The maximum length in bytes of the memo of a shielded note
+pub const MAX_MEMO_LEN: usize = 512;
+
+/// A request for a payment to a shielded payment address, encoded as a URI
+/// in the style of ZIP 321:
+/// `namada:<payment address>?amount=<amount>&token=<address>&memo=<text>&
+/// expiry=<RFC 3339 date-time>`, where all the parameters are optional and
+/// percent-encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentRequest {
+    /// The payment address to pay to
+    pub address: PaymentAddress,
+    /// The requested amount
+    pub amount: Option<DenominatedAmount>,
+    /// The requested token
+    pub token: Option<Address>,
+    /// The memo to attach to the shielded note of the payment
+    pub memo: Option<String>,
+    /// The time after which the request must no longer be paid
+    pub expiry: Option<DateTimeUtc>,
+}
+
+/// Errors of the payment request URIs
+#[derive(Error, Debug)]
+pub enum PaymentRequestError {
+    /// The URI doesn't have the payment request scheme
+    #[error("Payment requests must start with \"{PAYMENT_REQUEST_SCHEME}:\"")]
+    InvalidScheme,
+    /// The payment address is invalid
+    #[error("Invalid payment address: {0}")]
+    InvalidAddress(DecodeError),
+    /// A parameter is invalid or given more than once
+    #[error("Invalid payment request parameter {0}: {1}")]
+    InvalidParam(String, String),
+    /// A required parameter is not supported
+    #[error("Unsupported required payment request parameter {0}")]
+    UnsupportedParam(String),
+}
+
+impl PaymentRequest {
+    /// Create a request for any payment to the given address
+    pub fn new(address: PaymentAddress) -> Self {
+        Self {
+            address,
+            amount: None,
+            token: None,
+            memo: None,
+            expiry: None,
+        }
+    }
+}
+
+impl Display for PaymentRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{PAYMENT_REQUEST_SCHEME}:{}", self.address)?;
+        let params = [
+            ("amount", self.amount.map(|amount| amount.to_string())),
+            ("token", self.token.as_ref().map(Address::encode)),
+            ("memo", self.memo.clone()),
+            ("expiry", self.expiry.map(|expiry| expiry.to_rfc3339())),
+        ];
+        let mut separator = '?';
+        for (name, value) in params {
+            if let Some(value) = value {
+                write!(f, "{separator}{name}={}", percent_encode(&value))?;
+                separator = '&';
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PaymentRequest {
+    type Err = PaymentRequestError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let uri = s
+            .strip_prefix(PAYMENT_REQUEST_SCHEME)
+            .and_then(|uri| uri.strip_prefix(':'))
+            .ok_or(PaymentRequestError::InvalidScheme)?;
+        let (address, query) = uri.split_once('?').unwrap_or((uri, ""));
+        let address = PaymentAddress::from_str(address)
+            .map_err(PaymentRequestError::InvalidAddress)?;
+        let mut request = Self::new(address);
+        for param in query.split('&').filter(|param| !param.is_empty()) {
+            let (name, value) = param.split_once('=').unwrap_or((param, ""));
+            let invalid = |err: String| {
+                PaymentRequestError::InvalidParam(name.to_string(), err)
+            };
+            let value = percent_decode(value).map_err(invalid)?;
+            let duplicate = match name {
+                "amount" => request
+                    .amount
+                    .replace(
+                        DenominatedAmount::from_str(&value)
+                            .map_err(|err| invalid(err.to_string()))?,
+                    )
+                    .is_some(),
+                "token" => request
+                    .token
+                    .replace(
+                        Address::decode(&value)
+                            .map_err(|err| invalid(err.to_string()))?,
+                    )
+                    .is_some(),
+                "memo" if value.len() > MAX_MEMO_LEN => {
+                    return Err(invalid(format!(
+                        "longer than {MAX_MEMO_LEN} bytes"
+                    )));
+                }
+                "memo" => request.memo.replace(value).is_some(),
+                "expiry" => request
+                    .expiry
+                    .replace(
+                        DateTimeUtc::from_str(&value)
+                            .map_err(|err| invalid(err.to_string()))?,
+                    )
+                    .is_some(),
+                // As in ZIP 321, parameters that the payer must understand
+                // are prefixed, whereas the others may be ignored
+                _ if name.starts_with("req-") => {
+                    return Err(PaymentRequestError::UnsupportedParam(
+                        name.to_string(),
+                    ));
+                }
+                _ => false,
+            };
+            if duplicate {
+                return Err(invalid("given more than once".to_string()));
+            }
+        }
+        Ok(request)
+    }
+}
+
+/// Percent-encode all the bytes of a URI query value except the unreserved
+/// characters
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Decode a percent-encoded URI query value
+fn percent_decode(value: &str) -> Result<String, String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut input = value.bytes();
+    while let Some(byte) = input.next() {
+        if byte == b'%' {
+            let hex = [input.next(), input.next()];
+            let [Some(hi), Some(lo)] = hex else {
+                return Err("truncated percent-encoding".to_string());
+            };
+            let hex = std::str::from_utf8(&[hi, lo])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| "invalid percent-encoding".to_string())?;
+            bytes.push(hex);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A payment address for the tests
+    const PAYMENT_ADDRESS: &str = "znam1qr57pyghrt5ek7v42nxsqdqggltwqrgj2hjlvm5sj0nr8hezzryxcu44qzcea7qdx6wh02cvt9jlu";
+
+    #[test]
+    fn test_payment_request_roundtrip() {
+        let address = PaymentAddress::from_str(PAYMENT_ADDRESS).unwrap();
+        let request = PaymentRequest::new(address);
+        let uri = request.to_string();
+        assert_eq!(uri, format!("namada:{PAYMENT_ADDRESS}"));
+        assert_eq!(PaymentRequest::from_str(&uri).unwrap(), request);
+
+        let request = PaymentRequest {
+            amount: Some(DenominatedAmount::from_str("12.5").unwrap()),
+            token: Some(
+                Address::decode(
+                    "tnam1q8j5s6xp55p05yznwnftkv3kr9gjtsw3nq7x6tw5",
+                )
+                .unwrap(),
+            ),
+            memo: Some("invoice #42, thanks & bye".to_string()),
+            expiry: Some(
+                DateTimeUtc::from_str("2024-03-01T12:00:00+00:00").unwrap(),
+            ),
+            ..request
+        };
+        let uri = request.to_string();
+        assert!(!uri.contains(' '));
+        assert_eq!(PaymentRequest::from_str(&uri).unwrap(), request);
+    }
+
+    #[test]
+    fn test_payment_request_params() {
+        let uri = format!("namada:{PAYMENT_ADDRESS}?memo=a&memo=b");
+        assert!(matches!(
+            PaymentRequest::from_str(&uri),
+            Err(PaymentRequestError::InvalidParam(..))
+        ));
+        let memo = "a".repeat(MAX_MEMO_LEN + 1);
+        let uri = format!("namada:{PAYMENT_ADDRESS}?memo={memo}");
+        assert!(matches!(
+            PaymentRequest::from_str(&uri),
+            Err(PaymentRequestError::InvalidParam(..))
+        ));
+        let uri = format!("namada:{PAYMENT_ADDRESS}?label=shop");
+        assert!(PaymentRequest::from_str(&uri).is_ok());
+        let uri = format!("namada:{PAYMENT_ADDRESS}?req-fee=1");
+        assert!(matches!(
+            PaymentRequest::from_str(&uri),
+            Err(PaymentRequestError::UnsupportedParam(_))
+        ));
+        let uri = format!("zcash:{PAYMENT_ADDRESS}");
+        assert!(matches!(
+            PaymentRequest::from_str(&uri),
+            Err(PaymentRequestError::InvalidScheme)
+        ));
+    }
+}
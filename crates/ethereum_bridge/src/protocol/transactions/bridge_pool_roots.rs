@@ -30,6 +30,22 @@ pub fn sign_bridge_pool_root<D, H>(
     eth_hot_key: &common::SecretKey,
     protocol_key: &common::SecretKey,
 ) -> Option<bridge_pool_roots::SignedVext>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    bridge_pool_root_vext(state, validator_addr, eth_hot_key)
+        .map(|ext| ext.sign(protocol_key))
+}
+
+/// Sign the latest Bridge pool root with the Ethereum hot key, and return the
+/// associated vote extension, which has yet to be signed with the validator's
+/// protocol key.
+pub fn bridge_pool_root_vext<D, H>(
+    state: &WlState<D, H>,
+    validator_addr: &Address,
+    eth_hot_key: &common::SecretKey,
+) -> Option<bridge_pool_roots::Vext>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
@@ -46,7 +62,7 @@ where
         validator_addr: validator_addr.clone(),
         sig: signed.sig,
     };
-    Some(ext.sign(protocol_key))
+    Some(ext)
 }
 
 /// Applies a tally of signatures on over the Ethereum
@@ -192,6 +192,20 @@ where
                     _ => Ok(false),
                 }
             }
+            Address::Internal(InternalAddress::PosShareToken(_)) => {
+                // Share tokens of liquid bonds are minted and burned by PoS,
+                // which validates the amounts against the bond changes
+                let minter_key = minter_key(token);
+                match self.ctx.read_post::<Address>(&minter_key)? {
+                    Some(minter)
+                        if minter
+                            == Address::Internal(InternalAddress::PoS) =>
+                    {
+                        Ok(verifiers.contains(&minter))
+                    }
+                    _ => Ok(false),
+                }
+            }
             _ => {
                 // ERC20 and other tokens should not be minted by a wasm
                 // transaction
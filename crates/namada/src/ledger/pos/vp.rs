@@ -4,11 +4,18 @@ use std::collections::BTreeSet;
 
 // use borsh::BorshDeserialize;
 pub use namada_proof_of_stake;
+use namada_proof_of_stake::liquid_staking::{
+    is_valid_liquid_bond_change, share_token_validator,
+};
 pub use namada_proof_of_stake::parameters::PosParams;
 // use namada_proof_of_stake::validation::validate;
-use namada_proof_of_stake::storage::read_pos_params;
-use namada_proof_of_stake::storage_key::is_params_key;
+use namada_proof_of_stake::storage::{read_pos_params, read_restake_settings};
+use namada_proof_of_stake::storage_key::{
+    is_bond_epoched_meta_key, is_bond_key, is_delegator_redelegations_key,
+    is_params_key, is_restake_settings_key, is_unbond_key,
+};
 pub use namada_proof_of_stake::types;
+use namada_proof_of_stake::types::BondId;
 // use crate::ledger::pos::{
 //     is_validator_address_raw_hash_key,
 //     is_validator_max_commission_rate_change_key,
@@ -21,6 +28,7 @@ use thiserror::Error;
 use crate::address::{Address, InternalAddress};
 use crate::ledger::native_vp::{self, Ctx, NativeVp};
 use crate::storage::{Key, KeySeg};
+use crate::token::storage_key::is_any_minted_balance_key;
 use crate::vm::WasmCacheAccess;
 
 #[allow(missing_docs)]
@@ -73,12 +81,56 @@ where
 
         let addr = Address::Internal(InternalAddress::PoS);
         // let mut changes: Vec<DataUpdate> = vec![];
-        let _current_epoch = self.ctx.pre().get_block_epoch()?;
+        let current_epoch = self.ctx.pre().get_block_epoch()?;
+        // Validators whose liquid bonds are changed by the tx
+        let mut liquid_bonds = BTreeSet::new();
 
         tracing::debug!("\nValidating PoS Tx\n");
 
         for key in keys_changed {
-            if is_params_key(key) {
+            if let Some(token) = is_any_minted_balance_key(key) {
+                if let Some(validator) = share_token_validator(token) {
+                    liquid_bonds.insert(validator);
+                }
+            } else if let Some(bond_id) = is_bond_key(key)
+                .map(|(bond_id, _)| bond_id)
+                .or_else(|| is_bond_epoched_meta_key(key))
+                .or_else(|| is_unbond_key(key).map(|(bond_id, _, _)| bond_id))
+            {
+                if let Some(validator) = share_token_validator(&bond_id.source)
+                {
+                    // Share tokens can only be bonded to their own validator
+                    if validator != bond_id.validator {
+                        return Ok(false);
+                    }
+                    liquid_bonds.insert(validator);
+                }
+            } else if let Some(BondId { source, validator }) =
+                is_restake_settings_key(key)
+            {
+                // The rewards of a liquid bond must always be restaked, from
+                // the next epoch at the latest
+                if share_token_validator(&source).is_some() {
+                    let settings = read_restake_settings(
+                        &self.ctx.post(),
+                        &source,
+                        &validator,
+                    )?;
+                    let is_restaked = settings.map_or(false, |settings| {
+                        settings.interval == 1
+                            && settings.next_epoch <= current_epoch.next()
+                    });
+                    if !is_restaked {
+                        return Ok(false);
+                    }
+                }
+            } else if let Some(delegator) = is_delegator_redelegations_key(key)
+            {
+                // Liquid bonds cannot be redelegated
+                if share_token_validator(delegator).is_some() {
+                    return Ok(false);
+                }
+            } else if is_params_key(key) {
                 let data = if let Some(data) = tx_data.data() {
                     data
                 } else {
@@ -107,6 +159,17 @@ where
             }
         }
 
+        for validator in &liquid_bonds {
+            if !is_valid_liquid_bond_change(
+                &self.ctx.pre(),
+                &self.ctx.post(),
+                validator,
+                current_epoch,
+            )? {
+                return Ok(false);
+            }
+        }
+
         let _params = read_pos_params(&self.ctx.pre())?;
         // let errors = validate(&params, changes, current_epoch);
         // Ok(if errors.is_empty() {
@@ -37,6 +37,7 @@ use crate::ledger::pos::{self, PosVP};
 use crate::state::{DBIter, State, StorageHasher, StorageRead, WlState, DB};
 use crate::storage;
 use crate::storage::TxIndex;
+use crate::token::storage_key::is_any_token_balance_key;
 use crate::token::Amount;
 use crate::vm::wasm::{TxCache, VpCache};
 use crate::vm::{self, wasm, WasmCacheAccess};
@@ -930,6 +931,24 @@ where
                                 .validate_tx(tx, &keys_changed, &verifiers)
                                 .map_err(Error::NutNativeVpError)
                         }
+                        InternalAddress::PosShareToken(_) => {
+                            // Share tokens cannot hold any tokens
+                            let holds_tokens = keys_changed.iter().any(|key| {
+                                matches!(
+                                    is_any_token_balance_key(key),
+                                    Some([_, owner]) if owner == addr
+                                )
+                            });
+                            // Otherwise, the address should be a part of a
+                            // multitoken key or of a PoS key of the liquid
+                            // bond, which is validated by the PoS VP
+                            Ok(!holds_tokens
+                                && (verifiers.contains(&Address::Internal(
+                                    InternalAddress::Multitoken,
+                                )) || verifiers.contains(
+                                    &Address::Internal(InternalAddress::PoS),
+                                )))
+                        }
                         InternalAddress::IbcToken(_)
                         | InternalAddress::Erc20(_) => {
                             // The address should be a part of a multitoken
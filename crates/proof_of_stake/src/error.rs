@@ -110,6 +110,16 @@ pub enum CommissionRateChangeError {
     CannotWrite(Address),
     #[error("Cannot read storage for validator {0}")]
     CannotRead(Address),
+    #[error(
+        "The commission rate change of validator {0} cannot take effect in \
+         epoch {1} before the pipeline epoch {2}"
+    )]
+    BeforePipeline(Address, Epoch, Epoch),
+    #[error(
+        "The commission rate change of validator {0} cannot take effect in \
+         epoch {1} after the last epoch {2} in which a change can be scheduled"
+    )]
+    AfterScheduleHorizon(Address, Epoch, Epoch),
 }
 
 #[allow(missing_docs)]
@@ -169,6 +179,35 @@ pub enum MetadataError {
     CannotRemoveEmail,
 }
 
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum RestakeError {
+    #[error("The given address {0} is not a validator address")]
+    NotAValidator(Address),
+    #[error(
+        "The given source address {0} is a validator address. Validators may \
+         not delegate."
+    )]
+    SourceMustNotBeAValidator(Address),
+    #[error("The restake interval must be at least one epoch")]
+    ZeroInterval,
+}
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum LiquidStakingError {
+    #[error("The validator {0} has no share token")]
+    NoShareToken(Address),
+    #[error(
+        "Trying to redeem more shares ({0}) than the balance of {1} ({2})"
+    )]
+    InsufficientShares(String, Address, String),
+    #[error("The liquid bonds to the validator {0} have been fully slashed")]
+    FullySlashed(Address),
+    #[error("Share amount overflow")]
+    Overflow,
+}
+
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
 pub enum ConsensusKeyChangeError {
@@ -242,6 +281,18 @@ impl From<MetadataError> for namada_storage::Error {
     }
 }
 
+impl From<RestakeError> for namada_storage::Error {
+    fn from(err: RestakeError) -> Self {
+        Self::new(err)
+    }
+}
+
+impl From<LiquidStakingError> for namada_storage::Error {
+    fn from(err: LiquidStakingError) -> Self {
+        Self::new(err)
+    }
+}
+
 impl From<ConsensusKeyChangeError> for namada_storage::Error {
     fn from(err: ConsensusKeyChangeError) -> Self {
         Self::new(err)
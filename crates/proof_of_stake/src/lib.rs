@@ -7,10 +7,12 @@
 #![deny(rustdoc::private_intra_doc_links)]
 
 pub mod epoched;
+pub mod liquid_staking;
 pub mod parameters;
 pub mod pos_queries;
 pub mod queries;
 pub mod rewards;
+pub mod simulation;
 pub mod slashing;
 pub mod storage;
 pub mod storage_key;
@@ -35,12 +37,13 @@ use namada_core::storage::BlockHeight;
 pub use namada_core::storage::{Epoch, Key, KeySeg};
 use namada_core::tendermint::abci::types::Misbehavior;
 use namada_storage::collections::lazy_map::{self, Collectable, LazyMap};
+use namada_storage::collections::lazy_set;
 use namada_storage::{StorageRead, StorageWrite};
 pub use namada_trans_token as token;
 pub use parameters::{OwnedPosParams, PosParams};
 use types::into_tm_voting_power;
 
-use crate::queries::{find_bonds, has_bonds};
+use crate::queries::{find_bonds, has_bonds, read_validator_epoch_record};
 use crate::rewards::{
     add_rewards_to_counter, compute_current_rewards_from_bonds,
     read_rewards_counter, take_rewards_from_counter,
@@ -51,32 +54,39 @@ use crate::slashing::{
 };
 use crate::storage::{
     below_capacity_validator_set_handle, bond_handle,
-    consensus_validator_set_handle, delegator_redelegated_bonds_handle,
-    delegator_redelegated_unbonds_handle, get_last_reward_claim_epoch,
+    commission_rate_change_notices_handle, consensus_validator_set_handle,
+    delegator_redelegated_bonds_handle, delegator_redelegated_unbonds_handle,
+    delete_restake_settings, get_last_reward_claim_epoch,
     liveness_missed_votes_handle, liveness_sum_missed_votes_handle,
+    read_below_capacity_validator_set_addresses,
     read_consensus_validator_set_addresses, read_non_pos_owned_params,
-    read_pos_params, read_validator_last_slash_epoch,
-    read_validator_max_commission_rate_change, read_validator_stake,
-    total_bonded_handle, total_consensus_stake_handle, total_unbonded_handle,
+    read_pos_params, read_validator_commission_rate,
+    read_validator_last_slash_epoch, read_validator_max_commission_rate_change,
+    read_validator_scheduled_commission_rates, read_validator_stake,
+    scheduled_commission_rates_handle, total_bonded_handle,
+    total_consensus_stake_handle, total_unbonded_handle,
     try_insert_consensus_key, unbond_handle, update_total_deltas,
     update_validator_deltas, validator_addresses_handle,
     validator_commission_rate_handle, validator_consensus_key_handle,
     validator_deltas_handle, validator_eth_cold_key_handle,
-    validator_eth_hot_key_handle, validator_incoming_redelegations_handle,
-    validator_outgoing_redelegations_handle, validator_protocol_key_handle,
-    validator_rewards_products_handle, validator_set_positions_handle,
-    validator_slashes_handle, validator_state_handle,
-    validator_total_redelegated_bonded_handle,
+    validator_eth_hot_key_handle, validator_history_epochs_handle,
+    validator_history_handle, validator_incoming_redelegations_handle,
+    validator_jail_history_handle, validator_outgoing_redelegations_handle,
+    validator_protocol_key_handle, validator_rewards_products_handle,
+    validator_set_positions_handle, validator_slashes_handle,
+    validator_state_handle, validator_total_redelegated_bonded_handle,
     validator_total_redelegated_unbonded_handle, write_last_reward_claim_epoch,
-    write_pos_params, write_validator_address_raw_hash, write_validator_avatar,
-    write_validator_description, write_validator_discord_handle,
-    write_validator_email, write_validator_max_commission_rate_change,
-    write_validator_metadata, write_validator_website,
+    write_pos_params, write_restake_settings, write_validator_address_raw_hash,
+    write_validator_avatar, write_validator_description,
+    write_validator_discord_handle, write_validator_email,
+    write_validator_max_commission_rate_change, write_validator_metadata,
+    write_validator_website,
 };
 use crate::storage_key::{bonds_for_source_prefix, is_bond_key};
 use crate::types::{
     BondId, ConsensusValidator, ConsensusValidatorSet,
-    EagerRedelegatedBondsMap, RedelegatedBondsOrUnbonds, RedelegatedTokens,
+    EagerRedelegatedBondsMap, JailReason, JailRecord,
+    RedelegatedBondsOrUnbonds, RedelegatedTokens, RestakeSettings,
     ResultSlashing, Slash, Unbonds, ValidatorMetaData, ValidatorSetUpdate,
     ValidatorState, VoteInfo,
 };
@@ -94,6 +104,9 @@ pub const ADDRESS: Address = Address::Internal(InternalAddress::PoS);
 pub const SLASH_POOL_ADDRESS: Address =
     Address::Internal(InternalAddress::PosSlashPool);
 
+/// Number of past epochs for which the history of the validators is kept
+pub const VALIDATOR_HISTORY_NUM_PAST_EPOCHS: u64 = 365;
+
 /// Address of the staking token (i.e. the native token)
 pub fn staking_token_address(storage: &impl StorageRead) -> Address {
     storage
@@ -236,6 +249,29 @@ where
     let staking_token = staking_token_address(storage);
     token::transfer(storage, &staking_token, source, &ADDRESS, amount)?;
 
+    bond_transferred_tokens(
+        storage,
+        source,
+        validator,
+        amount,
+        current_epoch,
+        offset_opt,
+    )
+}
+
+/// Add a bond of tokens that have already been transferred to the PoS account
+/// from the `source` to the `validator`.
+pub(crate) fn bond_transferred_tokens<S>(
+    storage: &mut S,
+    source: &Address,
+    validator: &Address,
+    amount: token::Amount,
+    current_epoch: Epoch,
+    offset_opt: Option<u64>,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
     let params = read_pos_params(storage)?;
     let offset = offset_opt.unwrap_or(params.pipeline_len);
     let offset_epoch = current_epoch + offset;
@@ -1490,13 +1526,48 @@ where
     Ok(withdrawable_amount)
 }
 
-/// Change the commission rate of a validator
+/// Change the commission rate of a validator from the pipeline epoch,
+/// replacing the changes of its rate that are scheduled after it
 pub fn change_validator_commission_rate<S>(
     storage: &mut S,
     validator: &Address,
     new_rate: Dec,
     current_epoch: Epoch,
 ) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let params = read_pos_params(storage)?;
+    schedule_validator_commission_rate(
+        storage,
+        validator,
+        new_rate,
+        current_epoch,
+        current_epoch + params.pipeline_len,
+    )
+}
+
+/// Change the commission rate of a validator from the `effective_epoch`, which
+/// cannot be before the pipeline epoch nor more than
+/// `max_commission_schedule_len` epochs after it. A change after the pipeline
+/// epoch is applied when its effective epoch becomes the pipeline epoch. The
+/// max commission rate change is checked against the rate in the epoch before
+/// the effective epoch, including the scheduled changes.
+///
+/// A change replaces the changes of the validator's rate that are already
+/// scheduled to take effect from its effective epoch on. Changing the rate to
+/// the current one at the pipeline epoch thus cancels all the scheduled
+/// changes.
+///
+/// The change is announced to the delegators in a
+/// [`types::CommissionRateChangeEvent`] emitted in the next block.
+pub fn schedule_validator_commission_rate<S>(
+    storage: &mut S,
+    validator: &Address,
+    new_rate: Dec,
+    current_epoch: Epoch,
+    effective_epoch: Epoch,
+) -> namada_storage::Result<()>
 where
     S: StorageRead + StorageWrite,
 {
@@ -1526,20 +1597,36 @@ where
     }
 
     let params = read_pos_params(storage)?;
-    let commission_handle = validator_commission_rate_handle(validator);
     let pipeline_epoch = current_epoch + params.pipeline_len;
-
-    let rate_at_pipeline = commission_handle
-        .get(storage, pipeline_epoch, &params)?
-        .expect("Could not find a rate in given epoch");
-    if new_rate == rate_at_pipeline {
-        return Ok(());
+    if effective_epoch < pipeline_epoch {
+        return Err(CommissionRateChangeError::BeforePipeline(
+            validator.clone(),
+            effective_epoch,
+            pipeline_epoch,
+        )
+        .into());
+    }
+    let last_schedule_epoch =
+        pipeline_epoch + params.max_commission_schedule_len;
+    if effective_epoch > last_schedule_epoch {
+        return Err(CommissionRateChangeError::AfterScheduleHorizon(
+            validator.clone(),
+            effective_epoch,
+            last_schedule_epoch,
+        )
+        .into());
     }
-    let rate_before_pipeline = commission_handle
-        .get(storage, pipeline_epoch.prev(), &params)?
-        .expect("Could not find a rate in given epoch");
 
-    let change_from_prev = new_rate.abs_diff(&rate_before_pipeline);
+    let rate_before_effective = read_validator_commission_rate(
+        storage,
+        &params,
+        validator,
+        current_epoch,
+        effective_epoch.prev(),
+    )?
+    .expect("Could not find a rate in given epoch");
+
+    let change_from_prev = new_rate.abs_diff(&rate_before_effective);
     if change_from_prev > max_change.unwrap() {
         return Err(CommissionRateChangeError::RateChangeTooLarge(
             change_from_prev,
@@ -1548,7 +1635,150 @@ where
         .into());
     }
 
-    commission_handle.set(storage, new_rate, current_epoch, params.pipeline_len)
+    // Cancel the changes scheduled from the effective epoch on
+    let scheduled =
+        read_validator_scheduled_commission_rates(storage, validator)?;
+    for epoch in scheduled.range(effective_epoch..).map(|(epoch, _)| epoch) {
+        scheduled_commission_rates_handle()
+            .at(epoch)
+            .remove(storage, validator)?;
+    }
+
+    let rate_at_effective = read_validator_commission_rate(
+        storage,
+        &params,
+        validator,
+        current_epoch,
+        effective_epoch,
+    )?
+    .expect("Could not find a rate in given epoch");
+    if new_rate == rate_at_effective {
+        return Ok(());
+    }
+
+    if effective_epoch == pipeline_epoch {
+        validator_commission_rate_handle(validator).set(
+            storage,
+            new_rate,
+            current_epoch,
+            params.pipeline_len,
+        )?;
+    } else {
+        scheduled_commission_rates_handle()
+            .at(&effective_epoch)
+            .insert(storage, validator.clone(), new_rate)?;
+    }
+    commission_rate_change_notices_handle()
+        .at(validator)
+        .insert(storage, effective_epoch)?;
+    Ok(())
+}
+
+/// Apply the commission rate changes that were scheduled to take effect from
+/// the pipeline epoch of a new epoch, or before it if the pipeline length has
+/// been shortened since they were scheduled
+fn apply_scheduled_commission_rates<S>(
+    storage: &mut S,
+    params: &PosParams,
+    current_epoch: Epoch,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let scheduled = scheduled_commission_rates_handle();
+    let mut due_changes: BTreeMap<Epoch, Vec<(Address, Dec)>> = BTreeMap::new();
+    for res in scheduled.iter(storage)? {
+        let (
+            lazy_map::NestedSubKey::Data {
+                key: epoch,
+                nested_sub_key: lazy_map::SubKey::Data(validator),
+            },
+            rate,
+        ) = res?;
+        if epoch <= pipeline_epoch {
+            due_changes
+                .entry(epoch)
+                .or_default()
+                .push((validator, rate));
+        }
+    }
+    // Apply the changes in the order of their epochs, so that the latest one
+    // of a validator prevails
+    for (epoch, changes) in due_changes {
+        for (validator, rate) in changes {
+            validator_commission_rate_handle(&validator).set(
+                storage,
+                rate,
+                current_epoch,
+                params.pipeline_len,
+            )?;
+            scheduled.at(&epoch).remove(storage, &validator)?;
+        }
+    }
+    Ok(())
+}
+
+/// Emit a [`types::CommissionRateChangeEvent`] for every commission rate
+/// change made since the last block and clear the notices of these changes. A
+/// change that has been reverted since has no event.
+fn emit_commission_rate_change_events<S>(
+    storage: &mut S,
+    params: &PosParams,
+    current_epoch: Epoch,
+    events: &mut impl EmitEvents,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let notices_handle = commission_rate_change_notices_handle();
+    let notices = notices_handle
+        .iter(storage)?
+        .map(|res| {
+            res.map(
+                |(
+                    lazy_map::NestedSubKey::Data {
+                        key: validator,
+                        nested_sub_key: lazy_set::SubKey::Data(effective_epoch),
+                    },
+                    (),
+                )| (validator, effective_epoch),
+            )
+        })
+        .collect::<namada_storage::Result<Vec<_>>>()?;
+    for (validator, effective_epoch) in notices {
+        notices_handle
+            .at(&validator)
+            .remove(storage, &effective_epoch)?;
+        let new_rate = read_validator_commission_rate(
+            storage,
+            params,
+            &validator,
+            current_epoch,
+            effective_epoch,
+        )?;
+        let old_rate = read_validator_commission_rate(
+            storage,
+            params,
+            &validator,
+            current_epoch,
+            effective_epoch.prev(),
+        )?;
+        if let (Some(old_rate), Some(new_rate)) = (old_rate, new_rate) {
+            if old_rate != new_rate {
+                events.emit(
+                    types::CommissionRateChangeEvent {
+                        validator,
+                        old_rate,
+                        new_rate,
+                        effective_epoch,
+                    }
+                    .into(),
+                );
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Get the total bond amount, including slashes, for a given bond ID and epoch.
@@ -1915,6 +2145,20 @@ where
         current_epoch,
         params.pipeline_len,
     )?;
+
+    // Close the latest period in the validator's jail history
+    let jail_history = validator_jail_history_handle(validator);
+    let last_jailed = jail_history.iter(storage)?.last().transpose()?;
+    if let Some((jail_epoch, record)) = last_jailed {
+        jail_history.insert(
+            storage,
+            jail_epoch,
+            JailRecord {
+                unjail_epoch: Some(pipeline_epoch),
+                ..record
+            },
+        )?;
+    }
     Ok(())
 }
 
@@ -2348,6 +2592,106 @@ where
     Ok(())
 }
 
+/// Record the commission rate, stake and state of the consensus and
+/// below-capacity validators in the given epoch into their history, which is
+/// kept after the epoched data has been pruned. The validators that have left
+/// these sets in the epoch get a last record of their new state. A record is
+/// only written if it differs from the one of the epoch before or if the
+/// validator has no history yet.
+///
+/// The records of all the validators that are no longer in effect in the last
+/// [`VALIDATOR_HISTORY_NUM_PAST_EPOCHS`] epochs are pruned.
+pub fn record_validator_history<S>(
+    storage: &mut S,
+    params: &PosParams,
+    epoch: Epoch,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let mut validators =
+        read_consensus_validator_set_addresses(storage, epoch)?;
+    validators
+        .extend(read_below_capacity_validator_set_addresses(storage, epoch)?);
+    if let Some(prev_epoch) = epoch.checked_sub(1) {
+        validators.extend(read_consensus_validator_set_addresses(
+            storage, prev_epoch,
+        )?);
+        validators.extend(read_below_capacity_validator_set_addresses(
+            storage, prev_epoch,
+        )?);
+    }
+    if let Some(oldest_epoch) =
+        epoch.checked_sub(VALIDATOR_HISTORY_NUM_PAST_EPOCHS)
+    {
+        prune_validator_history(storage, oldest_epoch)?;
+    }
+    for validator in validators {
+        let Some(record) =
+            read_validator_epoch_record(storage, params, &validator, epoch)?
+        else {
+            continue;
+        };
+        let history = validator_history_handle(&validator);
+        let prev_record = match epoch.checked_sub(1) {
+            Some(prev_epoch) => read_validator_epoch_record(
+                storage, params, &validator, prev_epoch,
+            )?,
+            None => None,
+        };
+        if prev_record != Some(record) || history.is_empty(storage)? {
+            history.insert(storage, epoch, record)?;
+            validator_history_epochs_handle()
+                .at(&epoch)
+                .insert(storage, validator)?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove the records from the histories of all the validators that are no
+/// longer in effect in the given oldest epoch to keep. For every validator,
+/// the latest record up to the oldest epoch is kept, as it's still in effect
+/// in it. The records are found by their epochs in the index of the
+/// histories, so the validators that have left the validator sets are pruned
+/// too.
+pub fn prune_validator_history<S>(
+    storage: &mut S,
+    oldest_epoch: Epoch,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let index = validator_history_epochs_handle();
+    let mut records = index
+        .iter(storage)?
+        .map(|res| {
+            res.map(
+                |(
+                    lazy_map::NestedSubKey::Data {
+                        key: epoch,
+                        nested_sub_key: lazy_set::SubKey::Data(validator),
+                    },
+                    (),
+                )| (epoch, validator),
+            )
+        })
+        .collect::<namada_storage::Result<Vec<_>>>()?;
+    records.retain(|(epoch, _validator)| *epoch <= oldest_epoch);
+    records.sort();
+    // Keep the latest record of every validator, which is still in effect
+    let mut in_effect = HashSet::new();
+    for (epoch, validator) in records.into_iter().rev() {
+        if in_effect.contains(&validator) {
+            validator_history_handle(&validator).remove(storage, &epoch)?;
+            index.at(&epoch).remove(storage, &validator)?;
+        } else {
+            in_effect.insert(validator);
+        }
+    }
+    Ok(())
+}
+
 /// Record the liveness data of the consensus validators
 pub fn record_liveness_data<S>(
     storage: &mut S,
@@ -2426,6 +2770,23 @@ where
     Ok(())
 }
 
+/// Get the number of missed votes in the liveness window at which a validator
+/// is jailed, derived from the `liveness_threshold` percentage
+pub fn liveness_missed_votes_threshold(
+    params: &OwnedPosParams,
+) -> namada_storage::Result<u64> {
+    let threshold = ((Dec::one() - params.liveness_threshold)
+        * params.liveness_window_check)
+        .to_uint()
+        .ok_or_else(|| {
+            namada_storage::Error::SimpleMessage(
+                "Found negative liveness threshold",
+            )
+        })?
+        .as_u64();
+    Ok(threshold)
+}
+
 /// Jail validators who failed to match the liveness threshold
 pub fn jail_for_liveness<S>(
     storage: &mut S,
@@ -2437,15 +2798,7 @@ where
     S: StorageRead + StorageWrite,
 {
     // Derive the actual missing votes limit from the percentage
-    let missing_votes_threshold = ((Dec::one() - params.liveness_threshold)
-        * params.liveness_window_check)
-        .to_uint()
-        .ok_or_else(|| {
-            namada_storage::Error::SimpleMessage(
-                "Found negative liveness threshold",
-            )
-        })?
-        .as_u64();
+    let missing_votes_threshold = liveness_missed_votes_threshold(params)?;
 
     // Jail inactive validators
     let validators_to_jail = liveness_sum_missed_votes_handle()
@@ -2476,7 +2829,14 @@ where
             validator,
             jail_epoch,
         );
-        jail_validator(storage, params, validator, current_epoch, jail_epoch)?;
+        jail_validator(
+            storage,
+            params,
+            validator,
+            current_epoch,
+            jail_epoch,
+            JailReason::Liveness,
+        )?;
     }
 
     Ok(())
@@ -2677,6 +3037,46 @@ where
     Ok(rewards_from_bonds + rewards_from_counter)
 }
 
+/// Enable or disable the automatic restaking of the rewards of a bond. When
+/// `interval` is `Some`, the rewards of the bond are claimed and bonded back to
+/// the validator every `interval` epochs, starting `interval` epochs from the
+/// current one. When `interval` is `None`, the restaking is disabled.
+pub fn set_restake<S>(
+    storage: &mut S,
+    source: Option<&Address>,
+    validator: &Address,
+    interval: Option<u64>,
+    current_epoch: Epoch,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let source = source.unwrap_or(validator);
+    let Some(interval) = interval else {
+        return delete_restake_settings(storage, source, validator);
+    };
+    if interval == 0 {
+        return Err(RestakeError::ZeroInterval.into());
+    }
+    if !is_validator(storage, validator)? {
+        return Err(RestakeError::NotAValidator(validator.clone()).into());
+    }
+    if source != validator && is_validator(storage, source)? {
+        return Err(
+            RestakeError::SourceMustNotBeAValidator(source.clone()).into()
+        );
+    }
+    write_restake_settings(
+        storage,
+        source,
+        validator,
+        RestakeSettings {
+            interval,
+            next_epoch: current_epoch + interval,
+        },
+    )
+}
+
 /// Jail a validator by removing it from and updating the validator sets and
 /// changing a its state to `Jailed`. Validators are jailed for liveness and for
 /// misbehaving.
@@ -2686,6 +3086,7 @@ fn jail_validator<S>(
     validator: &Address,
     current_epoch: Epoch,
     validator_set_update_epoch: Epoch,
+    reason: JailReason,
 ) -> namada_storage::Result<()>
 where
     S: StorageRead + StorageWrite,
@@ -2696,6 +3097,23 @@ where
         validator_set_update_epoch
     );
 
+    // Record the jailing, unless the validator is already jailed
+    let update_epoch_state = validator_state_handle(validator).get(
+        storage,
+        validator_set_update_epoch,
+        params,
+    )?;
+    if update_epoch_state != Some(ValidatorState::Jailed) {
+        validator_jail_history_handle(validator).insert(
+            storage,
+            validator_set_update_epoch,
+            JailRecord {
+                reason,
+                unjail_epoch: None,
+            },
+        )?;
+    }
+
     // Remove the validator from the set starting at the update epoch and up
     // thru the pipeline epoch.
     let pipeline_epoch = current_epoch + params.pipeline_len;
@@ -2769,7 +3187,7 @@ where
 /// Apply PoS updates for a block
 pub fn finalize_block<S>(
     storage: &mut S,
-    _events: &mut impl EmitEvents,
+    events: &mut impl EmitEvents,
     is_new_epoch: bool,
     validator_set_update_epoch: Epoch,
     votes: Vec<VoteInfo>,
@@ -2795,8 +3213,20 @@ where
         // Compute the total stake of the consensus validator set and record
         // it in storage
         compute_and_store_total_consensus_stake(storage, current_epoch)?;
+
+        // Apply the commission rate changes scheduled for the new pipeline
+        // epoch
+        apply_scheduled_commission_rates(storage, &pos_params, current_epoch)?;
     }
 
+    // Announce the commission rate changes made in the previous block
+    emit_commission_rate_change_events(
+        storage,
+        &pos_params,
+        current_epoch,
+        events,
+    )?;
+
     // Invariant: Has to be applied before `record_slashes_from_evidence`
     // because it potentially needs to be able to read validator state from
     // previous epoch and jailing validator removes the historical state
@@ -2867,6 +3297,12 @@ where
         // Prune liveness data from validators that are no longer in the
         // consensus set
         prune_liveness_data(storage, current_epoch)?;
+
+        // Keep a record of the validators in the last epoch, which is no
+        // longer subject to changes
+        if let Some(last_epoch) = current_epoch.checked_sub(1) {
+            record_validator_history(storage, &pos_params, last_epoch)?;
+        }
     }
 
     Ok(())
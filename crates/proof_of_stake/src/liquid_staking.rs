@@ -0,0 +1,418 @@
+//! Liquid staking with transferable share tokens.
+//!
+//! Liquid bonds to a validator are pooled into a single PoS bond whose source
+//! is the validator's share token address. The holders of the share token own
+//! the pooled bond pro-rata to their balance, with slashes applied through
+//! [`compute_bond_at_epoch`]. The rewards of the pooled bond are restaked every
+//! epoch, so the value of a share grows with the rewards and shrinks with the
+//! slashes of the validator.
+
+use namada_core::address::{Address, InternalAddress};
+use namada_core::storage::Epoch;
+use namada_storage::collections::lazy_map::{NestedSubKey, SubKey};
+use namada_storage::{ResultExt, StorageRead, StorageWrite};
+
+use crate::slashing::compute_bond_at_epoch;
+use crate::storage::{
+    bond_handle, read_pos_params, read_restake_settings, unbond_handle,
+    write_restake_settings,
+};
+use crate::types::RestakeSettings;
+use crate::{
+    bond_transferred_tokens, is_validator, query_reward_tokens,
+    staking_token_address, token, unbond_tokens, BondError, LiquidStakingError,
+    OwnedPosParams, ADDRESS,
+};
+
+/// Get the address of the share token of the given validator. Returns `None`
+/// for addresses that cannot be validators.
+pub fn share_token_address(validator: &Address) -> Option<Address> {
+    match validator {
+        Address::Established(addr) => Some(Address::Internal(
+            InternalAddress::PosShareToken(addr.clone()),
+        )),
+        _ => None,
+    }
+}
+
+/// Get the validator whose liquid bonds are represented by the given token,
+/// if it is a share token.
+pub fn share_token_validator(token: &Address) -> Option<Address> {
+    match token {
+        Address::Internal(InternalAddress::PosShareToken(addr)) => {
+            Some(Address::Established(addr.clone()))
+        }
+        _ => None,
+    }
+}
+
+fn share_token_or_err(validator: &Address) -> namada_storage::Result<Address> {
+    share_token_address(validator).ok_or_else(|| {
+        LiquidStakingError::NoShareToken(validator.clone()).into()
+    })
+}
+
+/// Compute `amount * num / denom`, rounded down.
+fn mul_div(
+    amount: token::Amount,
+    num: token::Amount,
+    denom: token::Amount,
+) -> namada_storage::Result<token::Amount> {
+    let (res, _rem) = amount
+        .raw_amount()
+        .checked_mul_div(num.raw_amount(), denom.raw_amount())
+        .ok_or(LiquidStakingError::Overflow)?;
+    token::Amount::from_uint(res, 0).into_storage_result()
+}
+
+/// Find the value of the pooled liquid bond to the given validator at the
+/// given epoch, with slashes applied.
+pub fn liquid_bond_value<S>(
+    storage: &S,
+    params: &OwnedPosParams,
+    validator: &Address,
+    epoch: Epoch,
+) -> namada_storage::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let share_token = share_token_or_err(validator)?;
+    let mut value = token::Amount::zero();
+    for next in bond_handle(&share_token, validator)
+        .get_data_handler()
+        .iter(storage)?
+    {
+        let (start, delta) = next?;
+        if start > epoch {
+            continue;
+        }
+        value += compute_bond_at_epoch(
+            storage, params, validator, epoch, start, delta, None,
+        )?;
+    }
+    Ok(value)
+}
+
+/// Find the value of the shares of the liquid bond to the given validator,
+/// which is the value of the pooled bond at the pipeline epoch, when a new
+/// bond would start contributing to it, and the rewards of the pooled bond
+/// that are pending to be restaked. The pending rewards are owned by the
+/// current holders of the shares, so a new deposit doesn't dilute them.
+pub fn liquid_shares_value<S>(
+    storage: &S,
+    params: &OwnedPosParams,
+    validator: &Address,
+    current_epoch: Epoch,
+) -> namada_storage::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let share_token = share_token_or_err(validator)?;
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let value = liquid_bond_value(storage, params, validator, pipeline_epoch)?;
+    let pending_rewards = query_reward_tokens(
+        storage,
+        Some(&share_token),
+        validator,
+        current_epoch,
+    )?;
+    Ok(value
+        .checked_add(pending_rewards)
+        .ok_or(LiquidStakingError::Overflow)?)
+}
+
+/// Compute the amount of shares that would be minted for a liquid bond of the
+/// given amount. The shares are priced by [`liquid_shares_value`].
+pub fn shares_for_deposit<S>(
+    storage: &S,
+    params: &OwnedPosParams,
+    validator: &Address,
+    amount: token::Amount,
+    current_epoch: Epoch,
+) -> namada_storage::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let share_token = share_token_or_err(validator)?;
+    let supply = token::read_total_supply(storage, &share_token)?;
+    if supply.is_zero() {
+        return Ok(amount);
+    }
+    let value = liquid_shares_value(storage, params, validator, current_epoch)?;
+    if value.is_zero() {
+        return Err(LiquidStakingError::FullySlashed(validator.clone()).into());
+    }
+    mul_div(amount, supply, value)
+}
+
+/// Compute the raw bond amount that has to be unbonded from the pooled liquid
+/// bond to redeem the given amount of shares, priced by
+/// [`liquid_shares_value`] like the deposits.
+///
+/// Like [`crate::unbond_tokens`], the bonds are taken from the most recent
+/// one, so that the unbonded amount matches the slashed value of the shares.
+pub fn bond_for_shares<S>(
+    storage: &S,
+    params: &OwnedPosParams,
+    validator: &Address,
+    shares: token::Amount,
+    current_epoch: Epoch,
+) -> namada_storage::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let share_token = share_token_or_err(validator)?;
+    let supply = token::read_total_supply(storage, &share_token)?;
+    if shares.is_zero() || supply.is_zero() {
+        return Ok(token::Amount::zero());
+    }
+    #[allow(clippy::needless_collect)]
+    let bonds: Vec<Result<_, _>> = bond_handle(&share_token, validator)
+        .get_data_handler()
+        .iter(storage)?
+        .collect();
+    if shares >= supply {
+        return bonds
+            .into_iter()
+            .try_fold(token::Amount::zero(), |acc, bond| {
+                bond.map(|(_start, amount)| acc + amount)
+            });
+    }
+
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let value = liquid_shares_value(storage, params, validator, current_epoch)?;
+    let mut remaining = mul_div(shares, value, supply)?;
+    let mut to_unbond = token::Amount::zero();
+    for bond in bonds.into_iter().rev() {
+        if remaining.is_zero() {
+            break;
+        }
+        let (start, amount) = bond?;
+        let slashed_amount = compute_bond_at_epoch(
+            storage,
+            params,
+            validator,
+            pipeline_epoch,
+            start,
+            amount,
+            None,
+        )?;
+        if slashed_amount <= remaining {
+            to_unbond += amount;
+            remaining -= slashed_amount;
+        } else {
+            to_unbond += mul_div(amount, remaining, slashed_amount)?;
+            break;
+        }
+    }
+    Ok(to_unbond)
+}
+
+/// Bond tokens from the `source` to the `validator` through the validator's
+/// liquid bond and mint the share tokens of the new bond to the `source`.
+/// Returns the amount of minted shares.
+pub fn bond_liquid_tokens<S>(
+    storage: &mut S,
+    source: &Address,
+    validator: &Address,
+    amount: token::Amount,
+    current_epoch: Epoch,
+) -> namada_storage::Result<token::Amount>
+where
+    S: StorageRead + StorageWrite,
+{
+    tracing::debug!(
+        "Liquid bonding token amount {} at epoch {current_epoch}",
+        amount.to_string_native()
+    );
+    if amount.is_zero() {
+        return Ok(token::Amount::zero());
+    }
+    if source != validator && is_validator(storage, source)? {
+        return Err(BondError::SourceMustNotBeAValidator(source.clone()).into());
+    }
+    let share_token = share_token_or_err(validator)?;
+    let params = read_pos_params(storage)?;
+
+    // The shares are priced before the pooled bond is increased
+    let shares =
+        shares_for_deposit(storage, &params, validator, amount, current_epoch)?;
+
+    let staking_token = staking_token_address(storage);
+    token::transfer(storage, &staking_token, source, &ADDRESS, amount)?;
+    bond_transferred_tokens(
+        storage,
+        &share_token,
+        validator,
+        amount,
+        current_epoch,
+        None,
+    )?;
+
+    token::credit_tokens(storage, &share_token, source, shares)?;
+    storage.write(&token::storage_key::minter_key(&share_token), ADDRESS)?;
+
+    // Compound the rewards of the pooled bond into the share price
+    if read_restake_settings(storage, &share_token, validator)?.is_none() {
+        write_restake_settings(
+            storage,
+            &share_token,
+            validator,
+            RestakeSettings {
+                interval: 1,
+                next_epoch: current_epoch.next(),
+            },
+        )?;
+    }
+    tracing::debug!(
+        "Minted {} shares of {share_token} to {source}",
+        shares.to_string_native()
+    );
+    Ok(shares)
+}
+
+/// Burn the given amount of the `owner`'s shares of the `validator`'s liquid
+/// bond and unbond their value for the `owner`. The unbonded tokens are
+/// withdrawn by the `owner` like any other unbond, with slashes applied at
+/// withdrawal. Returns the raw amount of unbonded tokens.
+pub fn redeem_liquid_tokens<S>(
+    storage: &mut S,
+    owner: &Address,
+    validator: &Address,
+    shares: token::Amount,
+    current_epoch: Epoch,
+) -> namada_storage::Result<token::Amount>
+where
+    S: StorageRead + StorageWrite,
+{
+    tracing::debug!(
+        "Redeeming {} shares at epoch {current_epoch}",
+        shares.to_string_native()
+    );
+    if shares.is_zero() {
+        return Ok(token::Amount::zero());
+    }
+    let share_token = share_token_or_err(validator)?;
+    let balance = token::read_balance(storage, &share_token, owner)?;
+    if shares > balance {
+        return Err(LiquidStakingError::InsufficientShares(
+            shares.to_string_native(),
+            owner.clone(),
+            balance.to_string_native(),
+        )
+        .into());
+    }
+    let params = read_pos_params(storage)?;
+
+    // The redeemed amount is computed before the shares are burned
+    let amount =
+        bond_for_shares(storage, &params, validator, shares, current_epoch)?;
+    token::burn_tokens(storage, &share_token, owner, shares)?;
+    unbond_tokens(
+        storage,
+        Some(&share_token),
+        validator,
+        amount,
+        current_epoch,
+        false,
+    )?;
+
+    // Hand the new unbonds of the pooled bond over to the owner
+    let token_unbonds = unbond_handle(&share_token, validator);
+    #[allow(clippy::needless_collect)]
+    let unbonds: Vec<Result<_, _>> = token_unbonds.iter(storage)?.collect();
+    let owner_unbonds = unbond_handle(owner, validator);
+    for unbond in unbonds {
+        let (
+            NestedSubKey::Data {
+                key: start,
+                nested_sub_key: SubKey::Data(withdraw),
+            },
+            unbond_amount,
+        ) = unbond?;
+        owner_unbonds
+            .at(&start)
+            .update(storage, withdraw, |current| {
+                current.unwrap_or_default() + unbond_amount
+            })?;
+        token_unbonds.remove_all(storage, &start)?;
+    }
+    tracing::debug!(
+        "Unbonded {} tokens from {validator} for {owner}",
+        amount.to_string_native()
+    );
+    Ok(amount)
+}
+
+/// Validate a change of the liquid bond to the given validator. The shares
+/// minted or burned in a transaction must match the change of the pooled bond
+/// at the share price of the prior state, and the unbonds of the pooled bond
+/// must be handed over to the owners of the redeemed shares.
+pub fn is_valid_liquid_bond_change<Pre, Post>(
+    pre: &Pre,
+    post: &Post,
+    validator: &Address,
+    current_epoch: Epoch,
+) -> namada_storage::Result<bool>
+where
+    Pre: StorageRead,
+    Post: StorageRead,
+{
+    let share_token = match share_token_address(validator) {
+        Some(token) => token,
+        None => return Ok(false),
+    };
+    if !unbond_handle(&share_token, validator).is_empty(post)? {
+        tracing::info!("Unbonds of a liquid bond must be handed to the owner");
+        return Ok(false);
+    }
+
+    let params = read_pos_params(pre)?;
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let bonds = bond_handle(&share_token, validator);
+    let bond_pre = bonds
+        .get_sum(pre, pipeline_epoch, &params)?
+        .unwrap_or_default();
+    let bond_post = bonds
+        .get_sum(post, pipeline_epoch, &params)?
+        .unwrap_or_default();
+    let supply_pre = token::read_total_supply(pre, &share_token)?;
+    let supply_post = token::read_total_supply(post, &share_token)?;
+
+    let is_valid = if supply_post > supply_pre {
+        let minted = supply_post - supply_pre;
+        bond_post > bond_pre
+            && minted
+                <= shares_for_deposit(
+                    pre,
+                    &params,
+                    validator,
+                    bond_post - bond_pre,
+                    current_epoch,
+                )?
+    } else if supply_post < supply_pre {
+        let burned = supply_pre - supply_post;
+        bond_post <= bond_pre
+            && bond_pre - bond_post
+                <= bond_for_shares(
+                    pre,
+                    &params,
+                    validator,
+                    burned,
+                    current_epoch,
+                )?
+    } else {
+        bond_post == bond_pre
+    };
+    if !is_valid {
+        tracing::info!(
+            "Share token supply change from {} to {} doesn't match the liquid \
+             bond change from {} to {}",
+            supply_pre.to_string_native(),
+            supply_post.to_string_native(),
+            bond_pre.to_string_native(),
+            bond_post.to_string_native(),
+        );
+    }
+    Ok(is_valid)
+}
@@ -70,6 +70,9 @@ pub struct OwnedPosParams {
     pub rewards_gain_p: Dec,
     /// PoS gain d (read only)
     pub rewards_gain_d: Dec,
+    /// The maximum number of epochs past the pipeline epoch in which a
+    /// scheduled change of a validator's commission rate can take effect
+    pub max_commission_schedule_len: u64,
 }
 
 impl Default for PosParams {
@@ -109,6 +112,7 @@ impl Default for OwnedPosParams {
             liveness_threshold: Dec::new(9, 1).expect("Test failed"),
             rewards_gain_p: Dec::from_str("0.25").expect("Test failed"),
             rewards_gain_d: Dec::from_str("0.25").expect("Test failed"),
+            max_commission_schedule_len: 30,
         }
     }
 }
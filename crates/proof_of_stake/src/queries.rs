@@ -1,23 +1,45 @@
 //! Queriezzz
 
 use std::cmp;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use borsh::BorshDeserialize;
 use namada_core::address::Address;
 use namada_core::dec::Dec;
-use namada_core::storage::Epoch;
+use namada_core::key::{common, tm_consensus_key_raw_hash};
+use namada_core::storage::{BlockHeight, Epoch};
 use namada_core::token;
-use namada_storage::collections::lazy_map::{NestedSubKey, SubKey};
-use namada_storage::StorageRead;
+use namada_storage::collections::lazy_map::{
+    Collectable, NestedSubKey, SubKey,
+};
+use namada_storage::{OptionExt, StorageRead};
 
-use crate::slashing::{find_validator_slashes, get_slashed_amount};
-use crate::storage::{bond_handle, read_pos_params, unbond_handle};
+use crate::simulation::{
+    ValidatorSetParams, ValidatorSetSnapshot, ValidatorSnapshot,
+};
+use crate::slashing::{
+    compute_amount_after_slashing_unbond,
+    compute_amount_after_slashing_withdraw, compute_list_slashes_losses,
+    find_validator_slashes, get_slashed_amount,
+};
+use crate::storage::{
+    bond_handle, delegator_redelegated_bonds_handle,
+    delegator_redelegated_unbonds_handle, find_validator_by_raw_hash,
+    liveness_sum_missed_votes_handle, read_all_validator_addresses,
+    read_pos_params, read_validator_stake, unbond_handle,
+    validator_commission_rate_handle, validator_consensus_key_handle,
+    validator_history_handle, validator_jail_history_handle,
+    validator_state_handle,
+};
 use crate::types::{
-    BondDetails, BondId, BondsAndUnbondsDetail, BondsAndUnbondsDetails, Slash,
-    UnbondDetails,
+    BondDetails, BondId, BondsAndUnbondsDetail, BondsAndUnbondsDetails,
+    ConsensusKeyRotation, ConsensusKeyStatus, DelegatorSlashLoss,
+    EagerRedelegatedBondsMap, LivenessWindow, PendingRedelegation,
+    PendingUnbond, PendingUnbondsAndRedelegations, Slash, SlashedHolding,
+    UnbondDetails, ValidatorEpochRecord, ValidatorHistoryDetails,
+    ValidatorLivenessDetails, ValidatorState,
 };
-use crate::{storage_key, PosParams};
+use crate::{epoched, liveness_missed_votes_threshold, storage_key, PosParams};
 
 /// Find all validators to which a given bond `owner` (or source) has a
 /// delegation
@@ -114,6 +136,340 @@ where
         .collect()
 }
 
+/// Read the commission rate, stake and state of a validator in the given epoch
+/// from the epoched data. Returns `None` if the validator doesn't exist in the
+/// epoch.
+pub fn read_validator_epoch_record<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    epoch: Epoch,
+) -> namada_storage::Result<Option<ValidatorEpochRecord>>
+where
+    S: StorageRead,
+{
+    let commission_rate = validator_commission_rate_handle(validator)
+        .get(storage, epoch, params)?;
+    let state =
+        validator_state_handle(validator).get(storage, epoch, params)?;
+    match (commission_rate, state) {
+        (Some(commission_rate), Some(state)) => {
+            let stake =
+                read_validator_stake(storage, params, validator, epoch)?;
+            Ok(Some(ValidatorEpochRecord {
+                commission_rate,
+                stake,
+                state,
+            }))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Read a snapshot of all the validators in the current epoch to start a
+/// simulation of the validator sets from. The records of the validators are
+/// read up to the pipeline epoch, in which the stakes and the states of the
+/// validators are last known.
+pub fn validator_set_snapshot<S>(
+    storage: &S,
+    current_epoch: Epoch,
+) -> namada_storage::Result<ValidatorSetSnapshot>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    // The validators that are being initialized are only found in the
+    // pipeline epoch
+    let mut addresses: Vec<Address> =
+        read_all_validator_addresses(storage, pipeline_epoch)?
+            .into_iter()
+            .collect();
+    addresses.sort();
+
+    let mut validators = Vec::with_capacity(addresses.len());
+    for address in addresses {
+        let mut records = BTreeMap::new();
+        for epoch in Epoch::iter_bounds_inclusive(current_epoch, pipeline_epoch)
+        {
+            if let Some(record) =
+                read_validator_epoch_record(storage, &params, &address, epoch)?
+            {
+                records.insert(epoch, record);
+            }
+        }
+        let missed_votes =
+            liveness_sum_missed_votes_handle().get(storage, &address)?;
+        validators.push(ValidatorSnapshot {
+            address,
+            records,
+            missed_votes,
+        });
+    }
+
+    Ok(ValidatorSetSnapshot {
+        epoch: current_epoch,
+        params: ValidatorSetParams::from(&params.owned),
+        validators,
+    })
+}
+
+/// Find the commission rate, stake and state of a validator in every epoch of
+/// the given inclusive range, and the slashes that occurred in the range.
+///
+/// The epochs that are still retained in the epoched data are read from it,
+/// while the older epochs are looked up in the validator's history. Epochs
+/// before the history was first recorded or whose records have been pruned, or
+/// in which the validator didn't exist, are omitted.
+pub fn validator_history<S>(
+    storage: &S,
+    validator: &Address,
+    start: Epoch,
+    end: Epoch,
+    current_epoch: Epoch,
+) -> namada_storage::Result<ValidatorHistoryDetails>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    let end = cmp::min(end, current_epoch + params.pipeline_len);
+    let oldest_retained_epoch =
+        current_epoch.sub_or_default(Epoch(epoched::DEFAULT_NUM_PAST_EPOCHS));
+    let history = validator_history_handle(validator).collect_map(storage)?;
+    // Skip the epochs for which there can't be any data
+    let first_epoch = history
+        .keys()
+        .next()
+        .map_or(oldest_retained_epoch, |first| {
+            cmp::min(*first, oldest_retained_epoch)
+        });
+    let start = cmp::max(start, first_epoch);
+
+    let mut records = BTreeMap::new();
+    if start <= end {
+        for epoch in Epoch::iter_bounds_inclusive(start, end) {
+            let record = if epoch >= oldest_retained_epoch {
+                read_validator_epoch_record(storage, &params, validator, epoch)?
+            } else {
+                // The latest record up to the epoch is still in effect
+                history
+                    .range(..=epoch)
+                    .next_back()
+                    .map(|(_, record)| *record)
+            };
+            if let Some(record) = record {
+                records.insert(epoch, record);
+            }
+        }
+    }
+    let slashes = find_validator_slashes(storage, validator)?
+        .into_iter()
+        .filter(|slash| start <= slash.epoch && slash.epoch <= end)
+        .collect();
+    Ok(ValidatorHistoryDetails { records, slashes })
+}
+
+/// Find the liveness of a validator in the current sliding window and its jail
+/// history. The `last_vote_height` is the height of the last block whose votes
+/// have been recorded, i.e. the block before the last committed one.
+///
+/// Only the blocks of the window since the validator last entered the
+/// consensus set are counted, as its votes are not recorded before.
+pub fn validator_liveness<S>(
+    storage: &S,
+    validator: &Address,
+    last_vote_height: BlockHeight,
+) -> namada_storage::Result<ValidatorLivenessDetails>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    let missed_votes =
+        liveness_sum_missed_votes_handle().get(storage, validator)?;
+    let window = match missed_votes {
+        Some(missed_votes) => {
+            // The votes are recorded from the first block
+            let window_start = BlockHeight(
+                last_vote_height
+                    .0
+                    .saturating_sub(params.liveness_window_check)
+                    + 1,
+            );
+            let start = consensus_entry_height(
+                storage,
+                &params,
+                validator,
+                last_vote_height,
+                window_start,
+            )?;
+            let num_blocks = (last_vote_height.0 + 1).saturating_sub(start.0);
+            Some(LivenessWindow {
+                num_blocks,
+                signed_votes: num_blocks.saturating_sub(missed_votes),
+                missed_votes,
+                missed_votes_threshold: liveness_missed_votes_threshold(
+                    &params,
+                )?,
+            })
+        }
+        None => None,
+    };
+    let jail_history =
+        validator_jail_history_handle(validator).collect_map(storage)?;
+    Ok(ValidatorLivenessDetails {
+        window,
+        jail_history,
+    })
+}
+
+/// Find the height of the first block since which the validator has been in
+/// the consensus set without interruption up to the given `height`. The
+/// search stops at the epoch that contains the `window_start` height, which is
+/// returned if the validator has been in the consensus set since then or if
+/// the heights of the epochs are not known.
+///
+/// Like in [`validator_history`], the states of the epochs that are no longer
+/// retained in the epoched data are looked up in the validator's history.
+fn consensus_entry_height<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    height: BlockHeight,
+    window_start: BlockHeight,
+) -> namada_storage::Result<BlockHeight>
+where
+    S: StorageRead,
+{
+    let Some(mut epoch) = storage.get_epoch_at_height(height)? else {
+        return Ok(window_start);
+    };
+    let oldest_retained_epoch =
+        epoch.sub_or_default(Epoch(epoched::DEFAULT_NUM_PAST_EPOCHS));
+    let mut history = None;
+    loop {
+        let Some(start_height) = storage.get_epoch_start_height(epoch)? else {
+            return Ok(window_start);
+        };
+        if start_height <= window_start {
+            return Ok(window_start);
+        }
+        let Some(prev_epoch) = epoch.checked_sub(1) else {
+            return Ok(start_height);
+        };
+        let state = if prev_epoch >= oldest_retained_epoch {
+            validator_state_handle(validator)
+                .get(storage, prev_epoch, params)?
+        } else {
+            if history.is_none() {
+                history = Some(
+                    validator_history_handle(validator).collect_map(storage)?,
+                );
+            }
+            // The latest record up to the epoch is still in effect
+            history.as_ref().and_then(|history| {
+                history
+                    .range(..=prev_epoch)
+                    .next_back()
+                    .map(|(_, record)| record.state)
+            })
+        };
+        if state != Some(ValidatorState::Consensus) {
+            return Ok(start_height);
+        }
+        epoch = prev_epoch;
+    }
+}
+
+/// Find the consensus key of a validator in the current epoch and the first
+/// change of the key that is pending, up to the pipeline epoch.
+pub fn consensus_key_rotation<S>(
+    storage: &S,
+    validator: &Address,
+    current_epoch: Epoch,
+) -> namada_storage::Result<ConsensusKeyRotation>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    let handle = validator_consensus_key_handle(validator);
+    let current_key = handle.get(storage, current_epoch, &params)?;
+    let mut pending = None;
+    for epoch in current_epoch.next().iter_range(params.pipeline_len) {
+        let key = handle.get(storage, epoch, &params)?;
+        if key != current_key {
+            pending = key.map(|key| (epoch, key));
+            break;
+        }
+    }
+    Ok(ConsensusKeyRotation {
+        current_key,
+        pending,
+    })
+}
+
+/// Find the validator that the given consensus key belongs to and the status
+/// of the key with respect to the changes of the validator's consensus key.
+/// Returns `None` if the key has never been a validator's consensus key.
+pub fn consensus_key_status<S>(
+    storage: &S,
+    consensus_key: &common::PublicKey,
+    current_epoch: Epoch,
+) -> namada_storage::Result<Option<(Address, ConsensusKeyStatus)>>
+where
+    S: StorageRead,
+{
+    let validator = match find_validator_by_raw_hash(
+        storage,
+        tm_consensus_key_raw_hash(consensus_key),
+    )? {
+        Some(validator) => validator,
+        None => return Ok(None),
+    };
+    let params = read_pos_params(storage)?;
+    let handle = validator_consensus_key_handle(&validator);
+    let is_current = handle.get(storage, current_epoch, &params)?.as_ref()
+        == Some(consensus_key);
+
+    // Find the first epoch up to the pipeline epoch in which the key starts
+    // or stops being the validator's consensus key
+    for epoch in current_epoch.next().iter_range(params.pipeline_len) {
+        let key = handle.get(storage, epoch, &params)?;
+        match key {
+            Some(new_key) if is_current && new_key != *consensus_key => {
+                return Ok(Some((
+                    validator,
+                    ConsensusKeyStatus::RotatingOut {
+                        switch_epoch: epoch,
+                        new_key,
+                    },
+                )));
+            }
+            Some(key) if !is_current && key == *consensus_key => {
+                return Ok(Some((
+                    validator,
+                    ConsensusKeyStatus::RotatingIn {
+                        switch_epoch: epoch,
+                    },
+                )));
+            }
+            _ => {}
+        }
+    }
+    if is_current {
+        return Ok(Some((validator, ConsensusKeyStatus::Active)));
+    }
+    // The consensus keys are unique, so a key that was used by the validator
+    // cannot become its consensus key again
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let new_key = handle
+        .get(storage, pipeline_epoch, &params)?
+        .ok_or_err_msg("A validator must have a consensus key")?;
+    Ok(Some((
+        validator,
+        ConsensusKeyStatus::RotatedOut { new_key },
+    )))
+}
+
 /// Find raw unbond deltas for the given source and validator address.
 pub fn find_unbonds<S>(
     storage: &S,
@@ -138,6 +494,380 @@ where
         .collect()
 }
 
+/// Find the unbonds of a delegator that have not been withdrawn yet and the
+/// redelegations of the delegator that can still be slashed for the infractions
+/// of their source validator, with their amounts after the known slashes.
+pub fn find_pending_unbonds_and_redelegations<S>(
+    storage: &S,
+    delegator: &Address,
+    current_epoch: Epoch,
+) -> namada_storage::Result<PendingUnbondsAndRedelegations>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+
+    let mut unbonds = vec![];
+    for validator in find_unbond_validators(storage, delegator)? {
+        let slashes = find_validator_slashes(storage, &validator)?;
+        for ((start_epoch, withdraw_epoch), amount) in
+            find_unbonds(storage, delegator, &validator)?
+        {
+            let redelegated = find_redelegated_unbond(
+                storage,
+                delegator,
+                &validator,
+                start_epoch,
+                withdraw_epoch,
+            )?;
+            // The amount is computed the same way as in `withdraw_tokens`
+            let unbond = BTreeMap::from([(
+                (start_epoch, withdraw_epoch),
+                (amount, redelegated.clone()),
+            )]);
+            let amount_after_slashing = compute_amount_after_slashing_withdraw(
+                storage,
+                &params,
+                &unbond,
+                slashes.clone(),
+            )?
+            .sum;
+            unbonds.push(PendingUnbond {
+                validator: validator.clone(),
+                start_epoch,
+                withdraw_epoch,
+                amount,
+                amount_after_slashing,
+                slashable_until: withdraw_epoch
+                    - params.unbonding_len
+                    - params.cubic_slashing_window_length,
+                redelegated: redelegated
+                    .into_iter()
+                    .map(|(src_validator, bonds)| {
+                        (src_validator, bonds.into_values().sum())
+                    })
+                    .collect(),
+            });
+        }
+    }
+
+    // Group the redelegated bonds by their redelegation, skipping the ones
+    // that are no longer slashable for the source validator's infractions
+    let mut redelegated_bonds: BTreeMap<
+        (Address, Address, Epoch),
+        BTreeMap<Epoch, token::Amount>,
+    > = BTreeMap::new();
+    for next in delegator_redelegated_bonds_handle(delegator).iter(storage)? {
+        let (
+            NestedSubKey::Data {
+                key: dest_validator,
+                nested_sub_key:
+                    NestedSubKey::Data {
+                        key: start_epoch,
+                        nested_sub_key:
+                            NestedSubKey::Data {
+                                key: src_validator,
+                                nested_sub_key: SubKey::Data(bond_start),
+                            },
+                    },
+            },
+            amount,
+        ) = next?;
+        let mature_epoch =
+            start_epoch.prev() + params.slash_processing_epoch_offset();
+        if mature_epoch <= current_epoch {
+            continue;
+        }
+        redelegated_bonds
+            .entry((src_validator, dest_validator, start_epoch))
+            .or_default()
+            .insert(bond_start, amount);
+    }
+
+    let mut redelegations = vec![];
+    for ((src_validator, dest_validator, start_epoch), bonds) in
+        redelegated_bonds
+    {
+        let amount: token::Amount = bonds.values().copied().sum();
+        let dest_slashes = find_validator_slashes(storage, &dest_validator)?;
+        // Slash the redelegated bond as if it was unbonded from the
+        // destination validator
+        let redelegated_bond = BTreeMap::from([(
+            start_epoch,
+            BTreeMap::from([(src_validator.clone(), bonds)]),
+        )]);
+        let amount_after_slashing = compute_amount_after_slashing_unbond(
+            storage,
+            &params,
+            &BTreeMap::from([(start_epoch, amount)]),
+            &redelegated_bond,
+            dest_slashes,
+        )?
+        .sum;
+        redelegations.push(PendingRedelegation {
+            src_validator,
+            dest_validator,
+            start_epoch,
+            amount,
+            amount_after_slashing,
+            slashable_until: start_epoch,
+            mature_epoch: start_epoch.prev()
+                + params.slash_processing_epoch_offset(),
+        });
+    }
+
+    Ok(PendingUnbondsAndRedelegations {
+        unbonds,
+        redelegations,
+    })
+}
+
+/// Find all validators from which a given `source` has unbonds that have not
+/// been withdrawn yet
+fn find_unbond_validators<S>(
+    storage: &S,
+    source: &Address,
+) -> namada_storage::Result<BTreeSet<Address>>
+where
+    S: StorageRead,
+{
+    let unbonds_prefix = storage_key::unbonds_for_source_prefix(source);
+    let mut validators: BTreeSet<Address> = BTreeSet::new();
+    for iter_result in
+        namada_storage::iter_prefix_bytes(storage, &unbonds_prefix)?
+    {
+        let (key, _unbond_bytes) = iter_result?;
+        if let Some((bond_id, _start, _withdraw)) =
+            storage_key::is_unbond_key(&key)
+        {
+            if bond_id.source == *source {
+                validators.insert(bond_id.validator);
+            }
+        }
+    }
+    Ok(validators)
+}
+
+/// Find the redelegated parts of an unbond, keyed by their source validator
+/// and the start epoch of their bond to the source validator
+fn find_redelegated_unbond<S>(
+    storage: &S,
+    delegator: &Address,
+    validator: &Address,
+    start_epoch: Epoch,
+    withdraw_epoch: Epoch,
+) -> namada_storage::Result<EagerRedelegatedBondsMap>
+where
+    S: StorageRead,
+{
+    let mut redelegated = EagerRedelegatedBondsMap::default();
+    for next in delegator_redelegated_unbonds_handle(delegator)
+        .at(validator)
+        .at(&start_epoch)
+        .at(&withdraw_epoch)
+        .iter(storage)?
+    {
+        let (
+            NestedSubKey::Data {
+                key: src_validator,
+                nested_sub_key: SubKey::Data(bond_start),
+            },
+            amount,
+        ) = next?;
+        redelegated
+            .entry(src_validator)
+            .or_default()
+            .insert(bond_start, amount);
+    }
+    Ok(redelegated)
+}
+
+/// Report every slash that took tokens from the bonds and the unbonds that a
+/// delegator currently holds, together with the amount that each of them lost.
+/// The amounts are computed with the same cubic slashing rules as the ones
+/// applied when the tokens are unbonded and withdrawn. The losses of the
+/// unbonds that have already been withdrawn are not reported, as these are no
+/// longer in storage. The losses are ordered by the slash infraction epoch.
+pub fn delegator_slash_report<S>(
+    storage: &S,
+    delegator: &Address,
+) -> namada_storage::Result<Vec<DelegatorSlashLoss>>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    let mut validators: BTreeSet<Address> =
+        find_delegation_validators(storage, delegator)?
+            .into_iter()
+            .collect();
+    validators.extend(find_unbond_validators(storage, delegator)?);
+
+    let mut losses = vec![];
+    for validator in validators {
+        let slashes = find_validator_slashes(storage, &validator)?;
+
+        let redelegated_bonds =
+            delegator_redelegated_bonds_handle(delegator).at(&validator);
+        for next in bond_handle(delegator, &validator)
+            .get_data_handler()
+            .iter(storage)?
+        {
+            let (start, amount) = next?;
+            let redelegated =
+                redelegated_bonds.at(&start).collect_map(storage)?;
+            let bond_slashes: Vec<Slash> = slashes
+                .iter()
+                .filter(|slash| start <= slash.epoch)
+                .cloned()
+                .collect();
+            collect_holding_slash_losses(
+                storage,
+                &params,
+                SlashedHolding {
+                    validator: validator.clone(),
+                    start,
+                    withdraw: None,
+                    redelegated_from: None,
+                },
+                amount,
+                &redelegated,
+                &bond_slashes,
+                &mut losses,
+            )?;
+        }
+
+        for ((start, withdraw), amount) in
+            find_unbonds(storage, delegator, &validator)?
+        {
+            let redelegated = find_redelegated_unbond(
+                storage, delegator, &validator, start, withdraw,
+            )?;
+            // The slashes are filtered the same way as in `withdraw_tokens`
+            let unbond_slashes: Vec<Slash> = slashes
+                .iter()
+                .filter(|slash| {
+                    start <= slash.epoch
+                        && slash.epoch
+                            + params.unbonding_len
+                            + params.cubic_slashing_window_length
+                            < withdraw
+                })
+                .cloned()
+                .collect();
+            collect_holding_slash_losses(
+                storage,
+                &params,
+                SlashedHolding {
+                    validator: validator.clone(),
+                    start,
+                    withdraw: Some(withdraw),
+                    redelegated_from: None,
+                },
+                amount,
+                &redelegated,
+                &unbond_slashes,
+                &mut losses,
+            )?;
+        }
+    }
+    losses.sort_by_key(|loss| loss.slash.epoch);
+    Ok(losses)
+}
+
+/// Collect the losses of a bond or unbond of `amount` to the given slashes of
+/// its validator. The redelegated parts of the bond are additionally slashed
+/// for the infractions of their source validator, like in
+/// `fold_and_slash_redelegated_bonds`.
+#[allow(clippy::too_many_arguments)]
+fn collect_holding_slash_losses<S>(
+    storage: &S,
+    params: &PosParams,
+    holding: SlashedHolding,
+    amount: token::Amount,
+    redelegated: &EagerRedelegatedBondsMap,
+    slashes: &[Slash],
+    losses: &mut Vec<DelegatorSlashLoss>,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead,
+{
+    let total_redelegated: token::Amount = redelegated
+        .values()
+        .flat_map(|bonds| bonds.values())
+        .copied()
+        .sum();
+    let not_redelegated =
+        amount.checked_sub(total_redelegated).unwrap_or_default();
+    let tagged_slashes: Vec<(Address, Slash)> = slashes
+        .iter()
+        .map(|slash| (holding.validator.clone(), slash.clone()))
+        .collect();
+    push_slash_losses(
+        params,
+        &tagged_slashes,
+        not_redelegated,
+        &holding,
+        losses,
+    );
+
+    let redelegation_start =
+        params.redelegation_start_epoch_from_end(holding.start);
+    for (src_validator, bonds) in redelegated {
+        let src_slashes = find_validator_slashes(storage, src_validator)?;
+        for (&bond_start, &change) in bonds {
+            let mut merged: Vec<(Address, Slash)> = src_slashes
+                .iter()
+                .filter(|slash| {
+                    params.in_redelegation_slashing_window(
+                        slash.epoch,
+                        redelegation_start,
+                        holding.start,
+                    ) && bond_start <= slash.epoch
+                })
+                .map(|slash| (src_validator.clone(), slash.clone()))
+                .chain(tagged_slashes.iter().cloned())
+                .collect();
+            merged.sort_by_key(|(_, slash)| slash.epoch);
+            let redelegated_holding = SlashedHolding {
+                redelegated_from: Some((src_validator.clone(), bond_start)),
+                ..holding.clone()
+            };
+            push_slash_losses(
+                params,
+                &merged,
+                change,
+                &redelegated_holding,
+                losses,
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Push the non-zero losses of `amount` to a list of the slashes, tagged with
+/// the slashed validator and ordered by the infraction epoch
+fn push_slash_losses(
+    params: &PosParams,
+    slashes: &[(Address, Slash)],
+    amount: token::Amount,
+    holding: &SlashedHolding,
+    losses: &mut Vec<DelegatorSlashLoss>,
+) {
+    let list_slashes: Vec<Slash> =
+        slashes.iter().map(|(_, slash)| slash.clone()).collect();
+    let amounts = compute_list_slashes_losses(params, &list_slashes, amount);
+    for ((slashed_validator, slash), amount) in slashes.iter().zip(amounts) {
+        if !amount.is_zero() {
+            losses.push(DelegatorSlashLoss {
+                slashed_validator: slashed_validator.clone(),
+                slash: slash.clone(),
+                holding: holding.clone(),
+                amount,
+            });
+        }
+    }
+}
+
 /// Collect the details of all bonds and unbonds that match the source and
 /// validator arguments. If either source or validator is `None`, then grab the
 /// information for all sources or validators, respectively.
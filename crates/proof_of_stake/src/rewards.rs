@@ -7,23 +7,30 @@ use namada_core::dec::Dec;
 use namada_core::storage::{BlockHeight, Epoch};
 use namada_core::token::{self, Amount};
 use namada_core::uint::{Uint, I256};
+use namada_governance::pgf::storage::keys as pgf_storage;
 use namada_parameters::storage as params_storage;
 use namada_storage::collections::lazy_map::NestedSubKey;
 use namada_storage::{ResultExt, StorageRead, StorageWrite};
 use thiserror::Error;
 
+use crate::queries::validator_liveness;
 use crate::storage::{
-    consensus_validator_set_handle, get_last_reward_claim_epoch,
-    read_pos_params, read_total_stake, read_validator_stake,
-    rewards_accumulator_handle, validator_commission_rate_handle,
-    validator_rewards_products_handle, validator_state_handle,
+    bond_handle, consensus_validator_set_handle, get_last_reward_claim_epoch,
+    read_consensus_validator_set_addresses_with_stake, read_pos_params,
+    read_total_stake, read_validator_stake, rewards_accumulator_handle,
+    validator_commission_rate_handle, validator_rewards_products_handle,
+    validator_state_handle,
 };
 use crate::token::storage_key::minted_balance_key;
 use crate::token::{credit_tokens, inflation};
-use crate::types::{into_tm_voting_power, BondId, ValidatorState, VoteInfo};
+use crate::types::{
+    into_tm_voting_power, BondId, BondRewardsProjection, RestakeSettings,
+    ValidatorState, VoteInfo,
+};
 use crate::{
-    bond_amounts_for_rewards, get_total_consensus_stake, staking_token_address,
-    storage, storage_key, InflationError, PosParams,
+    bond_amounts_for_rewards, bond_tokens, claim_reward_tokens,
+    get_total_consensus_stake, is_validator, staking_token_address, storage,
+    storage_key, InflationError, PosParams,
 };
 
 /// This is equal to 0.01.
@@ -368,6 +375,283 @@ where
         .write(&params_storage::get_pos_inflation_amount_key(), inflation)?;
     storage.write(&params_storage::get_staked_ratio_key(), locked_ratio)?;
 
+    // Now that the rewards products of the last epoch are known, restake the
+    // rewards of the bonds that are due in the new epoch
+    restake_rewards(storage, &params, last_epoch.next())?;
+
+    Ok(())
+}
+
+/// Project the rewards that a new bond of the given `amount` to the
+/// `validator` would earn from the pipeline epoch on. The projection assumes
+/// that the consensus set doesn't change otherwise and that the validator
+/// keeps signing blocks at the rate of the current liveness window, whose last
+/// block with recorded votes is at `last_vote_height`.
+///
+/// The PoS inflation is projected by running the rewards controller with the
+/// staked ratio that includes the new bond. As a validator proposes blocks and
+/// earns signing rewards in proportion to its stake, the rewards of the bond
+/// are the inflation times the bond's share of the consensus stake, weighted
+/// by the validator's uptime and less the validator's commission.
+///
+/// The real APR discounts the nominal APR by the growth of the staking token
+/// supply, which is minted both by the PoS inflation and by the PGF inflation
+/// of the funding and of the stewards.
+pub fn project_bond_rewards<S>(
+    storage: &S,
+    validator: &Address,
+    amount: token::Amount,
+    current_epoch: Epoch,
+    last_vote_height: BlockHeight,
+) -> namada_storage::Result<BondRewardsProjection>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    let start_epoch = current_epoch + params.pipeline_len;
+
+    // Read from Parameters storage
+    let epochs_per_year: u64 = storage
+        .read(&params_storage::get_epochs_per_year_key())?
+        .ok_or_else(|| {
+            namada_storage::Error::new_const(
+                "Epochs per year should exist in storage",
+            )
+        })?;
+    let pos_last_staked_ratio: Dec = storage
+        .read(&params_storage::get_staked_ratio_key())?
+        .ok_or_else(|| {
+            namada_storage::Error::new_const(
+                "PoS staked ratio should exist in storage",
+            )
+        })?;
+    let pos_last_inflation_amount: token::Amount = storage
+        .read(&params_storage::get_pos_inflation_amount_key())?
+        .ok_or_else(|| {
+            namada_storage::Error::new_const(
+                "PoS inflation amount should exist in storage",
+            )
+        })?;
+    let pgf_inflation_rate: Dec = storage
+        .read(&pgf_storage::get_pgf_inflation_rate_key())?
+        .ok_or_else(|| {
+            namada_storage::Error::new_const(
+                "PGF inflation rate should exist in storage",
+            )
+        })?;
+    let stewards_inflation_rate: Dec = storage
+        .read(&pgf_storage::get_steward_inflation_rate_key())?
+        .ok_or_else(|| {
+            namada_storage::Error::new_const(
+                "PGF stewards inflation rate should exist in storage",
+            )
+        })?;
+
+    let staking_token = staking_token_address(storage);
+    let total_tokens: token::Amount = storage
+        .read(&minted_balance_key(&staking_token))?
+        .ok_or_else(|| {
+            namada_storage::Error::new_const(
+                "Total NAM balance should exist in storage",
+            )
+        })?;
+    let pos_locked_supply =
+        read_total_stake(storage, &params, start_epoch)? + amount;
+
+    // Run rewards PD controller with the new bond
+    let pos_controller = inflation::PosRewardsController {
+        locked_tokens: pos_locked_supply.raw_amount(),
+        total_native_tokens: total_tokens.raw_amount(),
+        locked_ratio_target: params.target_staked_ratio,
+        locked_ratio_last: pos_last_staked_ratio,
+        max_reward_rate: params.max_inflation_rate,
+        last_inflation_amount: pos_last_inflation_amount.raw_amount(),
+        p_gain_nom: params.rewards_gain_p,
+        d_gain_nom: params.rewards_gain_d,
+        epochs_per_year,
+    };
+    let inflation::PosValsToUpdate {
+        locked_ratio: staked_ratio,
+        inflation,
+    } = pos_controller.run();
+    let inflation_per_epoch =
+        token::Amount::from_uint(inflation, 0).into_storage_result()?;
+
+    // Find the consensus stake with the new bond, if the validator would be
+    // in the consensus set
+    let validator_stake =
+        read_validator_stake(storage, &params, validator, start_epoch)?
+            + amount;
+    let consensus_set = read_consensus_validator_set_addresses_with_stake(
+        storage,
+        start_epoch,
+    )?;
+    let consensus_stake = consensus_set
+        .iter()
+        .fold(token::Amount::zero(), |acc, weighted| {
+            acc + weighted.bonded_stake
+        });
+    let state =
+        validator_state_handle(validator).get(storage, start_epoch, &params)?;
+    let consensus_stake = match state {
+        Some(ValidatorState::Consensus) => Some(consensus_stake + amount),
+        Some(
+            ValidatorState::BelowCapacity | ValidatorState::BelowThreshold,
+        ) if validator_stake >= params.validator_stake_threshold => {
+            if (consensus_set.len() as u64) < params.max_validator_slots {
+                Some(consensus_stake + validator_stake)
+            } else {
+                // The set is ordered by stake, so the first validator is the
+                // one that would be swapped out
+                consensus_set
+                    .first()
+                    .filter(|min| validator_stake > min.bonded_stake)
+                    .map(|min| {
+                        consensus_stake - min.bonded_stake + validator_stake
+                    })
+            }
+        }
+        _ => None,
+    };
+
+    let liveness = validator_liveness(storage, validator, last_vote_height)?;
+    let uptime = liveness
+        .window
+        .filter(|window| window.num_blocks > 0)
+        .map_or(Dec::one(), |window| {
+            Dec::from(window.signed_votes) / Dec::from(window.num_blocks)
+        });
+    let commission_rate = validator_commission_rate_handle(validator)
+        .get(storage, start_epoch, &params)?
+        .unwrap_or_default();
+
+    // The rewards rate of a token bonded to the validator per epoch
+    let rate_per_epoch = match consensus_stake {
+        Some(consensus_stake) if !consensus_stake.is_zero() => {
+            // Assume that all the consensus validators sign the blocks
+            let rewards_calculator = PosRewardsCalculator {
+                proposer_reward: params.block_proposer_reward,
+                signer_reward: params.block_vote_reward,
+                signing_stake: consensus_stake,
+                total_stake: consensus_stake,
+            };
+            let coeffs = rewards_calculator
+                .get_reward_coeffs()
+                .map_err(InflationError::Rewards)
+                .into_storage_result()?;
+            let rewards_frac = coeffs.proposer_coeff
+                + uptime * coeffs.signer_coeff
+                + coeffs.active_val_coeff;
+            (Dec::one() - commission_rate)
+                * rewards_frac
+                * (Dec::from(inflation_per_epoch) / Dec::from(consensus_stake))
+        }
+        _ => Dec::zero(),
+    };
+    let nominal_apr = rate_per_epoch * epochs_per_year;
+    // The PGF inflation mints its yearly rates of the total supply
+    let pos_supply_growth = if total_tokens.is_zero() {
+        Dec::zero()
+    } else {
+        Dec::from(inflation_per_epoch) * epochs_per_year
+            / Dec::from(total_tokens)
+    };
+    let supply_growth =
+        pos_supply_growth + pgf_inflation_rate + stewards_inflation_rate;
+    let real_apr =
+        (Dec::one() + nominal_apr) / (Dec::one() + supply_growth) - Dec::one();
+
+    Ok(BondRewardsProjection {
+        start_epoch,
+        rewards_per_epoch: rate_per_epoch * amount,
+        nominal_apr,
+        real_apr,
+        inflation_per_epoch,
+        staked_ratio,
+        commission_rate,
+        uptime,
+        is_consensus: consensus_stake.is_some(),
+    })
+}
+
+/// Claim the rewards of every bond whose restake settings are due in the
+/// current epoch and bond them back to the same validator. Like any other
+/// bond, the restaked tokens are added at the pipeline offset and only start
+/// earning rewards from the epoch `current_epoch + pipeline_len`. The rewards
+/// tallied in the rewards counter are restaked together with the rewards of
+/// the bond itself.
+///
+/// The restake settings of a bond that has been fully unbonded, or that can no
+/// longer be bonded to (e.g. because the source became a validator), are
+/// removed and its remaining rewards are left to be claimed manually.
+pub fn restake_rewards<S>(
+    storage: &mut S,
+    params: &PosParams,
+    current_epoch: Epoch,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let prefix = storage_key::restake_settings_prefix();
+    let mut due = vec![];
+    for res in namada_storage::iter_prefix::<RestakeSettings>(storage, &prefix)?
+    {
+        let (key, settings) = res?;
+        if let Some(bond_id) = storage_key::is_restake_settings_key(&key) {
+            if settings.next_epoch <= current_epoch {
+                due.push((bond_id, settings));
+            }
+        }
+    }
+
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    for (BondId { source, validator }, settings) in due {
+        let has_bonds = !bond_handle(&source, &validator)
+            .get_data_handler()
+            .is_empty(storage)?;
+        // `bond_tokens` must not fail here, so its preconditions are checked
+        // ahead of claiming the rewards
+        let can_bond = validator_state_handle(&validator)
+            .get(storage, pipeline_epoch, params)?
+            .is_some()
+            && (source == validator || !is_validator(storage, &source)?);
+        if !has_bonds || !can_bond {
+            tracing::debug!(
+                "Disabling the restaking of rewards from {source} to \
+                 {validator}"
+            );
+            storage::delete_restake_settings(storage, &source, &validator)?;
+            continue;
+        }
+
+        let amount = claim_reward_tokens(
+            storage,
+            Some(&source),
+            &validator,
+            current_epoch,
+        )?;
+        tracing::debug!(
+            "Restaking {} reward tokens from {source} to {validator}",
+            amount.to_string_native()
+        );
+        bond_tokens(
+            storage,
+            Some(&source),
+            &validator,
+            amount,
+            current_epoch,
+            None,
+        )?;
+        storage::write_restake_settings(
+            storage,
+            &source,
+            &validator,
+            RestakeSettings {
+                next_epoch: current_epoch + settings.interval,
+                ..settings
+            },
+        )?;
+    }
     Ok(())
 }
 
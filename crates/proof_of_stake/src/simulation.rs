@@ -0,0 +1,291 @@
+//! Simulation of the validator sets under hypothetical changes of the PoS
+//! parameters, to inform the governance discussions about these changes.
+//!
+//! The simulation starts from a [`ValidatorSetSnapshot`] of the validators in
+//! some epoch, which can be read from storage with
+//! [`crate::queries::validator_set_snapshot`] or loaded from an export of the
+//! snapshot, and replays the known stake and state changes of the validators
+//! up to the pipeline epoch. From the pipeline epoch on, the stakes are assumed
+//! to not change.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use namada_core::address::Address;
+use namada_core::dec::Dec;
+use namada_core::storage::Epoch;
+use namada_core::token;
+use namada_core::uint::Uint;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{ValidatorEpochRecord, ValidatorState};
+use crate::{liveness_missed_votes_threshold, OwnedPosParams};
+
+/// The PoS parameters that determine the membership of the validator sets
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct ValidatorSetParams {
+    /// A maximum number of consensus validators
+    pub max_validator_slots: u64,
+    /// The minimum amount of bonded tokens that a validator needs to be in
+    /// either the `consensus` or `below_capacity` validator sets
+    pub validator_stake_threshold: token::Amount,
+    /// The length, in blocks, of the sliding window for consensus validators
+    /// inactivity verification
+    pub liveness_window_check: u64,
+    /// The minimum required activity of consesus validators, in percentage,
+    /// over the `liveness_window_check`
+    pub liveness_threshold: Dec,
+}
+
+impl From<&OwnedPosParams> for ValidatorSetParams {
+    fn from(params: &OwnedPosParams) -> Self {
+        Self {
+            max_validator_slots: params.max_validator_slots,
+            validator_stake_threshold: params.validator_stake_threshold,
+            liveness_window_check: params.liveness_window_check,
+            liveness_threshold: params.liveness_threshold,
+        }
+    }
+}
+
+/// A validator in a [`ValidatorSetSnapshot`]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct ValidatorSnapshot {
+    /// The address of the validator
+    pub address: Address,
+    /// The records of the validator from the snapshot epoch up to the pipeline
+    /// epoch. A validator that is being initialized has no records in the
+    /// epochs before it becomes active.
+    pub records: BTreeMap<Epoch, ValidatorEpochRecord>,
+    /// The sum of the votes missed in the current liveness window, if the
+    /// validator is tracked for liveness
+    pub missed_votes: Option<u64>,
+}
+
+/// The validators and the parameters of the validator sets in an epoch, from
+/// which a simulation can be started
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct ValidatorSetSnapshot {
+    /// The epoch of the snapshot
+    pub epoch: Epoch,
+    /// The parameters of the validator sets in the snapshot epoch
+    pub params: ValidatorSetParams,
+    /// All the validators
+    pub validators: Vec<ValidatorSnapshot>,
+}
+
+/// The concentration of the voting power in the consensus validator set
+#[derive(
+    Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct VotingPowerConcentration {
+    /// The total stake of the consensus validators
+    pub total_stake: token::Amount,
+    /// The share of the total stake of the largest consensus validator
+    pub largest_share: Dec,
+    /// The smallest number of consensus validators that together hold more
+    /// than 1/3 of the voting power, enough to halt the chain
+    pub halting_validators: u64,
+    /// The smallest number of consensus validators that together hold more
+    /// than 2/3 of the voting power, enough to commit blocks
+    pub committing_validators: u64,
+}
+
+/// The validator sets in an epoch of a simulation
+#[derive(
+    Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct SimulatedValidatorSets {
+    /// The simulated epoch
+    pub epoch: Epoch,
+    /// The consensus validators with their stake, ordered by descending stake
+    pub consensus: Vec<(Address, token::Amount)>,
+    /// The below-capacity validators with their stake, ordered by descending
+    /// stake
+    pub below_capacity: Vec<(Address, token::Amount)>,
+    /// The below-threshold validators with their stake, ordered by descending
+    /// stake
+    pub below_threshold: Vec<(Address, token::Amount)>,
+    /// The jailed validators, including the ones that would be jailed for
+    /// liveness with the simulated parameters
+    pub jailed: BTreeSet<Address>,
+    /// The concentration of the voting power in the consensus set
+    pub concentration: VotingPowerConcentration,
+}
+
+/// Simulate the validator sets with the given parameters in `num_epochs`
+/// epochs starting from the snapshot epoch.
+///
+/// In every epoch, the validators that are neither jailed nor inactive are
+/// ranked by their stake and the `max_validator_slots` largest ones that are
+/// not below the `validator_stake_threshold` make up the consensus set. On
+/// equal stake, the validators that were in the consensus set in the epoch
+/// before keep their place. The validators that are tracked for liveness and
+/// missed at least as many votes as allowed with the simulated
+/// `liveness_threshold` are jailed from the epoch after the snapshot epoch
+/// on, as the protocol would jail them in the next block, removing them from
+/// the validator sets of the next epoch. The missed votes are not simulated
+/// further, so no other validators are jailed for liveness later on.
+pub fn simulate_validator_sets(
+    snapshot: &ValidatorSetSnapshot,
+    params: &ValidatorSetParams,
+    num_epochs: u64,
+) -> Vec<SimulatedValidatorSets> {
+    let liveness_jail_epoch = snapshot.epoch.next();
+    // There's no threshold for a liveness threshold above 100%
+    let missed_votes_threshold =
+        liveness_missed_votes_threshold(&OwnedPosParams {
+            liveness_window_check: params.liveness_window_check,
+            liveness_threshold: params.liveness_threshold,
+            ..OwnedPosParams::default()
+        })
+        .ok();
+    let jailed_for_liveness: BTreeSet<&Address> = snapshot
+        .validators
+        .iter()
+        .filter(|validator| {
+            matches!(
+                (validator.missed_votes, missed_votes_threshold),
+                (Some(missed_votes), Some(threshold))
+                    if missed_votes >= threshold
+            )
+        })
+        .map(|validator| &validator.address)
+        .collect();
+
+    // Start from the consensus validators in the snapshot epoch
+    let mut prev_consensus: BTreeSet<Address> = snapshot
+        .validators
+        .iter()
+        .filter(|validator| {
+            matches!(
+                validator.records.get(&snapshot.epoch),
+                Some(record) if record.state == ValidatorState::Consensus
+            )
+        })
+        .map(|validator| validator.address.clone())
+        .collect();
+
+    snapshot
+        .epoch
+        .iter_range(num_epochs)
+        .map(|epoch| {
+            let mut jailed = BTreeSet::new();
+            let mut candidates: Vec<(Address, token::Amount)> = vec![];
+            for validator in &snapshot.validators {
+                // The records after the pipeline epoch stay the same as in
+                // the pipeline epoch
+                let Some((_, record)) =
+                    validator.records.range(..=epoch).next_back()
+                else {
+                    continue;
+                };
+                match record.state {
+                    ValidatorState::Jailed => {
+                        jailed.insert(validator.address.clone());
+                    }
+                    ValidatorState::Inactive => {}
+                    _ if epoch >= liveness_jail_epoch
+                        && jailed_for_liveness.contains(&validator.address) =>
+                    {
+                        jailed.insert(validator.address.clone());
+                    }
+                    _ => candidates
+                        .push((validator.address.clone(), record.stake)),
+                }
+            }
+
+            let (mut eligible, mut below_threshold): (Vec<_>, Vec<_>) =
+                candidates.into_iter().partition(|(_, stake)| {
+                    *stake >= params.validator_stake_threshold
+                });
+            eligible.sort_by_key(|(address, stake)| {
+                (Reverse(*stake), !prev_consensus.contains(address))
+            });
+            below_threshold.sort_by_key(|(_, stake)| Reverse(*stake));
+            let num_consensus = usize::try_from(params.max_validator_slots)
+                .unwrap_or(usize::MAX)
+                .min(eligible.len());
+            let below_capacity = eligible.split_off(num_consensus);
+            let consensus = eligible;
+
+            prev_consensus = consensus
+                .iter()
+                .map(|(address, _)| address.clone())
+                .collect();
+            SimulatedValidatorSets {
+                epoch,
+                concentration: voting_power_concentration(&consensus),
+                consensus,
+                below_capacity,
+                below_threshold,
+                jailed,
+            }
+        })
+        .collect()
+}
+
+/// Find the concentration of the voting power in a consensus set, ordered by
+/// descending stake
+fn voting_power_concentration(
+    consensus: &[(Address, token::Amount)],
+) -> VotingPowerConcentration {
+    let total_stake: token::Amount =
+        consensus.iter().map(|(_, stake)| *stake).sum();
+    let largest_share = match consensus.first() {
+        Some((_, stake)) if !total_stake.is_zero() => {
+            Dec::from(*stake) / Dec::from(total_stake)
+        }
+        _ => Dec::zero(),
+    };
+    // The number of the largest validators whose stake exceeds the given
+    // fraction of the total stake
+    let num_to_exceed = |numerator: u64, denominator: u64| -> u64 {
+        let mut sum = token::Amount::zero();
+        let mut num: u64 = 0;
+        for (_, stake) in consensus {
+            if sum.raw_amount() * Uint::from(denominator)
+                > total_stake.raw_amount() * Uint::from(numerator)
+            {
+                break;
+            }
+            sum += *stake;
+            num += 1;
+        }
+        num
+    };
+    VotingPowerConcentration {
+        total_stake,
+        largest_share,
+        halting_validators: num_to_exceed(1, 3),
+        committing_validators: num_to_exceed(2, 3),
+    }
+}
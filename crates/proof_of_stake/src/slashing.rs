@@ -26,8 +26,8 @@ use crate::storage::{
     write_validator_last_slash_epoch,
 };
 use crate::types::{
-    EagerRedelegatedBondsMap, ResultSlashing, Slash, SlashType, SlashedAmount,
-    Slashes, TotalRedelegatedUnbonded, ValidatorState,
+    EagerRedelegatedBondsMap, JailReason, ResultSlashing, Slash, SlashType,
+    SlashedAmount, Slashes, TotalRedelegatedUnbonded, ValidatorState,
 };
 use crate::validator_set_update::update_validator_set;
 use crate::{
@@ -181,6 +181,7 @@ where
         validator,
         current_epoch,
         validator_set_update_epoch,
+        JailReason::Misbehavior(slash_type),
     )?;
 
     // No other actions are performed here until the epoch in which the slash is
@@ -802,6 +803,35 @@ pub fn apply_list_slashes(
     final_amount
 }
 
+/// Computes how much each slash of a list of slashes takes from an amount of
+/// tokens, in the same way as [`apply_list_slashes`].
+///
+/// - `slashes` - a list of slashes ordered by misbehaving epoch.
+/// - `amount` - the amount of slashable tokens.
+pub fn compute_list_slashes_losses(
+    params: &OwnedPosParams,
+    slashes: &[Slash],
+    amount: token::Amount,
+) -> Vec<token::Amount> {
+    let mut remaining = amount;
+    let mut computed_slashes = BTreeMap::<Epoch, token::Amount>::new();
+    slashes
+        .iter()
+        .map(|slash| {
+            let slashed_amount = compute_slashable_amount(
+                params,
+                slash,
+                amount,
+                &computed_slashes,
+            );
+            computed_slashes.insert(slash.epoch, slashed_amount);
+            let lost = cmp::min(slashed_amount, remaining);
+            remaining -= lost;
+            lost
+        })
+        .collect()
+}
+
 /// Computes how much is left from a bond or unbond after applying a slash given
 /// that a set of slashes may have been previously applied.
 // `def computeSlashableAmount`
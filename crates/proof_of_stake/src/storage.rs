@@ -1,7 +1,7 @@
 //! PoS functions for reading and writing to storage and lazy collection handles
 //! associated with given `storage_key`s.
 
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
 use namada_account::protocol_pk_key;
 use namada_core::address::Address;
@@ -10,24 +10,25 @@ use namada_core::key::{common, tm_consensus_key_raw_hash};
 use namada_core::storage::Epoch;
 use namada_core::token;
 use namada_governance::storage::get_max_proposal_period;
-use namada_storage::collections::lazy_map::NestedSubKey;
+use namada_storage::collections::lazy_map::{self, NestedSubKey};
 use namada_storage::collections::{LazyCollection, LazySet};
 use namada_storage::{Result, StorageRead, StorageWrite};
 use num_traits::CheckedAdd;
 
 use crate::storage_key::consensus_keys_key;
 use crate::types::{
-    BelowCapacityValidatorSets, BondId, Bonds, CommissionRates,
-    ConsensusValidatorSets, DelegatorRedelegatedBonded,
+    BelowCapacityValidatorSets, BondId, Bonds, CommissionRateChangeNotices,
+    CommissionRates, ConsensusValidatorSets, DelegatorRedelegatedBonded,
     DelegatorRedelegatedUnbonded, EpochedSlashes, IncomingRedelegations,
     LivenessMissedVotes, LivenessSumMissedVotes, OutgoingRedelegations,
-    ReverseOrdTokenAmount, RewardsAccumulator, RewardsProducts, Slashes,
-    TotalConsensusStakes, TotalDeltas, TotalRedelegatedBonded,
-    TotalRedelegatedUnbonded, Unbonds, ValidatorAddresses,
-    ValidatorConsensusKeys, ValidatorDeltas, ValidatorEthColdKeys,
-    ValidatorEthHotKeys, ValidatorMetaData, ValidatorProtocolKeys,
-    ValidatorSetPositions, ValidatorState, ValidatorStates,
-    ValidatorTotalUnbonded, WeightedValidator,
+    RestakeSettings, ReverseOrdTokenAmount, RewardsAccumulator,
+    RewardsProducts, ScheduledCommissionRates, Slashes, TotalConsensusStakes,
+    TotalDeltas, TotalRedelegatedBonded, TotalRedelegatedUnbonded, Unbonds,
+    ValidatorAddresses, ValidatorConsensusKeys, ValidatorDeltas,
+    ValidatorEthColdKeys, ValidatorEthHotKeys, ValidatorHistory,
+    ValidatorHistoryEpochs, ValidatorJailHistory, ValidatorMetaData,
+    ValidatorProtocolKeys, ValidatorSetPositions, ValidatorState,
+    ValidatorStates, ValidatorTotalUnbonded, WeightedValidator,
 };
 use crate::{storage_key, MetadataError, OwnedPosParams, PosParams};
 
@@ -162,6 +163,27 @@ pub fn validator_slashes_handle(validator: &Address) -> Slashes {
     Slashes::open(key)
 }
 
+/// Get the storage handle to a PoS validator's history
+pub fn validator_history_handle(validator: &Address) -> ValidatorHistory {
+    let key = storage_key::validator_history_key(validator);
+    ValidatorHistory::open(key)
+}
+
+/// Get the storage handle to the index of the epochs of the records in the
+/// validators' histories
+pub fn validator_history_epochs_handle() -> ValidatorHistoryEpochs {
+    let key = storage_key::validator_history_epochs_key();
+    ValidatorHistoryEpochs::open(key)
+}
+
+/// Get the storage handle to a PoS validator's jail history
+pub fn validator_jail_history_handle(
+    validator: &Address,
+) -> ValidatorJailHistory {
+    let key = storage_key::validator_jail_history_key(validator);
+    ValidatorJailHistory::open(key)
+}
+
 /// Get the storage handle to list of all slashes to be processed and ultimately
 /// placed in the `validator_slashes_handle`
 pub fn enqueued_slashes_handle() -> EpochedSlashes {
@@ -244,6 +266,20 @@ pub fn liveness_sum_missed_votes_handle() -> LivenessSumMissedVotes {
     LivenessSumMissedVotes::open(key)
 }
 
+/// Get the storage handle to the commission rate changes scheduled after the
+/// pipeline epoch
+pub fn scheduled_commission_rates_handle() -> ScheduledCommissionRates {
+    let key = storage_key::scheduled_commission_rates_key();
+    ScheduledCommissionRates::open(key)
+}
+
+/// Get the storage handle to the commission rate changes that are yet to be
+/// announced in an event
+pub fn commission_rate_change_notices_handle() -> CommissionRateChangeNotices {
+    let key = storage_key::commission_rate_change_notices_key();
+    CommissionRateChangeNotices::open(key)
+}
+
 // ---- Storage read + write ----
 
 /// Read PoS parameters
@@ -339,6 +375,59 @@ where
     storage.write(&key, change)
 }
 
+/// Read the commission rate changes of a validator that are scheduled to take
+/// effect after the pipeline epoch, keyed by the epoch from which they take
+/// effect.
+pub fn read_validator_scheduled_commission_rates<S>(
+    storage: &S,
+    validator: &Address,
+) -> namada_storage::Result<BTreeMap<Epoch, Dec>>
+where
+    S: StorageRead,
+{
+    let mut rates = BTreeMap::new();
+    for res in scheduled_commission_rates_handle().iter(storage)? {
+        let (
+            NestedSubKey::Data {
+                key: epoch,
+                nested_sub_key: lazy_map::SubKey::Data(address),
+            },
+            rate,
+        ) = res?;
+        if &address == validator {
+            rates.insert(epoch, rate);
+        }
+    }
+    Ok(rates)
+}
+
+/// Read the commission rate of a validator in the given epoch, including the
+/// changes that are scheduled to take effect after the pipeline epoch.
+pub fn read_validator_commission_rate<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    current_epoch: Epoch,
+    epoch: Epoch,
+) -> namada_storage::Result<Option<Dec>>
+where
+    S: StorageRead,
+{
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    if epoch > pipeline_epoch {
+        let scheduled =
+            read_validator_scheduled_commission_rates(storage, validator)?;
+        if let Some((_, rate)) = scheduled.range(..=epoch).next_back() {
+            return Ok(Some(*rate));
+        }
+    }
+    validator_commission_rate_handle(validator).get(
+        storage,
+        epoch.min(pipeline_epoch),
+        params,
+    )
+}
+
 /// Read the most recent slash epoch for the given epoch
 pub fn read_validator_last_slash_epoch<S>(
     storage: &S,
@@ -837,6 +926,46 @@ where
     storage.write(&key, epoch)
 }
 
+/// Read the restake settings of a delegation, if any
+pub fn read_restake_settings<S>(
+    storage: &S,
+    source: &Address,
+    validator: &Address,
+) -> namada_storage::Result<Option<RestakeSettings>>
+where
+    S: StorageRead,
+{
+    let key = storage_key::restake_settings_key(source, validator);
+    storage.read(&key)
+}
+
+/// Write the restake settings of a delegation
+pub fn write_restake_settings<S>(
+    storage: &mut S,
+    source: &Address,
+    validator: &Address,
+    settings: RestakeSettings,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage_key::restake_settings_key(source, validator);
+    storage.write(&key, settings)
+}
+
+/// Delete the restake settings of a delegation
+pub fn delete_restake_settings<S>(
+    storage: &mut S,
+    source: &Address,
+    validator: &Address,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage_key::restake_settings_key(source, validator);
+    storage.delete(&key)
+}
+
 /// Check if the given consensus key is already being used to ensure uniqueness.
 ///
 /// If it's not being used, it will be inserted into the set that's being used
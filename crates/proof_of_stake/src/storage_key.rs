@@ -27,6 +27,9 @@ const VALIDATOR_LAST_KNOWN_PRODUCT_EPOCH_KEY: &str =
 const SLASHES_PREFIX: &str = "slash";
 const ENQUEUED_SLASHES_KEY: &str = "enqueued_slashes";
 const VALIDATOR_LAST_SLASH_EPOCH: &str = "last_slash_epoch";
+const VALIDATOR_HISTORY_KEY: &str = "history";
+const VALIDATOR_HISTORY_EPOCHS_KEY: &str = "validator_history_epochs";
+const VALIDATOR_JAIL_HISTORY_KEY: &str = "jail_history";
 const BOND_STORAGE_KEY: &str = "bond";
 const UNBOND_STORAGE_KEY: &str = "unbond";
 const VALIDATOR_TOTAL_BONDED_STORAGE_KEY: &str = "total_bonded";
@@ -43,6 +46,7 @@ const CONSENSUS_VALIDATOR_SET_ACCUMULATOR_STORAGE_KEY: &str =
     "validator_rewards_accumulator";
 const LAST_REWARD_CLAIM_EPOCH: &str = "last_reward_claim_epoch";
 const REWARDS_COUNTER_KEY: &str = "validator_rewards_commissions";
+const RESTAKE_SETTINGS_KEY: &str = "restake_settings";
 const VALIDATOR_INCOMING_REDELEGATIONS_KEY: &str = "incoming_redelegations";
 const VALIDATOR_OUTGOING_REDELEGATIONS_KEY: &str = "outgoing_redelegations";
 const VALIDATOR_TOTAL_REDELEGATED_BONDED_KEY: &str = "total_redelegated_bonded";
@@ -58,6 +62,9 @@ const VALIDATOR_AVATAR_KEY: &str = "avatar";
 const LIVENESS_PREFIX: &str = "liveness";
 const LIVENESS_MISSED_VOTES: &str = "missed_votes";
 const LIVENESS_MISSED_VOTES_SUM: &str = "sum_missed_votes";
+const SCHEDULED_COMMISSION_RATES_KEY: &str = "scheduled_commission_rates";
+const COMMISSION_RATE_CHANGE_NOTICES_KEY: &str =
+    "commission_rate_change_notices";
 
 /// Is the given key a PoS storage key?
 pub fn is_pos_key(key: &Key) -> bool {
@@ -338,6 +345,38 @@ pub fn is_rewards_counter_key(key: &Key) -> Option<BondId> {
     }
 }
 
+/// Storage prefix for the restake settings of all delegations.
+pub fn restake_settings_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&RESTAKE_SETTINGS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the restake settings of a delegation.
+pub fn restake_settings_key(source: &Address, validator: &Address) -> Key {
+    restake_settings_prefix()
+        .push(&source.to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&validator.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is the storage key for the restake settings of a delegation?
+pub fn is_restake_settings_key(key: &Key) -> Option<BondId> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(key),
+            DbKeySeg::AddressSeg(source),
+            DbKeySeg::AddressSeg(validator),
+        ] if addr == &ADDRESS && key == RESTAKE_SETTINGS_KEY => Some(BondId {
+            source: source.clone(),
+            validator: validator.clone(),
+        }),
+        _ => None,
+    }
+}
+
 /// Storage key for a validator's incoming redelegations, where the prefixed
 /// validator is the destination validator.
 pub fn validator_incoming_redelegations_key(validator: &Address) -> Key {
@@ -634,6 +673,29 @@ pub fn validator_last_slash_key(validator: &Address) -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Storage key for validator's history of commission rates, stakes and
+/// states.
+pub fn validator_history_key(validator: &Address) -> Key {
+    validator_prefix(validator)
+        .push(&VALIDATOR_HISTORY_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the index of the epochs of the records in the validators'
+/// histories.
+pub fn validator_history_epochs_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&VALIDATOR_HISTORY_EPOCHS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for validator's history of jailing.
+pub fn validator_jail_history_key(validator: &Address) -> Key {
+    validator_prefix(validator)
+        .push(&VALIDATOR_JAIL_HISTORY_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Storage key prefix for all bonds.
 pub fn bonds_prefix() -> Key {
     Key::from(ADDRESS.to_db_key())
@@ -1044,3 +1106,19 @@ pub fn liveness_sum_missed_votes_key() -> Key {
         .push(&LIVENESS_MISSED_VOTES_SUM.to_owned())
         .expect("Cannot obtain a storage key")
 }
+
+/// Storage key for the commission rate changes scheduled to take effect after
+/// the pipeline epoch.
+pub fn scheduled_commission_rates_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&SCHEDULED_COMMISSION_RATES_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the commission rate changes that are yet to be announced in
+/// an event.
+pub fn commission_rate_change_notices_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&COMMISSION_RATE_CHANGE_NOTICES_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
@@ -9,29 +9,38 @@ use namada_core::key::testing::{common_sk_from_simple_seed, gen_keypair};
 use namada_core::key::RefTo;
 use namada_core::storage::{BlockHeight, Epoch};
 use namada_core::{address, key};
+use namada_governance::pgf::parameters::PgfParameters;
+use namada_parameters::storage as params_storage;
 use namada_state::testing::TestState;
 use namada_storage::collections::lazy_map::Collectable;
-use namada_storage::StorageRead;
+use namada_storage::{StorageRead, StorageWrite};
 use proptest::prelude::*;
 use proptest::test_runner::Config;
 // Use `RUST_LOG=info` (or another tracing level) and `--nocapture` to see
 // `tracing` logs from tests
 use test_log::test;
 
+use crate::liquid_staking::{
+    bond_liquid_tokens, liquid_bond_value, liquid_shares_value,
+    redeem_liquid_tokens, share_token_address, share_token_validator,
+};
 use crate::parameters::testing::arb_pos_params;
 use crate::parameters::OwnedPosParams;
-use crate::queries::bonds_and_unbonds;
+use crate::queries::{
+    bonds_and_unbonds, consensus_key_rotation, consensus_key_status,
+};
 use crate::rewards::{
-    log_block_rewards_aux, update_rewards_products_and_mint_inflation,
+    add_rewards_to_counter, log_block_rewards_aux, project_bond_rewards,
+    restake_rewards, update_rewards_products_and_mint_inflation,
     PosRewardsCalculator,
 };
 use crate::slashing::{process_slashes, slash};
 use crate::storage::{
     get_consensus_key_set, liveness_sum_missed_votes_handle,
     read_below_threshold_validator_set_addresses,
-    read_consensus_validator_set_addresses_with_stake, read_total_stake,
-    read_validator_deltas_value, rewards_accumulator_handle,
-    total_deltas_handle,
+    read_consensus_validator_set_addresses_with_stake, read_restake_settings,
+    read_total_stake, read_validator_deltas_value, rewards_accumulator_handle,
+    total_deltas_handle, validator_jail_history_handle,
 };
 use crate::test_utils::test_init_genesis;
 use crate::tests::helpers::{
@@ -40,16 +49,18 @@ use crate::tests::helpers::{
 use crate::token::{credit_tokens, read_balance};
 use crate::types::{
     into_tm_voting_power, BondDetails, BondId, BondsAndUnbondsDetails,
-    GenesisValidator, SlashType, UnbondDetails, ValidatorState, VoteInfo,
+    ConsensusKeyRotation, ConsensusKeyStatus, GenesisValidator, JailReason,
+    JailRecord, SlashType, UnbondDetails, ValidatorState, VoteInfo,
     WeightedValidator,
 };
 use crate::{
     below_capacity_validator_set_handle, bond_handle, bond_tokens,
     change_consensus_key, consensus_validator_set_handle, is_delegator,
-    is_validator, jail_for_liveness, read_validator_stake, redelegate_tokens,
-    staking_token_address, token, unbond_handle, unbond_tokens,
-    unjail_validator, validator_consensus_key_handle,
-    validator_set_positions_handle, validator_state_handle, withdraw_tokens,
+    is_validator, jail_for_liveness, query_reward_tokens, read_validator_stake,
+    redelegate_tokens, set_restake, staking_token_address, token,
+    unbond_handle, unbond_tokens, unjail_validator,
+    validator_consensus_key_handle, validator_set_positions_handle,
+    validator_state_handle, withdraw_tokens,
 };
 
 proptest! {
@@ -148,6 +159,51 @@ proptest! {
     }
 }
 
+proptest! {
+    // Generate arb valid input for `test_restake_rewards_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_restake_rewards(
+        genesis_validators in arb_genesis_validators(1..4, None),
+
+    ) {
+        test_restake_rewards_aux(genesis_validators)
+    }
+}
+
+proptest! {
+    // Generate arb valid input for `test_liquid_bonds_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_liquid_bonds(
+        genesis_validators in arb_genesis_validators(1..4, None),
+
+    ) {
+        test_liquid_bonds_aux(genesis_validators)
+    }
+}
+
+proptest! {
+    // Generate arb valid input for `test_project_bond_rewards_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_project_bond_rewards(
+        genesis_validators in arb_genesis_validators(1..4, None),
+
+    ) {
+        test_project_bond_rewards_aux(genesis_validators)
+    }
+}
+
 proptest! {
     // Generate arb valid input for `test_consensus_key_change`
     #![proptest_config(Config {
@@ -902,6 +958,22 @@ fn test_unjail_validator_aux(
         );
     }
 
+    // Check the jail history
+    let jail_epoch = current_epoch.next();
+    let jail_history = validator_jail_history_handle(val_addr)
+        .collect_map(&s)
+        .unwrap();
+    assert_eq!(
+        jail_history,
+        BTreeMap::from([(
+            jail_epoch,
+            JailRecord {
+                reason: JailReason::Misbehavior(slash_0_type),
+                unjail_epoch: None,
+            },
+        )])
+    );
+
     // Advance past an epoch in which we can unbond
     let unfreeze_epoch =
         slash_0_evidence_epoch + params.slash_processing_epoch_offset();
@@ -951,6 +1023,16 @@ fn test_unjail_validator_aux(
         assert_eq!(state, ValidatorState::BelowThreshold);
     };
 
+    // The jailed period is closed at the pipeline epoch
+    let jail_record = validator_jail_history_handle(val_addr)
+        .get(&s, &jail_epoch)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        jail_record.unjail_epoch,
+        Some(current_epoch + params.pipeline_len)
+    );
+
     // Advance another epoch
     current_epoch = advance_epoch(&mut s, &params);
     process_slashes(&mut s, current_epoch).unwrap();
@@ -1453,6 +1535,420 @@ fn test_update_rewards_products_aux(validators: Vec<GenesisValidator>) {
     assert!(rewards_handle.is_empty(&s).unwrap());
 }
 
+fn test_restake_rewards_aux(mut validators: Vec<GenesisValidator>) {
+    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+    let validator = validators[0].address.clone();
+
+    let mut s = TestState::default();
+    let mut current_epoch = s.in_mem().block.epoch;
+    let params = test_init_genesis(
+        &mut s,
+        OwnedPosParams::default(),
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    s.commit_block().unwrap();
+
+    // Delegate to the validator and enable restaking once the bond has
+    // contributed to the stake for an epoch
+    let staking_token = staking_token_address(&s);
+    let delegator = address::testing::gen_implicit_address();
+    let amount = token::Amount::native_whole(100_000);
+    credit_tokens(&mut s, &staking_token, &delegator, amount).unwrap();
+    bond_tokens(
+        &mut s,
+        Some(&delegator),
+        &validator,
+        amount,
+        current_epoch,
+        None,
+    )
+    .unwrap();
+    let interval = params.pipeline_len + 1;
+    assert!(
+        set_restake(
+            &mut s,
+            Some(&delegator),
+            &validator,
+            Some(0),
+            current_epoch
+        )
+        .is_err()
+    );
+    assert!(
+        set_restake(
+            &mut s,
+            Some(&delegator),
+            &delegator,
+            Some(interval),
+            current_epoch
+        )
+        .is_err()
+    );
+    set_restake(
+        &mut s,
+        Some(&delegator),
+        &validator,
+        Some(interval),
+        current_epoch,
+    )
+    .unwrap();
+    let due_epoch = current_epoch + interval;
+
+    let num_blocks_in_last_epoch = 1000;
+    while current_epoch < due_epoch {
+        current_epoch = advance_epoch(&mut s, &params);
+        let last_epoch = current_epoch.prev();
+
+        // Distribute some inflation over the last epoch
+        let consensus_set =
+            crate::read_consensus_validator_set_addresses(&s, last_epoch)
+                .unwrap();
+        let accum_val = Dec::one() / consensus_set.len() as u64;
+        for validator in &consensus_set {
+            rewards_accumulator_handle()
+                .insert(
+                    &mut s,
+                    validator.clone(),
+                    accum_val * num_blocks_in_last_epoch,
+                )
+                .unwrap();
+        }
+        update_rewards_products_and_mint_inflation(
+            &mut s,
+            &params,
+            last_epoch,
+            num_blocks_in_last_epoch,
+            token::Amount::native_whole(10_000_000),
+            &staking_token,
+        )
+        .unwrap();
+
+        let rewards = query_reward_tokens(
+            &s,
+            Some(&delegator),
+            &validator,
+            current_epoch,
+        )
+        .unwrap();
+        let pipeline_epoch = current_epoch + params.pipeline_len;
+        let bond_pre = bond_handle(&delegator, &validator)
+            .get_sum(&s, pipeline_epoch, &params)
+            .unwrap()
+            .unwrap_or_default();
+
+        restake_rewards(&mut s, &params, current_epoch).unwrap();
+
+        let bond_post = bond_handle(&delegator, &validator)
+            .get_sum(&s, pipeline_epoch, &params)
+            .unwrap()
+            .unwrap_or_default();
+        let settings = read_restake_settings(&s, &delegator, &validator)
+            .unwrap()
+            .unwrap();
+        if current_epoch < due_epoch {
+            // Nothing is restaked before the interval has elapsed
+            assert_eq!(bond_post, bond_pre);
+            assert_eq!(settings.next_epoch, due_epoch);
+        } else {
+            // The rewards are bonded at the pipeline offset
+            assert!(!rewards.is_zero());
+            assert_eq!(bond_post, bond_pre + rewards);
+            assert_eq!(settings.next_epoch, current_epoch + interval);
+            assert!(
+                query_reward_tokens(
+                    &s,
+                    Some(&delegator),
+                    &validator,
+                    current_epoch
+                )
+                .unwrap()
+                .is_zero()
+            );
+            assert!(
+                read_balance(&s, &staking_token, &delegator)
+                    .unwrap()
+                    .is_zero()
+            );
+        }
+    }
+
+    // Disable the restaking
+    set_restake(&mut s, Some(&delegator), &validator, None, current_epoch)
+        .unwrap();
+    assert!(
+        read_restake_settings(&s, &delegator, &validator)
+            .unwrap()
+            .is_none()
+    );
+}
+
+fn test_liquid_bonds_aux(mut validators: Vec<GenesisValidator>) {
+    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+    let validator = validators[0].address.clone();
+
+    let mut s = TestState::default();
+    let mut current_epoch = s.in_mem().block.epoch;
+    let params = test_init_genesis(
+        &mut s,
+        OwnedPosParams::default(),
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    s.commit_block().unwrap();
+
+    let staking_token = staking_token_address(&s);
+    let share_token = share_token_address(&validator).unwrap();
+    assert_eq!(share_token_validator(&share_token), Some(validator.clone()));
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let pooled_bond = |s: &TestState| {
+        bond_handle(&share_token, &validator)
+            .get_sum(s, pipeline_epoch, &params)
+            .unwrap()
+            .unwrap_or_default()
+    };
+
+    // The first liquid bonds mint the shares one to one
+    let amount = token::Amount::native_whole(100_000);
+    let delegators = [
+        address::testing::gen_implicit_address(),
+        address::testing::gen_implicit_address(),
+        address::testing::gen_implicit_address(),
+    ];
+    for delegator in &delegators {
+        credit_tokens(&mut s, &staking_token, delegator, amount).unwrap();
+    }
+    for delegator in &delegators[..2] {
+        let shares = bond_liquid_tokens(
+            &mut s,
+            delegator,
+            &validator,
+            amount,
+            current_epoch,
+        )
+        .unwrap();
+        assert_eq!(shares, amount);
+        assert_eq!(read_balance(&s, &share_token, delegator).unwrap(), amount);
+        assert!(
+            read_balance(&s, &staking_token, delegator)
+                .unwrap()
+                .is_zero()
+        );
+    }
+    assert_eq!(pooled_bond(&s), amount * 2);
+    assert_eq!(
+        s.read::<Address>(&token::storage_key::minter_key(&share_token))
+            .unwrap(),
+        Some(crate::ADDRESS)
+    );
+    // The rewards of the pooled bond are restaked every epoch
+    let settings = read_restake_settings(&s, &share_token, &validator)
+        .unwrap()
+        .unwrap();
+    assert_eq!(settings.interval, 1);
+
+    // Restaked rewards increase the value of the shares
+    let rewards = token::Amount::native_whole(20_000);
+    credit_tokens(&mut s, &staking_token, &share_token, rewards).unwrap();
+    bond_tokens(
+        &mut s,
+        Some(&share_token),
+        &validator,
+        rewards,
+        current_epoch,
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        liquid_bond_value(&s, &params, &validator, pipeline_epoch).unwrap(),
+        amount * 2 + rewards
+    );
+
+    // So do the rewards that are pending to be restaked
+    let pending_rewards = token::Amount::native_whole(10_000);
+    add_rewards_to_counter(&mut s, &share_token, &validator, pending_rewards)
+        .unwrap();
+    let value = amount * 2 + rewards + pending_rewards;
+    assert_eq!(
+        liquid_shares_value(&s, &params, &validator, current_epoch).unwrap(),
+        value
+    );
+    let shares = bond_liquid_tokens(
+        &mut s,
+        &delegators[2],
+        &validator,
+        amount,
+        current_epoch,
+    )
+    .unwrap();
+    let expected_shares = token::Amount::from_uint(
+        amount
+            .raw_amount()
+            .mul_div((amount * 2).raw_amount(), value.raw_amount())
+            .0,
+        0,
+    )
+    .unwrap();
+    assert_eq!(shares, expected_shares);
+    assert!(shares < amount);
+
+    // Redeeming shares hands over an unbond of their value to the owner
+    let owner = &delegators[0];
+    assert!(
+        redeem_liquid_tokens(
+            &mut s,
+            owner,
+            &validator,
+            amount + token::Amount::from(1),
+            current_epoch,
+        )
+        .is_err()
+    );
+    let supply = token::read_total_supply(&s, &share_token).unwrap();
+    let bond_pre = pooled_bond(&s);
+    let unbonded =
+        redeem_liquid_tokens(&mut s, owner, &validator, amount, current_epoch)
+            .unwrap();
+    let expected_unbonded = token::Amount::from_uint(
+        amount
+            .raw_amount()
+            .mul_div(
+                (bond_pre + pending_rewards).raw_amount(),
+                supply.raw_amount(),
+            )
+            .0,
+        0,
+    )
+    .unwrap();
+    assert!(unbonded > amount);
+    assert!(unbonded <= expected_unbonded);
+    assert_eq!(pooled_bond(&s), bond_pre - unbonded);
+    assert!(read_balance(&s, &share_token, owner).unwrap().is_zero());
+    assert_eq!(
+        token::read_total_supply(&s, &share_token).unwrap(),
+        supply - amount
+    );
+    assert!(
+        unbond_handle(&share_token, &validator)
+            .is_empty(&s)
+            .unwrap()
+    );
+    let owner_unbonds = unbond_handle(owner, &validator)
+        .collect_map(&s)
+        .unwrap()
+        .into_values()
+        .flat_map(|unbonds| unbonds.into_values())
+        .fold(token::Amount::zero(), |acc, amount| acc + amount);
+    assert_eq!(owner_unbonds, unbonded);
+
+    // The owner withdraws the unbond like any other
+    for _ in 0..params.withdrawable_epoch_offset() {
+        current_epoch = advance_epoch(&mut s, &params);
+    }
+    let withdrawn =
+        withdraw_tokens(&mut s, Some(owner), &validator, current_epoch)
+            .unwrap();
+    assert_eq!(withdrawn, unbonded);
+    assert_eq!(read_balance(&s, &staking_token, owner).unwrap(), unbonded);
+}
+
+fn test_project_bond_rewards_aux(mut validators: Vec<GenesisValidator>) {
+    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+    let validator = validators[0].address.clone();
+
+    let mut s = TestState::default();
+    let current_epoch = s.in_mem().block.epoch;
+    let params = test_init_genesis(
+        &mut s,
+        OwnedPosParams::default(),
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    s.commit_block().unwrap();
+
+    // Set up the inflation parameters with a low staked ratio
+    let epochs_per_year = 365_u64;
+    s.write(&params_storage::get_epochs_per_year_key(), epochs_per_year)
+        .unwrap();
+    s.write(&params_storage::get_staked_ratio_key(), Dec::zero())
+        .unwrap();
+    s.write(
+        &params_storage::get_pos_inflation_amount_key(),
+        token::Amount::zero(),
+    )
+    .unwrap();
+    let staking_token = staking_token_address(&s);
+    let holder = address::testing::established_address_2();
+    credit_tokens(
+        &mut s,
+        &staking_token,
+        &holder,
+        token::Amount::native_whole(1_000_000_000),
+    )
+    .unwrap();
+
+    let amount = token::Amount::native_whole(1_000);
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+
+    // The projection needs the PGF inflation rates
+    let projection = project_bond_rewards(
+        &s,
+        &validator,
+        amount,
+        current_epoch,
+        BlockHeight(0),
+    );
+    assert!(projection.is_err());
+    let pgf_params = PgfParameters::default();
+    pgf_params.init_storage(&mut s).unwrap();
+
+    let projection = project_bond_rewards(
+        &s,
+        &validator,
+        amount,
+        current_epoch,
+        BlockHeight(0),
+    )
+    .unwrap();
+    assert_eq!(projection.start_epoch, pipeline_epoch);
+    assert!(projection.is_consensus);
+    assert_eq!(projection.uptime, Dec::one());
+    assert_eq!(projection.commission_rate, Dec::new(5, 2).unwrap());
+    assert!(!projection.inflation_per_epoch.is_zero());
+
+    // With full uptime, the bond earns its share of the inflation less the
+    // commission
+    let consensus_stake =
+        read_consensus_validator_set_addresses_with_stake(&s, pipeline_epoch)
+            .unwrap()
+            .into_iter()
+            .fold(amount, |acc, validator| acc + validator.bonded_stake);
+    let rate_per_epoch = (Dec::one() - projection.commission_rate)
+        * Dec::one()
+        * (Dec::from(projection.inflation_per_epoch)
+            / Dec::from(consensus_stake));
+    assert_eq!(projection.rewards_per_epoch, rate_per_epoch * amount);
+    assert_eq!(projection.nominal_apr, rate_per_epoch * epochs_per_year);
+    assert!(projection.nominal_apr > Dec::zero());
+    // The supply grows with both the PoS and the PGF inflation, so the real
+    // APR is lower
+    let total_tokens = token::read_total_supply(&s, &staking_token).unwrap();
+    let supply_growth = Dec::from(projection.inflation_per_epoch)
+        * epochs_per_year
+        / Dec::from(total_tokens)
+        + pgf_params.pgf_inflation_rate
+        + pgf_params.stewards_inflation_rate;
+    assert_eq!(
+        projection.real_apr,
+        (Dec::one() + projection.nominal_apr) / (Dec::one() + supply_growth)
+            - Dec::one()
+    );
+    assert!(projection.real_apr < projection.nominal_apr);
+}
+
 fn test_consensus_key_change_aux(validators: Vec<GenesisValidator>) {
     assert_eq!(validators.len(), 1);
 
@@ -1517,6 +2013,40 @@ fn test_consensus_key_change_aux(validators: Vec<GenesisValidator>) {
         .unwrap();
     assert_eq!(ck, ck_2);
 
+    // Check the status of the keys during the rotation
+    let rotation =
+        consensus_key_rotation(&storage, &validator, current_epoch).unwrap();
+    assert_eq!(
+        rotation,
+        ConsensusKeyRotation {
+            current_key: Some(og_ck.clone()),
+            pending: Some((pipeline_epoch, ck_2.clone())),
+        }
+    );
+    assert_eq!(
+        consensus_key_status(&storage, &og_ck, current_epoch).unwrap(),
+        Some((
+            validator.clone(),
+            ConsensusKeyStatus::RotatingOut {
+                switch_epoch: pipeline_epoch,
+                new_key: ck_2.clone(),
+            }
+        ))
+    );
+    assert_eq!(
+        consensus_key_status(&storage, &ck_2, current_epoch).unwrap(),
+        Some((
+            validator.clone(),
+            ConsensusKeyStatus::RotatingIn {
+                switch_epoch: pipeline_epoch,
+            }
+        ))
+    );
+    assert_eq!(
+        consensus_key_status(&storage, &secp_ck, current_epoch).unwrap(),
+        None
+    );
+
     // Advance to the pipeline epoch
     loop {
         current_epoch = advance_epoch(&mut storage, &params);
@@ -1525,6 +2055,30 @@ fn test_consensus_key_change_aux(validators: Vec<GenesisValidator>) {
         }
     }
 
+    // The old key has been rotated out
+    let rotation =
+        consensus_key_rotation(&storage, &validator, current_epoch).unwrap();
+    assert_eq!(
+        rotation,
+        ConsensusKeyRotation {
+            current_key: Some(ck_2.clone()),
+            pending: None,
+        }
+    );
+    assert_eq!(
+        consensus_key_status(&storage, &og_ck, current_epoch).unwrap(),
+        Some((
+            validator.clone(),
+            ConsensusKeyStatus::RotatedOut {
+                new_key: ck_2.clone()
+            }
+        ))
+    );
+    assert_eq!(
+        consensus_key_status(&storage, &ck_2, current_epoch).unwrap(),
+        Some((validator.clone(), ConsensusKeyStatus::Active))
+    );
+
     // Check the consensus keys again
     let consensus_keys = get_consensus_key_set(&storage).unwrap();
     assert_eq!(consensus_keys.len(), 2);
@@ -1735,6 +2289,12 @@ fn test_jail_for_liveness_aux(validators: Vec<GenesisValidator>) {
                 .unwrap()
                 .expect("Validator should have a state for the jail epoch");
             assert_eq!(state_jail_epoch, ValidatorState::Jailed);
+
+            let jail_record = validator_jail_history_handle(address)
+                .get(s, &jail_epoch)
+                .unwrap()
+                .expect("Validator should have a jail record");
+            assert_eq!(jail_record.reason, JailReason::Liveness);
         }
     }
 
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::ops::Deref;
 use std::str::FromStr;
 
@@ -15,7 +16,10 @@ use proptest::test_runner::Config;
 // `tracing` logs from tests
 use test_log::test;
 
-use crate::queries::bonds_and_unbonds;
+use crate::queries::{
+    bonds_and_unbonds, delegator_slash_report,
+    find_pending_unbonds_and_redelegations,
+};
 use crate::slashing::{process_slashes, slash};
 use crate::storage::{
     bond_handle, delegator_redelegated_bonds_handle,
@@ -32,7 +36,10 @@ use crate::tests::helpers::{
     test_slashes_with_unbonding_params,
 };
 use crate::token::{credit_tokens, read_balance};
-use crate::types::{BondId, GenesisValidator, SlashType};
+use crate::types::{
+    BondId, GenesisValidator, PendingRedelegation, PendingUnbond, SlashType,
+    SlashedHolding,
+};
 use crate::{
     bond_tokens, redelegate_tokens, staking_token_address, token,
     unbond_tokens, withdraw_tokens, OwnedPosParams, RedelegationError,
@@ -1500,3 +1507,400 @@ fn test_slashed_bond_amount_aux(validators: Vec<GenesisValidator>) {
     let diff = val_stake - self_bond_amount - del_bond_amount;
     assert!(diff <= 2.into());
 }
+
+proptest! {
+    // Generate arb valid input for
+    // `test_pending_unbonds_and_redelegations_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_pending_unbonds_and_redelegations(
+
+    genesis_validators in arb_genesis_validators(2..4, None),
+
+    ) {
+        test_pending_unbonds_and_redelegations_aux(genesis_validators)
+    }
+}
+
+fn test_pending_unbonds_and_redelegations_aux(
+    mut validators: Vec<GenesisValidator>,
+) {
+    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+    let src_validator = validators[0].address.clone();
+    let dest_validator = validators[1].address.clone();
+
+    let mut storage = TestState::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        ..Default::default()
+    };
+
+    // Genesis
+    let mut current_epoch = storage.in_mem().block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+
+    let staking_token = staking_token_address(&storage);
+    let delegator = address::testing::gen_implicit_address();
+    let del_balance = token::Amount::native_whole(1_000);
+    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
+        .unwrap();
+
+    // Nothing is pending without any unbonds or redelegations
+    let pending = find_pending_unbonds_and_redelegations(
+        &storage,
+        &delegator,
+        current_epoch,
+    )
+    .unwrap();
+    assert!(pending.unbonds.is_empty());
+    assert!(pending.redelegations.is_empty());
+
+    bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &src_validator,
+        token::Amount::native_whole(100),
+        current_epoch,
+        None,
+    )
+    .unwrap();
+    let bond_start = current_epoch + params.pipeline_len;
+    for _ in 0..params.pipeline_len + 1 {
+        current_epoch = advance_epoch(&mut storage, &params);
+        process_slashes(&mut storage, current_epoch).unwrap();
+    }
+
+    // Redelegate and unbond some of the bond
+    let redel_amount = token::Amount::native_whole(40);
+    let unbond_amount = token::Amount::native_whole(30);
+    redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &src_validator,
+        &dest_validator,
+        current_epoch,
+        redel_amount,
+    )
+    .unwrap();
+    let redel_start = current_epoch + params.pipeline_len;
+    let redel_mature =
+        redel_start.prev() + params.slash_processing_epoch_offset();
+    unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &src_validator,
+        unbond_amount,
+        current_epoch,
+        false,
+    )
+    .unwrap();
+    let withdraw_epoch = current_epoch + params.withdrawable_epoch_offset();
+
+    let pending = find_pending_unbonds_and_redelegations(
+        &storage,
+        &delegator,
+        current_epoch,
+    )
+    .unwrap();
+    assert_eq!(
+        pending.unbonds,
+        vec![PendingUnbond {
+            validator: src_validator.clone(),
+            start_epoch: bond_start,
+            withdraw_epoch,
+            amount: unbond_amount,
+            amount_after_slashing: unbond_amount,
+            slashable_until: withdraw_epoch
+                - params.unbonding_len
+                - params.cubic_slashing_window_length,
+            redelegated: Default::default(),
+        }]
+    );
+    assert_eq!(
+        pending.redelegations,
+        vec![PendingRedelegation {
+            src_validator: src_validator.clone(),
+            dest_validator: dest_validator.clone(),
+            start_epoch: redel_start,
+            amount: redel_amount,
+            amount_after_slashing: redel_amount,
+            slashable_until: redel_start,
+            mature_epoch: redel_mature,
+        }]
+    );
+
+    // Unbond some of the redelegated tokens from the destination validator
+    while current_epoch < redel_start {
+        current_epoch = advance_epoch(&mut storage, &params);
+        process_slashes(&mut storage, current_epoch).unwrap();
+    }
+    let redel_unbond_amount = token::Amount::native_whole(10);
+    unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &dest_validator,
+        redel_unbond_amount,
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    let pending = find_pending_unbonds_and_redelegations(
+        &storage,
+        &delegator,
+        current_epoch,
+    )
+    .unwrap();
+    assert_eq!(pending.unbonds.len(), 2);
+    let redel_unbond = pending
+        .unbonds
+        .iter()
+        .find(|unbond| unbond.validator == dest_validator)
+        .unwrap();
+    assert_eq!(redel_unbond.start_epoch, redel_start);
+    assert_eq!(redel_unbond.amount, redel_unbond_amount);
+    assert_eq!(
+        redel_unbond.redelegated,
+        BTreeMap::from([(src_validator.clone(), redel_unbond_amount)])
+    );
+    assert_eq!(pending.redelegations.len(), 1);
+    assert_eq!(
+        pending.redelegations[0].amount,
+        redel_amount - redel_unbond_amount
+    );
+
+    // The redelegation is no longer pending once it matures
+    while current_epoch < redel_mature {
+        current_epoch = advance_epoch(&mut storage, &params);
+        process_slashes(&mut storage, current_epoch).unwrap();
+    }
+    let pending = find_pending_unbonds_and_redelegations(
+        &storage,
+        &delegator,
+        current_epoch,
+    )
+    .unwrap();
+    assert!(pending.redelegations.is_empty());
+    assert_eq!(pending.unbonds.len(), 2);
+
+    // The unbond is no longer pending once withdrawn
+    assert!(withdraw_epoch <= current_epoch);
+    withdraw_tokens(
+        &mut storage,
+        Some(&delegator),
+        &src_validator,
+        current_epoch,
+    )
+    .unwrap();
+    let pending = find_pending_unbonds_and_redelegations(
+        &storage,
+        &delegator,
+        current_epoch,
+    )
+    .unwrap();
+    assert_eq!(pending.unbonds.len(), 1);
+    assert_eq!(pending.unbonds[0].validator, dest_validator);
+}
+
+proptest! {
+    // Generate arb valid input for `test_delegator_slash_report_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_delegator_slash_report(
+
+    genesis_validators in arb_genesis_validators(2..4, None),
+
+    ) {
+        test_delegator_slash_report_aux(genesis_validators)
+    }
+}
+
+fn test_delegator_slash_report_aux(mut validators: Vec<GenesisValidator>) {
+    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+    let src_validator = validators[0].address.clone();
+    let dest_validator = validators[1].address.clone();
+
+    let mut storage = TestState::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        ..Default::default()
+    };
+
+    // Genesis
+    let mut current_epoch = storage.in_mem().block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+
+    let staking_token = staking_token_address(&storage);
+    let delegator = address::testing::gen_implicit_address();
+    let del_balance = token::Amount::native_whole(1_000);
+    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
+        .unwrap();
+
+    let bond_amount = token::Amount::native_whole(100);
+    bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &src_validator,
+        bond_amount,
+        current_epoch,
+        None,
+    )
+    .unwrap();
+    let bond_start = current_epoch + params.pipeline_len;
+    for _ in 0..params.pipeline_len + 1 {
+        current_epoch = advance_epoch(&mut storage, &params);
+        process_slashes(&mut storage, current_epoch).unwrap();
+    }
+
+    // Nothing is reported without any slashes
+    assert!(
+        delegator_slash_report(&storage, &delegator)
+            .unwrap()
+            .is_empty()
+    );
+
+    // Redelegate and unbond some of the bond and slash the source validator
+    // for an infraction in the same epoch
+    let redel_amount = token::Amount::native_whole(40);
+    let unbond_amount = token::Amount::native_whole(30);
+    redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &src_validator,
+        &dest_validator,
+        current_epoch,
+        redel_amount,
+    )
+    .unwrap();
+    let redel_start = current_epoch + params.pipeline_len;
+    unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &src_validator,
+        unbond_amount,
+        current_epoch,
+        false,
+    )
+    .unwrap();
+    let withdraw_epoch = current_epoch + params.withdrawable_epoch_offset();
+    let infraction_epoch = current_epoch;
+    slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        infraction_epoch,
+        0u64,
+        SlashType::DuplicateVote,
+        &src_validator,
+        current_epoch.next(),
+    )
+    .unwrap();
+
+    // The slash is not reported until it is processed
+    assert!(
+        delegator_slash_report(&storage, &delegator)
+            .unwrap()
+            .is_empty()
+    );
+    while validator_slashes_handle(&src_validator)
+        .is_empty(&storage)
+        .unwrap()
+    {
+        current_epoch = advance_epoch(&mut storage, &params);
+        process_slashes(&mut storage, current_epoch).unwrap();
+    }
+
+    let losses = delegator_slash_report(&storage, &delegator).unwrap();
+    assert_eq!(losses.len(), 3);
+    for loss in &losses {
+        assert_eq!(loss.slashed_validator, src_validator);
+        assert_eq!(loss.slash.epoch, infraction_epoch);
+        assert_eq!(loss.slash.r#type, SlashType::DuplicateVote);
+    }
+
+    // The losses match the slashed amounts of the bond, unbond and
+    // redelegation
+    let pending = find_pending_unbonds_and_redelegations(
+        &storage,
+        &delegator,
+        current_epoch,
+    )
+    .unwrap();
+    let remaining_bond = bond_amount - redel_amount - unbond_amount;
+    let bond_after_slashing = crate::bond_amount(
+        &storage,
+        &BondId {
+            source: delegator.clone(),
+            validator: src_validator.clone(),
+        },
+        current_epoch,
+    )
+    .unwrap();
+    let bond_loss = losses
+        .iter()
+        .find(|loss| {
+            loss.holding
+                == SlashedHolding {
+                    validator: src_validator.clone(),
+                    start: bond_start,
+                    withdraw: None,
+                    redelegated_from: None,
+                }
+        })
+        .unwrap();
+    assert_eq!(bond_loss.amount, remaining_bond - bond_after_slashing);
+
+    let unbond_loss = losses
+        .iter()
+        .find(|loss| {
+            loss.holding
+                == SlashedHolding {
+                    validator: src_validator.clone(),
+                    start: bond_start,
+                    withdraw: Some(withdraw_epoch),
+                    redelegated_from: None,
+                }
+        })
+        .unwrap();
+    assert_eq!(
+        unbond_loss.amount,
+        unbond_amount - pending.unbonds[0].amount_after_slashing
+    );
+
+    let redel_loss = losses
+        .iter()
+        .find(|loss| {
+            loss.holding
+                == SlashedHolding {
+                    validator: dest_validator.clone(),
+                    start: redel_start,
+                    withdraw: None,
+                    redelegated_from: Some((src_validator.clone(), bond_start)),
+                }
+        })
+        .unwrap();
+    assert_eq!(
+        redel_loss.amount,
+        redel_amount - pending.redelegations[0].amount_after_slashing
+    );
+}
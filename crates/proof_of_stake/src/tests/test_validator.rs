@@ -1,16 +1,18 @@
 use std::cmp::min;
+use std::collections::{BTreeMap, HashSet};
 
 use namada_core::address::testing::arb_established_address;
 use namada_core::address::{self, Address, EstablishedAddressGen};
 use namada_core::dec::Dec;
+use namada_core::event::Event;
 use namada_core::key::testing::{
     arb_common_keypair, common_sk_from_simple_seed,
 };
 use namada_core::key::{self, common, RefTo};
-use namada_core::storage::Epoch;
+use namada_core::storage::{BlockHeight, Epoch};
 use namada_core::token;
 use namada_state::testing::TestState;
-use namada_storage::collections::lazy_map;
+use namada_storage::collections::lazy_map::{self, Collectable};
 use proptest::prelude::*;
 use proptest::test_runner::Config;
 // Use `RUST_LOG=info` (or another tracing level) and `--nocapture` to see
@@ -18,15 +20,24 @@ use proptest::test_runner::Config;
 use test_log::test;
 
 use crate::epoched::DEFAULT_NUM_PAST_EPOCHS;
+use crate::queries::{
+    read_validator_epoch_record, validator_history, validator_liveness,
+    validator_set_snapshot,
+};
+use crate::simulation::{simulate_validator_sets, ValidatorSetParams};
 use crate::storage::{
     below_capacity_validator_set_handle, bond_handle,
     consensus_validator_set_handle, find_validator_by_raw_hash,
-    get_num_consensus_validators,
+    get_num_consensus_validators, liveness_sum_missed_votes_handle,
     read_below_capacity_validator_set_addresses_with_stake,
     read_below_threshold_validator_set_addresses,
-    read_consensus_validator_set_addresses_with_stake, update_validator_deltas,
-    validator_addresses_handle, validator_consensus_key_handle,
-    validator_set_positions_handle, write_validator_address_raw_hash,
+    read_consensus_validator_set_addresses_with_stake,
+    read_validator_commission_rate, read_validator_scheduled_commission_rates,
+    scheduled_commission_rates_handle, update_validator_deltas,
+    validator_addresses_handle, validator_commission_rate_handle,
+    validator_consensus_key_handle, validator_history_handle,
+    validator_set_positions_handle, validator_state_handle,
+    write_validator_address_raw_hash,
 };
 use crate::test_utils::{init_genesis_helper, test_init_genesis};
 use crate::tests::helpers::{
@@ -35,16 +46,21 @@ use crate::tests::helpers::{
 };
 use crate::token::credit_tokens;
 use crate::types::{
-    into_tm_voting_power, ConsensusValidator, GenesisValidator, Position,
-    ReverseOrdTokenAmount, ValidatorSetUpdate, WeightedValidator,
+    into_tm_voting_power, CommissionRateChangeEvent, ConsensusValidator,
+    GenesisValidator, Position, ReverseOrdTokenAmount, ValidatorEpochRecord,
+    ValidatorSetUpdate, ValidatorState, WeightedValidator,
 };
 use crate::validator_set_update::{
     insert_validator_into_validator_set, update_validator_set,
 };
 use crate::{
-    become_validator, bond_tokens, change_consensus_key, is_validator,
-    staking_token_address, unbond_tokens, withdraw_tokens, BecomeValidator,
-    OwnedPosParams,
+    apply_scheduled_commission_rates, become_validator, bond_tokens,
+    change_consensus_key, change_validator_commission_rate,
+    deactivate_validator, emit_commission_rate_change_events, is_validator,
+    jail_for_liveness, prune_validator_history, record_validator_history,
+    schedule_validator_commission_rate, staking_token_address, unbond_tokens,
+    withdraw_tokens, BecomeValidator, OwnedPosParams, PosParams,
+    VALIDATOR_HISTORY_NUM_PAST_EPOCHS,
 };
 
 proptest! {
@@ -1369,3 +1385,704 @@ fn test_purge_validator_information_aux(validators: Vec<GenesisValidator>) {
         assert!(!consensus_val_set.at(&ep).is_empty(&s).unwrap());
     }
 }
+
+proptest! {
+    // Generate arb valid input for `test_validator_history_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_validator_history(
+
+        genesis_validators in arb_genesis_validators(1..4, None),
+
+    ) {
+        test_validator_history_aux(genesis_validators)
+    }
+}
+
+/// Test that the history of a validator is kept after the epoched data has
+/// been pruned, only for the consensus and below-capacity validators and until
+/// the records are no longer in effect in the retained epochs.
+fn test_validator_history_aux(validators: Vec<GenesisValidator>) {
+    let mut s = TestState::default();
+    let mut current_epoch = s.in_mem().block.epoch;
+    let params = test_init_genesis(
+        &mut s,
+        OwnedPosParams::default(),
+        validators.clone().into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    s.commit_block().unwrap();
+
+    let validator = validators.first().unwrap().address.clone();
+    let delegator = address::testing::established_address_2();
+    let staking_token = staking_token_address(&s);
+    let amount = token::Amount::native_whole(1_000);
+    credit_tokens(&mut s, &staking_token, &delegator, amount).unwrap();
+
+    let mut expected = BTreeMap::new();
+    for _ in 0..8 {
+        if current_epoch == Epoch(2) {
+            bond_tokens(
+                &mut s,
+                Some(&delegator),
+                &validator,
+                amount,
+                current_epoch,
+                None,
+            )
+            .unwrap();
+        }
+        if current_epoch == Epoch(3) {
+            change_validator_commission_rate(
+                &mut s,
+                &validator,
+                Dec::new(6, 2).unwrap(),
+                current_epoch,
+            )
+            .unwrap();
+        }
+        let record =
+            read_validator_epoch_record(&s, &params, &validator, current_epoch)
+                .unwrap()
+                .unwrap();
+        expected.insert(current_epoch, record);
+
+        current_epoch = advance_epoch(&mut s, &params);
+        record_validator_history(&mut s, &params, current_epoch.prev())
+            .unwrap();
+    }
+
+    let history = validator_history(
+        &s,
+        &validator,
+        Epoch(0),
+        current_epoch,
+        current_epoch,
+    )
+    .unwrap();
+    for (epoch, record) in &expected {
+        assert_eq!(history.records.get(epoch), Some(record));
+    }
+    assert!(history.slashes.is_empty());
+
+    // Only the changes of the records are kept
+    let num_recorded = validator_history_handle(&validator)
+        .iter(&s)
+        .unwrap()
+        .count();
+    assert!(num_recorded < expected.len());
+    assert_eq!(expected[&Epoch(0)].commission_rate, Dec::new(5, 2).unwrap());
+    assert_eq!(expected[&Epoch(7)].commission_rate, Dec::new(6, 2).unwrap());
+
+    // The below-threshold validators have no history
+    for GenesisValidator { address, .. } in &validators {
+        let record =
+            read_validator_epoch_record(&s, &params, address, current_epoch)
+                .unwrap()
+                .unwrap();
+        assert_eq!(
+            validator_history_handle(address).is_empty(&s).unwrap(),
+            record.state == ValidatorState::BelowThreshold
+        );
+    }
+
+    // Only the record that is still in effect in the oldest retained epoch is
+    // kept from the older records
+    let oldest_epoch = Epoch(6);
+    prune_validator_history(&mut s, oldest_epoch).unwrap();
+    let num_in_effect = validator_history_handle(&validator)
+        .iter(&s)
+        .unwrap()
+        .filter(|res| res.as_ref().unwrap().0 <= oldest_epoch)
+        .count();
+    assert_eq!(num_in_effect, 1);
+    let history = validator_history(
+        &s,
+        &validator,
+        Epoch(0),
+        current_epoch,
+        current_epoch,
+    )
+    .unwrap();
+    for (epoch, record) in expected.range(oldest_epoch..) {
+        assert_eq!(history.records.get(epoch), Some(record));
+    }
+    assert!(!history.records.contains_key(&Epoch(0)));
+
+    // The history of a validator that has left the validator sets is pruned
+    // too, down to its last record
+    deactivate_validator(&mut s, &validator, current_epoch).unwrap();
+    for _ in 0..=params.pipeline_len {
+        current_epoch = advance_epoch(&mut s, &params);
+        record_validator_history(&mut s, &params, current_epoch.prev())
+            .unwrap();
+    }
+    let last_epoch = current_epoch.prev();
+    let last_record =
+        read_validator_epoch_record(&s, &params, &validator, last_epoch)
+            .unwrap()
+            .unwrap();
+    assert_eq!(last_record.state, ValidatorState::Inactive);
+    assert!(
+        validator_history_handle(&validator)
+            .iter(&s)
+            .unwrap()
+            .count()
+            > 1
+    );
+    record_validator_history(
+        &mut s,
+        &params,
+        last_epoch + VALIDATOR_HISTORY_NUM_PAST_EPOCHS,
+    )
+    .unwrap();
+    let records = validator_history_handle(&validator)
+        .collect_map(&s)
+        .unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records.values().next(), Some(&last_record));
+}
+
+proptest! {
+    // Generate arb valid input for `test_validator_liveness_window_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_validator_liveness_window(
+
+        genesis_validators in arb_genesis_validators(1..4, None),
+
+    ) {
+        test_validator_liveness_window_aux(genesis_validators)
+    }
+}
+
+/// Test that the liveness window of a validator only counts the blocks since
+/// the validator last entered the consensus set.
+fn test_validator_liveness_window_aux(mut validators: Vec<GenesisValidator>) {
+    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+    let validator = validators[0].address.clone();
+
+    let mut s = TestState::default();
+    let current_epoch = s.in_mem().block.epoch;
+    let params = test_init_genesis(
+        &mut s,
+        OwnedPosParams {
+            liveness_window_check: 30,
+            ..Default::default()
+        },
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    s.commit_block().unwrap();
+    liveness_sum_missed_votes_handle()
+        .insert(&mut s, validator.clone(), 2)
+        .unwrap();
+    let last_vote_height = BlockHeight(45);
+    let num_blocks = |s: &TestState| {
+        let window = validator_liveness(s, &validator, last_vote_height)
+            .unwrap()
+            .window
+            .unwrap();
+        assert_eq!(window.signed_votes, window.num_blocks - 2);
+        window.num_blocks
+    };
+
+    // Without the heights of the epochs, the whole window is counted
+    assert_eq!(num_blocks(&s), params.liveness_window_check);
+
+    // The epochs start every 10 blocks, so the `last_vote_height` is in epoch
+    // 4 and the window starts in epoch 1. The epochs before the retained ones
+    // are found in the validator's history.
+    s.in_mem_mut().block.pred_epochs.first_block_heights =
+        (0..5).map(|epoch| BlockHeight(epoch * 10 + 1)).collect();
+    let record = read_validator_epoch_record(&s, &params, &validator, Epoch(0))
+        .unwrap()
+        .unwrap();
+    assert_eq!(record.state, ValidatorState::Consensus);
+    let history = validator_history_handle(&validator);
+    history.insert(&mut s, Epoch(0), record).unwrap();
+    assert_eq!(num_blocks(&s), params.liveness_window_check);
+
+    // The validator left the consensus set in an epoch that is only found in
+    // its history
+    history
+        .insert(
+            &mut s,
+            Epoch(1),
+            ValidatorEpochRecord {
+                state: ValidatorState::BelowCapacity,
+                ..record
+            },
+        )
+        .unwrap();
+    history.insert(&mut s, Epoch(2), record).unwrap();
+    assert_eq!(num_blocks(&s), 25);
+
+    // The validator left the consensus set in a retained epoch
+    let states = validator_state_handle(&validator);
+    states
+        .set(&mut s, ValidatorState::BelowCapacity, Epoch(3), 0)
+        .unwrap();
+    states
+        .set(&mut s, ValidatorState::Consensus, Epoch(4), 0)
+        .unwrap();
+    assert_eq!(num_blocks(&s), 5);
+}
+
+proptest! {
+    // Generate arb valid input for `test_simulate_validator_sets_aux`
+    #![proptest_config(Config {
+        cases: 10,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_simulate_validator_sets(
+
+        genesis_validators in arb_genesis_validators(2..10, None),
+
+    ) {
+        test_simulate_validator_sets_aux(genesis_validators)
+    }
+}
+
+/// Test the simulation of the validator sets with changed parameters from a
+/// snapshot of the validators.
+fn test_simulate_validator_sets_aux(validators: Vec<GenesisValidator>) {
+    let mut s = TestState::default();
+    let current_epoch = s.in_mem().block.epoch;
+    let params = OwnedPosParams {
+        max_validator_slots: validators.len() as u64,
+        ..Default::default()
+    };
+    let params = test_init_genesis(
+        &mut s,
+        params,
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    s.commit_block().unwrap();
+
+    // A consensus validator missed some votes
+    let consensus_set =
+        read_consensus_validator_set_addresses_with_stake(&s, current_epoch)
+            .unwrap();
+    let inactive_validator =
+        consensus_set.iter().next().unwrap().address.clone();
+    liveness_sum_missed_votes_handle()
+        .insert(&mut s, inactive_validator.clone(), 100)
+        .unwrap();
+
+    let snapshot = validator_set_snapshot(&s, current_epoch).unwrap();
+    assert_eq!(snapshot.epoch, current_epoch);
+    assert_eq!(snapshot.params, ValidatorSetParams::from(&params.owned));
+    let num_epochs = params.pipeline_len + 1;
+
+    // With the current parameters, the simulated sets match the current ones
+    let baseline =
+        simulate_validator_sets(&snapshot, &snapshot.params, num_epochs);
+    assert_eq!(baseline.len() as u64, num_epochs);
+    let current_sets = &baseline[0];
+    let addresses = |set: &[(Address, token::Amount)]| {
+        set.iter()
+            .map(|(address, _)| address.clone())
+            .collect::<HashSet<_>>()
+    };
+    assert_eq!(
+        addresses(&current_sets.consensus),
+        consensus_set
+            .iter()
+            .map(|validator| validator.address.clone())
+            .collect::<HashSet<_>>()
+    );
+    assert!(current_sets.below_capacity.is_empty());
+    assert_eq!(
+        addresses(&current_sets.below_threshold),
+        read_below_threshold_validator_set_addresses(&s, current_epoch)
+            .unwrap()
+    );
+    assert!(current_sets.jailed.is_empty());
+    let concentration = &current_sets.concentration;
+    assert!(concentration.halting_validators >= 1);
+    assert!(
+        concentration.halting_validators <= concentration.committing_validators
+    );
+    assert!(
+        concentration.committing_validators as usize
+            <= current_sets.consensus.len()
+    );
+
+    // With one less slot, the smallest consensus validator is below capacity
+    let simulated = simulate_validator_sets(
+        &snapshot,
+        &ValidatorSetParams {
+            max_validator_slots: params.max_validator_slots - 1,
+            ..snapshot.params.clone()
+        },
+        num_epochs,
+    );
+    for (simulated, baseline) in simulated.iter().zip(&baseline) {
+        if baseline.consensus.len() as u64 == params.max_validator_slots {
+            assert_eq!(simulated.consensus.len(), baseline.consensus.len() - 1);
+            assert_eq!(
+                simulated.below_capacity.first().map(|(_, stake)| stake),
+                baseline.consensus.last().map(|(_, stake)| stake)
+            );
+        } else {
+            assert_eq!(simulated, baseline);
+        }
+    }
+
+    // With a higher liveness threshold, the validator that missed votes is
+    // jailed from the epoch after the snapshot epoch
+    let liveness_threshold = Dec::new(995, 3).unwrap();
+    let simulated = simulate_validator_sets(
+        &snapshot,
+        &ValidatorSetParams {
+            liveness_threshold,
+            ..snapshot.params.clone()
+        },
+        num_epochs,
+    );
+    for sets in &simulated {
+        let is_jailed = sets.epoch >= current_epoch.next();
+        assert_eq!(sets.jailed.contains(&inactive_validator), is_jailed);
+        assert_eq!(
+            addresses(&sets.consensus).contains(&inactive_validator),
+            !is_jailed
+        );
+    }
+
+    // The protocol jails the validator in the next block with this threshold
+    // and it leaves the consensus set in the same epoch as simulated
+    let jail_params = PosParams {
+        owned: OwnedPosParams {
+            liveness_threshold,
+            ..params.owned.clone()
+        },
+        ..params.clone()
+    };
+    jail_for_liveness(
+        &mut s,
+        &jail_params,
+        current_epoch,
+        current_epoch.next(),
+    )
+    .unwrap();
+    for sets in &simulated {
+        let consensus =
+            read_consensus_validator_set_addresses_with_stake(&s, sets.epoch)
+                .unwrap();
+        assert_eq!(
+            consensus
+                .iter()
+                .any(|validator| validator.address == inactive_validator),
+            addresses(&sets.consensus).contains(&inactive_validator),
+            "Epoch {}",
+            sets.epoch
+        );
+    }
+}
+
+proptest! {
+    // Generate arb valid input for `test_schedule_commission_rate_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_schedule_commission_rate(
+
+        genesis_validators in arb_genesis_validators(1..4, None),
+
+    ) {
+        test_schedule_commission_rate_aux(genesis_validators)
+    }
+}
+
+/// Test scheduling commission rate changes after the pipeline epoch, their
+/// application and the events announcing them.
+fn test_schedule_commission_rate_aux(validators: Vec<GenesisValidator>) {
+    let mut s = TestState::default();
+    let mut current_epoch = s.in_mem().block.epoch;
+    let params = test_init_genesis(
+        &mut s,
+        OwnedPosParams::default(),
+        validators.clone().into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    s.commit_block().unwrap();
+
+    let validator = validators.first().unwrap().address.clone();
+    let rate = |percent: i128| Dec::new(percent, 2).unwrap();
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let first_epoch = pipeline_epoch + 3;
+    let second_epoch = first_epoch.next();
+
+    // A change cannot take effect before the pipeline epoch
+    let res = schedule_validator_commission_rate(
+        &mut s,
+        &validator,
+        rate(6),
+        current_epoch,
+        pipeline_epoch.prev(),
+    );
+    assert!(res.is_err());
+
+    schedule_validator_commission_rate(
+        &mut s,
+        &validator,
+        rate(6),
+        current_epoch,
+        first_epoch,
+    )
+    .unwrap();
+    // The max change is checked against the scheduled rate
+    let res = schedule_validator_commission_rate(
+        &mut s,
+        &validator,
+        rate(8),
+        current_epoch,
+        second_epoch,
+    );
+    assert!(res.is_err());
+    schedule_validator_commission_rate(
+        &mut s,
+        &validator,
+        rate(7),
+        current_epoch,
+        second_epoch,
+    )
+    .unwrap();
+
+    let read_rate = |s: &TestState, current_epoch: Epoch, epoch: Epoch| {
+        read_validator_commission_rate(
+            s,
+            &params,
+            &validator,
+            current_epoch,
+            epoch,
+        )
+        .unwrap()
+        .unwrap()
+    };
+    assert_eq!(read_rate(&s, current_epoch, first_epoch.prev()), rate(5));
+    assert_eq!(read_rate(&s, current_epoch, first_epoch), rate(6));
+    assert_eq!(read_rate(&s, current_epoch, second_epoch + 2), rate(7));
+
+    // The changes are announced once
+    let mut events: Vec<Event> = vec![];
+    emit_commission_rate_change_events(
+        &mut s,
+        &params,
+        current_epoch,
+        &mut events,
+    )
+    .unwrap();
+    let mut announced = events
+        .iter()
+        .map(|event| CommissionRateChangeEvent::try_from(event).unwrap())
+        .collect::<Vec<_>>();
+    announced.sort_by_key(|event| event.effective_epoch);
+    assert_eq!(
+        announced,
+        vec![
+            CommissionRateChangeEvent {
+                validator: validator.clone(),
+                old_rate: rate(5),
+                new_rate: rate(6),
+                effective_epoch: first_epoch,
+            },
+            CommissionRateChangeEvent {
+                validator: validator.clone(),
+                old_rate: rate(6),
+                new_rate: rate(7),
+                effective_epoch: second_epoch,
+            },
+        ]
+    );
+    let mut events: Vec<Event> = vec![];
+    emit_commission_rate_change_events(
+        &mut s,
+        &params,
+        current_epoch,
+        &mut events,
+    )
+    .unwrap();
+    assert!(events.is_empty());
+
+    // The scheduled changes are applied when their epoch becomes the pipeline
+    // epoch
+    while current_epoch + params.pipeline_len < second_epoch {
+        current_epoch = advance_epoch(&mut s, &params);
+        apply_scheduled_commission_rates(&mut s, &params, current_epoch)
+            .unwrap();
+        let pipeline_epoch = current_epoch + params.pipeline_len;
+        let scheduled =
+            read_validator_scheduled_commission_rates(&s, &validator).unwrap();
+        assert!(scheduled.keys().all(|epoch| *epoch > pipeline_epoch));
+    }
+    assert!(scheduled_commission_rates_handle().is_empty(&s).unwrap());
+    let commission_handle = validator_commission_rate_handle(&validator);
+    for (epoch, expected) in [
+        (first_epoch.prev(), rate(5)),
+        (first_epoch, rate(6)),
+        (second_epoch, rate(7)),
+    ] {
+        assert_eq!(
+            commission_handle.get(&s, epoch, &params).unwrap(),
+            Some(expected)
+        );
+        assert_eq!(read_rate(&s, current_epoch, epoch), expected);
+    }
+}
+
+proptest! {
+    // Generate arb valid input for `test_replace_scheduled_commission_rate_aux`
+    #![proptest_config(Config {
+        cases: 1,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_replace_scheduled_commission_rate(
+
+        genesis_validators in arb_genesis_validators(1..4, None),
+
+    ) {
+        test_replace_scheduled_commission_rate_aux(genesis_validators)
+    }
+}
+
+/// Test that commission rate changes can only be scheduled up to the
+/// `max_commission_schedule_len` epochs after the pipeline epoch and that a
+/// change replaces the ones scheduled from its effective epoch on, or cancels
+/// them when the rate is changed back to the current one.
+fn test_replace_scheduled_commission_rate_aux(
+    validators: Vec<GenesisValidator>,
+) {
+    let mut s = TestState::default();
+    let current_epoch = s.in_mem().block.epoch;
+    let params = test_init_genesis(
+        &mut s,
+        OwnedPosParams::default(),
+        validators.clone().into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    s.commit_block().unwrap();
+
+    let validator = validators.first().unwrap().address.clone();
+    let rate = |percent: i128| Dec::new(percent, 2).unwrap();
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let last_schedule_epoch =
+        pipeline_epoch + params.max_commission_schedule_len;
+    let first_epoch = pipeline_epoch + 2;
+    let scheduled = |s: &TestState| {
+        read_validator_scheduled_commission_rates(s, &validator).unwrap()
+    };
+    let read_rate = |s: &TestState, epoch: Epoch| {
+        read_validator_commission_rate(
+            s,
+            &params,
+            &validator,
+            current_epoch,
+            epoch,
+        )
+        .unwrap()
+        .unwrap()
+    };
+
+    // A change cannot take effect after the schedule horizon
+    let res = schedule_validator_commission_rate(
+        &mut s,
+        &validator,
+        rate(6),
+        current_epoch,
+        last_schedule_epoch.next(),
+    );
+    assert!(res.is_err());
+    schedule_validator_commission_rate(
+        &mut s,
+        &validator,
+        rate(6),
+        current_epoch,
+        last_schedule_epoch,
+    )
+    .unwrap();
+
+    // A change before a scheduled one replaces it
+    schedule_validator_commission_rate(
+        &mut s,
+        &validator,
+        rate(4),
+        current_epoch,
+        first_epoch,
+    )
+    .unwrap();
+    assert_eq!(scheduled(&s), BTreeMap::from([(first_epoch, rate(4))]));
+    assert_eq!(read_rate(&s, last_schedule_epoch), rate(4));
+
+    // A change in the same epoch as a scheduled one overwrites it, and a
+    // change after it is scheduled along with it
+    schedule_validator_commission_rate(
+        &mut s,
+        &validator,
+        rate(6),
+        current_epoch,
+        first_epoch,
+    )
+    .unwrap();
+    schedule_validator_commission_rate(
+        &mut s,
+        &validator,
+        rate(7),
+        current_epoch,
+        first_epoch.next(),
+    )
+    .unwrap();
+    assert_eq!(
+        scheduled(&s),
+        BTreeMap::from([(first_epoch, rate(6)), (first_epoch.next(), rate(7))])
+    );
+
+    // A change from the pipeline epoch replaces all the scheduled ones
+    change_validator_commission_rate(
+        &mut s,
+        &validator,
+        rate(4),
+        current_epoch,
+    )
+    .unwrap();
+    assert!(scheduled(&s).is_empty());
+    assert_eq!(read_rate(&s, pipeline_epoch), rate(4));
+    assert_eq!(read_rate(&s, last_schedule_epoch), rate(4));
+
+    // Changing the rate back to the current one cancels a scheduled change
+    schedule_validator_commission_rate(
+        &mut s,
+        &validator,
+        rate(5),
+        current_epoch,
+        first_epoch,
+    )
+    .unwrap();
+    assert_eq!(read_rate(&s, first_epoch), rate(5));
+    change_validator_commission_rate(
+        &mut s,
+        &validator,
+        rate(4),
+        current_epoch,
+    )
+    .unwrap();
+    assert!(scheduled(&s).is_empty());
+    assert_eq!(read_rate(&s, first_epoch), rate(4));
+}
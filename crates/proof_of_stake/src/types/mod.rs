@@ -11,6 +11,7 @@ use std::ops::Sub;
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_core::address::Address;
 use namada_core::dec::Dec;
+use namada_core::event::{Event, EventError, EventLevel, EventType};
 use namada_core::key::common;
 use namada_core::storage::{Epoch, KeySeg};
 use namada_core::token;
@@ -259,6 +260,18 @@ pub type LivenessMissedVotes = NestedMap<Address, LazySet<u64>>;
 /// elements in the corresponding inner LazySet of [`LivenessMissedVotes`].
 pub type LivenessSumMissedVotes = LazyMap<Address, u64>;
 
+/// Commission rate changes scheduled to take effect after the pipeline epoch.
+/// The map keys from outside in are:
+///
+/// - the epoch from which the new rate takes effect
+/// - validator address
+pub type ScheduledCommissionRates = NestedMap<Epoch, LazyMap<Address, Dec>>;
+
+/// Commission rate changes that are yet to be announced in a
+/// [`CommissionRateChangeEvent`], keyed by the validator address. The inner
+/// set holds the epochs from which the new rates take effect.
+pub type CommissionRateChangeNotices = NestedMap<Address, LazySet<Epoch>>;
+
 #[derive(
     Debug, Clone, BorshSerialize, BorshDeserialize, Eq, Hash, PartialEq,
 )]
@@ -279,6 +292,413 @@ pub struct CommissionPair {
     pub max_commission_change_per_epoch: Dec,
 }
 
+/// The announcement of a change of a validator's commission rate, emitted in
+/// the block after the change was made so that the delegators can react
+/// before it takes effect
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct CommissionRateChangeEvent {
+    /// The validator whose commission rate changes
+    pub validator: Address,
+    /// The commission rate in the epoch before the change
+    pub old_rate: Dec,
+    /// The new commission rate
+    pub new_rate: Dec,
+    /// The epoch from which the new commission rate takes effect
+    pub effective_epoch: Epoch,
+}
+
+impl CommissionRateChangeEvent {
+    /// The attribute holding the epoch from which the new rate takes effect
+    pub const EFFECTIVE_EPOCH_ATTR: &'static str = "effective_epoch";
+    /// The attribute holding the new commission rate
+    pub const NEW_RATE_ATTR: &'static str = "new_rate";
+    /// The attribute holding the old commission rate
+    pub const OLD_RATE_ATTR: &'static str = "old_rate";
+    /// The attribute holding the address of the validator, which can be used
+    /// to subscribe to the events of a single validator
+    pub const VALIDATOR_ATTR: &'static str = "validator";
+}
+
+impl From<CommissionRateChangeEvent> for Event {
+    fn from(event: CommissionRateChangeEvent) -> Self {
+        let attributes = HashMap::from([
+            (
+                CommissionRateChangeEvent::VALIDATOR_ATTR.to_string(),
+                event.validator.to_string(),
+            ),
+            (
+                CommissionRateChangeEvent::OLD_RATE_ATTR.to_string(),
+                event.old_rate.to_string(),
+            ),
+            (
+                CommissionRateChangeEvent::NEW_RATE_ATTR.to_string(),
+                event.new_rate.to_string(),
+            ),
+            (
+                CommissionRateChangeEvent::EFFECTIVE_EPOCH_ATTR.to_string(),
+                event.effective_epoch.to_string(),
+            ),
+        ]);
+        Self {
+            event_type: EventType::CommissionChange,
+            level: EventLevel::Block,
+            attributes,
+        }
+    }
+}
+
+impl TryFrom<&Event> for CommissionRateChangeEvent {
+    type Error = EventError;
+
+    fn try_from(event: &Event) -> Result<Self, Self::Error> {
+        if event.event_type != EventType::CommissionChange {
+            return Err(EventError::InvalidEventType);
+        }
+        let attr = |key: &str| {
+            event
+                .get(key)
+                .ok_or_else(|| EventError::MissingKey(key.to_string()))
+        };
+        let invalid = |key: &str| EventError::MissingValue(key.to_string());
+        Ok(Self {
+            validator: Address::decode(attr(Self::VALIDATOR_ATTR)?)
+                .map_err(|_| invalid(Self::VALIDATOR_ATTR))?,
+            old_rate: attr(Self::OLD_RATE_ATTR)?
+                .parse()
+                .map_err(|_| invalid(Self::OLD_RATE_ATTR))?,
+            new_rate: attr(Self::NEW_RATE_ATTR)?
+                .parse()
+                .map_err(|_| invalid(Self::NEW_RATE_ATTR))?,
+            effective_epoch: attr(Self::EFFECTIVE_EPOCH_ATTR)?
+                .parse::<u64>()
+                .map(Epoch)
+                .map_err(|_| invalid(Self::EFFECTIVE_EPOCH_ATTR))?,
+        })
+    }
+}
+
+/// A validator's commission rate, stake and state in some epoch
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct ValidatorEpochRecord {
+    /// Commission rate
+    pub commission_rate: Dec,
+    /// Total stake
+    pub stake: token::Amount,
+    /// Validator state
+    pub state: ValidatorState,
+}
+
+/// Compact history of a validator's epoch records that is kept after the
+/// epoched data has been pruned. A record is only written in an epoch in which
+/// it differs from the record of the epoch before.
+pub type ValidatorHistory = LazyMap<Epoch, ValidatorEpochRecord>;
+
+/// Index of the records in the validators' histories, keyed by:
+/// - the epoch of the record
+/// - validator address
+///
+/// It's used to prune the records of all the validators, including the ones
+/// that are no longer in the validator sets.
+pub type ValidatorHistoryEpochs = NestedMap<Epoch, LazySet<Address>>;
+
+/// A validator's epoch records over a range of epochs and the slashes that
+/// occurred in the range
+#[derive(
+    Debug, Clone, Default, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct ValidatorHistoryDetails {
+    /// Records of the epochs in which the validator existed
+    pub records: BTreeMap<Epoch, ValidatorEpochRecord>,
+    /// Slashes that occurred in the range of epochs
+    pub slashes: Vec<Slash>,
+}
+
+/// The cause of jailing a validator
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub enum JailReason {
+    /// Missed too many votes in the liveness window
+    Liveness,
+    /// Evidence of a misbehavior
+    Misbehavior(SlashType),
+}
+
+impl Display for JailReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JailReason::Liveness => write!(f, "missed too many votes"),
+            JailReason::Misbehavior(slash_type) => {
+                write!(f, "misbehavior ({slash_type})")
+            }
+        }
+    }
+}
+
+/// A period in which a validator was jailed
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct JailRecord {
+    /// The cause of jailing
+    pub reason: JailReason,
+    /// The epoch from which the validator was unjailed, if it has been
+    pub unjail_epoch: Option<Epoch>,
+}
+
+/// The jail history of a validator, keyed by the epoch from which the
+/// validator was jailed
+pub type ValidatorJailHistory = LazyMap<Epoch, JailRecord>;
+
+/// The liveness of a consensus validator in the current sliding window of
+/// `liveness_window_check` blocks
+#[derive(Debug, Clone, Copy, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct LivenessWindow {
+    /// Number of blocks in the window since the validator last entered the
+    /// consensus set. This is less than `liveness_window_check` only in the
+    /// first blocks of the chain or after the validator entered the set.
+    pub num_blocks: u64,
+    /// Number of blocks in the window which the validator didn't miss
+    pub signed_votes: u64,
+    /// Number of blocks in the window which the validator missed
+    pub missed_votes: u64,
+    /// Number of missed votes in the window at which a validator is jailed,
+    /// derived from `liveness_threshold`
+    pub missed_votes_threshold: u64,
+}
+
+impl LivenessWindow {
+    /// Number of votes the validator can still miss in the window before it
+    /// gets jailed
+    pub fn missed_votes_until_jail(&self) -> u64 {
+        self.missed_votes_threshold
+            .saturating_sub(self.missed_votes)
+    }
+}
+
+/// A validator's liveness in the current window and its jail history
+#[derive(
+    Debug, Clone, Default, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct ValidatorLivenessDetails {
+    /// Liveness in the current window, if the validator is tracked for
+    /// liveness, i.e. it is or recently was in the consensus set
+    pub window: Option<LivenessWindow>,
+    /// The periods in which the validator was jailed, keyed by the epoch
+    /// from which it was jailed
+    pub jail_history: BTreeMap<Epoch, JailRecord>,
+}
+
+/// An unbond of a delegator that has not been withdrawn yet
+#[derive(
+    Debug, Clone, BorshSerialize, BorshDeserialize, BorshSchema, PartialEq,
+)]
+pub struct PendingUnbond {
+    /// The validator from which the tokens were unbonded
+    pub validator: Address,
+    /// The epoch from which the unbonded bond contributed to the validator's
+    /// stake
+    pub start_epoch: Epoch,
+    /// The epoch from which the unbond can be withdrawn
+    pub withdraw_epoch: Epoch,
+    /// The unbonded amount
+    pub amount: token::Amount,
+    /// The amount that would be withdrawn after the known slashes
+    pub amount_after_slashing: token::Amount,
+    /// The unbond is still slashable for infractions of the validator that
+    /// occurred before this epoch
+    pub slashable_until: Epoch,
+    /// The parts of the unbond that were redelegated to the validator, by
+    /// source validator
+    pub redelegated: BTreeMap<Address, token::Amount>,
+}
+
+/// A redelegation of a delegator whose tokens can still be slashed for
+/// infractions of the source validator
+#[derive(
+    Debug, Clone, BorshSerialize, BorshDeserialize, BorshSchema, PartialEq,
+)]
+pub struct PendingRedelegation {
+    /// The validator from which the tokens were redelegated
+    pub src_validator: Address,
+    /// The validator to which the tokens were redelegated
+    pub dest_validator: Address,
+    /// The epoch from which the tokens contribute to the destination
+    /// validator's stake
+    pub start_epoch: Epoch,
+    /// The redelegated amount that is still bonded to the destination
+    /// validator
+    pub amount: token::Amount,
+    /// The redelegated amount after the known slashes of both validators
+    pub amount_after_slashing: token::Amount,
+    /// The redelegated tokens are still slashable for infractions of the
+    /// source validator that occurred before this epoch
+    pub slashable_until: Epoch,
+    /// The epoch in which the slashes of the source validator's infractions
+    /// have all been processed, from which the tokens can be redelegated again
+    pub mature_epoch: Epoch,
+}
+
+/// The unbonds and redelegations of a delegator that are still in flight
+#[derive(
+    Debug, Clone, Default, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct PendingUnbondsAndRedelegations {
+    /// Unbonds that have not been withdrawn yet
+    pub unbonds: Vec<PendingUnbond>,
+    /// Redelegations that are still slashable for the source validator's
+    /// infractions
+    pub redelegations: Vec<PendingRedelegation>,
+}
+
+/// The consensus key of a validator and its pending change, if any
+#[derive(
+    Debug, Clone, BorshSerialize, BorshDeserialize, BorshSchema, PartialEq,
+)]
+pub struct ConsensusKeyRotation {
+    /// The consensus key in the current epoch, if the validator is already
+    /// active
+    pub current_key: Option<common::PublicKey>,
+    /// The epoch from which the next consensus key replaces the current one
+    /// and the next key, if a change is pending
+    pub pending: Option<(Epoch, common::PublicKey)>,
+}
+
+/// The status of a consensus key of a validator with respect to the changes
+/// of the validator's consensus key
+#[derive(
+    Debug, Clone, BorshSerialize, BorshDeserialize, BorshSchema, PartialEq,
+)]
+pub enum ConsensusKeyStatus {
+    /// The key is the validator's consensus key and no change is pending
+    Active,
+    /// The key is the validator's consensus key until it's replaced by the
+    /// new key in the switch epoch
+    RotatingOut {
+        /// The first epoch of the new key
+        switch_epoch: Epoch,
+        /// The new consensus key
+        new_key: common::PublicKey,
+    },
+    /// The key becomes the validator's consensus key in the switch epoch
+    RotatingIn {
+        /// The first epoch of the key
+        switch_epoch: Epoch,
+    },
+    /// The key has been replaced by another consensus key of the validator
+    RotatedOut {
+        /// The latest consensus key of the validator
+        new_key: common::PublicKey,
+    },
+}
+
+/// A bond or unbond of a delegator, or a part of it that was redelegated,
+/// which lost tokens to a slash
+#[derive(
+    Debug, Clone, BorshSerialize, BorshDeserialize, BorshSchema, PartialEq,
+)]
+pub struct SlashedHolding {
+    /// The validator of the bond or unbond
+    pub validator: Address,
+    /// The epoch from which the bond contributed to the validator's stake
+    pub start: Epoch,
+    /// The withdrawal epoch, if the tokens have been unbonded
+    pub withdraw: Option<Epoch>,
+    /// The source validator and the start epoch of the bond to the source
+    /// validator, if this part of the bond was redelegated
+    pub redelegated_from: Option<(Address, Epoch)>,
+}
+
+/// The tokens that a delegator lost to a slash
+#[derive(
+    Debug, Clone, BorshSerialize, BorshDeserialize, BorshSchema, PartialEq,
+)]
+pub struct DelegatorSlashLoss {
+    /// The slashed validator
+    pub slashed_validator: Address,
+    /// The slash
+    pub slash: Slash,
+    /// The slashed bond or unbond
+    pub holding: SlashedHolding,
+    /// The amount lost to the slash
+    pub amount: token::Amount,
+}
+
+/// Projection of the rewards that a new bond to a validator would earn
+#[derive(Debug, Clone, Copy, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct BondRewardsProjection {
+    /// The first epoch in which the bond would earn rewards, i.e. the
+    /// pipeline epoch
+    pub start_epoch: Epoch,
+    /// Projected rewards of the bond in every epoch from the `start_epoch`
+    pub rewards_per_epoch: token::Amount,
+    /// Projected nominal annual percentage rate of the bond
+    pub nominal_apr: Dec,
+    /// Projected annual percentage rate of the bond net of the inflation of
+    /// the staking token supply, minted by both the PoS and the PGF inflation
+    pub real_apr: Dec,
+    /// Projected PoS inflation per epoch
+    pub inflation_per_epoch: token::Amount,
+    /// Projected ratio of the staked tokens to the total supply
+    pub staked_ratio: Dec,
+    /// Commission rate of the validator in the `start_epoch`
+    pub commission_rate: Dec,
+    /// Fraction of the blocks signed by the validator in the current liveness
+    /// window, used as its expected signing rate
+    pub uptime: Dec,
+    /// Whether the validator would be in the consensus set in the
+    /// `start_epoch`. Only consensus validators earn rewards.
+    pub is_consensus: bool,
+}
+
+/// Settings of a delegation whose rewards are automatically bonded back to
+/// the validator
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct RestakeSettings {
+    /// Number of epochs between two restakes of the rewards
+    pub interval: u64,
+    /// The first epoch in which the rewards will be restaked next
+    pub next_epoch: Epoch,
+}
+
 /// Epoched rewards products
 pub type RewardsProducts = LazyMap<Epoch, Dec>;
 
@@ -513,6 +933,8 @@ impl Position {
     BorshSchema,
     PartialEq,
     Eq,
+    Serialize,
+    Deserialize,
 )]
 pub enum ValidatorState {
     /// A validator who may participate in the consensus
@@ -4,13 +4,14 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::Duration as StdDuration;
 
+use namada_account::SpendingLimit;
 use namada_core::address::Address;
 use namada_core::chain::ChainId;
 use namada_core::dec::Dec;
 use namada_core::ethereum_events::EthAddress;
 use namada_core::keccak::KeccakHash;
 use namada_core::key::{common, SchemeType};
-use namada_core::masp::PaymentAddress;
+use namada_core::masp::{PaymentAddress, PaymentRequest};
 use namada_core::storage::{BlockHeight, Epoch};
 use namada_core::time::DateTimeUtc;
 use namada_core::{storage, token};
@@ -24,7 +25,9 @@ use zeroize::Zeroizing;
 
 use crate::eth_bridge::bridge_pool;
 use crate::ibc::core::host::types::identifiers::{ChannelId, PortId};
+use crate::masp::{NoteSelection, ShieldedHistoryFormat};
 use crate::signing::SigningTxData;
+use crate::wallet::address_book::AddressBookFormat;
 use crate::{rpc, tx, Namada};
 
 /// [`Duration`](StdDuration) wrapper that provides a
@@ -239,6 +242,8 @@ pub struct TxTransfer<C: NamadaTypes = SdkTypes> {
     pub token: C::Address,
     /// Transferred token amount
     pub amount: InputAmount,
+    /// The payment request that the transfer pays, if any
+    pub payment_request: Option<PaymentRequest>,
     /// Path to the TX WASM code file
     pub tx_code_path: PathBuf,
 }
@@ -286,6 +291,24 @@ impl<C: NamadaTypes> TxTransfer<C> {
 }
 
 impl TxTransfer {
+    /// Pay the given payment request. The target and the token and amount
+    /// that the request specifies replace those of the transfer. The memo of
+    /// the request is attached to the shielded note of the payment.
+    pub fn payment_request(mut self, request: PaymentRequest) -> Self {
+        self.target =
+            namada_core::masp::TransferTarget::PaymentAddress(request.address);
+        if let Some(token) = &request.token {
+            self.token = token.clone();
+        }
+        if let Some(amount) = request.amount {
+            self.amount = InputAmount::Unvalidated(amount);
+        }
+        Self {
+            payment_request: Some(request),
+            ..self
+        }
+    }
+
     /// Build a transaction from this builder
     pub async fn build(
         &mut self,
@@ -296,6 +319,79 @@ impl TxTransfer {
     }
 }
 
+/// Note consolidation transaction arguments
+#[derive(Clone, Debug)]
+pub struct TxConsolidateNotes<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// The spending key whose notes are merged
+    pub source: C::SpendingKey,
+    /// The token of the notes to merge
+    pub token: C::Address,
+    /// The maximum number of notes to merge
+    pub max_notes: usize,
+    /// Only merge the notes of at most this amount
+    pub dust_threshold: Option<InputAmount>,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for TxConsolidateNotes<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        TxConsolidateNotes {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> TxConsolidateNotes<C> {
+    /// The spending key whose notes are merged
+    pub fn source(self, source: C::SpendingKey) -> Self {
+        Self { source, ..self }
+    }
+
+    /// The token of the notes to merge
+    pub fn token(self, token: C::Address) -> Self {
+        Self { token, ..self }
+    }
+
+    /// The maximum number of notes to merge
+    pub fn max_notes(self, max_notes: usize) -> Self {
+        Self { max_notes, ..self }
+    }
+
+    /// Only merge the notes of at most this amount
+    pub fn dust_threshold(self, dust_threshold: InputAmount) -> Self {
+        Self {
+            dust_threshold: Some(dust_threshold),
+            ..self
+        }
+    }
+
+    /// Path to the TX WASM code file
+    pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
+        Self {
+            tx_code_path,
+            ..self
+        }
+    }
+}
+
+impl TxConsolidateNotes {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &mut self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(namada_tx::Tx, SigningTxData, Option<Epoch>)>
+    {
+        tx::build_consolidate_notes(context, self).await
+    }
+}
+
 /// IBC transfer transaction arguments
 #[derive(Clone, Debug)]
 pub struct TxIbcTransfer<C: NamadaTypes = SdkTypes> {
@@ -874,6 +970,110 @@ impl TxUpdateAccount {
     }
 }
 
+/// Transaction to update the spending policy of an account arguments
+#[derive(Clone, Debug)]
+pub struct TxUpdateSpendingPolicy<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+    /// Address of the account whose spending policy is to be updated
+    pub addr: C::Address,
+    /// Spending limits to set per token, `None` removes the limit
+    pub limits: Vec<(C::Address, Option<SpendingLimit>)>,
+    /// Recipients to add to the allowlist
+    pub allowed_recipients: Vec<C::Address>,
+    /// Recipients to remove from the allowlist
+    pub disallowed_recipients: Vec<C::Address>,
+    /// Allowances of delegate keys per token, a zero amount removes the
+    /// allowance
+    pub allowances: Vec<(C::PublicKey, C::Address, token::Amount)>,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for TxUpdateSpendingPolicy<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        TxUpdateSpendingPolicy {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> TxUpdateSpendingPolicy<C> {
+    /// Path to the TX WASM code file
+    pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
+        Self {
+            tx_code_path,
+            ..self
+        }
+    }
+
+    /// Address of the account whose spending policy is to be updated
+    pub fn addr(self, addr: C::Address) -> Self {
+        Self { addr, ..self }
+    }
+
+    /// Limit the amount of the token that can be spent within a window of
+    /// epochs
+    pub fn spending_limit(
+        mut self,
+        token: C::Address,
+        amount: token::Amount,
+        window_epochs: u64,
+    ) -> Self {
+        self.limits.push((
+            token,
+            Some(SpendingLimit {
+                amount,
+                window_epochs,
+            }),
+        ));
+        self
+    }
+
+    /// Remove the spending limit of the token
+    pub fn remove_spending_limit(mut self, token: C::Address) -> Self {
+        self.limits.push((token, None));
+        self
+    }
+
+    /// Add a recipient to the allowlist
+    pub fn allow_recipient(mut self, recipient: C::Address) -> Self {
+        self.allowed_recipients.push(recipient);
+        self
+    }
+
+    /// Remove a recipient from the allowlist
+    pub fn disallow_recipient(mut self, recipient: C::Address) -> Self {
+        self.disallowed_recipients.push(recipient);
+        self
+    }
+
+    /// Allow a delegate key to spend up to the given amount of the token
+    pub fn allowance(
+        mut self,
+        delegate: C::PublicKey,
+        token: C::Address,
+        amount: token::Amount,
+    ) -> Self {
+        self.allowances.push((delegate, token, amount));
+        self
+    }
+}
+
+impl TxUpdateSpendingPolicy {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(namada_tx::Tx, SigningTxData)> {
+        tx::build_update_spending_policy(context, self).await
+    }
+}
+
 /// Bond arguments
 #[derive(Clone, Debug)]
 pub struct Bond<C: NamadaTypes = SdkTypes> {
@@ -941,6 +1141,130 @@ impl Bond {
     }
 }
 
+/// Liquid bond arguments
+#[derive(Clone, Debug)]
+pub struct LiquidBond<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// Validator address
+    pub validator: C::Address,
+    /// Amount of tokens to stake in the liquid bond
+    pub amount: token::Amount,
+    /// Source address of the tokens and owner of the minted shares
+    pub source: C::Address,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for LiquidBond<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        LiquidBond {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> LiquidBond<C> {
+    /// Validator address
+    pub fn validator(self, validator: C::Address) -> Self {
+        Self { validator, ..self }
+    }
+
+    /// Amount of tokens to stake in the liquid bond
+    pub fn amount(self, amount: token::Amount) -> Self {
+        Self { amount, ..self }
+    }
+
+    /// Source address of the tokens and owner of the minted shares
+    pub fn source(self, source: C::Address) -> Self {
+        Self { source, ..self }
+    }
+
+    /// Path to the TX WASM code file
+    pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
+        Self {
+            tx_code_path,
+            ..self
+        }
+    }
+}
+
+impl LiquidBond {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(namada_tx::Tx, SigningTxData)> {
+        tx::build_liquid_bond(context, self).await
+    }
+}
+
+/// Redeem shares arguments
+#[derive(Clone, Debug)]
+pub struct RedeemShares<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// Validator address
+    pub validator: C::Address,
+    /// Amount of share tokens to redeem
+    pub shares: token::Amount,
+    /// Owner of the shares and of the resulting unbond
+    pub owner: C::Address,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for RedeemShares<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        RedeemShares {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> RedeemShares<C> {
+    /// Validator address
+    pub fn validator(self, validator: C::Address) -> Self {
+        Self { validator, ..self }
+    }
+
+    /// Amount of share tokens to redeem
+    pub fn shares(self, shares: token::Amount) -> Self {
+        Self { shares, ..self }
+    }
+
+    /// Owner of the shares and of the resulting unbond
+    pub fn owner(self, owner: C::Address) -> Self {
+        Self { owner, ..self }
+    }
+
+    /// Path to the TX WASM code file
+    pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
+        Self {
+            tx_code_path,
+            ..self
+        }
+    }
+}
+
+impl RedeemShares {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(namada_tx::Tx, SigningTxData)> {
+        tx::build_redeem_shares(context, self).await
+    }
+}
+
 /// Unbond arguments
 #[derive(Clone, Debug)]
 pub struct Unbond<C: NamadaTypes = SdkTypes> {
@@ -1250,6 +1574,71 @@ impl ClaimRewards {
     }
 }
 
+/// Restake arguments
+#[derive(Clone, Debug)]
+pub struct Restake<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// Validator address
+    pub validator: C::Address,
+    /// Source address of the bond. For self-bonds, the validator is also the
+    /// source
+    pub source: Option<C::Address>,
+    /// Number of epochs between two restakes of the rewards, or `None` to
+    /// disable the restaking
+    pub interval: Option<u64>,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for Restake<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        Restake {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> Restake<C> {
+    /// Source address of the bond
+    pub fn source(self, source: C::Address) -> Self {
+        Self {
+            source: Some(source),
+            ..self
+        }
+    }
+
+    /// Number of epochs between two restakes of the rewards
+    pub fn interval(self, interval: u64) -> Self {
+        Self {
+            interval: Some(interval),
+            ..self
+        }
+    }
+
+    /// Path to the TX WASM code file
+    pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
+        Self {
+            tx_code_path,
+            ..self
+        }
+    }
+}
+
+impl Restake {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(namada_tx::Tx, SigningTxData)> {
+        tx::build_restake(context, self).await
+    }
+}
+
 /// Query asset conversions
 #[derive(Clone, Debug)]
 pub struct QueryConversions<C: NamadaTypes = SdkTypes> {
@@ -1294,6 +1683,33 @@ pub struct QueryTransfers<C: NamadaTypes = SdkTypes> {
     pub token: Option<C::Address>,
 }
 
+/// Export the shielded history of a viewing key
+#[derive(Clone, Debug)]
+pub struct ExportShieldedHistory<C: NamadaTypes = SdkTypes> {
+    /// Common query args
+    pub query: Query<C>,
+    /// The viewing key whose history is exported
+    pub viewing_key: C::ViewingKey,
+    /// File format
+    pub format: ShieldedHistoryFormat,
+    /// Output file path, the history is printed if not given
+    pub output: Option<PathBuf>,
+}
+
+/// Estimate the MASP rewards of a token
+#[derive(Clone, Debug)]
+pub struct QueryMaspRewards<C: NamadaTypes = SdkTypes> {
+    /// Common query args
+    pub query: Query<C>,
+    /// The token held in the shielded pool
+    pub token: C::Address,
+    /// The amount of the token held in the shielded pool, if any
+    pub amount: Option<InputAmount>,
+    /// The number of epochs after the current one to project the rewards
+    /// over
+    pub epochs: u64,
+}
+
 /// Query PoS bond(s)
 #[derive(Clone, Debug)]
 pub struct QueryBonds<C: NamadaTypes = SdkTypes> {
@@ -1327,6 +1743,15 @@ pub struct QueryValidatorState<C: NamadaTypes = SdkTypes> {
     pub epoch: Option<Epoch>,
 }
 
+/// Query the consensus key of a validator and its pending change
+#[derive(Clone, Debug)]
+pub struct QueryConsensusKey<C: NamadaTypes = SdkTypes> {
+    /// Common query args
+    pub query: Query<C>,
+    /// Address of a validator
+    pub validator: C::Address,
+}
+
 #[derive(Clone, Debug)]
 /// Commission rate change args
 pub struct CommissionRateChange<C: NamadaTypes = SdkTypes> {
@@ -1336,6 +1761,10 @@ pub struct CommissionRateChange<C: NamadaTypes = SdkTypes> {
     pub validator: C::Address,
     /// Value to which the tx changes the commission rate
     pub rate: Dec,
+    /// The epoch from which the new rate takes effect, defaulting to the
+    /// pipeline epoch. A scheduled change has to be submitted with the
+    /// [`tx::TX_SCHEDULE_COMMISSION_WASM`].
+    pub effective_epoch: Option<Epoch>,
     /// Path to the TX WASM code file
     pub tx_code_path: PathBuf,
 }
@@ -1363,6 +1792,16 @@ impl<C: NamadaTypes> CommissionRateChange<C> {
         Self { rate, ..self }
     }
 
+    /// The epoch from which the new rate takes effect. This also sets the
+    /// path to the scheduled commission change WASM.
+    pub fn effective_epoch(self, effective_epoch: Epoch) -> Self {
+        Self {
+            effective_epoch: Some(effective_epoch),
+            tx_code_path: PathBuf::from(tx::TX_SCHEDULE_COMMISSION_WASM),
+            ..self
+        }
+    }
+
     /// Path to the TX WASM code file
     pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
         Self {
@@ -1816,7 +2255,7 @@ pub struct SignTx<C: NamadaTypes = SdkTypes> {
 pub struct ShieldedSync<C: NamadaTypes = SdkTypes> {
     /// The ledger address
     pub ledger_address: C::TendermintAddress,
-    /// The number of txs to fetch before caching
+    /// The number of blocks to fetch before caching
     pub batch_size: u64,
     /// Height to sync up to. Defaults to most recent
     pub last_query_height: Option<BlockHeight>,
@@ -1824,6 +2263,31 @@ pub struct ShieldedSync<C: NamadaTypes = SdkTypes> {
     pub spending_keys: Vec<C::SpendingKey>,
     /// Viewing keys used to determine note ownership
     pub viewing_keys: Vec<C::ViewingKey>,
+    /// File of indexed MASP txs to sync from instead of the node
+    pub masp_txs_file: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug)]
+/// Index the MASP txs of a node in a local file, from which shielded
+/// contexts can be synced. Indexing can be told to stop at a given block
+/// height.
+pub struct ShieldedIndex<C: NamadaTypes = SdkTypes> {
+    /// The ledger address
+    pub ledger_address: C::TendermintAddress,
+    /// The file of indexed MASP txs
+    pub masp_txs_file: PathBuf,
+    /// Height to index up to. Defaults to most recent
+    pub last_query_height: Option<BlockHeight>,
+}
+
+/// List, merge or delete the named shielded contexts of a chain. The contexts
+/// are listed when neither a merge nor a deletion is given.
+#[derive(Clone, Debug)]
+pub struct ShieldedContexts {
+    /// The name of a context to merge into the selected one
+    pub merge: Option<String>,
+    /// The name of a context to delete
+    pub delete: Option<String>,
 }
 
 /// Query PoS commission rate
@@ -1846,6 +2310,19 @@ pub struct QueryMetaData<C: NamadaTypes = SdkTypes> {
     pub validator: C::Address,
 }
 
+/// Query the history of a validator
+#[derive(Clone, Debug)]
+pub struct QueryValidatorHistory<C: NamadaTypes = SdkTypes> {
+    /// Common query args
+    pub query: Query<C>,
+    /// Address of a validator
+    pub validator: C::Address,
+    /// First epoch of the queried range
+    pub start_epoch: Epoch,
+    /// Last epoch of the queried range, current if not specified
+    pub end_epoch: Option<Epoch>,
+}
+
 /// Query PoS slashes
 #[derive(Clone, Debug)]
 pub struct QuerySlashes<C: NamadaTypes = SdkTypes> {
@@ -1866,6 +2343,17 @@ pub struct QueryRewards<C: NamadaTypes = SdkTypes> {
     pub validator: C::Address,
 }
 
+/// Query the projected rewards of a new bond
+#[derive(Clone, Debug)]
+pub struct QueryRewardsProjection<C: NamadaTypes = SdkTypes> {
+    /// Common query args
+    pub query: Query<C>,
+    /// Address of the validator
+    pub validator: C::Address,
+    /// Amount of the new bond
+    pub amount: token::DenominatedAmount,
+}
+
 /// Query PoS delegations
 #[derive(Clone, Debug)]
 pub struct QueryDelegations<C: NamadaTypes = SdkTypes> {
@@ -1875,6 +2363,45 @@ pub struct QueryDelegations<C: NamadaTypes = SdkTypes> {
     pub owner: C::Address,
 }
 
+/// Query the pending unbonds and redelegations of a delegator
+#[derive(Clone, Debug)]
+pub struct QueryPendingUnbonds<C: NamadaTypes = SdkTypes> {
+    /// Common query args
+    pub query: Query<C>,
+    /// Address of the delegator
+    pub owner: C::Address,
+}
+
+/// Query the losses of a delegator to the slashes of their validators
+#[derive(Clone, Debug)]
+pub struct QuerySlashReport<C: NamadaTypes = SdkTypes> {
+    /// Common query args
+    pub query: Query<C>,
+    /// Address of the delegator
+    pub owner: C::Address,
+}
+
+/// Simulate the PoS validator sets with changed parameters
+#[derive(Clone, Debug)]
+pub struct SimulateValidatorSets<C: NamadaTypes = SdkTypes> {
+    /// Common query args
+    pub query: Query<C>,
+    /// The number of epochs to simulate
+    pub epochs: u64,
+    /// The simulated maximum number of consensus validators
+    pub max_validator_slots: Option<u64>,
+    /// The simulated minimum stake of the consensus and below-capacity
+    /// validators
+    pub validator_stake_threshold: Option<token::Amount>,
+    /// The simulated minimum activity of the consensus validators
+    pub liveness_threshold: Option<Dec>,
+    /// The JSON export of a validator set snapshot to simulate from, instead
+    /// of the current validators of the node
+    pub input: Option<PathBuf>,
+    /// The file to export the validator set snapshot to
+    pub output: Option<PathBuf>,
+}
+
 /// Query PoS to find a validator
 #[derive(Clone, Debug)]
 pub struct QueryFindValidator<C: NamadaTypes = SdkTypes> {
@@ -1947,6 +2474,8 @@ pub struct Tx<C: NamadaTypes = SdkTypes> {
     pub memo: Option<Memo>,
     /// Use device to sign the transaction
     pub use_device: bool,
+    /// The strategy to select the notes that fund a shielded transfer
+    pub note_selection: NoteSelection,
 }
 
 /// Builder functions for Tx
@@ -2042,6 +2571,13 @@ pub trait TxBuilder<C: NamadaTypes>: Sized {
             ..x
         })
     }
+    /// The strategy to select the notes that fund a shielded transfer
+    fn note_selection(self, note_selection: NoteSelection) -> Self {
+        self.tx(|x| Tx {
+            note_selection,
+            ..x
+        })
+    }
     /// The max amount of gas used to process tx
     fn gas_limit(self, gas_limit: GasLimit) -> Self {
         self.tx(|x| Tx { gas_limit, ..x })
@@ -2122,6 +2658,11 @@ pub struct KeyGen {
     pub prompt_bip39_passphrase: bool,
     /// Allow non-compliant derivation path
     pub allow_non_compliant: bool,
+    /// Birthday height of a shielded key. Defaults to the last committed
+    /// block height of the node at `ledger_address`.
+    pub birthday: Option<BlockHeight>,
+    /// The address of the node queried for the default birthday height
+    pub ledger_address: tendermint_rpc::Url,
 }
 
 /// Wallet restore key and implicit address arguments
@@ -2145,6 +2686,8 @@ pub struct KeyDerive {
     pub prompt_bip39_passphrase: bool,
     /// Use device to generate key and address
     pub use_device: bool,
+    /// Birthday height of a shielded key
+    pub birthday: Option<BlockHeight>,
 }
 
 /// Wallet list arguments
@@ -2177,6 +2720,8 @@ pub struct KeyAddressFind {
     pub public_key_hash: Option<String>,
     /// Payment address to find
     pub payment_address: Option<PaymentAddress>,
+    /// Query to search the address book with
+    pub search: Option<String>,
     /// Find keys only
     pub keys_only: bool,
     /// Find addresses only
@@ -2211,6 +2756,8 @@ pub struct KeyImport {
     pub alias_force: bool,
     /// Don't encrypt the key
     pub unsafe_dont_encrypt: bool,
+    /// Birthday height of a shielded key
+    pub birthday: Option<BlockHeight>,
 }
 
 /// Wallet key / address add arguments
@@ -2224,6 +2771,63 @@ pub struct KeyAddressAdd {
     pub value: String,
     /// Don't encrypt the key
     pub unsafe_dont_encrypt: bool,
+    /// Birthday height of a shielded key
+    pub birthday: Option<BlockHeight>,
+}
+
+/// Wallet address book contact arguments
+#[derive(Clone, Debug)]
+pub struct ContactSet {
+    /// Alias of the address or payment address
+    pub alias: String,
+    /// Notes about the contact
+    pub note: Option<String>,
+    /// Tags of the contact
+    pub tags: Vec<String>,
+    /// Alias or address of the token sent to the contact by default
+    pub default_token: Option<String>,
+    /// Memo attached to transfers to the contact by default
+    pub default_memo: Option<String>,
+    /// Shielded payment address of the contact
+    pub payment_address: Option<PaymentAddress>,
+}
+
+/// Wallet address book export arguments
+#[derive(Clone, Debug)]
+pub struct AddressBookExport {
+    /// File format
+    pub format: AddressBookFormat,
+    /// Output file path, the address book is printed if not given
+    pub output: Option<PathBuf>,
+}
+
+/// Wallet address book import arguments
+#[derive(Clone, Debug)]
+pub struct AddressBookImport {
+    /// File name
+    pub file_path: String,
+    /// File format
+    pub format: AddressBookFormat,
+    /// Whether to force overwrite the aliases
+    pub alias_force: bool,
+}
+
+/// Wallet agent arguments
+#[derive(Clone, Debug)]
+pub struct AgentRun {
+    /// The socket to listen on, defaults to one in the base directory
+    pub socket: Option<PathBuf>,
+    /// For how long keys stay unlocked by default
+    pub lifetime: Duration,
+}
+
+/// Wallet agent key unlocking arguments
+#[derive(Clone, Debug)]
+pub struct AgentUnlock {
+    /// Aliases of the keys to unlock
+    pub aliases: Vec<String>,
+    /// For how long the keys stay unlocked, defaults to the agent's lifetime
+    pub lifetime: Option<Duration>,
 }
 
 /// Wallet key / address remove arguments
@@ -2246,6 +2850,14 @@ pub struct PayAddressGen<C: NamadaTypes = SdkTypes> {
     pub viewing_key: C::ViewingKey,
     /// Pin
     pub pin: bool,
+    /// Amount to request a payment of
+    pub amount: Option<token::DenominatedAmount>,
+    /// Alias or address of the token to request a payment in
+    pub token: Option<String>,
+    /// Memo to request the payment with
+    pub memo: Option<String>,
+    /// Time after which the payment request expires
+    pub expiry: Option<DateTimeUtc>,
 }
 
 /// Bridge pool batch recommendation.
@@ -6,6 +6,7 @@ use namada_core::ethereum_events::EthAddress;
 use namada_core::event::EventError;
 use namada_core::storage;
 use namada_core::storage::Epoch;
+use namada_core::time::DateTimeUtc;
 use namada_tx::Tx;
 use prost::EncodeError;
 use tendermint_rpc::Error as RpcError;
@@ -126,6 +127,18 @@ pub enum TxSubmitError {
     /// Invalid commission rate set
     #[error("Invalid new commission rate, received {0}")]
     InvalidCommissionRate(Dec),
+    /// Commission rate change before the pipeline epoch
+    #[error(
+        "The commission rate change cannot take effect in epoch {0} before \
+         the pipeline epoch {1}."
+    )]
+    CommissionRateChangeBeforePipeline(Epoch, Epoch),
+    /// Commission rate change scheduled too far in the future
+    #[error(
+        "The commission rate change cannot take effect in epoch {0} after \
+         the last epoch {1} in which a change can be scheduled."
+    )]
+    CommissionRateChangeAfterScheduleHorizon(Epoch, Epoch),
     /// Invalid validator address
     #[error("The address {0} doesn't belong to any known validator account.")]
     InvalidValidatorAddress(Address),
@@ -174,6 +187,12 @@ pub enum TxSubmitError {
     /// Unond amount is zero
     #[error("The requested unbond amount is 0.")]
     UnbondIsZero,
+    /// Redeemed shares amount is zero
+    #[error("The requested amount of shares to redeem is 0.")]
+    RedeemSharesIsZero,
+    /// The restake interval is zero
+    #[error("The requested restake interval is 0.")]
+    RestakeIntervalIsZero,
     /// No unbonded bonds ready to withdraw in the current epoch
     #[error(
         "There are no unbonded bonds ready to withdraw in the current epoch \
@@ -228,6 +247,15 @@ pub enum TxSubmitError {
     /// No Balance found for token
     #[error("{0}")]
     MaspError(String),
+    /// The payment request has expired
+    #[error("The payment request expired at {0}")]
+    PaymentRequestExpired(DateTimeUtc),
+    /// The transfer doesn't match the payment request
+    #[error("The {0} of the transfer doesn't match the payment request")]
+    PaymentRequestMismatch(&'static str),
+    /// Not enough notes to consolidate
+    #[error("Fewer than two notes of token {0} can be consolidated")]
+    NoNotesToConsolidate(Address),
     /// Error in the fee unshielding transaction
     #[error("Error in fee unshielding: {0}")]
     FeeUnshieldingError(String),
@@ -8,8 +8,10 @@
 
 use std::collections::HashMap;
 
+use namada_core::address::Address;
 use namada_core::hash::Hash;
 use namada_core::storage::BlockHeight;
+use namada_proof_of_stake::types::CommissionRateChangeEvent;
 
 use crate::events::{Event, EventType};
 use crate::ibc::core::client::types::Height as IbcHeight;
@@ -61,6 +63,20 @@ impl QueryMatcher {
         }
     }
 
+    /// Returns a query matching the commission rate changes of the given
+    /// validator.
+    pub fn commission_change(validator: &Address) -> Self {
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            CommissionRateChangeEvent::VALIDATOR_ATTR.to_string(),
+            validator.to_string(),
+        );
+        Self {
+            event_type: EventType::CommissionChange,
+            attributes,
+        }
+    }
+
     /// Returns a query matching the given IBC UpdateClient parameters
     pub fn ibc_update_client(
         client_id: ClientId,
@@ -41,13 +41,16 @@ use namada_core::dec::Dec;
 use namada_core::ethereum_events::EthAddress;
 use namada_core::ibc::core::host::types::identifiers::{ChannelId, PortId};
 use namada_core::key::*;
-use namada_core::masp::{TransferSource, TransferTarget};
+use namada_core::masp::{ExtendedSpendingKey, TransferSource, TransferTarget};
 use namada_tx::data::wrapper::GasLimit;
 use namada_tx::Tx;
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use crate::io::Io;
-use crate::masp::{ShieldedContext, ShieldedUtils};
+use crate::masp::{
+    NoteSelection, ShieldedContext, ShieldedUtils,
+    DEFAULT_MAX_CONSOLIDATED_NOTES,
+};
 use crate::rpc::{
     denominate_amount, format_denominated_amount, query_native_token,
 };
@@ -58,11 +61,12 @@ use crate::tx::{
     TX_BRIDGE_POOL_WASM, TX_CHANGE_COMMISSION_WASM,
     TX_CHANGE_CONSENSUS_KEY_WASM, TX_CHANGE_METADATA_WASM,
     TX_CLAIM_REWARDS_WASM, TX_DEACTIVATE_VALIDATOR_WASM, TX_IBC_WASM,
-    TX_INIT_ACCOUNT_WASM, TX_INIT_PROPOSAL, TX_REACTIVATE_VALIDATOR_WASM,
-    TX_REDELEGATE_WASM, TX_RESIGN_STEWARD, TX_REVEAL_PK, TX_TRANSFER_WASM,
+    TX_INIT_ACCOUNT_WASM, TX_INIT_PROPOSAL, TX_LIQUID_BOND_WASM,
+    TX_REACTIVATE_VALIDATOR_WASM, TX_REDEEM_SHARES_WASM, TX_REDELEGATE_WASM,
+    TX_RESIGN_STEWARD, TX_REVEAL_PK, TX_SET_RESTAKE_WASM, TX_TRANSFER_WASM,
     TX_UNBOND_WASM, TX_UNJAIL_VALIDATOR_WASM, TX_UPDATE_ACCOUNT_WASM,
-    TX_UPDATE_STEWARD_COMMISSION, TX_VOTE_PROPOSAL, TX_WITHDRAW_WASM,
-    VP_USER_WASM,
+    TX_UPDATE_SPENDING_POLICY_WASM, TX_UPDATE_STEWARD_COMMISSION,
+    TX_VOTE_PROPOSAL, TX_WITHDRAW_WASM, VP_USER_WASM,
 };
 use crate::wallet::{Wallet, WalletIo, WalletStorage};
 
@@ -146,6 +150,7 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             password: None,
             memo: None,
             use_device: false,
+            note_selection: NoteSelection::default(),
         }
     }
 
@@ -162,6 +167,24 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             target,
             token,
             amount,
+            payment_request: None,
+            tx_code_path: PathBuf::from(TX_TRANSFER_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
+    /// Make a TxConsolidateNotes builder from the given minimum set of
+    /// arguments
+    fn new_consolidate_notes(
+        &self,
+        source: ExtendedSpendingKey,
+        token: Address,
+    ) -> args::TxConsolidateNotes {
+        args::TxConsolidateNotes {
+            source,
+            token,
+            max_notes: DEFAULT_MAX_CONSOLIDATED_NOTES,
+            dust_threshold: None,
             tx_code_path: PathBuf::from(TX_TRANSFER_WASM),
             tx: self.tx_builder(),
         }
@@ -205,6 +228,38 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
         }
     }
 
+    /// Make a LiquidBond builder from the given minimum set of arguments
+    fn new_liquid_bond(
+        &self,
+        validator: Address,
+        amount: token::Amount,
+        source: Address,
+    ) -> args::LiquidBond {
+        args::LiquidBond {
+            validator,
+            amount,
+            source,
+            tx: self.tx_builder(),
+            tx_code_path: PathBuf::from(TX_LIQUID_BOND_WASM),
+        }
+    }
+
+    /// Make a RedeemShares builder from the given minimum set of arguments
+    fn new_redeem_shares(
+        &self,
+        validator: Address,
+        shares: token::Amount,
+        owner: Address,
+    ) -> args::RedeemShares {
+        args::RedeemShares {
+            validator,
+            shares,
+            owner,
+            tx: self.tx_builder(),
+            tx_code_path: PathBuf::from(TX_REDEEM_SHARES_WASM),
+        }
+    }
+
     /// Make a Unbond builder from the given minimum set of arguments
     fn new_unbond(
         &self,
@@ -291,6 +346,23 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
         }
     }
 
+    /// Make a TxUpdateSpendingPolicy builder from the given minimum set of
+    /// arguments
+    fn new_update_spending_policy(
+        &self,
+        addr: Address,
+    ) -> args::TxUpdateSpendingPolicy {
+        args::TxUpdateSpendingPolicy {
+            addr,
+            limits: vec![],
+            allowed_recipients: vec![],
+            disallowed_recipients: vec![],
+            allowances: vec![],
+            tx_code_path: PathBuf::from(TX_UPDATE_SPENDING_POLICY_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
     /// Make a VoteProposal builder from the given minimum set of arguments
     fn new_vote_prposal(
         &self,
@@ -318,6 +390,7 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
         args::CommissionRateChange {
             rate,
             validator,
+            effective_epoch: None,
             tx_code_path: PathBuf::from(TX_CHANGE_COMMISSION_WASM),
             tx: self.tx_builder(),
         }
@@ -471,6 +544,18 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
         }
     }
 
+    /// Make a Restake builder from the given minimum set of arguments. The
+    /// restaking is disabled unless an interval is set.
+    fn new_restake(&self, validator: Address) -> args::Restake {
+        args::Restake {
+            validator,
+            source: None,
+            interval: None,
+            tx_code_path: PathBuf::from(TX_SET_RESTAKE_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
     /// Make a Withdraw builder from the given minimum set of arguments
     fn new_add_erc20_transfer(
         &self,
@@ -661,6 +746,7 @@ where
                 password: None,
                 memo: None,
                 use_device: false,
+                note_selection: NoteSelection::default(),
             },
         }
     }
@@ -784,7 +870,8 @@ pub mod testing {
     use namada_tx::data::pgf::UpdateStewardCommission;
     use namada_tx::data::pos::{
         BecomeValidator, Bond, CommissionChange, ConsensusKeyChange,
-        MetaDataChange, Redelegation, Unbond, Withdraw,
+        MetaDataChange, Redelegation, ScheduledCommissionChange, Unbond,
+        Withdraw,
     };
     use namada_tx::data::{DecryptedTx, Fee, TxType, WrapperTx};
     use proptest::prelude::{Just, Strategy};
@@ -806,10 +893,11 @@ pub mod testing {
     use crate::tx::data::pos::tests::{
         arb_become_validator, arb_bond, arb_commission_change,
         arb_consensus_key_change, arb_metadata_change, arb_redelegation,
-        arb_withdraw,
+        arb_scheduled_commission_change, arb_withdraw,
     };
     use crate::tx::{
         Code, Commitment, Header, MaspBuilder, Section, Signature,
+        TX_SCHEDULE_COMMISSION_WASM,
     };
 
     #[derive(Debug, Clone)]
@@ -817,6 +905,7 @@ pub mod testing {
     // To facilitate propagating debugging information
     pub enum TxData {
         CommissionChange(CommissionChange),
+        ScheduledCommissionChange(ScheduledCommissionChange),
         ConsensusKeyChange(ConsensusKeyChange),
         MetaDataChange(MetaDataChange),
         ClaimRewards(Withdraw),
@@ -1302,6 +1391,22 @@ pub mod testing {
         }
     }
 
+    prop_compose! {
+        // Generate an arbitrary scheduled commission change transaction
+        pub fn arb_scheduled_commission_change_tx()(
+            mut header in arb_header(),
+            wrapper in arb_wrapper_tx(),
+            commission_change in arb_scheduled_commission_change(),
+            code_hash in arb_hash(),
+        ) -> (Tx, TxData) {
+            header.tx_type = TxType::Wrapper(Box::new(wrapper));
+            let mut tx = Tx { header, sections: vec![] };
+            tx.add_data(commission_change.clone());
+            tx.add_code_from_hash(code_hash, Some(TX_SCHEDULE_COMMISSION_WASM.to_owned()));
+            (tx, TxData::ScheduledCommissionChange(commission_change))
+        }
+    }
+
     prop_compose! {
         // Generate an arbitrary commission change transaction
         pub fn arb_metadata_change_tx()(
@@ -1480,6 +1585,7 @@ pub mod testing {
             arb_withdraw_tx(),
             arb_claim_rewards_tx(),
             arb_commission_change_tx(),
+            arb_scheduled_commission_change_tx(),
             arb_metadata_change_tx(),
             arb_unjail_validator_tx(),
             arb_deactivate_validator_tx(),
@@ -5,14 +5,16 @@ use std::collections::{btree_map, BTreeMap, BTreeSet, HashMap, HashSet};
 use std::env;
 use std::fmt::Debug;
 use std::ops::Deref;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use borsh_ext::BorshSerializeExt;
+use futures::StreamExt;
 use itertools::Either;
 use lazy_static::lazy_static;
 use masp_primitives::asset_type::AssetType;
+use masp_primitives::consensus::BranchId;
 #[cfg(feature = "mainnet")]
 use masp_primitives::consensus::MainNetwork;
 #[cfg(not(feature = "mainnet"))]
@@ -34,15 +36,15 @@ use masp_primitives::transaction::builder::{self, *};
 use masp_primitives::transaction::components::sapling::builder::SaplingMetadata;
 use masp_primitives::transaction::components::transparent::builder::TransparentBuilder;
 use masp_primitives::transaction::components::{
-    ConvertDescription, I128Sum, OutputDescription, SpendDescription, TxOut,
-    U64Sum, ValueSum,
+    sapling, transparent, ConvertDescription, I128Sum, OutputDescription,
+    SpendDescription, TxOut, U64Sum, ValueSum, GROTH_PROOF_SIZE,
 };
 use masp_primitives::transaction::fees::fixed::FeeRule;
 use masp_primitives::transaction::sighash::{signature_hash, SignableInput};
 use masp_primitives::transaction::txid::TxIdDigester;
 use masp_primitives::transaction::{
     Authorization, Authorized, Transaction, TransactionData,
-    TransparentAddress, Unauthorized,
+    TransparentAddress, TxVersion, Unauthorized,
 };
 use masp_primitives::zip32::{ExtendedFullViewingKey, ExtendedSpendingKey};
 use masp_proofs::bellman::groth16::PreparedVerifyingKey;
@@ -60,11 +62,14 @@ use namada_core::storage::{BlockHeight, Epoch, IndexedTx, TxIndex};
 use namada_core::time::{DateTimeUtc, DurationSecs};
 use namada_core::uint::Uint;
 use namada_ibc::IbcMessage;
-use namada_token::{self as token, Denomination, MaspDigitPos, Transfer};
+use namada_token::{
+    self as token, DenominatedAmount, Denomination, MaspDigitPos, Transfer,
+};
 use namada_tx::data::{TxResult, WrapperTx};
 use namada_tx::Tx;
 use rand_core::{CryptoRng, OsRng, RngCore};
 use ripemd::Digest as RipemdDigest;
+use serde::{Deserialize, Serialize};
 use sha2::Digest;
 use thiserror::Error;
 use token::storage_key::{balance_key, is_any_shielded_action_balance_key};
@@ -79,6 +84,7 @@ use crate::rpc::{
 };
 use crate::tendermint_rpc::query::Query;
 use crate::tendermint_rpc::Order;
+use crate::wallet::address_book::escape_csv_field;
 use crate::{display_line, edisplay_line, rpc, MaybeSend, MaybeSync, Namada};
 
 /// Env var to point to a dir with MASP parameters. When not specified,
@@ -102,6 +108,17 @@ pub const SPEND_NAME: &str = "masp-spend.params";
 pub const OUTPUT_NAME: &str = "masp-output.params";
 /// Convert circuit name
 pub const CONVERT_NAME: &str = "masp-convert.params";
+/// The MASP parameter files with their expected size in bytes. Their hashes
+/// are pinned in the `masp_proofs` crate.
+pub const PARAMS_FILES: [(&str, u64); 3] = [
+    (SPEND_NAME, 49848572),
+    (OUTPUT_NAME, 16398620),
+    (CONVERT_NAME, 22570940),
+];
+
+/// The default maximum number of notes that a consolidation merges, which
+/// bounds the size of its proof
+pub const DEFAULT_MAX_CONSOLIDATED_NOTES: usize = 16;
 
 /// Type alias for convenience and profit
 pub type IndexedNoteData = BTreeMap<
@@ -115,6 +132,17 @@ pub type IndexedNoteEntry = (
     (Epoch, BTreeSet<namada_core::storage::Key>, Transaction),
 );
 
+/// The outputs of a shielded tx that a viewing key decrypts, with their index
+/// in the tx
+type DecryptedOutputs = Vec<(usize, Note, Diversifier, MemoBytes)>;
+
+/// The number of blocks whose shielded txs are fetched concurrently
+const FETCH_CONCURRENCY: usize = 16;
+
+/// The maximum number of new note commitments kept by a shielded context. A
+/// context that appended more since it was saved is saved whole.
+const MAX_NEW_COMMITMENTS: usize = 1 << 16;
+
 /// Shielded transfer
 #[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
 pub struct ShieldedTransfer {
@@ -148,6 +176,39 @@ pub enum TransferErr {
     /// errors
     #[error("{0}")]
     General(#[from] Error),
+    /// Missing or corrupt MASP parameters
+    #[error("{0}")]
+    Params(#[from] ParamsError),
+}
+
+/// An error with the MASP parameter files
+#[derive(Error, Debug)]
+pub enum ParamsError {
+    /// A parameter file is missing
+    #[error("The MASP parameter file {} is missing", .0.display())]
+    Missing(PathBuf),
+    /// A parameter file doesn't have the expected size
+    #[error(
+        "The MASP parameter file {} has {actual} bytes instead of {expected}",
+        path.display()
+    )]
+    WrongSize {
+        path: PathBuf,
+        expected: u64,
+        actual: u64,
+    },
+    /// The parameters don't match their pinned hashes
+    #[error(
+        "The MASP parameters in {} are corrupt, install them again: {reason}",
+        dir.display()
+    )]
+    Corrupt { dir: PathBuf, reason: String },
+    /// A parameter file couldn't be read or written
+    #[error(
+        "Failed to access the MASP parameter file {}: {reason}",
+        path.display()
+    )]
+    Io { path: PathBuf, reason: String },
 }
 
 /// MASP verifying keys
@@ -401,6 +462,95 @@ pub fn get_params_dir() -> PathBuf {
     }
 }
 
+/// Check that the MASP parameter files are present in the given directory with
+/// their expected size. This is cheap, unlike the verification of their hashes
+/// by [`verify_params`]. Returns the problems found, if any.
+pub fn check_params_files(params_dir: &Path) -> Vec<ParamsError> {
+    PARAMS_FILES
+        .iter()
+        .filter_map(|(name, expected)| {
+            let path = params_dir.join(name);
+            match std::fs::metadata(&path) {
+                Ok(metadata) if metadata.len() == *expected => None,
+                Ok(metadata) => Some(ParamsError::WrongSize {
+                    path,
+                    expected: *expected,
+                    actual: metadata.len(),
+                }),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                    Some(ParamsError::Missing(path))
+                }
+                Err(err) => Some(ParamsError::Io {
+                    path,
+                    reason: err.to_string(),
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Load the MASP parameters from the given directory with the given
+/// function, which panics if they don't match their pinned hashes. The
+/// function runs on its own thread, whose panic is turned into an error, so
+/// that the process-wide panic hook is left alone.
+fn load_params<T: Send>(
+    params_dir: &Path,
+    load: impl FnOnce() -> T + Send,
+) -> Result<T, ParamsError> {
+    let result = std::thread::scope(|scope| scope.spawn(load).join());
+    result.map_err(|panic| {
+        let reason = panic
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap_or_else(|| "unknown error".to_string());
+        ParamsError::Corrupt {
+            dir: params_dir.to_path_buf(),
+            reason,
+        }
+    })
+}
+
+/// Verify the MASP parameter files in the given directory: they must be
+/// present with their expected size and match their pinned hashes.
+pub fn verify_params(params_dir: &Path) -> Result<(), ParamsError> {
+    if let Some(err) = check_params_files(params_dir).into_iter().next() {
+        return Err(err);
+    }
+    let [spend_path, output_path, convert_path] =
+        [SPEND_NAME, OUTPUT_NAME, CONVERT_NAME].map(|p| params_dir.join(p));
+    load_params(params_dir, || {
+        masp_proofs::load_parameters(&spend_path, &output_path, &convert_path)
+    })?;
+    Ok(())
+}
+
+/// Install the MASP parameter files from the given bundle directory into the
+/// given parameters directory, after verifying them. Each file is replaced
+/// atomically.
+pub fn install_params(
+    bundle_dir: &Path,
+    params_dir: &Path,
+) -> Result<(), ParamsError> {
+    verify_params(bundle_dir)?;
+    let to_error = |path: &Path| {
+        let path = path.to_path_buf();
+        move |err: std::io::Error| ParamsError::Io {
+            path,
+            reason: err.to_string(),
+        }
+    };
+    std::fs::create_dir_all(params_dir).map_err(to_error(params_dir))?;
+    for (name, _) in PARAMS_FILES {
+        let path = params_dir.join(name);
+        let tmp_path = path.with_extension("tmp");
+        std::fs::copy(bundle_dir.join(name), &tmp_path)
+            .map_err(to_error(&tmp_path))?;
+        std::fs::rename(&tmp_path, &path).map_err(to_error(&path))?;
+    }
+    Ok(())
+}
+
 /// Freeze a Builder into the format necessary for inclusion in a Tx. This is
 /// the format used by hardware wallets to validate a MASP Transaction.
 struct WalletMap;
@@ -447,6 +597,13 @@ pub trait ShieldedUtils:
     /// Get a MASP transaction prover
     fn local_tx_prover(&self) -> LocalTxProver;
 
+    /// Get a MASP transaction prover, failing if the MASP parameters that it
+    /// loads are missing or corrupt. The parameters aren't checked by
+    /// default.
+    fn checked_tx_prover(&self) -> Result<LocalTxProver, ParamsError> {
+        Ok(self.local_tx_prover())
+    }
+
     /// Load up the currently saved ShieldedContext
     async fn load<U: ShieldedUtils + MaybeSend>(
         &self,
@@ -461,6 +618,400 @@ pub trait ShieldedUtils:
     ) -> std::io::Result<()>;
 }
 
+/// A source of the MASP txs of the chain, from which shielded contexts are
+/// synced
+#[cfg_attr(feature = "async-send", async_trait::async_trait)]
+#[cfg_attr(not(feature = "async-send"), async_trait::async_trait(?Send))]
+pub trait MaspTxSource {
+    /// Get the address of the native token of the chain
+    async fn native_token(&self) -> Result<Address, Error>;
+
+    /// Get the height of the last block whose MASP txs are available
+    async fn last_height(&self) -> Result<BlockHeight, Error>;
+
+    /// Get the accepted MASP txs of the block at the given height
+    async fn block_txs(
+        &self,
+        height: BlockHeight,
+    ) -> Result<IndexedNoteData, Error>;
+
+    /// Get the note commitment tree as of the end of the block at the given
+    /// height, if the source has it
+    async fn commitment_tree(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Option<CommitmentTree<Node>>, Error>;
+}
+
+/// A [`MaspTxSource`] querying the MASP txs from a node
+#[derive(Debug, Clone, Copy)]
+pub struct LedgerMaspTxSource<'client, C> {
+    client: &'client C,
+}
+
+impl<'client, C: Client + Sync> LedgerMaspTxSource<'client, C> {
+    /// Create a source of the MASP txs of the node behind the given client
+    pub fn new(client: &'client C) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg_attr(feature = "async-send", async_trait::async_trait)]
+#[cfg_attr(not(feature = "async-send"), async_trait::async_trait(?Send))]
+impl<'client, C: Client + Sync> MaspTxSource
+    for LedgerMaspTxSource<'client, C>
+{
+    async fn native_token(&self) -> Result<Address, Error> {
+        query_native_token(self.client).await
+    }
+
+    async fn last_height(&self) -> Result<BlockHeight, Error> {
+        Ok(query_block(self.client)
+            .await?
+            .map_or_else(BlockHeight::first, |block| block.height))
+    }
+
+    async fn block_txs(
+        &self,
+        height: BlockHeight,
+    ) -> Result<IndexedNoteData, Error> {
+        let client = self.client;
+        let mut shielded_txs = BTreeMap::new();
+        // Get the valid masp transactions at the specified height
+        let epoch =
+            query_epoch_at_height(client, height)
+                .await?
+                .ok_or_else(|| {
+                    Error::from(QueryError::General(
+                        "Queried height is greater than the last committed \
+                         block height"
+                            .to_string(),
+                    ))
+                })?;
+
+        let txs_results =
+            match get_indexed_masp_events_at_height(client, height, None)
+                .await?
+            {
+                Some(events) => events,
+                None => return Ok(shielded_txs),
+            };
+
+        // Query the actual block to get the txs bytes. If we only need one
+        // tx it might be slightly better to query the /tx endpoint to
+        // reduce the amount of data sent over the network, but this is a
+        // minimal improvement and it's even hard to tell how many times
+        // we'd need a single masp tx to make this worth it
+        let block = client
+            .block(height.0 as u32)
+            .await
+            .map_err(|e| Error::from(QueryError::General(e.to_string())))?
+            .block
+            .data;
+
+        for (idx, tx_event) in txs_results {
+            let tx = Tx::try_from(block[idx.0 as usize].as_ref())
+                .map_err(|e| Error::Other(e.to_string()))?;
+            let (changed_keys, masp_transaction) = extract_masp_tx(
+                &tx,
+                ExtractShieldedActionArg::Event::<C>(&tx_event),
+                true,
+            )
+            .await?;
+
+            // Collect the current transaction
+            shielded_txs.insert(
+                IndexedTx { height, index: idx },
+                (epoch, changed_keys, masp_transaction),
+            );
+        }
+
+        Ok(shielded_txs)
+    }
+
+    async fn commitment_tree(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Option<CommitmentTree<Node>>, Error> {
+        let (tree, _proof) = rpc::query_storage_value_bytes(
+            self.client,
+            &token::storage_key::masp_commitment_tree_key(),
+            Some(height),
+            false,
+        )
+        .await?;
+        tree.map(|tree| CommitmentTree::try_from_slice(&tree))
+            .transpose()
+            .map_err(|e| Error::Other(e.to_string()))
+    }
+}
+
+/// A shielded output in the compact form of a MASP tx
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CompactOutput {
+    /// The note commitment
+    pub cmu: [u8; 32],
+    /// The ephemeral public key used to encrypt the note
+    pub ephemeral_key: [u8; 32],
+    /// The encrypted note, along with its memo
+    pub enc_ciphertext: Vec<u8>,
+}
+
+/// The compact form of a MASP tx, made of the data needed to scan it: the
+/// note commitments and ciphertexts of its outputs, the nullifiers of its
+/// spends and its transparent bundle, along with the storage keys changed by
+/// the tx that are needed to track the transfers. The value commitments, the
+/// proofs and the signatures are left out.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct CompactTx {
+    /// The epoch of the block of the tx
+    pub epoch: Epoch,
+    /// The storage keys changed by the tx
+    pub changed_keys: BTreeSet<namada_core::storage::Key>,
+    /// The transparent inputs and outputs of the tx
+    pub transparent_bundle:
+        Option<transparent::Bundle<transparent::Authorized>>,
+    /// The nullifiers of the notes spent by the tx
+    pub nullifiers: Vec<Nullifier>,
+    /// The shielded outputs of the tx
+    pub outputs: Vec<CompactOutput>,
+}
+
+impl CompactTx {
+    /// Make the compact form of the given MASP tx
+    pub fn new(
+        epoch: Epoch,
+        changed_keys: BTreeSet<namada_core::storage::Key>,
+        shielded: &Transaction,
+    ) -> Self {
+        let bundle = shielded.sapling_bundle();
+        Self {
+            epoch,
+            changed_keys,
+            transparent_bundle: shielded.transparent_bundle().cloned(),
+            nullifiers: bundle
+                .map_or(&vec![], |x| &x.shielded_spends)
+                .iter()
+                .map(|ss| ss.nullifier)
+                .collect(),
+            outputs: bundle
+                .map_or(&vec![], |x| &x.shielded_outputs)
+                .iter()
+                .map(|so| CompactOutput {
+                    cmu: so.cmu.to_repr(),
+                    ephemeral_key: so.ephemeral_key.0,
+                    enc_ciphertext: so.enc_ciphertext.to_vec(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Expand the compact form into a MASP tx that can be scanned. What is
+    /// left out of the compact form is filled with placeholders, so the
+    /// expanded tx doesn't pass verification.
+    pub fn expand(&self) -> Result<Transaction, Error> {
+        let invalid = || Error::Other("Invalid compact MASP tx".to_string());
+        let placeholder_sig = || {
+            masp_primitives::sapling::redjubjub::Signature::read(&[0; 64][..])
+                .map_err(|e| Error::Other(e.to_string()))
+        };
+        let shielded_spends = self
+            .nullifiers
+            .iter()
+            .map(|nullifier| {
+                Ok(SpendDescription {
+                    cv: masp_primitives::jubjub::ExtendedPoint::identity(),
+                    anchor: Option::from(PrimeField::from_repr([0; 32]))
+                        .ok_or_else(invalid)?,
+                    nullifier: *nullifier,
+                    rk: PublicKey(
+                        masp_primitives::jubjub::ExtendedPoint::identity(),
+                    ),
+                    zkproof: [0; GROTH_PROOF_SIZE],
+                    spend_auth_sig: placeholder_sig()?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let shielded_outputs = self
+            .outputs
+            .iter()
+            .map(|output| {
+                Ok(OutputDescription {
+                    cv: masp_primitives::jubjub::ExtendedPoint::identity(),
+                    cmu: Option::from(PrimeField::from_repr(output.cmu))
+                        .ok_or_else(invalid)?,
+                    ephemeral_key: output.ephemeral_key.into(),
+                    enc_ciphertext: output
+                        .enc_ciphertext
+                        .as_slice()
+                        .try_into()
+                        .map_err(|_| invalid())?,
+                    out_ciphertext: [0; 80],
+                    zkproof: [0; GROTH_PROOF_SIZE],
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let sapling_bundle =
+            if shielded_spends.is_empty() && shielded_outputs.is_empty() {
+                None
+            } else {
+                Some(sapling::Bundle {
+                    shielded_spends,
+                    shielded_converts: vec![],
+                    shielded_outputs,
+                    value_balance: ValueSum::zero(),
+                    authorization: sapling::Authorized {
+                        binding_sig: placeholder_sig()?,
+                    },
+                })
+            };
+        TransactionData::from_parts(
+            TxVersion::MASPv5,
+            BranchId::MASP,
+            0,
+            0u32.into(),
+            self.transparent_bundle.clone(),
+            sapling_bundle,
+        )
+        .freeze()
+        .map_err(|e| Error::Other(e.to_string()))
+    }
+}
+
+/// The compact MASP txs of the chain's first blocks, as indexed off-chain. A
+/// lightweight indexer can keep such an index in a file next to a node, from
+/// which many shielded contexts are then synced.
+#[derive(Debug, Clone, Default)]
+pub struct MaspTxIndex {
+    /// The address of the native token of the chain
+    pub native_token: Option<Address>,
+    /// The last indexed block height. All the blocks up to it are indexed.
+    pub last_height: Option<BlockHeight>,
+    /// The compact MASP txs of the indexed blocks
+    pub txs: BTreeMap<IndexedTx, CompactTx>,
+    /// The last block height stored in the index file, if any. Only the
+    /// blocks after it are appended to the file.
+    stored_height: Option<BlockHeight>,
+}
+
+impl MaspTxIndex {
+    /// Index the MASP txs of the blocks following the last indexed one, up to
+    /// the given height or the last height of the source if not given
+    pub async fn index<S: MaspTxSource + Sync, IO: Io>(
+        &mut self,
+        source: &S,
+        logger: &impl ProgressLogger<IO>,
+        last_query_height: Option<BlockHeight>,
+    ) -> Result<(), Error> {
+        if self.native_token.is_none() {
+            self.native_token = Some(source.native_token().await?);
+        }
+        let last_query_height = match last_query_height {
+            Some(height) => height,
+            None => source.last_height().await?,
+        };
+        let first_height_to_index = self
+            .last_height
+            .map_or_else(BlockHeight::first, |height| height.next_height());
+        let heights =
+            logger.fetch(first_height_to_index.0..=last_query_height.0);
+        let mut blocks = futures::stream::iter(heights)
+            .map(|height| fetch_block_txs(source, height))
+            .buffered(FETCH_CONCURRENCY);
+        while let Some(block) = blocks.next().await {
+            let (height, txs) = block?;
+            self.txs.extend(txs.into_iter().map(
+                |(indexed_tx, (epoch, changed_keys, shielded))| {
+                    (indexed_tx, CompactTx::new(epoch, changed_keys, &shielded))
+                },
+            ));
+            self.last_height = Some(height);
+        }
+        Ok(())
+    }
+}
+
+#[cfg_attr(feature = "async-send", async_trait::async_trait)]
+#[cfg_attr(not(feature = "async-send"), async_trait::async_trait(?Send))]
+impl MaspTxSource for MaspTxIndex {
+    async fn native_token(&self) -> Result<Address, Error> {
+        self.native_token.clone().ok_or_else(|| {
+            Error::Other("The MASP txs index is empty".to_string())
+        })
+    }
+
+    async fn last_height(&self) -> Result<BlockHeight, Error> {
+        self.last_height.ok_or_else(|| {
+            Error::Other("The MASP txs index is empty".to_string())
+        })
+    }
+
+    async fn block_txs(
+        &self,
+        height: BlockHeight,
+    ) -> Result<IndexedNoteData, Error> {
+        if Some(height) > self.last_height {
+            return Err(Error::Other(format!(
+                "The block at height {height} isn't in the MASP txs index"
+            )));
+        }
+        let first = IndexedTx {
+            height,
+            index: TxIndex(0),
+        };
+        self.txs
+            .range(first..)
+            .take_while(|(indexed_tx, _)| indexed_tx.height == height)
+            .map(|(indexed_tx, tx)| {
+                Ok((
+                    *indexed_tx,
+                    (tx.epoch, tx.changed_keys.clone(), tx.expand()?),
+                ))
+            })
+            .collect()
+    }
+
+    async fn commitment_tree(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Option<CommitmentTree<Node>>, Error> {
+        if Some(height) > self.last_height {
+            return Ok(None);
+        }
+        // Build the tree from all the notes indexed up to the height
+        let mut tree = CommitmentTree::empty();
+        let end = IndexedTx {
+            height: height.next_height(),
+            index: TxIndex(0),
+        };
+        for output in self.txs.range(..end).flat_map(|(_, tx)| &tx.outputs) {
+            tree.append(Node::new(output.cmu)).map_err(|()| {
+                Error::Other("note commitment tree is full".to_string())
+            })?;
+        }
+        Ok(Some(tree))
+    }
+}
+
+/// Get the MASP txs of the block at the given height from a source, with the
+/// height
+async fn fetch_block_txs<S: MaspTxSource>(
+    source: &S,
+    height: u64,
+) -> Result<(BlockHeight, IndexedNoteData), Error> {
+    let height = BlockHeight(height);
+    Ok((height, source.block_txs(height).await?))
+}
+
+/// The last tx of the blocks preceding the given height, up to which a
+/// viewing key born at that height is considered scanned
+fn last_tx_before(height: BlockHeight) -> Option<IndexedTx> {
+    (height > BlockHeight::first()).then(|| IndexedTx {
+        height: height.prev_height(),
+        index: TxIndex(u32::MAX),
+    })
+}
+
 /// Make a ViewingKey that can view notes encrypted by given ExtendedSpendingKey
 pub fn to_viewing_key(esk: &ExtendedSpendingKey) -> FullViewingKey {
     ExtendedFullViewingKey::from(esk).fvk
@@ -527,10 +1078,13 @@ pub type TransactionDelta = HashMap<ViewingKey, I128Sum>;
 /// A cache of fetched indexed transactions.
 ///
 /// The cache is designed so that it either contains
-/// all transactions from a given height, or none.
+/// all transactions from a given height, or none. The range of heights whose
+/// transactions are all cached serves as the checkpoint from which an
+/// interrupted fetch resumes.
 #[derive(BorshSerialize, BorshDeserialize, Debug, Default, Clone)]
 pub struct Unscanned {
     txs: IndexedNoteData,
+    fetched: Option<(BlockHeight, BlockHeight)>,
 }
 
 impl Unscanned {
@@ -541,8 +1095,31 @@ impl Unscanned {
         self.txs.extend(items);
     }
 
-    fn contains_height(&self, height: u64) -> bool {
-        self.txs.keys().any(|k| k.height.0 == height)
+    /// Record that all the transactions of the given height are cached. The
+    /// heights must be fetched in order.
+    fn fetched(&mut self, height: BlockHeight) {
+        self.fetched = match self.fetched {
+            Some((from, to)) if to.next_height() == height => {
+                Some((from, height))
+            }
+            _ => Some((height, height)),
+        };
+    }
+
+    /// Get the height from which to fetch the transactions needed from the
+    /// given height onwards. If the cache doesn't cover the given height, it
+    /// is dropped and fetching starts over from the given height.
+    fn resume_height(&mut self, start: BlockHeight) -> BlockHeight {
+        match self.fetched {
+            Some((from, to)) if from <= start && start <= to.next_height() => {
+                to.next_height()
+            }
+            _ => {
+                self.txs.clear();
+                self.fetched = None;
+                start
+            }
+        }
     }
 
     /// We remove all indices from blocks that have been entirely scanned.
@@ -550,9 +1127,71 @@ impl Unscanned {
     /// cache.
     fn scanned(&mut self, ix: &IndexedTx) {
         self.txs.retain(|i, _| i.height >= ix.height);
+        if let Some((from, _)) = self.fetched.as_mut() {
+            *from = std::cmp::max(*from, ix.height);
+        }
     }
 }
 
+/// Trial-decrypt the outputs of the given txs with the viewing keys that
+/// haven't scanned them yet. Only the outputs that decrypt are returned.
+fn trial_decrypt(
+    txs: &IndexedNoteData,
+    vk_heights: &BTreeMap<ViewingKey, Option<IndexedTx>>,
+) -> BTreeMap<IndexedTx, BTreeMap<ViewingKey, DecryptedOutputs>> {
+    let ivks: Vec<_> = vk_heights
+        .iter()
+        .map(|(vk, height)| {
+            (vk, *height, PreparedIncomingViewingKey::new(&vk.ivk()))
+        })
+        .collect();
+    let decrypt_tx = |(indexed_tx, (_, _, shielded)): (
+        &IndexedTx,
+        &(Epoch, BTreeSet<namada_core::storage::Key>, Transaction),
+    )| {
+        let decrypted: BTreeMap<_, _> = ivks
+            .iter()
+            .filter(|(_, height, _)| *height < Some(*indexed_tx))
+            .filter_map(|(vk, _, ivk)| {
+                let outputs = decrypt_outputs(shielded, ivk);
+                (!outputs.is_empty()).then_some((**vk, outputs))
+            })
+            .collect();
+        (!decrypted.is_empty()).then_some((*indexed_tx, decrypted))
+    };
+    #[cfg(feature = "multicore")]
+    {
+        use rayon::prelude::*;
+        txs.par_iter().filter_map(decrypt_tx).collect()
+    }
+    #[cfg(not(feature = "multicore"))]
+    {
+        txs.iter().filter_map(decrypt_tx).collect()
+    }
+}
+
+/// Trial-decrypt the outputs of a shielded tx with an incoming viewing key
+fn decrypt_outputs(
+    shielded: &Transaction,
+    ivk: &PreparedIncomingViewingKey,
+) -> DecryptedOutputs {
+    shielded
+        .sapling_bundle()
+        .map_or(&vec![], |x| &x.shielded_outputs)
+        .iter()
+        .enumerate()
+        .filter_map(|(output_idx, so)| {
+            try_sapling_note_decryption::<_, OutputDescription<<<Authorized as Authorization>::SaplingAuth as masp_primitives::transaction::components::sapling::Authorization>::Proof>>(
+                &NETWORK,
+                1.into(),
+                ivk,
+                so,
+            )
+            .map(|(note, pa, memo)| (output_idx, note, *pa.diversifier(), memo))
+        })
+        .collect()
+}
+
 impl IntoIterator for Unscanned {
     type IntoIter = <IndexedNoteData as IntoIterator>::IntoIter;
     type Item = IndexedNoteEntry;
@@ -562,6 +1201,159 @@ impl IntoIterator for Unscanned {
     }
 }
 
+/// The direction of the flow of a token in the shielded history of a viewing
+/// key
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferDirection {
+    /// The balance of the token increased
+    Incoming,
+    /// The balance of the token decreased
+    Outgoing,
+}
+
+impl std::fmt::Display for TransferDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Incoming => write!(f, "incoming"),
+            Self::Outgoing => write!(f, "outgoing"),
+        }
+    }
+}
+
+/// An entry of the shielded history of a viewing key, i.e. the change that a
+/// tx made to its balance of a token
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShieldedHistoryEntry {
+    /// The height of the block of the tx
+    pub height: BlockHeight,
+    /// The index of the tx in its block
+    pub index: TxIndex,
+    /// The epoch of the tx
+    pub epoch: Epoch,
+    /// The hash of the tx
+    pub tx_hash: String,
+    /// The token whose balance changed
+    pub token: Address,
+    /// The absolute change of the balance, after realizing the rewards of
+    /// the spent notes
+    pub amount: DenominatedAmount,
+    /// Whether the balance increased or decreased
+    pub direction: TransferDirection,
+    /// The conversion rewards realized by the tx in this token
+    pub rewards: DenominatedAmount,
+    /// The text of the memos of the notes that the viewing key received in
+    /// the tx, if any
+    pub memo: Option<String>,
+    /// The transparent accounts whose balances the tx changed
+    pub counterparties: Vec<Address>,
+}
+
+/// The file formats of the shielded history export
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ShieldedHistoryFormat {
+    /// A JSON array of entries
+    Json,
+    /// Comma-separated values with a header row. Counterparties are
+    /// separated by semicolons.
+    Csv,
+}
+
+impl std::fmt::Display for ShieldedHistoryFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::Csv => write!(f, "csv"),
+        }
+    }
+}
+
+impl FromStr for ShieldedHistoryFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(Error::Other(format!(
+                "Unknown shielded history format {s}, expected json or csv"
+            ))),
+        }
+    }
+}
+
+/// The columns of the CSV shielded history, in order
+const HISTORY_CSV_HEADER: [&str; 10] = [
+    "height",
+    "index",
+    "epoch",
+    "tx_hash",
+    "token",
+    "amount",
+    "direction",
+    "rewards",
+    "memo",
+    "counterparties",
+];
+
+/// Encode the shielded history entries in the given format
+pub fn encode_shielded_history(
+    entries: &[ShieldedHistoryEntry],
+    format: ShieldedHistoryFormat,
+) -> Result<String, Error> {
+    match format {
+        ShieldedHistoryFormat::Json => serde_json::to_string_pretty(entries)
+            .map_err(|e| Error::Other(e.to_string())),
+        ShieldedHistoryFormat::Csv => {
+            let mut out = HISTORY_CSV_HEADER.join(",");
+            out.push('\n');
+            for entry in entries {
+                let fields = [
+                    entry.height.to_string(),
+                    entry.index.0.to_string(),
+                    entry.epoch.to_string(),
+                    entry.tx_hash.clone(),
+                    entry.token.encode(),
+                    entry.amount.to_string(),
+                    entry.direction.to_string(),
+                    entry.rewards.to_string(),
+                    entry.memo.clone().unwrap_or_default(),
+                    entry
+                        .counterparties
+                        .iter()
+                        .map(Address::encode)
+                        .collect::<Vec<_>>()
+                        .join(";"),
+                ];
+                let row = fields
+                    .iter()
+                    .map(|field| escape_csv_field(field))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                out.push_str(&row);
+                out.push('\n');
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// Get the text of a memo, or `None` if it is empty or not a text memo as
+/// specified by ZIP 302
+fn memo_text(memo: &MemoBytes) -> Option<String> {
+    let bytes = memo.as_slice();
+    // Text memos start with a byte of at most 0xF4, whereas 0xF6 marks the
+    // empty memo and the other values are reserved for arbitrary data
+    if bytes.first().map_or(true, |first| *first > 0xF4) {
+        return None;
+    }
+    let len = bytes.iter().rposition(|b| *b != 0).map_or(0, |pos| pos + 1);
+    std::str::from_utf8(&bytes[..len])
+        .ok()
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 /// The possible sync states of the shielded context
 pub enum ContextSyncStatus {
@@ -572,6 +1364,54 @@ pub enum ContextSyncStatus {
     Speculative,
 }
 
+/// The strategies to select the notes that fund a shielded transfer
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum NoteSelection {
+    /// Spend the oldest notes first
+    #[default]
+    OldestFirst,
+    /// Spend the largest notes first, to use as few notes as possible
+    FewestNotes,
+    /// Spend the smallest notes first, to merge dust notes
+    SmallestFirst,
+    /// Spend the notes in a random order, so that the selection doesn't
+    /// reveal how old the notes are
+    Random,
+    /// Spend the notes that don't need to be converted to the current epoch
+    /// first
+    AvoidConversions,
+}
+
+impl std::fmt::Display for NoteSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OldestFirst => write!(f, "oldest-first"),
+            Self::FewestNotes => write!(f, "fewest-notes"),
+            Self::SmallestFirst => write!(f, "smallest-first"),
+            Self::Random => write!(f, "random"),
+            Self::AvoidConversions => write!(f, "avoid-conversions"),
+        }
+    }
+}
+
+impl FromStr for NoteSelection {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "oldest-first" => Ok(Self::OldestFirst),
+            "fewest-notes" => Ok(Self::FewestNotes),
+            "smallest-first" => Ok(Self::SmallestFirst),
+            "random" => Ok(Self::Random),
+            "avoid-conversions" => Ok(Self::AvoidConversions),
+            _ => Err(Error::Other(format!(
+                "Unknown note selection {s}, expected oldest-first, \
+                 fewest-notes, smallest-first, random or avoid-conversions"
+            ))),
+        }
+    }
+}
+
 /// Represents the current state of the shielded pool from the perspective of
 /// the chosen viewing keys.
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
@@ -581,9 +1421,17 @@ pub struct ShieldedContext<U: ShieldedUtils> {
     pub utils: U,
     /// The commitment tree produced by scanning all transactions up to tx_pos
     pub tree: CommitmentTree<Node>,
+    /// The last note commitments appended to the commitment tree, from which
+    /// the saved context is updated instead of saving the whole tree and
+    /// witnesses again
+    #[borsh(skip)]
+    pub new_commitments: Vec<Node>,
     /// Maps viewing keys to the block height to which they are synced.
     /// In particular, the height given by the value *has been scanned*.
     pub vk_heights: BTreeMap<ViewingKey, Option<IndexedTx>>,
+    /// Maps viewing keys to their birthday, i.e. the height of the first
+    /// block that may contain their notes
+    pub vk_birthdays: BTreeMap<ViewingKey, BlockHeight>,
     /// Maps viewing keys to applicable note positions
     pub pos_map: HashMap<ViewingKey, BTreeSet<usize>>,
     /// Maps a nullifier to the note position to which it applies
@@ -594,7 +1442,8 @@ pub struct ShieldedContext<U: ShieldedUtils> {
     pub memo_map: HashMap<usize, MemoBytes>,
     /// Maps note positions to the diversifier of their payment address
     pub div_map: HashMap<usize, Diversifier>,
-    /// Maps note positions to their witness (used to make merkle paths)
+    /// Maps the positions of the unspent notes to their witness (used to make
+    /// merkle paths)
     pub witness_map: HashMap<usize, IncrementalWitness<Node>>,
     /// Tracks what each transaction does to various account balances
     pub delta_map:
@@ -607,6 +1456,9 @@ pub struct ShieldedContext<U: ShieldedUtils> {
     pub vk_map: HashMap<usize, ViewingKey>,
     /// Maps a shielded tx to the index of its first output note.
     pub tx_note_map: BTreeMap<IndexedTx, usize>,
+    /// The height up to which the commitment tree was taken from a snapshot
+    /// of the source instead of being built from the scanned txs, if any
+    pub tree_snapshot_height: Option<BlockHeight>,
     /// A cache of fetched indexed txs.
     pub unscanned: Unscanned,
     /// The sync state of the context
@@ -620,8 +1472,11 @@ impl<U: ShieldedUtils + Default> Default for ShieldedContext<U> {
         ShieldedContext::<U> {
             utils: U::default(),
             vk_heights: BTreeMap::new(),
+            vk_birthdays: BTreeMap::new(),
             tx_note_map: BTreeMap::default(),
+            tree_snapshot_height: None,
             tree: CommitmentTree::empty(),
+            new_commitments: Vec::default(),
             pos_map: HashMap::default(),
             nf_map: HashMap::default(),
             note_map: HashMap::default(),
@@ -661,18 +1516,97 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         self.utils.save(self).await
     }
 
+    /// Merge the viewing keys and notes of another shielded context into this
+    /// one. Both contexts must have built their commitment trees up to the
+    /// same point, so that the witnesses of their notes agree, unless this
+    /// context has no viewing keys yet, in which case it takes the whole state
+    /// of the other. Otherwise the cache of fetched txs of the other context
+    /// is dropped.
+    pub fn merge(&mut self, other: ShieldedContext<U>) -> Result<(), Error> {
+        if self.vk_heights.is_empty() {
+            *self = ShieldedContext {
+                utils: self.utils.clone(),
+                ..other
+            };
+            return Ok(());
+        }
+        if self.tree.size() != other.tree.size()
+            || self.tree.root() != other.tree.root()
+        {
+            return Err(Error::Other(
+                "The shielded contexts are synced to different heights, sync \
+                 both of them to the same height first"
+                    .to_string(),
+            ));
+        }
+        for (vk, height) in other.vk_heights {
+            let entry = self.vk_heights.entry(vk).or_default();
+            *entry = std::cmp::max(*entry, height);
+        }
+        for (vk, birthday) in other.vk_birthdays {
+            let entry = self.vk_birthdays.entry(vk).or_insert(birthday);
+            *entry = std::cmp::min(*entry, birthday);
+        }
+        for (vk, positions) in other.pos_map {
+            self.pos_map.entry(vk).or_default().extend(positions);
+        }
+        for (indexed_tx, (epoch, transfer_delta, tx_delta)) in other.delta_map {
+            self.delta_map
+                .entry(indexed_tx)
+                .or_insert_with(|| (epoch, transfer_delta, HashMap::new()))
+                .2
+                .extend(tx_delta);
+        }
+        self.nf_map.extend(other.nf_map);
+        self.note_map.extend(other.note_map);
+        self.memo_map.extend(other.memo_map);
+        self.div_map.extend(other.div_map);
+        self.witness_map.extend(other.witness_map);
+        self.spents.extend(other.spents);
+        let spents = &self.spents;
+        self.witness_map
+            .retain(|note_pos, _| !spents.contains(note_pos));
+        self.asset_types.extend(other.asset_types);
+        self.vk_map.extend(other.vk_map);
+        self.tx_note_map.extend(other.tx_note_map);
+        if let ContextSyncStatus::Speculative = other.sync_status {
+            self.sync_status = ContextSyncStatus::Speculative;
+        }
+        Ok(())
+    }
+
+    /// The last tx whose notes have been added to the commitment tree, if any
+    fn last_witnessed_tx(&self) -> Option<IndexedTx> {
+        let last_scanned_tx = self.tx_note_map.keys().max().cloned();
+        let snapshot_tx = self
+            .tree_snapshot_height
+            .and_then(|height| last_tx_before(height.next_height()));
+        std::cmp::max(last_scanned_tx, snapshot_tx)
+    }
+
     /// Update the merkle tree of witnesses the first time we
-    /// scan a new MASP transaction.
+    /// scan a new MASP transaction. Witnesses are only kept for our unspent
+    /// notes, i.e. the notes that are already known or that were decrypted by
+    /// one of our viewing keys, and that haven't been spent.
     fn update_witness_map(
         &mut self,
         indexed_tx: IndexedTx,
         shielded: &Transaction,
+        decrypted: Option<&BTreeMap<ViewingKey, DecryptedOutputs>>,
     ) -> Result<(), Error> {
         let mut note_pos = self.tree.size();
         self.tx_note_map.insert(indexed_tx, note_pos);
-        for so in shielded
+        let decrypted_outputs: BTreeSet<usize> = decrypted
+            .into_iter()
+            .flat_map(BTreeMap::values)
+            .flatten()
+            .map(|(output_idx, ..)| *output_idx)
+            .collect();
+        for (output_idx, so) in shielded
             .sapling_bundle()
             .map_or(&vec![], |x| &x.shielded_outputs)
+            .iter()
+            .enumerate()
         {
             // Create merkle tree leaf node from note commitment
             let node = Node::new(so.cmu.to_repr());
@@ -686,25 +1620,45 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
             self.tree.append(node).map_err(|()| {
                 Error::Other("note commitment tree is full".to_string())
             })?;
+            if self.new_commitments.len() == MAX_NEW_COMMITMENTS {
+                self.new_commitments.clear();
+            }
+            self.new_commitments.push(node);
             // Finally, make it easier to construct merkle paths to this new
-            // note
-            let witness = IncrementalWitness::<Node>::from_tree(&self.tree);
-            self.witness_map.insert(note_pos, witness);
+            // note if it's ours
+            if (self.note_map.contains_key(&note_pos)
+                || decrypted_outputs.contains(&output_idx))
+                && !self.spents.contains(&note_pos)
+            {
+                let witness = IncrementalWitness::<Node>::from_tree(&self.tree);
+                self.witness_map.insert(note_pos, witness);
+            }
             note_pos += 1;
         }
         Ok(())
     }
 
     /// Fetch the current state of the multi-asset shielded pool into a
-    /// ShieldedContext
-    pub async fn fetch<C: Client + Sync, IO: Io>(
+    /// ShieldedContext from the given source of MASP txs.
+    ///
+    /// The MASP txs of consecutive blocks are fetched concurrently, and the
+    /// fetched txs are persisted in the cache every `batch_size` blocks, from
+    /// where an interrupted fetch resumes. The notes of the fetched txs are
+    /// then trial-decrypted with all the viewing keys at once, in parallel
+    /// with the `multicore` feature, before the txs are scanned in order.
+    ///
+    /// The viewing keys may be given with their birthday height, before which
+    /// they aren't scanned. If all the keys have a birthday, the commitment
+    /// tree is started from the source's snapshot of the tree before the
+    /// earliest birthday.
+    pub async fn fetch<S: MaspTxSource + Sync, IO: Io>(
         &mut self,
-        client: &C,
+        source: &S,
         logger: &impl ProgressLogger<IO>,
         last_query_height: Option<BlockHeight>,
-        _batch_size: u64,
+        batch_size: u64,
         sks: &[ExtendedSpendingKey],
-        fvks: &[ViewingKey],
+        fvks: &[(ViewingKey, Option<BlockHeight>)],
     ) -> Result<(), Error> {
         // add new viewing keys
         // Reload the state from file to get the last confirmed state and
@@ -721,58 +1675,90 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
             };
         }
 
-        for esk in sks {
-            let vk = to_viewing_key(esk).vk;
-            self.vk_heights.entry(vk).or_default();
+        let sks = sks.iter().map(|esk| (to_viewing_key(esk).vk, None));
+        for (vk, birthday) in sks.chain(fvks.iter().copied()) {
+            if let btree_map::Entry::Vacant(entry) = self.vk_heights.entry(vk) {
+                // A new key only scans the blocks from its birthday
+                entry.insert(birthday.and_then(last_tx_before));
+                if let Some(birthday) = birthday {
+                    self.vk_birthdays.insert(vk, birthday);
+                }
+            }
         }
-        for vk in fvks {
-            self.vk_heights.entry(*vk).or_default();
+        // Witnesses are only kept for our notes, so the commitment tree must
+        // be rebuilt to witness the notes of new viewing keys
+        let last_witnessed_tx = self.last_witnessed_tx();
+        if self.vk_heights.values().any(|h| *h < last_witnessed_tx) {
+            self.tree = CommitmentTree::empty();
+            self.new_commitments.clear();
+            self.witness_map.clear();
+            self.tx_note_map.clear();
+            self.tree_snapshot_height = None;
         }
         let _ = self.save().await;
-        let native_token = query_native_token(client).await?;
+        let native_token = source.native_token().await?;
         // the latest block height which has been added to the witness Merkle
         // tree
         let Some(least_idx) = self.vk_heights.values().min().cloned() else {
             return Ok(());
         };
-        let last_witnessed_tx = self.tx_note_map.keys().max().cloned();
+        // Start a new commitment tree from a snapshot before the earliest
+        // birthday, if the source has one. Otherwise it's built from genesis.
+        if self.tree.size() == 0 && self.tree_snapshot_height.is_none() {
+            let snapshot_height = self
+                .vk_heights
+                .keys()
+                .map(|vk| self.vk_birthdays.get(vk).copied())
+                .min()
+                .flatten()
+                .filter(|birthday| *birthday > BlockHeight::first())
+                .map(|birthday| birthday.prev_height());
+            if let Some(height) = snapshot_height {
+                if let Ok(Some(tree)) = source.commitment_tree(height).await {
+                    self.tree = tree;
+                    self.tree_snapshot_height = Some(height);
+                }
+            }
+        }
+        let last_witnessed_tx = self.last_witnessed_tx();
         // get the bounds on the block heights to fetch
-        let start_idx =
-            std::cmp::min(last_witnessed_tx, least_idx).map(|ix| ix.height);
-        // Load all transactions accepted until this point
-        // N.B. the cache is a hash map
-        let range = match (last_query_height, start_idx) {
-            (Some(lqh), Some(si)) => (si.0..=lqh.0).collect::<Vec<_>>(),
-            _ => vec![], // or some other default value
-        };
-        let chunk_size = match (last_query_height, start_idx) {
-            (Some(lqh), Some(si)) => ((lqh - si) / 20) as usize,
-            _ => 0, // or some other default value
+        let start_height = std::cmp::min(last_witnessed_tx, least_idx)
+            .map_or_else(BlockHeight::first, |ix| ix.height);
+        let last_query_height = match last_query_height {
+            Some(height) => height,
+            None => source.last_height().await?,
         };
-        let mut handles = vec![];
-        
-        for chunk in range.collect::<Vec<_>>().chunks(chunk_size) {
-            if let (start, end) = (chunk.first().copied(), chunk.last().copied()) {
-                let client = client.clone();
-                let logger = logger.clone();
-                let handle = tokio::task::spawn(async move {
-                    self.fetch_shielded_transfers(client, logger, start, end).await
-                });
-                handles.push(handle);
+        // Resume from the last checkpoint of the cache
+        let first_height_to_fetch = self.unscanned.resume_height(start_height);
+
+        // Load all transactions accepted until this point, fetching the
+        // blocks in a pipeline
+        let batch_size = batch_size.max(1);
+        let heights =
+            logger.fetch(first_height_to_fetch.0..=last_query_height.0);
+        let mut blocks = futures::stream::iter(heights)
+            .map(|height| fetch_block_txs(source, height))
+            .buffered(FETCH_CONCURRENCY);
+        let mut fetched_blocks = 0;
+        while let Some(block) = blocks.next().await {
+            let (height, txs) = block?;
+            self.unscanned.extend(txs);
+            self.unscanned.fetched(height);
+            fetched_blocks += 1;
+            // persist the cache in case of interruptions.
+            if fetched_blocks % batch_size == 0 {
+                let _ = self.save().await;
             }
         }
-        
-        for handle in handles {
-            let result = handle.await??;
-            self.unscanned.extend(result);
-        }
-        // persist the cache in case of interruptions.
         let _ = self.save().await;
 
+        // Only the notes that decrypt need to be processed further
+        let decrypted = trial_decrypt(&self.unscanned.txs, &self.vk_heights);
         let txs = logger.scan(self.unscanned.clone());
         for (indexed_tx, (epoch, tx, stx)) in txs {
+            let tx_decrypted = decrypted.get(&indexed_tx);
             if Some(indexed_tx) > last_witnessed_tx {
-                self.update_witness_map(indexed_tx, &stx)?;
+                self.update_witness_map(indexed_tx, &stx, tx_decrypted)?;
             }
             let mut vk_heights = BTreeMap::new();
             std::mem::swap(&mut vk_heights, &mut self.vk_heights);
@@ -780,12 +1766,17 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
                 .iter_mut()
                 .filter(|(_vk, h)| **h < Some(indexed_tx))
             {
-                self.scan_tx(
+                let outputs = tx_decrypted
+                    .and_then(|decrypted| decrypted.get(vk))
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                self.scan_decrypted_tx(
                     indexed_tx,
                     epoch,
                     &tx,
                     &stx,
                     vk,
+                    outputs,
                     native_token.clone(),
                 )?;
                 *h = Some(indexed_tx);
@@ -819,212 +1810,18 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         let first_height_to_query =
             last_indexed_tx.map_or_else(|| 1, |last| last.0);
         let heights = logger.fetch(first_height_to_query..=last_query_height.0);
-        for height in heights {
-            if self.unscanned.contains_height(height) {
-                continue;
-            }
-            // Get the valid masp transactions at the specified height
-            let epoch = query_epoch_at_height(client, height.into())
-                .await?
-                .ok_or_else(|| {
-                    Error::from(QueryError::General(
-                        "Queried height is greater than the last committed \
-                         block height"
-                            .to_string(),
-                    ))
-                })?;
-
-            let txs_results = match get_indexed_masp_events_at_height(
-                client,
-                height.into(),
-                None,
-            )
-            .await?
-            {
-                Some(events) => events,
-                None => continue,
-            };
-
-            // Query the actual block to get the txs bytes. If we only need one
-            // tx it might be slightly better to query the /tx endpoint to
-            // reduce the amount of data sent over the network, but this is a
-            // minimal improvement and it's even hard to tell how many times
-            // we'd need a single masp tx to make this worth it
-            let block = client
-                .block(height as u32)
-                .await
-                .map_err(|e| Error::from(QueryError::General(e.to_string())))?
-                .block
-                .data;
-
-            for (idx, tx_event) in txs_results {
-                let tx = Tx::try_from(block[idx.0 as usize].as_ref())
-                    .map_err(|e| Error::Other(e.to_string()))?;
-                let (changed_keys, masp_transaction) = Self::extract_masp_tx(
-                    &tx,
-                    ExtractShieldedActionArg::Event::<C>(&tx_event),
-                    true,
-                )
-                .await?;
-
-                // Collect the current transaction
-                shielded_txs.insert(
-                    IndexedTx {
-                        height: height.into(),
-                        index: idx,
-                    },
-                    (epoch, changed_keys, masp_transaction),
-                );
-            }
+        let source = LedgerMaspTxSource::new(client);
+        let mut blocks = futures::stream::iter(heights)
+            .map(|height| fetch_block_txs(&source, height))
+            .buffered(FETCH_CONCURRENCY);
+        while let Some(block) = blocks.next().await {
+            let (_height, txs) = block?;
+            shielded_txs.extend(txs);
         }
 
         Ok(shielded_txs)
     }
 
-    /// Extract the relevant shield portions of a [`Tx`], if any.
-    async fn extract_masp_tx<'args, C: Client + Sync>(
-        tx: &Tx,
-        action_arg: ExtractShieldedActionArg<'args, C>,
-        check_header: bool,
-    ) -> Result<(BTreeSet<namada_core::storage::Key>, Transaction), Error> {
-        let maybe_transaction = if check_header {
-            let tx_header = tx.header();
-            // NOTE: simply looking for masp sections attached to the tx
-            // is not safe. We don't validate the sections attached to a
-            // transaction se we could end up with transactions carrying
-            // an unnecessary masp section. We must instead look for the
-            // required masp sections in the signed commitments (hashes)
-            // of the transactions' headers/data sections
-            if let Some(wrapper_header) = tx_header.wrapper() {
-                let hash =
-                    wrapper_header.unshield_section_hash.ok_or_else(|| {
-                        Error::Other(
-                            "Missing expected fee unshielding section hash"
-                                .to_string(),
-                        )
-                    })?;
-
-                let masp_transaction = tx
-                    .get_section(&hash)
-                    .ok_or_else(|| {
-                        Error::Other(
-                            "Missing expected masp section".to_string(),
-                        )
-                    })?
-                    .masp_tx()
-                    .ok_or_else(|| {
-                        Error::Other("Missing masp transaction".to_string())
-                    })?;
-
-                // We use the changed keys instead of the Transfer object
-                // because those are what the masp validity predicate works on
-                let changed_keys =
-                    if let ExtractShieldedActionArg::Event(tx_event) =
-                        action_arg
-                    {
-                        let tx_result_str = tx_event
-                            .attributes
-                            .iter()
-                            .find_map(|attr| {
-                                if attr.key == "inner_tx" {
-                                    Some(&attr.value)
-                                } else {
-                                    None
-                                }
-                            })
-                            .ok_or_else(|| {
-                                Error::Other(
-                                    "Missing required tx result in event"
-                                        .to_string(),
-                                )
-                            })?;
-                        TxResult::from_str(tx_result_str)
-                            .map_err(|e| Error::Other(e.to_string()))?
-                            .changed_keys
-                    } else {
-                        BTreeSet::default()
-                    };
-
-                Some((changed_keys, masp_transaction))
-            } else {
-                None
-            }
-        } else {
-            None
-        };
-
-        let result = if let Some(tx) = maybe_transaction {
-            tx
-        } else {
-            // Expect decrypted transaction
-            let tx_data = tx.data().ok_or_else(|| {
-                Error::Other("Missing data section".to_string())
-            })?;
-            match Transfer::try_from_slice(&tx_data) {
-                Ok(transfer) => {
-                    let masp_transaction = tx
-                        .get_section(&transfer.shielded.ok_or_else(|| {
-                            Error::Other(
-                                "Missing masp section hash".to_string(),
-                            )
-                        })?)
-                        .ok_or_else(|| {
-                            Error::Other(
-                                "Missing masp section in transaction"
-                                    .to_string(),
-                            )
-                        })?
-                        .masp_tx()
-                        .ok_or_else(|| {
-                            Error::Other("Missing masp transaction".to_string())
-                        })?;
-
-                    // We use the changed keys instead of the Transfer object
-                    // because those are what the masp validity predicate works
-                    // on
-                    let changed_keys =
-                        if let ExtractShieldedActionArg::Event(tx_event) =
-                            action_arg
-                        {
-                            let tx_result_str = tx_event
-                                .attributes
-                                .iter()
-                                .find_map(|attr| {
-                                    if attr.key == "inner_tx" {
-                                        Some(&attr.value)
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .ok_or_else(|| {
-                                    Error::Other(
-                                        "Missing required tx result in event"
-                                            .to_string(),
-                                    )
-                                })?;
-                            TxResult::from_str(tx_result_str)
-                                .map_err(|e| Error::Other(e.to_string()))?
-                                .changed_keys
-                        } else {
-                            BTreeSet::default()
-                        };
-                    (changed_keys, masp_transaction)
-                }
-                Err(_) => {
-                    // This should be a MASP over IBC transaction, it
-                    // could be a ShieldedTransfer or an Envelope
-                    // message, need to try both
-
-                    extract_payload_from_shielded_action::<C>(
-                        &tx_data, action_arg,
-                    )
-                    .await?
-                }
-            }
-        };
-        Ok(result)
-    }
-
     /// Applies the given transaction to the supplied context. More precisely,
     /// the shielded transaction's outputs are added to the commitment tree.
     /// Newly discovered notes are associated to the supplied viewing keys. Note
@@ -1041,61 +1838,82 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         shielded: &Transaction,
         vk: &ViewingKey,
         native_token: Address,
+    ) -> Result<(), Error> {
+        // Listen for notes sent to our viewing keys, only if we are syncing
+        // (i.e. in a confirmed status)
+        let outputs = match self.sync_status {
+            ContextSyncStatus::Confirmed => decrypt_outputs(
+                shielded,
+                &PreparedIncomingViewingKey::new(&vk.ivk()),
+            ),
+            ContextSyncStatus::Speculative => vec![],
+        };
+        self.scan_decrypted_tx(
+            indexed_tx,
+            epoch,
+            tx_changed_keys,
+            shielded,
+            vk,
+            &outputs,
+            native_token,
+        )
+    }
+
+    /// Applies the given transaction to the supplied context like
+    /// [`Self::scan_tx`], given the outputs of the transaction that the
+    /// viewing key decrypts.
+    #[allow(clippy::too_many_arguments)]
+    fn scan_decrypted_tx(
+        &mut self,
+        indexed_tx: IndexedTx,
+        epoch: Epoch,
+        tx_changed_keys: &BTreeSet<namada_core::storage::Key>,
+        shielded: &Transaction,
+        vk: &ViewingKey,
+        outputs: &[(usize, Note, Diversifier, MemoBytes)],
+        native_token: Address,
     ) -> Result<(), Error> {
         // For tracking the account changes caused by this Transaction
         let mut transaction_delta = TransactionDelta::new();
         if let ContextSyncStatus::Confirmed = self.sync_status {
-            let mut note_pos = self.tx_note_map[&indexed_tx];
-            // Listen for notes sent to our viewing keys, only if we are syncing
-            // (i.e. in a confirmed status)
-            for so in shielded
-                .sapling_bundle()
-                .map_or(&vec![], |x| &x.shielded_outputs)
-            {
-                // Let's try to see if this viewing key can decrypt latest
-                // note
-                let notes = self.pos_map.entry(*vk).or_default();
-                let decres = try_sapling_note_decryption::<_, OutputDescription<<<Authorized as Authorization>::SaplingAuth as masp_primitives::transaction::components::sapling::Authorization>::Proof>>(
-                &NETWORK,
-                1.into(),
-                &PreparedIncomingViewingKey::new(&vk.ivk()),
-                so,
+            let first_note_pos = self.tx_note_map[&indexed_tx];
+            // The key's balance is known once it has scanned a tx
+            let notes = self.pos_map.entry(*vk).or_default();
+            // Add the notes decrypted by this viewing key to its list
+            notes.extend(
+                outputs
+                    .iter()
+                    .map(|(output_idx, ..)| first_note_pos + output_idx),
             );
-                // So this current viewing key does decrypt this current note...
-                if let Some((note, pa, memo)) = decres {
-                    // Add this note to list of notes decrypted by this viewing
-                    // key
-                    notes.insert(note_pos);
-                    // Compute the nullifier now to quickly recognize when spent
-                    let nf = note.nf(
-                        &vk.nk,
-                        note_pos.try_into().map_err(|_| {
-                            Error::Other("Can not get nullifier".to_string())
-                        })?,
-                    );
-                    self.note_map.insert(note_pos, note);
-                    self.memo_map.insert(note_pos, memo);
-                    // The payment address' diversifier is required to spend
-                    // note
-                    self.div_map.insert(note_pos, *pa.diversifier());
-                    self.nf_map.insert(nf, note_pos);
-                    // Note the account changes
-                    let balance = transaction_delta
-                        .entry(*vk)
-                        .or_insert_with(I128Sum::zero);
-                    *balance += I128Sum::from_nonnegative(
-                        note.asset_type,
-                        note.value as i128,
+            for (output_idx, note, diversifier, memo) in outputs {
+                let note_pos = first_note_pos + output_idx;
+                // Compute the nullifier now to quickly recognize when spent
+                let nf = note.nf(
+                    &vk.nk,
+                    note_pos.try_into().map_err(|_| {
+                        Error::Other("Can not get nullifier".to_string())
+                    })?,
+                );
+                self.note_map.insert(note_pos, *note);
+                self.memo_map.insert(note_pos, memo.clone());
+                // The payment address' diversifier is required to spend
+                // note
+                self.div_map.insert(note_pos, *diversifier);
+                self.nf_map.insert(nf, note_pos);
+                // Note the account changes
+                let balance =
+                    transaction_delta.entry(*vk).or_insert_with(I128Sum::zero);
+                *balance += I128Sum::from_nonnegative(
+                    note.asset_type,
+                    note.value as i128,
+                )
+                .map_err(|()| {
+                    Error::Other(
+                        "found note with invalid value or asset type"
+                            .to_string(),
                     )
-                    .map_err(|()| {
-                        Error::Other(
-                            "found note with invalid value or asset type"
-                                .to_string(),
-                        )
-                    })?;
-                    self.vk_map.insert(note_pos, *vk);
-                }
-                note_pos += 1;
+                })?;
+                self.vk_map.insert(note_pos, *vk);
             }
         }
 
@@ -1108,6 +1926,9 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
             // is rendered unusable
             if let Some(note_pos) = self.nf_map.get(&ss.nullifier) {
                 self.spents.insert(*note_pos);
+                // A spent note can't be spent again, so its witness is no
+                // longer needed
+                self.witness_map.remove(note_pos);
                 // Note the account changes
                 let balance = transaction_delta
                     .entry(self.vk_map[note_pos])
@@ -1275,6 +2096,155 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         &self.delta_map
     }
 
+    /// Get the shielded history of the given viewing key from the scanned
+    /// txs, with an entry for every token whose balance a tx changed. The
+    /// context must be synced beforehand.
+    pub async fn history(
+        &mut self,
+        client: &(impl Client + Sync),
+        io: &impl Io,
+        vk: &ViewingKey,
+    ) -> Result<Vec<ShieldedHistoryEntry>, Error> {
+        if !self.vk_heights.contains_key(vk) {
+            return Err(Error::Other(
+                "The viewing key has not been synced".to_string(),
+            ));
+        }
+        let txs: Vec<_> = self
+            .delta_map
+            .iter()
+            .filter_map(|(indexed_tx, (epoch, tfer_delta, tx_delta))| {
+                tx_delta.get(vk).map(|delta| {
+                    let counterparties = tfer_delta
+                        .keys()
+                        .filter(|account| **account != MASP)
+                        .cloned()
+                        .collect();
+                    (*indexed_tx, *epoch, delta.clone(), counterparties)
+                })
+            })
+            .collect();
+        let mut history = vec![];
+        let mut block: Option<(BlockHeight, Vec<Vec<u8>>)> = None;
+        for (indexed_tx, epoch, delta, counterparties) in txs {
+            // Spending notes of past epochs realizes their rewards. These
+            // are the difference between the raw change of the balance and
+            // the change after exchanging the spent notes to the tx's epoch.
+            let (exchanged, _, _) = self
+                .compute_exchanged_amount(
+                    client,
+                    io,
+                    delta.clone(),
+                    epoch,
+                    Conversions::new(),
+                )
+                .await?;
+            let (changes, _) = self
+                .decode_combine_sum_to_epoch(client, exchanged, epoch)
+                .await;
+            let (raw_changes, _) =
+                self.decode_combine_sum_to_epoch(client, delta, epoch).await;
+            let rewards = raw_changes - changes.clone();
+
+            let memo = self.tx_memo(vk, &indexed_tx);
+            if block.as_ref().map(|(height, _)| *height)
+                != Some(indexed_tx.height)
+            {
+                let data = client
+                    .block(indexed_tx.height.0 as u32)
+                    .await
+                    .map_err(|e| {
+                        Error::from(QueryError::General(e.to_string()))
+                    })?
+                    .block
+                    .data;
+                block = Some((indexed_tx.height, data));
+            }
+            let tx_bytes = block
+                .as_ref()
+                .and_then(|(_, data)| data.get(indexed_tx.index.0 as usize))
+                .ok_or_else(|| {
+                    Error::Other(format!(
+                        "Missing tx {} of block {}",
+                        indexed_tx.index.0, indexed_tx.height
+                    ))
+                })?;
+            let tx_hash = Tx::try_from(tx_bytes.as_ref())
+                .map_err(|e| Error::Other(e.to_string()))?
+                .header_hash()
+                .to_string();
+
+            let tokens: BTreeSet<Address> = changes
+                .components()
+                .chain(rewards.components())
+                .map(|(token, _)| token.clone())
+                .collect();
+            for token in tokens {
+                let change = changes.get(&token);
+                let reward = rewards.get(&token);
+                if change.is_zero() && reward.is_zero() {
+                    continue;
+                }
+                let direction = if change.is_negative() {
+                    TransferDirection::Outgoing
+                } else {
+                    TransferDirection::Incoming
+                };
+                history.push(ShieldedHistoryEntry {
+                    height: indexed_tx.height,
+                    index: indexed_tx.index,
+                    epoch,
+                    tx_hash: tx_hash.clone(),
+                    amount: rpc::denominate_amount(
+                        client,
+                        io,
+                        &token,
+                        change.into(),
+                    )
+                    .await,
+                    direction,
+                    rewards: rpc::denominate_amount(
+                        client,
+                        io,
+                        &token,
+                        reward.into(),
+                    )
+                    .await,
+                    token,
+                    memo: memo.clone(),
+                    counterparties: counterparties.clone(),
+                });
+            }
+        }
+        Ok(history)
+    }
+
+    /// Get the text of the memos of the notes that the viewing key received
+    /// in the given tx, if any
+    fn tx_memo(
+        &self,
+        vk: &ViewingKey,
+        indexed_tx: &IndexedTx,
+    ) -> Option<String> {
+        let first_note_pos = *self.tx_note_map.get(indexed_tx)?;
+        // The notes of a tx precede those of the next one
+        let end = self
+            .tx_note_map
+            .range((
+                std::ops::Bound::Excluded(indexed_tx),
+                std::ops::Bound::Unbounded,
+            ))
+            .next()
+            .map_or(usize::MAX, |(_, pos)| *pos);
+        let memos: Vec<_> = self
+            .pos_map
+            .get(vk)?
+            .range(first_note_pos..end)
+            .filter_map(|pos| self.memo_map.get(pos).and_then(memo_text))
+            .collect();
+        (!memos.is_empty()).then(|| memos.join("; "))
+    }
+
     /// Compute the total unspent notes associated with the viewing key in the
     /// context. If the key is not in the context, then we do not know the
     /// balance and hence we return None.
@@ -1632,6 +2602,7 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         vk: &ViewingKey,
         target: I128Sum,
         target_epoch: Epoch,
+        selection: NoteSelection,
     ) -> Result<
         (
             I128Sum,
@@ -1640,99 +2611,220 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         ),
         Error,
     > {
-        // TODO: we should try to use the smallest notes possible to fund the
-        // transaction to allow people to fetch less often
         // Establish connection with which to do exchange rate queries
         let mut conversions = BTreeMap::new();
         let mut val_acc = I128Sum::zero();
         let mut normed_val_acc = I128Sum::zero();
         let mut notes = Vec::new();
-        // Retrieve the notes that can be spent by this key
-        if let Some(avail_notes) = self.pos_map.get(vk).cloned() {
-            for note_idx in &avail_notes {
-                // No more transaction inputs are required once we have met
-                // the target amount
-                if normed_val_acc >= target {
-                    break;
-                }
-                // Spent notes cannot contribute a new transaction's pool
-                if self.spents.contains(note_idx) {
-                    continue;
-                }
-                // Get note, merkle path, diversifier associated with this ID
-                let note = *self.note_map.get(note_idx).ok_or_else(|| {
-                    Error::Other(format!("Unable to get note {note_idx}"))
-                })?;
-
-                // The amount contributed by this note before conversion
-                let pre_contr =
-                    I128Sum::from_pair(note.asset_type, note.value as i128)
-                        .map_err(|()| {
-                            Error::Other(
-                                "received note has invalid value or asset type"
-                                    .to_string(),
-                            )
-                        })?;
-                let (contr, normed_contr, proposed_convs) = self
-                    .compute_exchanged_amount(
-                        context.client(),
-                        context.io(),
-                        pre_contr,
-                        target_epoch,
-                        conversions.clone(),
-                    )
-                    .await?;
-
-                // Use this note only if it brings us closer to our target
-                if is_amount_required(
-                    normed_val_acc.clone(),
-                    target.clone(),
-                    normed_contr.clone(),
-                ) {
-                    // Be sure to record the conversions used in computing
-                    // accumulated value
-                    val_acc += contr;
-                    normed_val_acc += normed_contr;
-                    // Commit the conversions that were used to exchange
-                    conversions = proposed_convs;
-                    let merkle_path = self
-                        .witness_map
-                        .get(note_idx)
-                        .ok_or_else(|| {
-                            Error::Other(format!(
-                                "Unable to get note {note_idx}"
-                            ))
-                        })?
-                        .path()
-                        .ok_or_else(|| {
-                            Error::Other(format!(
-                                "Unable to get path: {}",
-                                line!()
-                            ))
-                        })?;
-                    let diversifier =
-                        self.div_map.get(note_idx).ok_or_else(|| {
-                            Error::Other(format!(
-                                "Unable to get note {note_idx}"
-                            ))
-                        })?;
-                    // Commit this note to our transaction
-                    notes.push((*diversifier, note, merkle_path));
-                }
+        // Retrieve the notes that can be spent by this key in the order of
+        // the selection strategy
+        let avail_notes = self
+            .unspent_notes_in_order(
+                context.client(),
+                vk,
+                &target,
+                selection,
+                target_epoch,
+            )
+            .await?;
+        for note_idx in &avail_notes {
+            // No more transaction inputs are required once we have met
+            // the target amount
+            if normed_val_acc >= target {
+                break;
             }
-        }
-        Ok((val_acc, notes, conversions))
-    }
+            // Get note, merkle path, diversifier associated with this ID
+            let note = *self.note_map.get(note_idx).ok_or_else(|| {
+                Error::Other(format!("Unable to get note {note_idx}"))
+            })?;
 
-    /// Compute the combined value of the output notes of the transaction pinned
-    /// at the given payment address. This computation uses the supplied viewing
-    /// keys to try to decrypt the output notes. If no transaction is pinned at
-    /// the given payment address fails with
-    /// `PinnedBalanceError::NoTransactionPinned`.
-    pub async fn compute_pinned_balance<C: Client + Sync>(
-        client: &C,
-        owner: PaymentAddress,
-        viewing_key: &ViewingKey,
+            // The amount contributed by this note before conversion
+            let pre_contr =
+                I128Sum::from_pair(note.asset_type, note.value as i128)
+                    .map_err(|()| {
+                        Error::Other(
+                            "received note has invalid value or asset type"
+                                .to_string(),
+                        )
+                    })?;
+            let (contr, normed_contr, proposed_convs) = self
+                .compute_exchanged_amount(
+                    context.client(),
+                    context.io(),
+                    pre_contr,
+                    target_epoch,
+                    conversions.clone(),
+                )
+                .await?;
+
+            // Use this note only if it brings us closer to our target
+            if is_amount_required(
+                normed_val_acc.clone(),
+                target.clone(),
+                normed_contr.clone(),
+            ) {
+                // Be sure to record the conversions used in computing
+                // accumulated value
+                val_acc += contr;
+                normed_val_acc += normed_contr;
+                // Commit the conversions that were used to exchange
+                conversions = proposed_convs;
+                let merkle_path = self
+                    .witness_map
+                    .get(note_idx)
+                    .ok_or_else(|| {
+                        Error::Other(format!("Unable to get note {note_idx}"))
+                    })?
+                    .path()
+                    .ok_or_else(|| {
+                        Error::Other(format!("Unable to get path: {}", line!()))
+                    })?;
+                let diversifier =
+                    self.div_map.get(note_idx).ok_or_else(|| {
+                        Error::Other(format!("Unable to get note {note_idx}"))
+                    })?;
+                // Commit this note to our transaction
+                notes.push((*diversifier, note, merkle_path));
+            }
+        }
+        Ok((val_acc, notes, conversions))
+    }
+
+    /// Get the unspent notes of the viewing key in the order in which the
+    /// given strategy spends them to reach the target. The notes of the
+    /// tokens of the target are ordered by the strategy and come before the
+    /// notes of other tokens, which can't contribute to the target.
+    async fn unspent_notes_in_order<C: Client + Sync>(
+        &mut self,
+        client: &C,
+        vk: &ViewingKey,
+        target: &I128Sum,
+        selection: NoteSelection,
+        target_epoch: Epoch,
+    ) -> Result<Vec<usize>, Error> {
+        // Spent notes cannot contribute a new transaction's pool
+        let mut notes: Vec<usize> = self
+            .pos_map
+            .get(vk)
+            .into_iter()
+            .flatten()
+            .filter(|note_idx| !self.spents.contains(note_idx))
+            .copied()
+            .collect();
+        if let NoteSelection::OldestFirst = selection {
+            return Ok(notes);
+        } else if let NoteSelection::Random = selection {
+            use rand::seq::SliceRandom;
+
+            notes.shuffle(&mut OsRng);
+            return Ok(notes);
+        }
+        // Amounts of different tokens can't be compared, so only the notes
+        // of the target tokens are ordered
+        let mut target_tokens = BTreeSet::new();
+        for (asset_type, _) in target.components() {
+            let decoded = self.decode_asset_type(client, *asset_type).await;
+            if let Some(data) = decoded {
+                target_tokens.insert(data.token);
+            }
+        }
+        // Find the amount of every note and whether it needs a conversion
+        let mut keyed_notes = Vec::with_capacity(notes.len());
+        let mut other_notes = vec![];
+        for note_idx in notes {
+            let note = *self.note_map.get(&note_idx).ok_or_else(|| {
+                Error::Other(format!("Unable to get note {note_idx}"))
+            })?;
+            match self.decode_asset_type(client, note.asset_type).await {
+                Some(data) if target_tokens.contains(&data.token) => {
+                    let amount = Amount::from_masp_denominated(
+                        note.value,
+                        data.position,
+                    );
+                    let needs_conversion =
+                        data.epoch.map_or(false, |epoch| epoch != target_epoch);
+                    keyed_notes.push((note_idx, amount, needs_conversion));
+                }
+                _ => other_notes.push(note_idx),
+            }
+        }
+        // The sorts are stable so that older notes still come first
+        match selection {
+            NoteSelection::FewestNotes => keyed_notes
+                .sort_by_key(|(_, amount, _)| std::cmp::Reverse(*amount)),
+            NoteSelection::SmallestFirst => {
+                keyed_notes.sort_by_key(|(_, amount, _)| *amount)
+            }
+            NoteSelection::AvoidConversions => keyed_notes
+                .sort_by_key(|(_, _, needs_conversion)| *needs_conversion),
+            NoteSelection::OldestFirst | NoteSelection::Random => {}
+        }
+        Ok(keyed_notes
+            .into_iter()
+            .map(|(note_idx, _, _)| note_idx)
+            .chain(other_notes)
+            .collect())
+    }
+
+    /// Get the total amount of the smallest unspent notes of the given token
+    /// that the viewing key can merge into one, along with their number. At
+    /// most the given number of notes are counted, and only those of at most
+    /// the dust threshold if one is given. Spending this amount with the
+    /// [`NoteSelection::SmallestFirst`] strategy spends these notes, or fewer
+    /// of them if conversions have raised their value.
+    pub async fn dust_notes<C: Client + Sync>(
+        &mut self,
+        client: &C,
+        vk: &ViewingKey,
+        token: &Address,
+        max_notes: usize,
+        dust_threshold: Option<Amount>,
+    ) -> Result<(Amount, usize), Error> {
+        let notes: Vec<Note> = self
+            .pos_map
+            .get(vk)
+            .into_iter()
+            .flatten()
+            .filter(|note_idx| !self.spents.contains(note_idx))
+            .filter_map(|note_idx| self.note_map.get(note_idx).copied())
+            .collect();
+        let mut amounts = vec![];
+        for note in notes {
+            match self.decode_asset_type(client, note.asset_type).await {
+                Some(data) if data.token == *token => {
+                    let amount = Amount::from_masp_denominated(
+                        note.value,
+                        data.position,
+                    );
+                    if dust_threshold
+                        .map_or(true, |threshold| amount <= threshold)
+                    {
+                        amounts.push(amount);
+                    }
+                }
+                _ => {}
+            }
+        }
+        amounts.sort();
+        amounts.truncate(max_notes);
+        let total = amounts
+            .iter()
+            .try_fold(Amount::zero(), |acc, amount| acc.checked_add(*amount))
+            .ok_or_else(|| {
+                Error::Other("Overflow in the amount of the notes".to_string())
+            })?;
+        Ok((total, amounts.len()))
+    }
+
+    /// Compute the combined value of the output notes of the transaction pinned
+    /// at the given payment address. This computation uses the supplied viewing
+    /// keys to try to decrypt the output notes. If no transaction is pinned at
+    /// the given payment address fails with
+    /// `PinnedBalanceError::NoTransactionPinned`.
+    pub async fn compute_pinned_balance<C: Client + Sync>(
+        client: &C,
+        owner: PaymentAddress,
+        viewing_key: &ViewingKey,
     ) -> Result<(I128Sum, Epoch), Error> {
         // Check that the supplied viewing key corresponds to given payment
         // address
@@ -1775,7 +2867,7 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
 
         let tx = Tx::try_from(block[indexed_tx.index.0 as usize].as_ref())
             .map_err(|e| Error::Other(e.to_string()))?;
-        let (_, shielded) = Self::extract_masp_tx(
+        let (_, shielded) = extract_masp_tx(
             &tx,
             ExtractShieldedActionArg::Request((
                 client,
@@ -1954,12 +3046,15 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
     /// UTXOs are sometimes used to make transactions balanced, but it is
     /// understood that transparent account changes are effected only by the
     /// amounts and signatures specified by the containing Transfer object.
+    /// The memo is attached to the notes paid to a shielded target.
     pub async fn gen_shielded_transfer(
         context: &impl Namada,
         source: &TransferSource,
         target: &TransferTarget,
         token: &Address,
         amount: token::DenominatedAmount,
+        memo: MemoBytes,
+        note_selection: NoteSelection,
         update_ctx: bool,
     ) -> Result<Option<ShieldedTransfer>, TransferErr> {
         // No shielded components are needed when neither source nor destination
@@ -1975,6 +3070,10 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         if spending_key.is_none() && payment_address.is_none() {
             return Ok(None);
         }
+        // Fail early if the MASP parameters needed to build the proofs are
+        // missing or corrupt
+        #[cfg(not(feature = "testing"))]
+        let prover = context.shielded().await.utils.checked_tx_prover()?;
         // We want to fund our transaction solely from supplied spending key
         let spending_key = spending_key.map(|x| x.into());
         {
@@ -1985,9 +3084,6 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         }
         // Determine epoch in which to submit potential shielded transaction
         let epoch = rpc::query_epoch(context.client()).await?;
-        // Context required for storing which notes are in the source's
-        // possession
-        let memo = MemoBytes::empty();
 
         // Try to get a seed from env var, if any.
         let rng = StdRng::from_rng(OsRng).unwrap();
@@ -2091,6 +3187,7 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
                     &to_viewing_key(&sk).vk,
                     I128Sum::from_sum(masp_amount),
                     epoch,
+                    note_selection,
                 )
                 .await?;
             // Commit the notes found to our transaction
@@ -2271,7 +3368,7 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
                                 sk.default_address().1,
                                 *asset_type,
                                 *amt as u64,
-                                memo.clone(),
+                                MemoBytes::empty(),
                             )
                             .map_err(builder::Error::SaplingBuild)?;
                     }
@@ -2301,8 +3398,6 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
 
         let builder_clone = builder.clone().map_builder(WalletMap);
         // Build and return the constructed transaction
-        #[cfg(not(feature = "testing"))]
-        let prover = context.shielded().await.utils.local_tx_prover();
         #[cfg(feature = "testing")]
         let prover = testing::MockTxProver(std::sync::Mutex::new(OsRng));
         let (masp_tx, metadata) =
@@ -2410,10 +3505,17 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         let vks = viewing_keys;
         let fvks: Vec<_> = vks
             .values()
-            .map(|fvk| ExtendedFullViewingKey::from(*fvk).fvk.vk)
+            .map(|fvk| (ExtendedFullViewingKey::from(*fvk).fvk.vk, None))
             .collect();
-        self.fetch(client, &DefaultLogger::new(io), None, 1, &[], &fvks)
-            .await?;
+        self.fetch(
+            &LedgerMaspTxSource::new(client),
+            &DefaultLogger::new(io),
+            None,
+            1,
+            &[],
+            &fvks,
+        )
+        .await?;
         // Save the update state so that future fetches can be short-circuited
         let _ = self.save().await;
         // Required for filtering out rejected transactions from Tendermint
@@ -2586,6 +3688,141 @@ fn extract_payload(
     Ok(())
 }
 
+/// Extract the relevant shield portions of a [`Tx`], if any.
+async fn extract_masp_tx<'args, C: Client + Sync>(
+    tx: &Tx,
+    action_arg: ExtractShieldedActionArg<'args, C>,
+    check_header: bool,
+) -> Result<(BTreeSet<namada_core::storage::Key>, Transaction), Error> {
+    let maybe_transaction = if check_header {
+        let tx_header = tx.header();
+        // NOTE: simply looking for masp sections attached to the tx
+        // is not safe. We don't validate the sections attached to a
+        // transaction se we could end up with transactions carrying
+        // an unnecessary masp section. We must instead look for the
+        // required masp sections in the signed commitments (hashes)
+        // of the transactions' headers/data sections
+        if let Some(wrapper_header) = tx_header.wrapper() {
+            let hash =
+                wrapper_header.unshield_section_hash.ok_or_else(|| {
+                    Error::Other(
+                        "Missing expected fee unshielding section hash"
+                            .to_string(),
+                    )
+                })?;
+
+            let masp_transaction = tx
+                .get_section(&hash)
+                .ok_or_else(|| {
+                    Error::Other("Missing expected masp section".to_string())
+                })?
+                .masp_tx()
+                .ok_or_else(|| {
+                    Error::Other("Missing masp transaction".to_string())
+                })?;
+
+            // We use the changed keys instead of the Transfer object
+            // because those are what the masp validity predicate works on
+            let changed_keys =
+                if let ExtractShieldedActionArg::Event(tx_event) = action_arg {
+                    let tx_result_str = tx_event
+                        .attributes
+                        .iter()
+                        .find_map(|attr| {
+                            if attr.key == "inner_tx" {
+                                Some(&attr.value)
+                            } else {
+                                None
+                            }
+                        })
+                        .ok_or_else(|| {
+                            Error::Other(
+                                "Missing required tx result in event"
+                                    .to_string(),
+                            )
+                        })?;
+                    TxResult::from_str(tx_result_str)
+                        .map_err(|e| Error::Other(e.to_string()))?
+                        .changed_keys
+                } else {
+                    BTreeSet::default()
+                };
+
+            Some((changed_keys, masp_transaction))
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+
+    let result = if let Some(tx) = maybe_transaction {
+        tx
+    } else {
+        // Expect decrypted transaction
+        let tx_data = tx
+            .data()
+            .ok_or_else(|| Error::Other("Missing data section".to_string()))?;
+        match Transfer::try_from_slice(&tx_data) {
+            Ok(transfer) => {
+                let masp_transaction = tx
+                    .get_section(&transfer.shielded.ok_or_else(|| {
+                        Error::Other("Missing masp section hash".to_string())
+                    })?)
+                    .ok_or_else(|| {
+                        Error::Other(
+                            "Missing masp section in transaction".to_string(),
+                        )
+                    })?
+                    .masp_tx()
+                    .ok_or_else(|| {
+                        Error::Other("Missing masp transaction".to_string())
+                    })?;
+
+                // We use the changed keys instead of the Transfer object
+                // because those are what the masp validity predicate works
+                // on
+                let changed_keys =
+                    if let ExtractShieldedActionArg::Event(tx_event) =
+                        action_arg
+                    {
+                        let tx_result_str = tx_event
+                            .attributes
+                            .iter()
+                            .find_map(|attr| {
+                                if attr.key == "inner_tx" {
+                                    Some(&attr.value)
+                                } else {
+                                    None
+                                }
+                            })
+                            .ok_or_else(|| {
+                                Error::Other(
+                                    "Missing required tx result in event"
+                                        .to_string(),
+                                )
+                            })?;
+                        TxResult::from_str(tx_result_str)
+                            .map_err(|e| Error::Other(e.to_string()))?
+                            .changed_keys
+                    } else {
+                        BTreeSet::default()
+                    };
+                (changed_keys, masp_transaction)
+            }
+            Err(_) => {
+                // This should be a MASP over IBC transaction, it
+                // could be a ShieldedTransfer or an Envelope
+                // message, need to try both
+
+                extract_payload_from_shielded_action::<C>(&tx_data, action_arg)
+                    .await?
+            }
+        }
+    };
+    Ok(result)
+}
+
 // Retrieves all the indexes and tx events at the specified height which refer
 // to a valid masp transaction. If an index is given, it filters only the
 // transactions with an index equal or greater to the provided one.
@@ -2861,6 +4098,214 @@ mod tests {
             &fake_params_paths[2].0,
         );
     }
+
+    /// Check that missing, truncated and corrupt MASP parameter files are
+    /// reported as errors
+    #[test]
+    fn test_verify_params() {
+        use super::{
+            check_params_files, verify_params, ParamsError, PARAMS_FILES,
+            SPEND_NAME,
+        };
+
+        let tempdir = tempfile::tempdir().expect("expected a temp dir");
+        let params_dir = tempdir.path();
+        let errors = check_params_files(params_dir);
+        assert_eq!(errors.len(), PARAMS_FILES.len());
+        assert!(
+            errors
+                .iter()
+                .all(|err| matches!(err, ParamsError::Missing(_)))
+        );
+
+        std::fs::write(params_dir.join(SPEND_NAME), b"fake params")
+            .expect("expected a writable temp file");
+        let errors = check_params_files(params_dir);
+        assert!(matches!(
+            errors[0],
+            ParamsError::WrongSize { actual: 11, .. }
+        ));
+        assert!(matches!(
+            verify_params(params_dir),
+            Err(ParamsError::WrongSize { .. })
+        ));
+
+        // Files of the right size with the wrong contents
+        for (name, size) in PARAMS_FILES {
+            let f = std::fs::File::create(params_dir.join(name))
+                .expect("expected a temp file");
+            f.set_len(size).expect("expected to extend the temp file");
+        }
+        assert!(check_params_files(params_dir).is_empty());
+        assert!(matches!(
+            verify_params(params_dir),
+            Err(ParamsError::Corrupt { .. })
+        ));
+    }
+
+    /// Check that the cache of unscanned txs is resumed only when it covers
+    /// the height to fetch from
+    #[test]
+    fn test_unscanned_resume_height() {
+        use namada_core::storage::{BlockHeight, IndexedTx, TxIndex};
+
+        use super::Unscanned;
+
+        let mut unscanned = Unscanned::default();
+        assert_eq!(unscanned.resume_height(BlockHeight(1)), BlockHeight(1));
+        for height in 1..=3 {
+            unscanned.fetched(BlockHeight(height));
+        }
+        assert_eq!(unscanned.fetched, Some((BlockHeight(1), BlockHeight(3))));
+        assert_eq!(unscanned.resume_height(BlockHeight(2)), BlockHeight(4));
+        assert_eq!(unscanned.resume_height(BlockHeight(4)), BlockHeight(4));
+
+        unscanned.scanned(&IndexedTx {
+            height: BlockHeight(2),
+            index: TxIndex(0),
+        });
+        assert_eq!(unscanned.fetched, Some((BlockHeight(2), BlockHeight(3))));
+
+        // Heights before the cached range drop the cache
+        assert_eq!(unscanned.resume_height(BlockHeight(1)), BlockHeight(1));
+        assert_eq!(unscanned.fetched, None);
+    }
+
+    /// Check that a viewing key born at a height is considered to have
+    /// scanned all the txs of the previous blocks only
+    #[test]
+    fn test_last_tx_before_birthday() {
+        use namada_core::storage::{BlockHeight, IndexedTx, TxIndex};
+
+        use super::last_tx_before;
+
+        assert_eq!(last_tx_before(BlockHeight(0)), None);
+        assert_eq!(last_tx_before(BlockHeight::first()), None);
+        let last_tx = last_tx_before(BlockHeight(10)).unwrap();
+        let first_tx_of_birthday = IndexedTx {
+            height: BlockHeight(10),
+            index: TxIndex(0),
+        };
+        let last_tx_of_previous_block = IndexedTx {
+            height: BlockHeight(9),
+            index: TxIndex(u32::MAX),
+        };
+        assert!(last_tx < first_tx_of_birthday);
+        assert!(last_tx >= last_tx_of_previous_block);
+    }
+
+    /// Check that only the text memos are exported in the shielded history
+    #[test]
+    fn test_memo_text() {
+        use masp_primitives::memo::MemoBytes;
+
+        use super::memo_text;
+
+        assert_eq!(memo_text(&MemoBytes::empty()), None);
+        let memo = MemoBytes::from_bytes(b"invoice 42").unwrap();
+        assert_eq!(memo_text(&memo), Some("invoice 42".to_string()));
+        let memo = MemoBytes::from_bytes(&[0xFF, 1, 2, 3]).unwrap();
+        assert_eq!(memo_text(&memo), None);
+    }
+
+    /// Check that the note selection strategies parse from their names
+    #[test]
+    fn test_note_selection_roundtrip() {
+        use super::NoteSelection;
+
+        for selection in [
+            NoteSelection::OldestFirst,
+            NoteSelection::FewestNotes,
+            NoteSelection::SmallestFirst,
+            NoteSelection::Random,
+            NoteSelection::AvoidConversions,
+        ] {
+            let parsed: NoteSelection =
+                selection.to_string().parse().expect("Test failed");
+            assert_eq!(parsed, selection);
+        }
+        assert!("largest-first".parse::<NoteSelection>().is_err());
+    }
+
+    /// Check that the named shielded contexts are stored in their own
+    /// directories and can be listed and deleted
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_named_contexts() {
+        use super::fs::{
+            delete_named_context, list_named_contexts, named_context_dir,
+            DEFAULT_CONTEXT_NAME,
+        };
+
+        let chain_dir = tempfile::tempdir().expect("Test failed");
+        let chain_dir = chain_dir.path();
+        assert_eq!(
+            named_context_dir(chain_dir, DEFAULT_CONTEXT_NAME).unwrap(),
+            chain_dir
+        );
+        assert!(named_context_dir(chain_dir, "").is_err());
+        assert!(named_context_dir(chain_dir, "../other").is_err());
+
+        for name in ["treasury", "payroll"] {
+            let dir = named_context_dir(chain_dir, name).unwrap();
+            assert_ne!(dir, chain_dir);
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        assert_eq!(
+            list_named_contexts(chain_dir).unwrap(),
+            vec![DEFAULT_CONTEXT_NAME, "payroll", "treasury"]
+        );
+        delete_named_context(chain_dir, "payroll").unwrap();
+        // Deleting the default context that was never saved is fine
+        delete_named_context(chain_dir, DEFAULT_CONTEXT_NAME).unwrap();
+        assert_eq!(
+            list_named_contexts(chain_dir).unwrap(),
+            vec![DEFAULT_CONTEXT_NAME, "treasury"]
+        );
+    }
+
+    proptest::proptest! {
+        /// Check that the compact form of a MASP tx survives its encoding and
+        /// expands into a tx with the same data needed to scan it
+        #[test]
+        fn test_compact_tx_expansion(
+            (transfer, _asset_types) in
+                super::testing::arb_shielded_transfer(0..4),
+        ) {
+            use borsh::BorshDeserialize;
+            use borsh_ext::BorshSerializeExt;
+
+            use super::CompactTx;
+
+            let masp_tx = transfer.masp_tx;
+            let compact =
+                CompactTx::new(transfer.epoch, Default::default(), &masp_tx);
+            let compact =
+                CompactTx::try_from_slice(&compact.serialize_to_vec()).unwrap();
+            assert_eq!(compact.epoch, transfer.epoch);
+            let expanded = compact.expand().unwrap();
+            assert_eq!(
+                expanded.transparent_bundle(),
+                masp_tx.transparent_bundle()
+            );
+            let nullifiers = |tx: &super::Transaction| {
+                tx.sapling_bundle()
+                    .map_or(&vec![], |x| &x.shielded_spends)
+                    .iter()
+                    .map(|ss| ss.nullifier)
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(nullifiers(&expanded), nullifiers(&masp_tx));
+            let outputs = |tx: &super::Transaction| {
+                tx.sapling_bundle()
+                    .map_or(&vec![], |x| &x.shielded_outputs)
+                    .iter()
+                    .map(|so| (so.cmu, so.ephemeral_key.0, so.enc_ciphertext))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(outputs(&expanded), outputs(&masp_tx));
+        }
+    }
 }
 
 #[cfg(any(test, feature = "testing"))]
@@ -2876,7 +4321,6 @@ pub mod testing {
     use masp_primitives::sapling::prover::TxProver;
     use masp_primitives::sapling::redjubjub::Signature;
     use masp_primitives::sapling::{ProofGenerationKey, Rseed};
-    use masp_primitives::transaction::components::GROTH_PROOF_SIZE;
     use masp_proofs::bellman::groth16::Proof;
     use proptest::prelude::*;
     use proptest::sample::SizeRange;
@@ -2885,7 +4329,6 @@ pub mod testing {
 
     use super::*;
     use crate::address::testing::arb_address;
-    use crate::masp_primitives::consensus::BranchId;
     use crate::masp_primitives::constants::VALUE_COMMITMENT_RANDOMNESS_GENERATOR;
     use crate::masp_primitives::merkle_tree::FrozenCommitmentTree;
     use crate::masp_primitives::sapling::keys::OutgoingViewingKey;
@@ -3732,21 +5175,724 @@ pub mod testing {
 /// Implementation of MASP functionality depending on a standard filesystem
 pub mod fs {
     use std::fs::{File, OpenOptions};
-    use std::io::{Read, Write};
+    use std::io::{Read, Seek, SeekFrom, Write};
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
 
     use super::*;
 
-    /// Shielded context file name
+    /// Legacy shielded context file name. A context in this file is migrated
+    /// to a snapshot and a journal when it's first loaded.
     const FILE_NAME: &str = "shielded.dat";
-    const TMP_FILE_NAME: &str = "shielded.tmp";
+    /// Snapshot of the confirmed shielded context
+    const SNAPSHOT_FILE_NAME: &str = "shielded.snapshot";
+    const SNAPSHOT_TMP_FILE_NAME: &str = "shielded.snapshot.tmp";
+    /// Journal of the updates of the confirmed shielded context since its
+    /// snapshot was taken
+    const JOURNAL_FILE_NAME: &str = "shielded.journal";
+    const JOURNAL_TMP_FILE_NAME: &str = "shielded.journal.tmp";
+    /// The cache of fetched txs of the confirmed shielded context, when it
+    /// changed since the snapshot was taken
+    const UNSCANNED_FILE_NAME: &str = "shielded.unscanned";
+    const UNSCANNED_TMP_FILE_NAME: &str = "shielded.unscanned.tmp";
+    const SNAPSHOT_MAGIC: &[u8; 8] = b"NAMSHSNP";
+    const MASP_TX_INDEX_MAGIC: &[u8; 8] = b"NAMMSPIX";
+    const JOURNAL_MAGIC: &[u8; 8] = b"NAMSHJNL";
+    const UNSCANNED_MAGIC: &[u8; 8] = b"NAMSHUNS";
+    const FORMAT_VERSION: u8 = 1;
+    /// The length of the header of the snapshot and journal files, made of
+    /// the magic bytes, the format version and the snapshot generation
+    const HEADER_LEN: usize = 8 + 1 + 8;
+    /// The length of the header of a journal entry, made of the length and
+    /// the SHA-256 hash of the entry
+    const ENTRY_HEADER_LEN: usize = 4 + 32;
+    /// The journal is compacted into a new snapshot once it outgrows both the
+    /// snapshot and this size
+    const MIN_COMPACTION_SIZE: u64 = 16 * 1024 * 1024;
     const SPECULATIVE_FILE_NAME: &str = "speculative_shielded.dat";
     const SPECULATIVE_TMP_FILE_NAME: &str = "speculative_shielded.tmp";
+    /// The magic bytes of a speculative context file. A speculative context
+    /// file without them is stored in the legacy format.
+    const SPECULATIVE_MAGIC: &[u8; 8] = b"NAMSHSPC";
+    /// The directory of the named shielded contexts, in the chain directory
+    const NAMED_CONTEXTS_DIR: &str = "shielded-contexts";
+    /// The name of the shielded context that is stored in the chain directory
+    /// itself
+    pub const DEFAULT_CONTEXT_NAME: &str = "default";
+
+    /// Get the directory of the shielded context with the given name. The
+    /// default context is stored in the chain directory itself, and every
+    /// other context in its own directory.
+    pub fn named_context_dir(
+        chain_dir: impl AsRef<Path>,
+        name: &str,
+    ) -> std::io::Result<PathBuf> {
+        let chain_dir = chain_dir.as_ref();
+        if name == DEFAULT_CONTEXT_NAME {
+            return Ok(chain_dir.to_path_buf());
+        }
+        let is_valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !is_valid {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Invalid shielded context name {name}, only ASCII \
+                     letters, digits, '-' and '_' are allowed"
+                ),
+            ));
+        }
+        Ok(chain_dir.join(NAMED_CONTEXTS_DIR).join(name))
+    }
+
+    /// List the names of the shielded contexts stored in the given chain
+    /// directory, starting with the default context
+    pub fn list_named_contexts(
+        chain_dir: impl AsRef<Path>,
+    ) -> std::io::Result<Vec<String>> {
+        let mut names = vec![DEFAULT_CONTEXT_NAME.to_string()];
+        let contexts_dir = chain_dir.as_ref().join(NAMED_CONTEXTS_DIR);
+        if !contexts_dir.exists() {
+            return Ok(names);
+        }
+        let mut named = vec![];
+        for entry in std::fs::read_dir(contexts_dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                named.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+        named.sort();
+        names.extend(named);
+        Ok(names)
+    }
+
+    /// Delete the stored files of the shielded context with the given name.
+    /// The default context can be deleted too, which resets it.
+    pub fn delete_named_context(
+        chain_dir: impl AsRef<Path>,
+        name: &str,
+    ) -> std::io::Result<()> {
+        let context_dir = named_context_dir(chain_dir, name)?;
+        if name == DEFAULT_CONTEXT_NAME {
+            for file_name in [
+                FILE_NAME,
+                SNAPSHOT_FILE_NAME,
+                JOURNAL_FILE_NAME,
+                UNSCANNED_FILE_NAME,
+                SPECULATIVE_FILE_NAME,
+            ] {
+                match std::fs::remove_file(context_dir.join(file_name)) {
+                    Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                        return Err(err);
+                    }
+                    _ => {}
+                }
+            }
+            Ok(())
+        } else {
+            std::fs::remove_dir_all(context_dir)
+        }
+    }
+
+    /// A segment of a MASP txs index file, made of the last block height
+    /// indexed by the segment and the compact MASP txs of the blocks that it
+    /// indexes
+    type IndexSegment = (BlockHeight, BTreeMap<IndexedTx, CompactTx>);
+
+    /// The lock on a MASP txs index file held by its writer, which is a file
+    /// next to it that is removed when the lock is dropped
+    struct IndexFileLock(PathBuf);
+
+    impl IndexFileLock {
+        /// Take the lock on the given MASP txs index file. If another writer
+        /// holds it, this fails right away.
+        fn acquire(path: &Path) -> std::io::Result<Self> {
+            let mut lock_path = path.as_os_str().to_owned();
+            lock_path.push(".lock");
+            let lock_path = PathBuf::from(lock_path);
+            OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&lock_path)?;
+            Ok(Self(lock_path))
+        }
+    }
+
+    impl Drop for IndexFileLock {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    impl MaspTxIndex {
+        /// Read the MASP txs index stored in the given file. A segment
+        /// truncated by an interrupted write is ignored, and left for the
+        /// next writer to drop.
+        pub fn read_file(path: impl AsRef<Path>) -> std::io::Result<Self> {
+            let (index, _len) = decode_index_file(&std::fs::read(path)?)?;
+            Ok(index)
+        }
+
+        /// Store the MASP txs index in the given file. Only the blocks
+        /// indexed since the index was last read from or written to the file
+        /// are appended to it, after dropping a segment truncated by an
+        /// interrupted write, if any. The file is locked while it's written.
+        pub fn write_file(
+            &mut self,
+            path: impl AsRef<Path>,
+        ) -> std::io::Result<()> {
+            let path = path.as_ref();
+            let Some(last_height) = self.last_height else {
+                return Ok(());
+            };
+            if self.stored_height == Some(last_height) {
+                return Ok(());
+            }
+            let _lock = IndexFileLock::acquire(path)?;
+            let mut bytes = vec![];
+            let mut file = if self.stored_height.is_some() {
+                let mut file =
+                    OpenOptions::new().read(true).write(true).open(path)?;
+                let mut stored = vec![];
+                file.read_to_end(&mut stored)?;
+                let (stored, len) = decode_index_file(&stored)?;
+                if stored.last_height != self.stored_height {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "The MASP txs index file was modified by another \
+                         writer",
+                    ));
+                }
+                file.set_len(len as u64)?;
+                file.seek(SeekFrom::End(0))?;
+                file
+            } else {
+                let native_token =
+                    self.native_token.as_ref().ok_or_else(|| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "The MASP txs index has no native token",
+                        )
+                    })?;
+                bytes.extend(file_header(MASP_TX_INDEX_MAGIC, 0));
+                native_token.serialize(&mut bytes)?;
+                File::create(path)?
+            };
+            let first = IndexedTx {
+                height: self
+                    .stored_height
+                    .map_or_else(BlockHeight::first, |h| h.next_height()),
+                index: TxIndex(0),
+            };
+            let txs: BTreeMap<IndexedTx, CompactTx> = self
+                .txs
+                .range(first..)
+                .map(|(indexed_tx, tx)| (*indexed_tx, tx.clone()))
+                .collect();
+            let segment = (last_height, txs).serialize_to_vec();
+            let len = u32::try_from(segment.len()).map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "The MASP txs index segment is too large",
+                )
+            })?;
+            bytes.extend_from_slice(&len.to_le_bytes());
+            bytes.extend(segment);
+            file.write_all(&bytes)?;
+            file.sync_data()?;
+            self.stored_height = Some(last_height);
+            Ok(())
+        }
+    }
+
+    /// Decode the given MASP txs index file, along with the length of its
+    /// segments that are whole. A segment truncated by an interrupted write
+    /// is ignored.
+    fn decode_index_file(
+        bytes: &[u8],
+    ) -> std::io::Result<(MaspTxIndex, usize)> {
+        read_file_header(bytes, MASP_TX_INDEX_MAGIC)?;
+        let mut rest = &bytes[HEADER_LEN..];
+        let native_token = Address::deserialize(&mut rest)?;
+        let mut index = MaspTxIndex {
+            native_token: Some(native_token),
+            ..Default::default()
+        };
+        while let Some(((last_height, txs), len)) = read_index_segment(rest) {
+            index.txs.extend(txs);
+            index.last_height = Some(last_height);
+            rest = &rest[len..];
+        }
+        index.stored_height = index.last_height;
+        Ok((index, bytes.len() - rest.len()))
+    }
+
+    /// Decode the MASP txs index segment at the start of the given bytes,
+    /// along with its encoded length
+    fn read_index_segment(bytes: &[u8]) -> Option<(IndexSegment, usize)> {
+        let len = bytes.get(..4)?;
+        let len = u32::from_le_bytes(len.try_into().ok()?) as usize;
+        let segment = bytes.get(4..4usize.checked_add(len)?)?;
+        let segment = IndexSegment::try_from_slice(segment).ok()?;
+        Some((segment, 4 + len))
+    }
+
+    /// Make the header of a shielded context or MASP txs index file
+    fn file_header(magic: &[u8; 8], generation: u64) -> Vec<u8> {
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(magic);
+        header.push(FORMAT_VERSION);
+        header.extend_from_slice(&generation.to_le_bytes());
+        header
+    }
+
+    /// Read the snapshot generation from the header of a shielded context or
+    /// MASP txs index file
+    fn read_file_header(bytes: &[u8], magic: &[u8; 8]) -> std::io::Result<u64> {
+        if bytes.len() < HEADER_LEN
+            || &bytes[..8] != magic
+            || bytes[8] != FORMAT_VERSION
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Unknown shielded context file format",
+            ));
+        }
+        let mut generation = [0; 8];
+        generation.copy_from_slice(&bytes[9..HEADER_LEN]);
+        Ok(u64::from_le_bytes(generation))
+    }
+
+    /// Write the given bytes to a temporary file and then atomically move it
+    /// to the given file. Inability to create the temporary file implies a
+    /// simultaneous write is in progress, in which case this fails right away.
+    /// This is unproblematic because the data intended to be stored can always
+    /// be re-fetched from the blockchain.
+    fn write_atomically(
+        dir: &Path,
+        tmp_file_name: &str,
+        file_name: &str,
+        bytes: &[u8],
+    ) -> std::io::Result<()> {
+        let tmp_path = dir.join(tmp_file_name);
+        {
+            let mut file = OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&tmp_path)?;
+            file.write_all(bytes)?;
+            file.sync_all()?;
+        }
+        // Atomicity is required to prevent other client instances from
+        // reading corrupt data
+        std::fs::rename(tmp_path, dir.join(file_name))
+    }
+
+    /// The layout of the shielded context before the birthdays of the viewing
+    /// keys, the height of the commitment tree snapshot and the range of
+    /// fetched heights of the cache were added. Contexts stored in the legacy
+    /// files are decoded with it.
+    #[derive(BorshDeserialize)]
+    struct LegacyShieldedContext {
+        tree: CommitmentTree<Node>,
+        vk_heights: BTreeMap<ViewingKey, Option<IndexedTx>>,
+        pos_map: HashMap<ViewingKey, BTreeSet<usize>>,
+        nf_map: HashMap<Nullifier, usize>,
+        note_map: HashMap<usize, Note>,
+        memo_map: HashMap<usize, MemoBytes>,
+        div_map: HashMap<usize, Diversifier>,
+        witness_map: HashMap<usize, IncrementalWitness<Node>>,
+        delta_map:
+            BTreeMap<IndexedTx, (Epoch, TransferDelta, TransactionDelta)>,
+        spents: HashSet<usize>,
+        asset_types: HashMap<AssetType, AssetData>,
+        vk_map: HashMap<usize, ViewingKey>,
+        tx_note_map: BTreeMap<IndexedTx, usize>,
+        unscanned: IndexedNoteData,
+        sync_status: ContextSyncStatus,
+    }
+
+    impl<U: ShieldedUtils> From<LegacyShieldedContext> for ShieldedContext<U> {
+        fn from(legacy: LegacyShieldedContext) -> Self {
+            Self {
+                utils: U::default(),
+                tree: legacy.tree,
+                new_commitments: vec![],
+                vk_heights: legacy.vk_heights,
+                // The viewing keys are synced from their first block
+                vk_birthdays: BTreeMap::new(),
+                pos_map: legacy.pos_map,
+                nf_map: legacy.nf_map,
+                note_map: legacy.note_map,
+                memo_map: legacy.memo_map,
+                div_map: legacy.div_map,
+                witness_map: legacy.witness_map,
+                delta_map: legacy.delta_map,
+                spents: legacy.spents,
+                asset_types: legacy.asset_types,
+                vk_map: legacy.vk_map,
+                tx_note_map: legacy.tx_note_map,
+                tree_snapshot_height: None,
+                // Without the range of fetched heights, the cached txs are
+                // dropped on the next fetch
+                unscanned: Unscanned {
+                    txs: legacy.unscanned,
+                    fetched: None,
+                },
+                sync_status: legacy.sync_status,
+            }
+        }
+    }
+
+    /// Decode a speculative shielded context, which is stored in the legacy
+    /// format if it has no header
+    fn read_speculative_context<U: ShieldedUtils>(
+        bytes: &[u8],
+    ) -> std::io::Result<ShieldedContext<U>> {
+        if bytes.starts_with(SPECULATIVE_MAGIC) {
+            read_file_header(bytes, SPECULATIVE_MAGIC)?;
+            ShieldedContext::<U>::deserialize(&mut &bytes[HEADER_LEN..])
+        } else {
+            LegacyShieldedContext::deserialize(&mut &bytes[..]).map(Into::into)
+        }
+    }
+
+    /// The changes made to the confirmed shielded context between two saves.
+    /// The commitment tree and the witnesses of the unspent notes are updated
+    /// by appending the new note commitments to them again, and the other
+    /// maps only grow, so only their new entries are stored. The cache of
+    /// fetched txs is stored in its own file instead.
+    #[derive(BorshSerialize, BorshDeserialize)]
+    struct ContextUpdate {
+        /// The note commitments appended to the commitment tree
+        commitments: Vec<Node>,
+        /// The frontier of the commitment tree with the new commitments, to
+        /// check that they're replayed onto the same tree
+        tree: CommitmentTree<Node>,
+        /// The positions of the new notes that are witnessed
+        new_witnesses: Vec<usize>,
+        /// The positions of the notes that are no longer witnessed
+        removed_witnesses: Vec<usize>,
+        vk_heights: BTreeMap<ViewingKey, Option<IndexedTx>>,
+        vk_birthdays: BTreeMap<ViewingKey, BlockHeight>,
+        tree_snapshot_height: Option<BlockHeight>,
+        pos_map: Vec<(ViewingKey, usize)>,
+        nf_map: Vec<(Nullifier, usize)>,
+        note_map: Vec<(usize, Note)>,
+        memo_map: Vec<(usize, MemoBytes)>,
+        div_map: Vec<(usize, Diversifier)>,
+        vk_map: Vec<(usize, ViewingKey)>,
+        delta_map: Vec<(IndexedTx, (Epoch, TransferDelta, TransactionDelta))>,
+        tx_note_map: Vec<(IndexedTx, usize)>,
+        spents: Vec<usize>,
+        asset_types: Vec<(AssetType, AssetData)>,
+    }
+
+    impl ContextUpdate {
+        /// The number of new entries of each of the growing maps, in the
+        /// order of [`map_lens`]
+        fn lens(&self) -> [usize; 10] {
+            [
+                self.pos_map.len(),
+                self.nf_map.len(),
+                self.note_map.len(),
+                self.memo_map.len(),
+                self.div_map.len(),
+                self.vk_map.len(),
+                self.delta_map.len(),
+                self.tx_note_map.len(),
+                self.spents.len(),
+                self.asset_types.len(),
+            ]
+        }
+
+        /// Apply this update to the given shielded context. Nothing is
+        /// applied and false is returned if the new note commitments don't
+        /// extend the commitment tree of the context.
+        fn apply<U: ShieldedUtils>(self, ctx: &mut ShieldedContext<U>) -> bool {
+            let mut tree = ctx.tree.clone();
+            for node in &self.commitments {
+                if tree.append(*node).is_err() {
+                    return false;
+                }
+            }
+            if tree.size() != self.tree.size()
+                || tree.root() != self.tree.root()
+            {
+                return false;
+            }
+            let new_witnesses: HashSet<usize> =
+                self.new_witnesses.into_iter().collect();
+            for node in self.commitments {
+                // Neither the tree nor the witnesses are full, as checked
+                // above
+                for witness in ctx.witness_map.values_mut() {
+                    let _ = witness.append(node);
+                }
+                let _ = ctx.tree.append(node);
+                let note_pos = ctx.tree.size() - 1;
+                if new_witnesses.contains(&note_pos) {
+                    ctx.witness_map.insert(
+                        note_pos,
+                        IncrementalWitness::from_tree(&ctx.tree),
+                    );
+                }
+            }
+            for note_pos in self.removed_witnesses {
+                ctx.witness_map.remove(&note_pos);
+            }
+            ctx.vk_heights = self.vk_heights;
+            ctx.vk_birthdays = self.vk_birthdays;
+            ctx.tree_snapshot_height = self.tree_snapshot_height;
+            for (vk, note_pos) in self.pos_map {
+                ctx.pos_map.entry(vk).or_default().insert(note_pos);
+            }
+            ctx.nf_map.extend(self.nf_map);
+            ctx.note_map.extend(self.note_map);
+            ctx.memo_map.extend(self.memo_map);
+            ctx.div_map.extend(self.div_map);
+            ctx.vk_map.extend(self.vk_map);
+            ctx.delta_map.extend(self.delta_map);
+            ctx.tx_note_map.extend(self.tx_note_map);
+            ctx.spents.extend(self.spents);
+            ctx.asset_types.extend(self.asset_types);
+            true
+        }
+
+        /// Encode this update into a journal entry
+        fn to_entry(&self) -> Vec<u8> {
+            let bytes = self.serialize_to_vec();
+            let len = u32::try_from(bytes.len())
+                .expect("shielded context update is too large");
+            let mut entry = Vec::with_capacity(ENTRY_HEADER_LEN + bytes.len());
+            entry.extend_from_slice(&len.to_le_bytes());
+            entry.extend_from_slice(&sha2::Sha256::digest(&bytes));
+            entry.extend_from_slice(&bytes);
+            entry
+        }
+
+        /// Decode the journal entry at the given offset, along with the offset
+        /// of the next entry. Nothing is returned if the entry is truncated or
+        /// corrupt, e.g. if the save that wrote it was interrupted.
+        fn from_entry(journal: &[u8], offset: usize) -> Option<(Self, usize)> {
+            let header = journal.get(offset..offset + ENTRY_HEADER_LEN)?;
+            let mut len = [0; 4];
+            len.copy_from_slice(&header[..4]);
+            let len = u32::from_le_bytes(len) as usize;
+            let start = offset + ENTRY_HEADER_LEN;
+            let bytes = journal.get(start..start.checked_add(len)?)?;
+            if sha2::Sha256::digest(bytes).as_slice() != &header[4..] {
+                return None;
+            }
+            let update = Self::try_from_slice(bytes).ok()?;
+            Some((update, start + len))
+        }
+    }
+
+    /// The number of entries of each of the maps of the given shielded context
+    /// that only grow
+    fn map_lens<U: ShieldedUtils>(ctx: &ShieldedContext<U>) -> [usize; 10] {
+        [
+            ctx.pos_map.values().map(BTreeSet::len).sum(),
+            ctx.nf_map.len(),
+            ctx.note_map.len(),
+            ctx.memo_map.len(),
+            ctx.div_map.len(),
+            ctx.vk_map.len(),
+            ctx.delta_map.len(),
+            ctx.tx_note_map.len(),
+            ctx.spents.len(),
+            ctx.asset_types.len(),
+        ]
+    }
+
+    /// The last height of the cache of fetched txs of the given shielded
+    /// context, if any
+    fn last_fetched<U: ShieldedUtils>(
+        ctx: &ShieldedContext<U>,
+    ) -> Option<BlockHeight> {
+        ctx.unscanned.fetched.map(|(_, to)| to)
+    }
+
+    /// A summary of the confirmed shielded context persisted in the snapshot
+    /// and journal files, from which the changes of the next save are found
+    #[derive(Debug)]
+    struct Persisted {
+        /// The generation of the snapshot
+        generation: u64,
+        /// The length of the snapshot file
+        snapshot_len: u64,
+        /// The length of the journal file
+        journal_len: u64,
+        /// The size of the commitment tree. The notes at this position or
+        /// later are new.
+        tree_size: usize,
+        /// The positions of the witnessed notes
+        witnesses: HashSet<usize>,
+        /// The last height of the persisted cache of fetched txs
+        last_fetched: Option<BlockHeight>,
+        vks: BTreeSet<ViewingKey>,
+        spents: HashSet<usize>,
+        asset_types: HashSet<AssetType>,
+        last_delta_tx: Option<IndexedTx>,
+        last_note_tx: Option<IndexedTx>,
+        map_lens: [usize; 10],
+    }
+
+    impl Persisted {
+        fn new<U: ShieldedUtils>(
+            ctx: &ShieldedContext<U>,
+            generation: u64,
+            snapshot_len: u64,
+            journal_len: u64,
+        ) -> Self {
+            Self {
+                generation,
+                snapshot_len,
+                journal_len,
+                tree_size: ctx.tree.size(),
+                witnesses: ctx.witness_map.keys().copied().collect(),
+                last_fetched: last_fetched(ctx),
+                vks: ctx.vk_heights.keys().copied().collect(),
+                spents: ctx.spents.clone(),
+                asset_types: ctx.asset_types.keys().copied().collect(),
+                last_delta_tx: ctx.delta_map.keys().next_back().copied(),
+                last_note_tx: ctx.tx_note_map.keys().next_back().copied(),
+                map_lens: map_lens(ctx),
+            }
+        }
+
+        /// Get the changes made to the given shielded context since it was
+        /// persisted. Nothing is returned if the changes aren't just new
+        /// entries, e.g. if the commitment tree was rebuilt or another context
+        /// was merged in, in which case a new snapshot must be taken.
+        fn update<U: ShieldedUtils>(
+            &self,
+            ctx: &ShieldedContext<U>,
+        ) -> Option<ContextUpdate> {
+            let is_new = |note_pos: &usize| *note_pos >= self.tree_size;
+            // The new commitments must all be known, and no note that was
+            // already in the tree can become witnessed
+            let new_commitments =
+                ctx.tree.size().checked_sub(self.tree_size)?;
+            let first_new_commitment =
+                ctx.new_commitments.len().checked_sub(new_commitments)?;
+            if !ctx.vk_heights.keys().eq(self.vks.iter())
+                || !ctx.witness_map.keys().all(|note_pos| {
+                    is_new(note_pos) || self.witnesses.contains(note_pos)
+                })
+            {
+                return None;
+            }
+            let mut new_witnesses: Vec<usize> =
+                ctx.witness_map.keys().copied().filter(is_new).collect();
+            new_witnesses.sort();
+            let update = ContextUpdate {
+                commitments: ctx.new_commitments[first_new_commitment..]
+                    .to_vec(),
+                tree: ctx.tree.clone(),
+                new_witnesses,
+                removed_witnesses: self
+                    .witnesses
+                    .iter()
+                    .filter(|note_pos| !ctx.witness_map.contains_key(note_pos))
+                    .copied()
+                    .collect(),
+                vk_heights: ctx.vk_heights.clone(),
+                vk_birthdays: ctx.vk_birthdays.clone(),
+                tree_snapshot_height: ctx.tree_snapshot_height,
+                pos_map: ctx
+                    .pos_map
+                    .iter()
+                    .flat_map(|(vk, positions)| {
+                        positions
+                            .range(self.tree_size..)
+                            .map(move |note_pos| (*vk, *note_pos))
+                    })
+                    .collect(),
+                nf_map: ctx
+                    .nf_map
+                    .iter()
+                    .filter(|(_, note_pos)| is_new(note_pos))
+                    .map(|(nf, note_pos)| (*nf, *note_pos))
+                    .collect(),
+                note_map: ctx
+                    .note_map
+                    .iter()
+                    .filter(|(note_pos, _)| is_new(note_pos))
+                    .map(|(note_pos, note)| (*note_pos, *note))
+                    .collect(),
+                memo_map: ctx
+                    .memo_map
+                    .iter()
+                    .filter(|(note_pos, _)| is_new(note_pos))
+                    .map(|(note_pos, memo)| (*note_pos, memo.clone()))
+                    .collect(),
+                div_map: ctx
+                    .div_map
+                    .iter()
+                    .filter(|(note_pos, _)| is_new(note_pos))
+                    .map(|(note_pos, div)| (*note_pos, *div))
+                    .collect(),
+                vk_map: ctx
+                    .vk_map
+                    .iter()
+                    .filter(|(note_pos, _)| is_new(note_pos))
+                    .map(|(note_pos, vk)| (*note_pos, *vk))
+                    .collect(),
+                delta_map: ctx
+                    .delta_map
+                    .iter()
+                    .filter(|(indexed_tx, _)| {
+                        Some(**indexed_tx) > self.last_delta_tx
+                    })
+                    .map(|(indexed_tx, delta)| (*indexed_tx, delta.clone()))
+                    .collect(),
+                tx_note_map: ctx
+                    .tx_note_map
+                    .iter()
+                    .filter(|(indexed_tx, _)| {
+                        Some(**indexed_tx) > self.last_note_tx
+                    })
+                    .map(|(indexed_tx, note_pos)| (*indexed_tx, *note_pos))
+                    .collect(),
+                spents: ctx.spents.difference(&self.spents).copied().collect(),
+                asset_types: ctx
+                    .asset_types
+                    .iter()
+                    .filter(|(asset_type, _)| {
+                        !self.asset_types.contains(asset_type)
+                    })
+                    .map(|(asset_type, data)| (*asset_type, data.clone()))
+                    .collect(),
+            };
+            // Make sure that no entries were removed or rewritten
+            let lens = self
+                .map_lens
+                .iter()
+                .zip(update.lens())
+                .map(|(persisted, new)| persisted + new);
+            lens.eq(map_lens(ctx)).then_some(update)
+        }
+    }
 
     #[derive(Debug, BorshSerialize, BorshDeserialize, Clone)]
-    /// An implementation of ShieldedUtils for standard filesystems
+    /// An implementation of ShieldedUtils for standard filesystems.
+    ///
+    /// The confirmed shielded context is stored as a snapshot and a journal
+    /// of the updates made since the snapshot was taken, to which each save
+    /// only appends the changes. The journal is compacted into a new snapshot
+    /// once it gets too large.
     pub struct FsShieldedUtils {
         #[borsh(skip)]
         context_dir: PathBuf,
+        /// The summary of the persisted confirmed context, shared by the
+        /// clones of these utils
+        #[borsh(skip)]
+        persisted: Arc<Mutex<Option<Persisted>>>,
     }
 
     impl FsShieldedUtils {
@@ -3782,19 +5928,218 @@ pub mod fs {
                     ContextSyncStatus::Confirmed
                 };
 
-            let utils = Self { context_dir };
+            let utils = Self {
+                context_dir,
+                persisted: Default::default(),
+            };
             ShieldedContext {
                 utils,
                 sync_status,
                 ..Default::default()
             }
         }
+
+        /// Initialize the shielded context with the given name, which is
+        /// stored in its own directory in the given chain directory. The
+        /// directory is created if the context is new.
+        pub fn new_named(
+            chain_dir: impl AsRef<Path>,
+            name: &str,
+        ) -> std::io::Result<ShieldedContext<Self>> {
+            let context_dir = named_context_dir(chain_dir, name)?;
+            std::fs::create_dir_all(&context_dir)?;
+            Ok(Self::new(context_dir))
+        }
+
+        /// Take a new snapshot of the given confirmed shielded context and
+        /// start a new journal for it. The snapshot is given the generation
+        /// following the one of the current snapshot, so that the journal of
+        /// the latter is ignored if writing the new journal is interrupted.
+        fn write_snapshot<U: ShieldedUtils>(
+            &self,
+            ctx: &ShieldedContext<U>,
+        ) -> std::io::Result<Persisted> {
+            let generation =
+                File::open(self.context_dir.join(SNAPSHOT_FILE_NAME))
+                    .and_then(|mut file| {
+                        let mut header = [0; HEADER_LEN];
+                        file.read_exact(&mut header)?;
+                        read_file_header(&header, SNAPSHOT_MAGIC)
+                    })
+                    .map_or(0, |generation| generation.wrapping_add(1));
+            let mut snapshot = file_header(SNAPSHOT_MAGIC, generation);
+            ctx.serialize(&mut snapshot)
+                .expect("cannot serialize shielded context");
+            write_atomically(
+                &self.context_dir,
+                SNAPSHOT_TMP_FILE_NAME,
+                SNAPSHOT_FILE_NAME,
+                &snapshot,
+            )?;
+            let journal = file_header(JOURNAL_MAGIC, generation);
+            write_atomically(
+                &self.context_dir,
+                JOURNAL_TMP_FILE_NAME,
+                JOURNAL_FILE_NAME,
+                &journal,
+            )?;
+            // The cache of fetched txs is in the snapshot
+            match std::fs::remove_file(
+                self.context_dir.join(UNSCANNED_FILE_NAME),
+            ) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(err);
+                }
+                _ => {}
+            }
+            Ok(Persisted::new(
+                ctx,
+                generation,
+                snapshot.len() as u64,
+                journal.len() as u64,
+            ))
+        }
+
+        /// Append the changes made to the given confirmed shielded context
+        /// since it was persisted to the journal. The cache of fetched txs is
+        /// stored whole when more blocks were fetched, but not when the
+        /// fetched txs are scanned, as scanned txs are skipped anyway if they
+        /// get loaded again. Returns false if a new snapshot must be taken
+        /// instead.
+        fn append_update<U: ShieldedUtils>(
+            &self,
+            persisted: &mut Persisted,
+            ctx: &ShieldedContext<U>,
+        ) -> std::io::Result<bool> {
+            let Some(update) = persisted.update(ctx) else {
+                return Ok(false);
+            };
+            let entry = update.to_entry();
+            let journal_len = persisted.journal_len + entry.len() as u64;
+            if journal_len > persisted.snapshot_len.max(MIN_COMPACTION_SIZE) {
+                return Ok(false);
+            }
+            let mut journal = match OpenOptions::new()
+                .append(true)
+                .open(self.context_dir.join(JOURNAL_FILE_NAME))
+            {
+                Ok(journal) => journal,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                    return Ok(false);
+                }
+                Err(err) => return Err(err),
+            };
+            // The context may have been saved by another client instance in
+            // the meantime
+            if journal.metadata()?.len() != persisted.journal_len {
+                return Ok(false);
+            }
+            journal.write_all(&entry)?;
+            journal.sync_data()?;
+            if last_fetched(ctx) != persisted.last_fetched {
+                let mut unscanned =
+                    file_header(UNSCANNED_MAGIC, persisted.generation);
+                ctx.unscanned
+                    .serialize(&mut unscanned)
+                    .expect("cannot serialize the cache of fetched txs");
+                write_atomically(
+                    &self.context_dir,
+                    UNSCANNED_TMP_FILE_NAME,
+                    UNSCANNED_FILE_NAME,
+                    &unscanned,
+                )?;
+            }
+            *persisted = Persisted::new(
+                ctx,
+                persisted.generation,
+                persisted.snapshot_len,
+                journal_len,
+            );
+            Ok(true)
+        }
+
+        /// Load the confirmed shielded context from its snapshot and replay
+        /// its journal, along with the summary of what's persisted. A context
+        /// stored in the legacy format is migrated first.
+        fn load_confirmed_context<U: ShieldedUtils>(
+            &self,
+        ) -> std::io::Result<(ShieldedContext<U>, Option<Persisted>)> {
+            let snapshot_path = self.context_dir.join(SNAPSHOT_FILE_NAME);
+            if !snapshot_path.exists() {
+                let legacy_path = self.context_dir.join(FILE_NAME);
+                let bytes = std::fs::read(&legacy_path)?;
+                let mut ctx: ShieldedContext<U> =
+                    LegacyShieldedContext::deserialize(&mut &bytes[..])?.into();
+                // The witnesses of spent notes are no longer kept
+                let spents = &ctx.spents;
+                ctx.witness_map
+                    .retain(|note_pos, _| !spents.contains(note_pos));
+                let persisted = self.write_snapshot(&ctx)?;
+                std::fs::remove_file(legacy_path)?;
+                return Ok((ctx, Some(persisted)));
+            }
+            let snapshot = std::fs::read(snapshot_path)?;
+            let generation = read_file_header(&snapshot, SNAPSHOT_MAGIC)?;
+            let mut ctx = ShieldedContext::<U>::deserialize(
+                &mut &snapshot[HEADER_LEN..],
+            )?;
+            let journal_path = self.context_dir.join(JOURNAL_FILE_NAME);
+            let journal = match std::fs::read(&journal_path) {
+                Ok(journal) => journal,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                    return Ok((ctx, None));
+                }
+                Err(err) => return Err(err),
+            };
+            // A journal of another generation belongs to a previous snapshot
+            // whose replacement was interrupted, and is ignored
+            if read_file_header(&journal, JOURNAL_MAGIC).ok()
+                != Some(generation)
+            {
+                return Ok((ctx, None));
+            }
+            let mut journal_len = HEADER_LEN;
+            while let Some((update, next)) =
+                ContextUpdate::from_entry(&journal, journal_len)
+            {
+                if !update.apply(&mut ctx) {
+                    break;
+                }
+                journal_len = next;
+            }
+            // A cache of another generation is older than the snapshot
+            if let Ok(unscanned) =
+                std::fs::read(self.context_dir.join(UNSCANNED_FILE_NAME))
+            {
+                if read_file_header(&unscanned, UNSCANNED_MAGIC).ok()
+                    == Some(generation)
+                {
+                    ctx.unscanned =
+                        Unscanned::deserialize(&mut &unscanned[HEADER_LEN..])?;
+                }
+            }
+            // Drop the entry written by an interrupted save, if any
+            if journal_len < journal.len() {
+                OpenOptions::new()
+                    .write(true)
+                    .open(journal_path)?
+                    .set_len(journal_len as u64)?;
+            }
+            let persisted = Persisted::new(
+                &ctx,
+                generation,
+                snapshot.len() as u64,
+                journal_len as u64,
+            );
+            Ok((ctx, Some(persisted)))
+        }
     }
 
     impl Default for FsShieldedUtils {
         fn default() -> Self {
             Self {
                 context_dir: PathBuf::from(FILE_NAME),
+                persisted: Default::default(),
             }
         }
     }
@@ -3815,6 +6160,18 @@ pub mod fs {
             }
         }
 
+        /// Check that the MASP parameter files are present with their
+        /// expected size and hashes before loading them
+        fn checked_tx_prover(&self) -> Result<LocalTxProver, ParamsError> {
+            let params_dir = get_params_dir();
+            if let Some(err) =
+                check_params_files(&params_dir).into_iter().next()
+            {
+                return Err(err);
+            }
+            load_params(&params_dir, || self.local_tx_prover())
+        }
+
         /// Try to load the last saved shielded context from the given context
         /// directory. If this fails, then leave the current context unchanged.
         async fn load<U: ShieldedUtils + MaybeSend>(
@@ -3822,70 +6179,504 @@ pub mod fs {
             ctx: &mut ShieldedContext<U>,
             force_confirmed: bool,
         ) -> std::io::Result<()> {
-            // Try to load shielded context from file
-            let file_name = if force_confirmed {
-                FILE_NAME
+            let mut persisted = self.persisted.lock().unwrap();
+            let loaded = if force_confirmed
+                || matches!(ctx.sync_status, ContextSyncStatus::Confirmed)
+            {
+                let (loaded, loaded_persisted) =
+                    self.load_confirmed_context()?;
+                *persisted = loaded_persisted;
+                loaded
             } else {
-                match ctx.sync_status {
-                    ContextSyncStatus::Confirmed => FILE_NAME,
-                    ContextSyncStatus::Speculative => SPECULATIVE_FILE_NAME,
-                }
+                let mut ctx_file =
+                    File::open(self.context_dir.join(SPECULATIVE_FILE_NAME))?;
+                let mut bytes = Vec::new();
+                ctx_file.read_to_end(&mut bytes)?;
+                // The next confirmed save must take a new snapshot, since the
+                // loaded context isn't the persisted confirmed one
+                *persisted = None;
+                read_speculative_context(&bytes)?
             };
-            let mut ctx_file = File::open(self.context_dir.join(file_name))?;
-            let mut bytes = Vec::new();
-            ctx_file.read_to_end(&mut bytes)?;
-            // Fill the supplied context with the deserialized object
+            // Fill the supplied context with the loaded one
             *ctx = ShieldedContext {
                 utils: ctx.utils.clone(),
-                ..ShieldedContext::<U>::deserialize(&mut &bytes[..])?
+                ..loaded
             };
             Ok(())
         }
 
-        /// Save this confirmed shielded context into its associated context
-        /// directory. At the same time, delete the speculative file if present
+        /// Save this shielded context into its associated context directory.
+        /// A confirmed context is appended to the journal when possible. At
+        /// the same time, delete the speculative file if present.
         async fn save<U: ShieldedUtils + MaybeSync>(
             &self,
             ctx: &ShieldedContext<U>,
         ) -> std::io::Result<()> {
-            // TODO: use mktemp crate?
-            let (tmp_file_name, file_name) = match ctx.sync_status {
-                ContextSyncStatus::Confirmed => (TMP_FILE_NAME, FILE_NAME),
+            match ctx.sync_status {
+                ContextSyncStatus::Confirmed => {
+                    let mut persisted = self.persisted.lock().unwrap();
+                    let appended = match persisted.as_mut() {
+                        Some(persisted) => {
+                            self.append_update(persisted, ctx)?
+                        }
+                        None => false,
+                    };
+                    if !appended {
+                        *persisted = Some(self.write_snapshot(ctx)?);
+                    }
+                    // Remove the speculative file if present since it's state
+                    // is overruled by the confirmed one we just saved
+                    let _ = std::fs::remove_file(
+                        self.context_dir.join(SPECULATIVE_FILE_NAME),
+                    );
+                    Ok(())
+                }
                 ContextSyncStatus::Speculative => {
-                    (SPECULATIVE_TMP_FILE_NAME, SPECULATIVE_FILE_NAME)
+                    let mut bytes = file_header(SPECULATIVE_MAGIC, 0);
+                    ctx.serialize(&mut bytes)
+                        .expect("cannot serialize shielded context");
+                    write_atomically(
+                        &self.context_dir,
+                        SPECULATIVE_TMP_FILE_NAME,
+                        SPECULATIVE_FILE_NAME,
+                        &bytes,
+                    )
                 }
-            };
-            let tmp_path = self.context_dir.join(tmp_file_name);
-            {
-                // First serialize the shielded context into a temporary file.
-                // Inability to create this file implies a simultaneuous write
-                // is in progress. In this case, immediately
-                // fail. This is unproblematic because the data
-                // intended to be stored can always be re-fetched
-                // from the blockchain.
-                let mut ctx_file = OpenOptions::new()
-                    .write(true)
-                    .create_new(true)
-                    .open(tmp_path.clone())?;
-                let mut bytes = Vec::new();
-                ctx.serialize(&mut bytes)
-                    .expect("cannot serialize shielded context");
-                ctx_file.write_all(&bytes[..])?;
-            }
-            // Atomically update the old shielded context file with new data.
-            // Atomicity is required to prevent other client instances from
-            // reading corrupt data.
-            std::fs::rename(tmp_path, self.context_dir.join(file_name))?;
-
-            // Remove the speculative file if present since it's state is
-            // overruled by the confirmed one we just saved
-            if let ContextSyncStatus::Confirmed = ctx.sync_status {
-                let _ = std::fs::remove_file(
-                    self.context_dir.join(SPECULATIVE_FILE_NAME),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use masp_primitives::sapling::Rseed;
+
+        use super::*;
+
+        /// Make an empty shielded context stored in the given directory
+        fn context_in(dir: &Path) -> ShieldedContext<FsShieldedUtils> {
+            ShieldedContext {
+                utils: FsShieldedUtils {
+                    context_dir: dir.to_path_buf(),
+                    persisted: Default::default(),
+                },
+                ..Default::default()
+            }
+        }
+
+        /// Append a note to the commitment tree of the given context, which
+        /// is witnessed if it's ours
+        fn append_note(ctx: &mut ShieldedContext<FsShieldedUtils>, ours: bool) {
+            let mut repr = [0; 32];
+            repr[0] = ctx.tree.size() as u8 + 1;
+            let node = Node::new(repr);
+            for witness in ctx.witness_map.values_mut() {
+                witness.append(node).unwrap();
+            }
+            ctx.tree.append(node).unwrap();
+            ctx.new_commitments.push(node);
+            if ours {
+                ctx.witness_map.insert(
+                    ctx.tree.size() - 1,
+                    IncrementalWitness::from_tree(&ctx.tree),
                 );
             }
+        }
 
-            Ok(())
+        /// Encode the given shielded context like the legacy files, with the
+        /// fields of the legacy layout in their order
+        fn legacy_bytes(ctx: &ShieldedContext<FsShieldedUtils>) -> Vec<u8> {
+            let mut bytes = vec![];
+            ctx.tree.serialize(&mut bytes).unwrap();
+            ctx.vk_heights.serialize(&mut bytes).unwrap();
+            ctx.pos_map.serialize(&mut bytes).unwrap();
+            ctx.nf_map.serialize(&mut bytes).unwrap();
+            ctx.note_map.serialize(&mut bytes).unwrap();
+            ctx.memo_map.serialize(&mut bytes).unwrap();
+            ctx.div_map.serialize(&mut bytes).unwrap();
+            ctx.witness_map.serialize(&mut bytes).unwrap();
+            ctx.delta_map.serialize(&mut bytes).unwrap();
+            ctx.spents.serialize(&mut bytes).unwrap();
+            ctx.asset_types.serialize(&mut bytes).unwrap();
+            ctx.vk_map.serialize(&mut bytes).unwrap();
+            ctx.tx_note_map.serialize(&mut bytes).unwrap();
+            ctx.unscanned.txs.serialize(&mut bytes).unwrap();
+            ctx.sync_status.serialize(&mut bytes).unwrap();
+            bytes
+        }
+
+        /// Load the confirmed shielded context stored in the given directory
+        async fn load_from(dir: &Path) -> ShieldedContext<FsShieldedUtils> {
+            let mut ctx = context_in(dir);
+            ctx.load_confirmed().await.unwrap();
+            ctx
+        }
+
+        /// Check that the changes of a shielded context are appended to its
+        /// journal, which is replayed on top of the snapshot when loading
+        #[tokio::test]
+        async fn test_incremental_persistence() {
+            let dir = tempfile::tempdir().unwrap();
+            let dir = dir.path();
+            let journal_path = dir.join(JOURNAL_FILE_NAME);
+            let vk = ExtendedFullViewingKey::from(
+                &ExtendedSpendingKey::master(b"incremental"),
+            )
+            .fvk
+            .vk;
+
+            let mut ctx = context_in(dir);
+            ctx.vk_heights.insert(vk, None);
+            for ours in [false, true, false] {
+                append_note(&mut ctx, ours);
+            }
+            ctx.save().await.unwrap();
+            let snapshot_len = std::fs::metadata(dir.join(SNAPSHOT_FILE_NAME))
+                .unwrap()
+                .len();
+            assert_eq!(
+                std::fs::metadata(&journal_path).unwrap().len(),
+                HEADER_LEN as u64
+            );
+
+            // Spend the witnessed note and witness a new one
+            let indexed_tx = IndexedTx {
+                height: BlockHeight(1),
+                index: TxIndex(0),
+            };
+            ctx.tx_note_map.insert(indexed_tx, ctx.tree.size());
+            for ours in [true, false] {
+                append_note(&mut ctx, ours);
+            }
+            ctx.spents.insert(1);
+            ctx.witness_map.remove(&1);
+            ctx.vk_heights.insert(vk, Some(indexed_tx));
+            ctx.unscanned.fetched(BlockHeight(1));
+            ctx.save().await.unwrap();
+            assert!(dir.join(UNSCANNED_FILE_NAME).exists());
+            assert_eq!(
+                std::fs::metadata(dir.join(SNAPSHOT_FILE_NAME))
+                    .unwrap()
+                    .len(),
+                snapshot_len
+            );
+            let journal_len = std::fs::metadata(&journal_path).unwrap().len();
+            assert!(journal_len > HEADER_LEN as u64);
+            let loaded = load_from(dir).await;
+            assert_eq!(loaded.serialize_to_vec(), ctx.serialize_to_vec());
+
+            // An entry left over by an interrupted save is dropped
+            let mut journal =
+                OpenOptions::new().append(true).open(&journal_path).unwrap();
+            journal.write_all(&[1, 2, 3]).unwrap();
+            let loaded = load_from(dir).await;
+            assert_eq!(loaded.serialize_to_vec(), ctx.serialize_to_vec());
+            assert_eq!(
+                std::fs::metadata(&journal_path).unwrap().len(),
+                journal_len
+            );
+
+            // Rebuilding the commitment tree takes a new snapshot
+            let mut loaded = loaded;
+            loaded.tree = CommitmentTree::empty();
+            loaded.witness_map.clear();
+            loaded.save().await.unwrap();
+            assert_eq!(
+                std::fs::metadata(&journal_path).unwrap().len(),
+                HEADER_LEN as u64
+            );
+            assert!(!dir.join(UNSCANNED_FILE_NAME).exists());
+            assert_eq!(
+                load_from(dir).await.serialize_to_vec(),
+                loaded.serialize_to_vec()
+            );
+        }
+
+        /// Check that only the newly indexed blocks are appended to a MASP
+        /// txs index file, and that a segment truncated by an interrupted
+        /// write is ignored when reading and dropped by the next writer
+        #[test]
+        fn test_masp_tx_index_file() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("masp_txs");
+            let mut index = MaspTxIndex {
+                native_token: Some(crate::address::testing::nam()),
+                last_height: Some(BlockHeight(10)),
+                ..Default::default()
+            };
+            index.write_file(&path).unwrap();
+            let len = std::fs::metadata(&path).unwrap().len();
+
+            let mut index = MaspTxIndex::read_file(&path).unwrap();
+            assert_eq!(index.last_height, Some(BlockHeight(10)));
+            assert_eq!(
+                index.native_token,
+                Some(crate::address::testing::nam())
+            );
+            // Nothing new to store
+            index.write_file(&path).unwrap();
+            assert_eq!(std::fs::metadata(&path).unwrap().len(), len);
+
+            index.last_height = Some(BlockHeight(20));
+            index.write_file(&path).unwrap();
+            let appended_len = std::fs::metadata(&path).unwrap().len();
+            assert!(appended_len > len);
+            assert_eq!(
+                MaspTxIndex::read_file(&path).unwrap().last_height,
+                Some(BlockHeight(20))
+            );
+
+            let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+            file.write_all(&[1, 2, 3]).unwrap();
+            let mut index = MaspTxIndex::read_file(&path).unwrap();
+            assert_eq!(index.last_height, Some(BlockHeight(20)));
+            // Reading leaves the file untouched
+            assert_eq!(
+                std::fs::metadata(&path).unwrap().len(),
+                appended_len + 3
+            );
+
+            // A writer can't append while another one holds the lock
+            let lock = IndexFileLock::acquire(&path).unwrap();
+            index.last_height = Some(BlockHeight(30));
+            assert!(index.write_file(&path).is_err());
+            drop(lock);
+
+            // The next segment replaces the truncated one
+            index.write_file(&path).unwrap();
+            assert_eq!(
+                MaspTxIndex::read_file(&path).unwrap().last_height,
+                Some(BlockHeight(30))
+            );
+            assert!(std::fs::metadata(&path).unwrap().len() > appended_len);
+
+            // A stale index can't append to a file written by another writer
+            let mut stale = MaspTxIndex::read_file(&path).unwrap();
+            index.last_height = Some(BlockHeight(40));
+            index.write_file(&path).unwrap();
+            stale.last_height = Some(BlockHeight(50));
+            assert!(stale.write_file(&path).is_err());
+        }
+
+        /// Check that a speculative shielded context is stored with a format
+        /// header, and that one stored in the legacy format is still loaded
+        #[tokio::test]
+        async fn test_speculative_context_format() {
+            let dir = tempfile::tempdir().unwrap();
+            let dir = dir.path();
+            let mut ctx = context_in(dir);
+            for ours in [true, false] {
+                append_note(&mut ctx, ours);
+            }
+            ctx.vk_birthdays.insert(
+                ExtendedFullViewingKey::from(&ExtendedSpendingKey::master(
+                    b"speculative",
+                ))
+                .fvk
+                .vk,
+                BlockHeight(2),
+            );
+            ctx.sync_status = ContextSyncStatus::Speculative;
+            ctx.save().await.unwrap();
+            let bytes = std::fs::read(dir.join(SPECULATIVE_FILE_NAME)).unwrap();
+            assert!(bytes.starts_with(SPECULATIVE_MAGIC));
+            let mut loaded = context_in(dir);
+            loaded.sync_status = ContextSyncStatus::Speculative;
+            loaded.load().await.unwrap();
+            assert_eq!(loaded.serialize_to_vec(), ctx.serialize_to_vec());
+
+            std::fs::write(dir.join(SPECULATIVE_FILE_NAME), legacy_bytes(&ctx))
+                .unwrap();
+            loaded.load().await.unwrap();
+            // The fields missing from the legacy format are left empty
+            ctx.vk_birthdays.clear();
+            assert_eq!(loaded.serialize_to_vec(), ctx.serialize_to_vec());
+        }
+
+        /// Check that a shielded context stored in the legacy file is migrated
+        /// to a snapshot
+        #[tokio::test]
+        async fn test_legacy_migration() {
+            let dir = tempfile::tempdir().unwrap();
+            let dir = dir.path();
+            let mut ctx = context_in(dir);
+            for ours in [true, false, true] {
+                append_note(&mut ctx, ours);
+            }
+            ctx.spents.insert(0);
+            std::fs::write(dir.join(FILE_NAME), legacy_bytes(&ctx)).unwrap();
+
+            let loaded = load_from(dir).await;
+            assert!(!dir.join(FILE_NAME).exists());
+            assert!(dir.join(SNAPSHOT_FILE_NAME).exists());
+            // The witness of the spent note is dropped
+            ctx.witness_map.remove(&0);
+            assert_eq!(loaded.serialize_to_vec(), ctx.serialize_to_vec());
+        }
+
+        /// Get the asset type of the given token and epoch, whose decoding
+        /// is cached in the given context
+        fn cached_asset_type(
+            ctx: &mut ShieldedContext<FsShieldedUtils>,
+            token: &Address,
+            epoch: Option<Epoch>,
+        ) -> AssetType {
+            let data = AssetData {
+                token: token.clone(),
+                denom: Denomination(0),
+                position: MaspDigitPos::Zero,
+                epoch,
+            };
+            let asset_type = data.encode().unwrap();
+            ctx.asset_types.insert(asset_type, data);
+            asset_type
+        }
+
+        /// Give the viewing key a note of the given token and value, returning
+        /// its position
+        fn add_note(
+            ctx: &mut ShieldedContext<FsShieldedUtils>,
+            vk: &ViewingKey,
+            token: &Address,
+            value: u64,
+            epoch: Option<Epoch>,
+        ) -> usize {
+            let asset_type = cached_asset_type(ctx, token, epoch);
+            let (div, _g_d) = find_valid_diversifier(&mut OsRng);
+            let note = vk
+                .to_payment_address(div)
+                .unwrap()
+                .create_note(asset_type, value, Rseed::AfterZip212([0; 32]))
+                .unwrap();
+            let note_idx = ctx.note_map.len();
+            ctx.note_map.insert(note_idx, note);
+            ctx.pos_map.entry(*vk).or_default().insert(note_idx);
+            note_idx
+        }
+
+        /// Make a context in which the viewing key has notes of two tokens,
+        /// one of which is spent, returning the context, the viewing key and
+        /// the tokens. The notes of the first token are worth 5, 20, 1, 10
+        /// and 1 (spent), and the notes of the second token are worth 1 and
+        /// 100. The note worth 20 is of an epoch before `Epoch(1)`.
+        fn context_with_notes(
+            dir: &Path,
+        ) -> (
+            ShieldedContext<FsShieldedUtils>,
+            ViewingKey,
+            Address,
+            Address,
+        ) {
+            use namada_core::address::testing::{btc, nam};
+
+            let mut ctx = context_in(dir);
+            let vk = ExtendedFullViewingKey::from(
+                &ExtendedSpendingKey::master(b"notes"),
+            )
+            .fvk
+            .vk;
+            let (token, other) = (nam(), btc());
+            add_note(&mut ctx, &vk, &token, 5, None);
+            add_note(&mut ctx, &vk, &other, 1, None);
+            add_note(&mut ctx, &vk, &token, 20, Some(Epoch(0)));
+            add_note(&mut ctx, &vk, &token, 1, None);
+            add_note(&mut ctx, &vk, &other, 100, None);
+            add_note(&mut ctx, &vk, &token, 10, Some(Epoch(1)));
+            let spent = add_note(&mut ctx, &vk, &token, 1, None);
+            ctx.spents.insert(spent);
+            (ctx, vk, token, other)
+        }
+
+        /// Check the order in which every note selection strategy spends the
+        /// unspent notes, with only the notes of the target token ordered
+        #[tokio::test]
+        async fn test_unspent_notes_in_order() {
+            use crate::queries::testing::TestClient;
+            use crate::queries::RPC;
+
+            let dir = tempfile::tempdir().unwrap();
+            let (mut ctx, vk, token, _other) = context_with_notes(dir.path());
+            let client = TestClient::new(RPC);
+            let target_asset_type =
+                cached_asset_type(&mut ctx, &token, Some(Epoch(1)));
+            let target = I128Sum::from_pair(target_asset_type, 10).unwrap();
+
+            for (selection, expected) in [
+                (NoteSelection::OldestFirst, vec![0, 1, 2, 3, 4, 5]),
+                (NoteSelection::FewestNotes, vec![2, 5, 0, 3, 1, 4]),
+                (NoteSelection::SmallestFirst, vec![3, 0, 5, 2, 1, 4]),
+                (NoteSelection::AvoidConversions, vec![0, 3, 5, 2, 1, 4]),
+            ] {
+                let notes = ctx
+                    .unspent_notes_in_order(
+                        &client,
+                        &vk,
+                        &target,
+                        selection,
+                        Epoch(1),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(notes, expected, "{selection}");
+            }
+            let mut notes = ctx
+                .unspent_notes_in_order(
+                    &client,
+                    &vk,
+                    &target,
+                    NoteSelection::Random,
+                    Epoch(1),
+                )
+                .await
+                .unwrap();
+            notes.sort();
+            assert_eq!(notes, vec![0, 1, 2, 3, 4, 5]);
+        }
+
+        /// Check that the dust notes to consolidate are the smallest unspent
+        /// notes of the token, which are the notes that the smallest-first
+        /// strategy spends first
+        #[tokio::test]
+        async fn test_dust_notes() {
+            use crate::queries::testing::TestClient;
+            use crate::queries::RPC;
+
+            let dir = tempfile::tempdir().unwrap();
+            let (mut ctx, vk, token, other) = context_with_notes(dir.path());
+            let client = TestClient::new(RPC);
+
+            for (max_notes, threshold, amount, notes) in [
+                (2, None, 6, 2),
+                (10, None, 36, 4),
+                (10, Some(10), 16, 3),
+                (10, Some(0), 0, 0),
+            ] {
+                let threshold = threshold.map(Amount::from);
+                let dust = ctx
+                    .dust_notes(&client, &vk, &token, max_notes, threshold)
+                    .await
+                    .unwrap();
+                assert_eq!(dust, (Amount::from(amount), notes));
+            }
+            let (amount, notes) = ctx
+                .dust_notes(&client, &vk, &other, 10, Some(Amount::from(50)))
+                .await
+                .unwrap();
+            assert_eq!((amount, notes), (Amount::from(1), 1));
+
+            // Consolidating the two smallest notes spends exactly them
+            let target_asset_type = cached_asset_type(&mut ctx, &token, None);
+            let target = I128Sum::from_pair(target_asset_type, 6).unwrap();
+            let order = ctx
+                .unspent_notes_in_order(
+                    &client,
+                    &vk,
+                    &target,
+                    NoteSelection::SmallestFirst,
+                    Epoch(1),
+                )
+                .await
+                .unwrap();
+            assert_eq!(order[..2], [3, 0]);
         }
     }
 }
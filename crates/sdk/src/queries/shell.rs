@@ -19,6 +19,7 @@ use namada_core::token::{Denomination, MaspDigitPos};
 use namada_core::uint::Uint;
 use namada_state::{DBIter, LastBlock, StateRead, StorageHasher, DB};
 use namada_storage::{ResultExt, StorageRead};
+use namada_token::conversion::MaspRewardEstimate;
 #[cfg(any(test, feature = "async-client"))]
 use namada_tx::data::TxResult;
 
@@ -95,6 +96,10 @@ router! {SHELL,
     // Conversion state access - read conversion
     ( "masp_reward_tokens" ) -> Vec<MaspTokenRewardData> = masp_reward_tokens,
 
+    // Estimate the MASP rewards of a token in the current and next epochs
+    ( "masp_reward_estimates" / [token: Address] / [epochs: u64] )
+        -> Vec<MaspRewardEstimate> = masp_reward_estimates,
+
     // Block results access - read bit-vec
     ( "results" ) -> Vec<BlockResults> = read_results,
 
@@ -299,6 +304,33 @@ where
     Ok(data)
 }
 
+/// Query to estimate the MASP rewards of a token in the current epoch and in
+/// the given number of epochs after it.
+fn masp_reward_estimates<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    token: Address,
+    epochs: u64,
+) -> namada_storage::Result<Vec<MaspRewardEstimate>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let is_reward_token = ctx
+        .state
+        .in_mem()
+        .conversion_state
+        .tokens
+        .values()
+        .any(|reward_token| *reward_token == token);
+    if !is_reward_token {
+        return Err(namada_storage::Error::new(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("The token {} doesn't earn MASP rewards", &token),
+        )));
+    }
+    namada_token::conversion::estimate_masp_rewards(ctx.state, &token, epochs)
+}
+
 fn epoch<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
 ) -> namada_storage::Result<Epoch>
@@ -5,12 +5,13 @@ use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_core::address::Address;
 use namada_core::key::common;
-use namada_core::storage::Epoch;
+use namada_core::storage::{BlockHeight, Epoch};
 use namada_core::token;
 use namada_proof_of_stake::parameters::PosParams;
 use namada_proof_of_stake::queries::{
     find_delegation_validators, find_delegations,
 };
+use namada_proof_of_stake::simulation::ValidatorSetSnapshot;
 use namada_proof_of_stake::slashing::{
     find_all_enqueued_slashes, find_all_slashes,
 };
@@ -18,22 +19,27 @@ use namada_proof_of_stake::storage::{
     bond_handle, read_all_validator_addresses,
     read_below_capacity_validator_set_addresses_with_stake,
     read_consensus_validator_set_addresses_with_stake, read_pos_params,
-    read_total_stake, read_validator_avatar, read_validator_description,
-    read_validator_discord_handle, read_validator_email,
-    read_validator_last_slash_epoch, read_validator_max_commission_rate_change,
-    read_validator_stake, read_validator_website, unbond_handle,
-    validator_commission_rate_handle, validator_incoming_redelegations_handle,
-    validator_slashes_handle, validator_state_handle,
+    read_total_stake, read_validator_avatar, read_validator_commission_rate,
+    read_validator_description, read_validator_discord_handle,
+    read_validator_email, read_validator_last_slash_epoch,
+    read_validator_max_commission_rate_change, read_validator_stake,
+    read_validator_website, unbond_handle,
+    validator_incoming_redelegations_handle, validator_slashes_handle,
+    validator_state_handle,
 };
 use namada_proof_of_stake::types::{
-    BondId, BondsAndUnbondsDetail, BondsAndUnbondsDetails, CommissionPair,
-    Slash, ValidatorMetaData, ValidatorState, WeightedValidator,
+    BondId, BondRewardsProjection, BondsAndUnbondsDetail,
+    BondsAndUnbondsDetails, CommissionPair, CommissionRateChangeEvent,
+    ConsensusKeyRotation, DelegatorSlashLoss, PendingUnbondsAndRedelegations,
+    Slash, ValidatorHistoryDetails, ValidatorLivenessDetails,
+    ValidatorMetaData, ValidatorState, WeightedValidator,
 };
 use namada_proof_of_stake::{bond_amount, query_reward_tokens};
 use namada_state::{DBIter, StorageHasher, DB};
 use namada_storage::collections::lazy_map;
-use namada_storage::OptionExt;
+use namada_storage::{OptionExt, ResultExt};
 
+use crate::events::log::dumb_queries;
 use crate::queries::types::RequestCtx;
 
 // PoS validity predicate queries
@@ -43,6 +49,9 @@ router! {POS,
 
         ( "consensus_key" / [addr: Address] ) -> Option<common::PublicKey> = consensus_key,
 
+        ( "consensus_key_rotation" / [addr: Address] )
+            -> ConsensusKeyRotation = consensus_key_rotation,
+
         ( "addresses" / [epoch: opt Epoch] )
             -> HashSet<Address> = validator_addresses,
 
@@ -66,6 +75,12 @@ router! {POS,
 
         ( "last_infraction_epoch" / [validator: Address] )
             -> Option<Epoch> = validator_last_infraction_epoch,
+
+        ( "history" / [validator: Address] / [start: Epoch] / [end: opt Epoch] )
+            -> ValidatorHistoryDetails = validator_history,
+
+        ( "liveness" / [validator: Address] )
+            -> ValidatorLivenessDetails = validator_liveness,
     },
 
     ( "validator_set" ) = {
@@ -98,6 +113,9 @@ router! {POS,
     ( "rewards" / [validator: Address] / [source: opt Address] )
         -> token::Amount = rewards,
 
+    ( "rewards_projection" / [validator: Address] / [amount: token::DenominatedAmount] )
+        -> BondRewardsProjection = rewards_projection,
+
     ( "bond_with_slashing" / [source: Address] / [validator: Address] / [epoch: opt Epoch] )
         -> token::Amount = bond_with_slashing,
 
@@ -117,6 +135,18 @@ router! {POS,
     ( "bonds_and_unbonds" / [source: opt Address] / "to" / [validator: opt Address] )
         -> BondsAndUnbondsDetails = bonds_and_unbonds,
 
+    ( "pending_unbonds_and_redelegations" / [delegator: Address] )
+        -> PendingUnbondsAndRedelegations = pending_unbonds_and_redelegations,
+
+    ( "delegator_slash_report" / [delegator: Address] )
+        -> Vec<DelegatorSlashLoss> = delegator_slash_report,
+
+    ( "validator_set_snapshot" )
+        -> ValidatorSetSnapshot = validator_set_snapshot,
+
+    ( "commission_rate_changes" / [validator: Address] )
+        -> Vec<CommissionRateChangeEvent> = commission_rate_changes,
+
     ( "enqueued_slashes" )
         -> HashMap<Address, BTreeMap<Epoch, Vec<Slash>>> = enqueued_slashes,
 
@@ -216,6 +246,23 @@ where
     )
 }
 
+/// Find the consensus key of a validator account and its pending change.
+fn consensus_key_rotation<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    addr: Address,
+) -> namada_storage::Result<ConsensusKeyRotation>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let current_epoch = ctx.state.in_mem().last_epoch;
+    namada_proof_of_stake::queries::consensus_key_rotation(
+        ctx.state,
+        &addr,
+        current_epoch,
+    )
+}
+
 /// Find if the given address is a delegator
 fn is_delegator<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -253,10 +300,16 @@ where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    let epoch = epoch.unwrap_or(ctx.state.in_mem().last_epoch);
+    let current_epoch = ctx.state.in_mem().last_epoch;
+    let epoch = epoch.unwrap_or(current_epoch);
     let params = read_pos_params(ctx.state)?;
-    let commission_rate = validator_commission_rate_handle(&validator)
-        .get(ctx.state, epoch, &params)?;
+    let commission_rate = read_validator_commission_rate(
+        ctx.state,
+        &params,
+        &validator,
+        current_epoch,
+        epoch,
+    )?;
     let max_commission_change_per_epoch =
         read_validator_max_commission_rate_change(ctx.state, &validator)?;
 
@@ -328,6 +381,87 @@ where
     read_validator_last_slash_epoch(ctx.state, &validator)
 }
 
+/// Get the commission rate, stake and state of a validator in every epoch from
+/// `start` up to `end` or current when `None`, and the validator's slashes in
+/// these epochs
+fn validator_history<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+    start: Epoch,
+    end: Option<Epoch>,
+) -> namada_storage::Result<ValidatorHistoryDetails>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let current_epoch = ctx.state.in_mem().last_epoch;
+    let end = end.unwrap_or(current_epoch);
+    namada_proof_of_stake::queries::validator_history(
+        ctx.state,
+        &validator,
+        start,
+        end,
+        current_epoch,
+    )
+}
+
+/// Project the rewards that a new bond of the given amount to a validator would
+/// earn
+fn rewards_projection<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+    amount: token::DenominatedAmount,
+) -> namada_storage::Result<BondRewardsProjection>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let amount = amount
+        .scale(token::NATIVE_MAX_DECIMAL_PLACES)
+        .into_storage_result()?;
+    let current_epoch = ctx.state.in_mem().last_epoch;
+    // The votes of the last committed block are recorded in the next one
+    let last_vote_height = BlockHeight(
+        ctx.state
+            .in_mem()
+            .get_last_block_height()
+            .0
+            .saturating_sub(1),
+    );
+    namada_proof_of_stake::rewards::project_bond_rewards(
+        ctx.state,
+        &validator,
+        amount,
+        current_epoch,
+        last_vote_height,
+    )
+}
+
+/// Get the liveness of a validator in the current window of blocks and its
+/// jail history
+fn validator_liveness<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+) -> namada_storage::Result<ValidatorLivenessDetails>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    // The votes of the last committed block are recorded in the next one
+    let last_vote_height = BlockHeight(
+        ctx.state
+            .in_mem()
+            .get_last_block_height()
+            .0
+            .saturating_sub(1),
+    );
+    namada_proof_of_stake::queries::validator_liveness(
+        ctx.state,
+        &validator,
+        last_vote_height,
+    )
+}
+
 /// Get the total stake of a validator at the given epoch or current when
 /// `None`. The total stake is a sum of validator's self-bonds and delegations
 /// to their address.
@@ -565,6 +699,39 @@ where
     )
 }
 
+/// Find the pending unbonds and the still slashable redelegations of the given
+/// `delegator`
+fn pending_unbonds_and_redelegations<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    delegator: Address,
+) -> namada_storage::Result<PendingUnbondsAndRedelegations>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let current_epoch = ctx.state.in_mem().last_epoch;
+    namada_proof_of_stake::queries::find_pending_unbonds_and_redelegations(
+        ctx.state,
+        &delegator,
+        current_epoch,
+    )
+}
+
+/// Find the losses of the bonds and unbonds of the given `delegator` to the
+/// slashes of their validators
+fn delegator_slash_report<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    delegator: Address,
+) -> namada_storage::Result<Vec<DelegatorSlashLoss>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    namada_proof_of_stake::queries::delegator_slash_report(
+        ctx.state, &delegator,
+    )
+}
+
 /// Find all the validator addresses to whom the given `owner` address has
 /// some delegation in any epoch
 fn delegation_validators<D, H, V, T>(
@@ -617,6 +784,41 @@ where
     find_all_slashes(ctx.state)
 }
 
+/// A snapshot of the validators in the current epoch to simulate the validator
+/// sets from
+fn validator_set_snapshot<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> namada_storage::Result<ValidatorSetSnapshot>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let current_epoch = ctx.state.in_mem().last_epoch;
+    namada_proof_of_stake::queries::validator_set_snapshot(
+        ctx.state,
+        current_epoch,
+    )
+}
+
+/// The commission rate changes of a validator that were announced in the
+/// blocks still held in the event log of the node
+fn commission_rate_changes<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+) -> namada_storage::Result<Vec<CommissionRateChangeEvent>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let matcher = dumb_queries::QueryMatcher::commission_change(&validator);
+    ctx.event_log
+        .iter_with_matcher(matcher)
+        .map(|event| {
+            CommissionRateChangeEvent::try_from(event).into_storage_result()
+        })
+        .collect()
+}
+
 /// Enqueued slashes
 fn enqueued_slashes<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
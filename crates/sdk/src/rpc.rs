@@ -32,10 +32,15 @@ use namada_ibc::storage::{
 };
 use namada_parameters::{storage as params_storage, EpochDuration};
 use namada_proof_of_stake::parameters::PosParams;
+use namada_proof_of_stake::simulation::ValidatorSetSnapshot;
 use namada_proof_of_stake::types::{
-    BondsAndUnbondsDetails, CommissionPair, ValidatorMetaData, ValidatorState,
+    BondRewardsProjection, BondsAndUnbondsDetails, CommissionPair,
+    CommissionRateChangeEvent, ConsensusKeyRotation, DelegatorSlashLoss,
+    PendingUnbondsAndRedelegations, ValidatorHistoryDetails,
+    ValidatorLivenessDetails, ValidatorMetaData, ValidatorState,
 };
 use namada_state::LastBlock;
+use namada_token::conversion::MaspRewardEstimate;
 use namada_tx::data::{ResultCode, TxResult};
 use serde::Serialize;
 
@@ -330,6 +335,20 @@ pub async fn query_masp_reward_tokens<C: crate::queries::Client + Sync>(
     convert_response::<C, _>(RPC.shell().masp_reward_tokens(client).await)
 }
 
+/// Query to estimate the MASP rewards of a token in the current epoch and in
+/// the given number of epochs after it. The projection spans at most a year.
+pub async fn query_masp_reward_estimates<C: crate::queries::Client + Sync>(
+    client: &C,
+    token: &Address,
+    epochs: u64,
+) -> Result<Vec<MaspRewardEstimate>, Error> {
+    convert_response::<C, _>(
+        RPC.shell()
+            .masp_reward_estimates(client, token, &epochs)
+            .await,
+    )
+}
+
 /// Query a wasm code hash
 pub async fn query_wasm_code_hash(
     context: &impl Namada,
@@ -877,6 +896,130 @@ pub async fn query_metadata<C: crate::queries::Client + Sync>(
     Ok((metadata, commission_info))
 }
 
+/// Query and return the commission rate, stake and state of a validator in
+/// every epoch from `start` up to `end` or current when `None`, together with
+/// the validator's slashes in these epochs
+pub async fn query_validator_history<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+    start: Epoch,
+    end: Option<Epoch>,
+) -> Result<ValidatorHistoryDetails, Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .validator_history(client, validator, &start, &end)
+            .await,
+    )
+}
+
+/// Query and return the projected rewards of a new bond of the given amount to
+/// a validator, including its nominal and real APR
+pub async fn query_rewards_projection<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+    amount: token::Amount,
+) -> Result<BondRewardsProjection, Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .rewards_projection(client, validator, &amount.native_denominated())
+            .await,
+    )
+}
+
+/// Query and return the consensus key of a validator and its pending change
+pub async fn query_consensus_key_rotation<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+) -> Result<ConsensusKeyRotation, Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .consensus_key_rotation(client, validator)
+            .await,
+    )
+}
+
+/// Query and return the pending unbonds and the still slashable redelegations
+/// of a delegator
+pub async fn query_pending_unbonds_and_redelegations<
+    C: crate::queries::Client + Sync,
+>(
+    client: &C,
+    delegator: &Address,
+) -> Result<PendingUnbondsAndRedelegations, Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .pending_unbonds_and_redelegations(client, delegator)
+            .await,
+    )
+}
+
+/// Query and return the losses of the bonds and unbonds of a delegator to the
+/// slashes of their validators
+pub async fn query_delegator_slash_report<C: crate::queries::Client + Sync>(
+    client: &C,
+    delegator: &Address,
+) -> Result<Vec<DelegatorSlashLoss>, Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .delegator_slash_report(client, delegator)
+            .await,
+    )
+}
+
+/// Query and return a snapshot of the validators in the current epoch to
+/// simulate the validator sets from
+pub async fn query_validator_set_snapshot<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<ValidatorSetSnapshot, Error> {
+    convert_response::<C, _>(
+        RPC.vp().pos().validator_set_snapshot(client).await,
+    )
+}
+
+/// Query and return the commission rate changes of a validator that were
+/// announced in the recent blocks still held in the event log of the node
+pub async fn query_commission_rate_changes<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+) -> Result<Vec<CommissionRateChangeEvent>, Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .commission_rate_changes(client, validator)
+            .await,
+    )
+}
+
+/// A Tendermint query to subscribe to the [`CommissionRateChangeEvent`]s of a
+/// validator, so that its delegators are notified of the changes of its
+/// commission rate before they take effect
+pub fn commission_rate_change_event_query(validator: &Address) -> Query {
+    Query::default().and_eq(
+        format!(
+            "{}.{}",
+            crate::events::EventType::CommissionChange,
+            CommissionRateChangeEvent::VALIDATOR_ATTR
+        ),
+        validator.to_string(),
+    )
+}
+
+/// Query and return the liveness of a validator in the current window of blocks
+/// and its jail history
+pub async fn query_validator_liveness<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+) -> Result<ValidatorLivenessDetails, Error> {
+    convert_response::<C, _>(
+        RPC.vp().pos().validator_liveness(client, validator).await,
+    )
+}
+
 /// Query and return the incoming redelegation epoch for a given pair of source
 /// validator and delegator, if there is any.
 pub async fn query_incoming_redelegations<C: crate::queries::Client + Sync>(
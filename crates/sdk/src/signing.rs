@@ -7,6 +7,7 @@ use borsh_ext::BorshSerializeExt;
 use data_encoding::HEXLOWER;
 use itertools::Itertools;
 use masp_primitives::asset_type::AssetType;
+use masp_primitives::memo::MemoBytes;
 use masp_primitives::transaction::components::sapling::fees::{
     InputView, OutputView,
 };
@@ -51,9 +52,10 @@ use crate::tx::{
     TX_CHANGE_COMMISSION_WASM, TX_CHANGE_CONSENSUS_KEY_WASM,
     TX_CHANGE_METADATA_WASM, TX_CLAIM_REWARDS_WASM,
     TX_DEACTIVATE_VALIDATOR_WASM, TX_IBC_WASM, TX_INIT_ACCOUNT_WASM,
-    TX_INIT_PROPOSAL, TX_REACTIVATE_VALIDATOR_WASM, TX_REDELEGATE_WASM,
-    TX_RESIGN_STEWARD, TX_REVEAL_PK, TX_TRANSFER_WASM, TX_UNBOND_WASM,
-    TX_UNJAIL_VALIDATOR_WASM, TX_UPDATE_ACCOUNT_WASM,
+    TX_INIT_PROPOSAL, TX_LIQUID_BOND_WASM, TX_REACTIVATE_VALIDATOR_WASM,
+    TX_REDEEM_SHARES_WASM, TX_REDELEGATE_WASM, TX_RESIGN_STEWARD, TX_REVEAL_PK,
+    TX_SCHEDULE_COMMISSION_WASM, TX_SET_RESTAKE_WASM, TX_TRANSFER_WASM,
+    TX_UNBOND_WASM, TX_UNJAIL_VALIDATOR_WASM, TX_UPDATE_ACCOUNT_WASM,
     TX_UPDATE_STEWARD_COMMISSION, TX_VOTE_PROPOSAL, TX_WITHDRAW_WASM,
     VP_USER_WASM,
 };
@@ -516,6 +518,8 @@ pub async fn validate_fee_and_gen_unshield<N: Namada>(
                         &target,
                         &args.fee_token,
                         fee_amount,
+                        MemoBytes::empty(),
+                        args.note_selection,
                     !(args.dry_run || args.dry_run_wrapper)
                     )
                     .await
@@ -1525,6 +1529,50 @@ pub async fn to_ledger_vector(
                 to_ledger_decimal(&bond.amount.to_string_native())
             ),
         ]);
+    } else if code_sec.tag == Some(TX_LIQUID_BOND_WASM.to_string()) {
+        let bond = pos::LiquidBond::try_from_slice(
+            &tx.data()
+                .ok_or_else(|| Error::Other("Invalid Data".to_string()))?,
+        )
+        .map_err(|err| {
+            Error::from(EncodingError::Conversion(err.to_string()))
+        })?;
+
+        tv.name = "Liquid_Bond_0".to_string();
+
+        let details = vec![
+            format!("Source : {}", bond.source),
+            format!("Validator : {}", bond.validator),
+            format!(
+                "Amount : NAM {}",
+                to_ledger_decimal(&bond.amount.to_string_native())
+            ),
+        ];
+        tv.output.push("Type : Liquid Bond".to_string());
+        tv.output.extend(details.clone());
+        tv.output_expert.extend(details);
+    } else if code_sec.tag == Some(TX_REDEEM_SHARES_WASM.to_string()) {
+        let redeem = pos::RedeemShares::try_from_slice(
+            &tx.data()
+                .ok_or_else(|| Error::Other("Invalid Data".to_string()))?,
+        )
+        .map_err(|err| {
+            Error::from(EncodingError::Conversion(err.to_string()))
+        })?;
+
+        tv.name = "Redeem_Shares_0".to_string();
+
+        let details = vec![
+            format!("Owner : {}", redeem.owner),
+            format!("Validator : {}", redeem.validator),
+            format!(
+                "Shares : {}",
+                to_ledger_decimal(&redeem.shares.to_string_native())
+            ),
+        ];
+        tv.output.push("Type : Redeem Shares".to_string());
+        tv.output.extend(details.clone());
+        tv.output_expert.extend(details);
     } else if code_sec.tag == Some(TX_UNBOND_WASM.to_string()) {
         let unbond = pos::Unbond::try_from_slice(
             &tx.data()
@@ -1603,6 +1651,34 @@ pub async fn to_ledger_vector(
         }
         tv.output_expert
             .push(format!("Validator : {}", claim.validator));
+    } else if code_sec.tag == Some(TX_SET_RESTAKE_WASM.to_string()) {
+        let restake = pos::Restake::try_from_slice(
+            &tx.data()
+                .ok_or_else(|| Error::Other("Invalid Data".to_string()))?,
+        )
+        .map_err(|err| {
+            Error::from(EncodingError::Conversion(err.to_string()))
+        })?;
+
+        tv.name = "Set_Restake_0".to_string();
+
+        let interval = match restake.interval {
+            Some(interval) => format!("Interval : {} epochs", interval),
+            None => "Interval : disabled".to_string(),
+        };
+        tv.output.push("Type : Set Restake".to_string());
+        if let Some(source) = restake.source.as_ref() {
+            tv.output.push(format!("Source : {}", source));
+        }
+        tv.output.push(format!("Validator : {}", restake.validator));
+        tv.output.push(interval.clone());
+
+        if let Some(source) = restake.source.as_ref() {
+            tv.output_expert.push(format!("Source : {}", source));
+        }
+        tv.output_expert
+            .push(format!("Validator : {}", restake.validator));
+        tv.output_expert.push(interval);
     } else if code_sec.tag == Some(TX_CHANGE_COMMISSION_WASM.to_string()) {
         let commission_change = pos::CommissionChange::try_from_slice(
             &tx.data()
@@ -1624,6 +1700,29 @@ pub async fn to_ledger_vector(
             format!("New rate : {}", commission_change.new_rate),
             format!("Validator : {}", commission_change.validator),
         ]);
+    } else if code_sec.tag == Some(TX_SCHEDULE_COMMISSION_WASM.to_string()) {
+        let commission_change = pos::ScheduledCommissionChange::try_from_slice(
+            &tx.data()
+                .ok_or_else(|| Error::Other("Invalid Data".to_string()))?,
+        )
+        .map_err(|err| {
+            Error::from(EncodingError::Conversion(err.to_string()))
+        })?;
+
+        tv.name = "Schedule_Commission_0".to_string();
+
+        tv.output.extend(vec![
+            format!("Type : Schedule commission"),
+            format!("New rate : {}", commission_change.new_rate),
+            format!("Validator : {}", commission_change.validator),
+            format!("Effective epoch : {}", commission_change.effective_epoch),
+        ]);
+
+        tv.output_expert.extend(vec![
+            format!("New rate : {}", commission_change.new_rate),
+            format!("Validator : {}", commission_change.validator),
+            format!("Effective epoch : {}", commission_change.effective_epoch),
+        ]);
     } else if code_sec.tag == Some(TX_CHANGE_METADATA_WASM.to_string()) {
         let metadata_change = pos::MetaDataChange::try_from_slice(
             &tx.data()
@@ -9,6 +9,7 @@ use std::time::Duration;
 use borsh::BorshSerialize;
 use borsh_ext::BorshSerializeExt;
 use masp_primitives::asset_type::AssetType;
+use masp_primitives::memo::MemoBytes;
 use masp_primitives::transaction::builder;
 use masp_primitives::transaction::builder::Builder;
 use masp_primitives::transaction::components::sapling::fees::{
@@ -18,7 +19,8 @@ use masp_primitives::transaction::components::transparent::fees::{
     InputView as TransparentInputView, OutputView as TransparentOutputView,
 };
 use masp_primitives::transaction::components::I128Sum;
-use namada_account::{InitAccount, UpdateAccount};
+use masp_primitives::zip32::ExtendedSpendingKey;
+use namada_account::{InitAccount, UpdateAccount, UpdateSpendingPolicy};
 use namada_core::address::{Address, InternalAddress, MASP};
 use namada_core::dec::Dec;
 use namada_core::hash::Hash;
@@ -31,7 +33,9 @@ use namada_core::ibc::core::host::types::identifiers::{ChannelId, PortId};
 use namada_core::ibc::primitives::{Msg, Timestamp as IbcTimestamp};
 use namada_core::ibc::{IbcShieldedTransfer, MsgShieldedTransfer};
 use namada_core::key::*;
-use namada_core::masp::{AssetData, TransferSource, TransferTarget};
+use namada_core::masp::{
+    AssetData, PaymentRequest, TransferSource, TransferTarget,
+};
 use namada_core::storage::Epoch;
 use namada_core::time::DateTimeUtc;
 use namada_core::{storage, token};
@@ -44,6 +48,7 @@ use namada_governance::storage::proposal::{
 };
 use namada_governance::storage::vote::ProposalVote;
 use namada_ibc::storage::channel_key;
+use namada_proof_of_stake::liquid_staking::share_token_address;
 use namada_proof_of_stake::parameters::PosParams;
 use namada_proof_of_stake::types::{CommissionPair, ValidatorState};
 use namada_token::storage_key::balance_key;
@@ -52,12 +57,14 @@ use namada_tx::data::pgf::UpdateStewardCommission;
 use namada_tx::data::{pos, ResultCode, TxResult};
 pub use namada_tx::{Signature, *};
 
-use crate::args::{self, InputAmount};
+use crate::args::{self, InputAmount, TxBuilder};
 use crate::control_flow::time;
 use crate::error::{EncodingError, Error, QueryError, Result, TxSubmitError};
 use crate::io::Io;
 use crate::masp::TransferErr::Build;
-use crate::masp::{ShieldedContext, ShieldedTransfer};
+use crate::masp::{
+    to_viewing_key, NoteSelection, ShieldedContext, ShieldedTransfer,
+};
 use crate::queries::Client;
 use crate::rpc::{
     self, query_wasm_code_hash, validate_amount, InnerTxResult,
@@ -87,6 +94,9 @@ pub const TX_VOTE_PROPOSAL: &str = "tx_vote_proposal.wasm";
 pub const TX_REVEAL_PK: &str = "tx_reveal_pk.wasm";
 /// Update validity predicate WASM path
 pub const TX_UPDATE_ACCOUNT_WASM: &str = "tx_update_account.wasm";
+/// Update spending policy WASM path
+pub const TX_UPDATE_SPENDING_POLICY_WASM: &str =
+    "tx_update_spending_policy.wasm";
 /// Transfer transaction WASM path
 pub const TX_TRANSFER_WASM: &str = "tx_transfer.wasm";
 /// IBC transaction WASM path
@@ -101,11 +111,20 @@ pub const TX_UNBOND_WASM: &str = "tx_unbond.wasm";
 pub const TX_WITHDRAW_WASM: &str = "tx_withdraw.wasm";
 /// Claim-rewards WASM path
 pub const TX_CLAIM_REWARDS_WASM: &str = "tx_claim_rewards.wasm";
+/// Liquid bond WASM path
+pub const TX_LIQUID_BOND_WASM: &str = "tx_liquid_bond.wasm";
+/// Redeem shares WASM path
+pub const TX_REDEEM_SHARES_WASM: &str = "tx_redeem_shares.wasm";
+/// Set-restake WASM path
+pub const TX_SET_RESTAKE_WASM: &str = "tx_set_restake.wasm";
 /// Bridge pool WASM path
 pub const TX_BRIDGE_POOL_WASM: &str = "tx_bridge_pool.wasm";
 /// Change commission WASM path
 pub const TX_CHANGE_COMMISSION_WASM: &str =
     "tx_change_validator_commission.wasm";
+/// Schedule commission change WASM path
+pub const TX_SCHEDULE_COMMISSION_WASM: &str =
+    "tx_schedule_validator_commission.wasm";
 /// Change consensus key WASM path
 pub const TX_CHANGE_CONSENSUS_KEY_WASM: &str = "tx_change_consensus_key.wasm";
 /// Change validator metadata WASM path
@@ -569,6 +588,7 @@ pub async fn build_validator_commission_change(
         tx: tx_args,
         validator,
         rate,
+        effective_epoch,
         tx_code_path,
     }: &args::CommissionRateChange,
 ) -> Result<(Tx, SigningTxData)> {
@@ -604,12 +624,49 @@ pub async fn build_validator_commission_change(
             )));
         }
 
-        let pipeline_epoch_minus_one = epoch + params.pipeline_len - 1;
+        let pipeline_epoch = epoch + params.pipeline_len;
+        let effective_epoch = effective_epoch.unwrap_or(pipeline_epoch);
+        if effective_epoch < pipeline_epoch {
+            edisplay_line!(
+                context.io(),
+                "The new rate cannot take effect in epoch {} before the \
+                 pipeline epoch {}.",
+                effective_epoch,
+                pipeline_epoch
+            );
+            if !tx_args.force {
+                return Err(Error::from(
+                    TxSubmitError::CommissionRateChangeBeforePipeline(
+                        effective_epoch,
+                        pipeline_epoch,
+                    ),
+                ));
+            }
+        }
+        let last_schedule_epoch =
+            pipeline_epoch + params.max_commission_schedule_len;
+        if effective_epoch > last_schedule_epoch {
+            edisplay_line!(
+                context.io(),
+                "The new rate cannot take effect in epoch {} after the last \
+                 epoch {} in which a change can be scheduled.",
+                effective_epoch,
+                last_schedule_epoch
+            );
+            if !tx_args.force {
+                return Err(Error::from(
+                    TxSubmitError::CommissionRateChangeAfterScheduleHorizon(
+                        effective_epoch,
+                        last_schedule_epoch,
+                    ),
+                ));
+            }
+        }
 
         match rpc::query_commission_rate(
             context.client(),
             &validator,
-            Some(pipeline_epoch_minus_one),
+            Some(effective_epoch.prev()),
         )
         .await?
         {
@@ -664,23 +721,44 @@ pub async fn build_validator_commission_change(
         }
     }
 
-    let data = pos::CommissionChange {
-        validator: validator.clone(),
-        new_rate: *rate,
+    let tx = match *effective_epoch {
+        Some(effective_epoch) => {
+            let data = pos::ScheduledCommissionChange {
+                validator: validator.clone(),
+                new_rate: *rate,
+                effective_epoch,
+            };
+            build(
+                context,
+                tx_args,
+                tx_code_path.clone(),
+                data,
+                do_nothing,
+                unshield,
+                fee_amount,
+                &signing_data.fee_payer,
+            )
+            .await?
+        }
+        None => {
+            let data = pos::CommissionChange {
+                validator: validator.clone(),
+                new_rate: *rate,
+            };
+            build(
+                context,
+                tx_args,
+                tx_code_path.clone(),
+                data,
+                do_nothing,
+                unshield,
+                fee_amount,
+                &signing_data.fee_payer,
+            )
+            .await?
+        }
     };
-
-    build(
-        context,
-        tx_args,
-        tx_code_path.clone(),
-        data,
-        do_nothing,
-        unshield,
-        fee_amount,
-        &signing_data.fee_payer,
-    )
-    .await
-    .map(|tx| (tx, signing_data))
+    Ok((tx, signing_data))
 }
 
 /// Submit validator metadata change
@@ -1544,6 +1622,79 @@ pub async fn build_claim_rewards(
     .map(|tx| (tx, signing_data))
 }
 
+/// Submit a transaction to enable or disable the restaking of the rewards of a
+/// bond
+pub async fn build_restake(
+    context: &impl Namada,
+    args::Restake {
+        tx: tx_args,
+        validator,
+        source,
+        interval,
+        tx_code_path,
+    }: &args::Restake,
+) -> Result<(Tx, SigningTxData)> {
+    let default_address = source.clone().unwrap_or(validator.clone());
+    let default_signer = Some(default_address.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(default_address),
+        default_signer,
+    )
+    .await?;
+    let (fee_amount, _, unshield) = validate_fee_and_gen_unshield(
+        context,
+        tx_args,
+        &signing_data.fee_payer,
+    )
+    .await?;
+
+    // Require a positive interval of epochs between the restakes
+    if *interval == Some(0) {
+        edisplay_line!(
+            context.io(),
+            "The requested restake interval is 0. An interval of at least one \
+             epoch must be requested."
+        );
+        if !tx_args.force {
+            return Err(Error::from(TxSubmitError::RestakeIntervalIsZero));
+        }
+    }
+
+    // Check that the validator address is actually a validator
+    let validator =
+        known_validator_or_err(validator.clone(), tx_args.force, context)
+            .await?;
+
+    // Check that the source address exists on chain
+    let source = match source.clone() {
+        Some(source) => source_exists_or_err(source, tx_args.force, context)
+            .await
+            .map(Some),
+        None => Ok(source.clone()),
+    }?;
+
+    let data = pos::Restake {
+        validator,
+        source,
+        interval: *interval,
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        unshield,
+        fee_amount,
+        &signing_data.fee_payer,
+    )
+    .await
+    .map(|tx| (tx, signing_data))
+}
+
 /// Submit a transaction to unbond
 pub async fn build_unbond(
     context: &impl Namada,
@@ -1892,6 +2043,189 @@ pub async fn build_bond(
     .map(|tx| (tx, signing_data))
 }
 
+/// Submit a transaction to liquid bond tokens in exchange for the share tokens
+/// of a validator
+pub async fn build_liquid_bond(
+    context: &impl Namada,
+    args::LiquidBond {
+        tx: tx_args,
+        validator,
+        amount,
+        source,
+        tx_code_path,
+    }: &args::LiquidBond,
+) -> Result<(Tx, SigningTxData)> {
+    // Require a positive amount of tokens to be bonded
+    if amount.is_zero() {
+        edisplay_line!(
+            context.io(),
+            "The requested bond amount is 0. A positive amount must be \
+             requested."
+        );
+        if !tx_args.force {
+            return Err(Error::from(TxSubmitError::BondIsZero));
+        }
+    }
+
+    // The validator must actually be a validator
+    let validator =
+        known_validator_or_err(validator.clone(), tx_args.force, context)
+            .await?;
+
+    // Check that the source address exists on chain and is not a validator
+    let is_src_also_val = rpc::is_validator(context.client(), source).await?;
+    let source =
+        source_exists_or_err(source.clone(), tx_args.force, context).await?;
+    if is_src_also_val && source != validator {
+        edisplay_line!(
+            context.io(),
+            "The given source address {} is a validator. A validator is \
+             prohibited from bonding to another validator.",
+            &source
+        );
+        if !tx_args.force {
+            return Err(Error::from(TxSubmitError::InvalidBondPair(
+                source.clone(),
+                validator.clone(),
+            )));
+        }
+    }
+
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(source.clone()),
+        Some(source.clone()),
+    )
+    .await?;
+    let (fee_amount, updated_balance, unshield) =
+        validate_fee_and_gen_unshield(
+            context,
+            tx_args,
+            &signing_data.fee_payer,
+        )
+        .await?;
+
+    // Check the source balance
+    let native_token = context.native_token();
+    let check_balance = if updated_balance.source == source
+        && updated_balance.token == native_token
+    {
+        CheckBalance::Balance(updated_balance.post_balance)
+    } else {
+        CheckBalance::Query(balance_key(&native_token, &source))
+    };
+    check_balance_too_low_err(
+        &native_token,
+        &source,
+        *amount,
+        check_balance,
+        tx_args.force,
+        context,
+    )
+    .await?;
+
+    let data = pos::LiquidBond {
+        validator,
+        amount: *amount,
+        source,
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        unshield,
+        fee_amount,
+        &signing_data.fee_payer,
+    )
+    .await
+    .map(|tx| (tx, signing_data))
+}
+
+/// Submit a transaction to redeem the share tokens of a validator for an
+/// unbond
+pub async fn build_redeem_shares(
+    context: &impl Namada,
+    args::RedeemShares {
+        tx: tx_args,
+        validator,
+        shares,
+        owner,
+        tx_code_path,
+    }: &args::RedeemShares,
+) -> Result<(Tx, SigningTxData)> {
+    // Require a positive amount of shares to be redeemed
+    if shares.is_zero() {
+        edisplay_line!(
+            context.io(),
+            "The requested amount of shares to redeem is 0. A positive amount \
+             must be requested."
+        );
+        if !tx_args.force {
+            return Err(Error::from(TxSubmitError::RedeemSharesIsZero));
+        }
+    }
+
+    // The validator must actually be a validator
+    let validator =
+        known_validator_or_err(validator.clone(), tx_args.force, context)
+            .await?;
+    let share_token = share_token_address(&validator).ok_or_else(|| {
+        Error::Other(format!("The validator {validator} has no share token"))
+    })?;
+
+    // Check that the owner address exists on chain
+    let owner =
+        source_exists_or_err(owner.clone(), tx_args.force, context).await?;
+
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(owner.clone()),
+        Some(owner.clone()),
+    )
+    .await?;
+    let (fee_amount, _, unshield) = validate_fee_and_gen_unshield(
+        context,
+        tx_args,
+        &signing_data.fee_payer,
+    )
+    .await?;
+
+    // Check the owner's balance of shares
+    check_balance_too_low_err(
+        &share_token,
+        &owner,
+        *shares,
+        CheckBalance::Query(balance_key(&share_token, &owner)),
+        tx_args.force,
+        context,
+    )
+    .await?;
+
+    let data = pos::RedeemShares {
+        validator,
+        shares: *shares,
+        owner,
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        unshield,
+        fee_amount,
+        &signing_data.fee_payer,
+    )
+    .await
+    .map(|tx| (tx, signing_data))
+}
+
 /// Build a default proposal governance
 pub async fn build_default_proposal(
     context: &impl Namada,
@@ -2208,6 +2542,8 @@ pub async fn build_ibc_transfer(
         &TransferTarget::Address(Address::Internal(InternalAddress::Ibc)),
         &args.token,
         validated_amount,
+        MemoBytes::empty(),
+        args.tx.note_selection,
         !(args.tx.dry_run || args.tx.dry_run_wrapper),
     )
     .await?;
@@ -2490,6 +2826,10 @@ pub async fn build_transfer<N: Namada>(
 
     args.amount = InputAmount::Validated(validated_amount);
 
+    if let Some(request) = &args.payment_request {
+        check_payment_request(context, args, request, validated_amount).await?;
+    }
+
     let check_balance = if updated_balance.source == source
         && updated_balance.token == args.token
     {
@@ -2526,12 +2866,29 @@ pub async fn build_transfer<N: Namada>(
         _ => None,
     };
 
+    // The memo of a payment request is only revealed to the payee in the
+    // note of the payment
+    let memo = match args
+        .payment_request
+        .as_ref()
+        .and_then(|request| request.memo.as_ref())
+    {
+        Some(memo) => MemoBytes::from_bytes(memo.as_bytes()).map_err(|_| {
+            Error::Other(format!(
+                "The memo of the payment request is longer than {} bytes",
+                namada_core::masp::MAX_MEMO_LEN
+            ))
+        })?,
+        None => MemoBytes::empty(),
+    };
     let shielded_parts = construct_shielded_parts(
         context,
         &args.source,
         &args.target,
         &args.token,
         validated_amount,
+        memo,
+        args.tx.note_selection,
         !(args.tx.dry_run || args.tx.dry_run_wrapper),
     )
     .await?;
@@ -2592,6 +2949,60 @@ pub async fn build_transfer<N: Namada>(
     Ok((tx, signing_data, shielded_tx_epoch))
 }
 
+/// Build a shielded transfer from a spending key to its own default payment
+/// address that merges its smallest notes of a token into a single note
+pub async fn build_consolidate_notes<N: Namada>(
+    context: &N,
+    args: &mut args::TxConsolidateNotes,
+) -> Result<(Tx, SigningTxData, Option<Epoch>)> {
+    let dust_threshold = match args.dust_threshold {
+        Some(threshold) => Some(
+            validate_amount(context, threshold, &args.token, args.tx.force)
+                .await?
+                .amount(),
+        ),
+        None => None,
+    };
+    let spending_key = ExtendedSpendingKey::from(args.source);
+    let vk = to_viewing_key(&spending_key).vk;
+    let (amount, notes) = {
+        let mut shielded = context.shielded_mut().await;
+        let _ = shielded.load().await;
+        shielded
+            .dust_notes(
+                context.client(),
+                &vk,
+                &args.token,
+                args.max_notes,
+                dust_threshold,
+            )
+            .await?
+    };
+    if notes < 2 {
+        return Err(Error::from(TxSubmitError::NoNotesToConsolidate(
+            args.token.clone(),
+        )));
+    }
+    let amount = rpc::denominate_amount(
+        context.client(),
+        context.io(),
+        &args.token,
+        amount,
+    )
+    .await;
+    let (_diversifier, payment_address) = spending_key.default_address();
+    let mut transfer = args::TxTransfer {
+        tx: args.tx.clone().note_selection(NoteSelection::SmallestFirst),
+        source: TransferSource::ExtendedSpendingKey(args.source),
+        target: TransferTarget::PaymentAddress(payment_address.into()),
+        token: args.token.clone(),
+        amount: InputAmount::Unvalidated(amount),
+        payment_request: None,
+        tx_code_path: args.tx_code_path.clone(),
+    };
+    build_transfer(context, &mut transfer).await
+}
+
 // Construct the shielded part of the transaction, if any
 async fn construct_shielded_parts<N: Namada>(
     context: &N,
@@ -2599,6 +3010,8 @@ async fn construct_shielded_parts<N: Namada>(
     target: &TransferTarget,
     token: &Address,
     amount: token::DenominatedAmount,
+    memo: MemoBytes,
+    note_selection: NoteSelection,
     update_ctx: bool,
 ) -> Result<Option<(ShieldedTransfer, HashSet<AssetData>)>> {
     // Precompute asset types to increase chances of success in decoding
@@ -2611,7 +3024,14 @@ async fn construct_shielded_parts<N: Namada>(
         .await;
     let stx_result =
         ShieldedContext::<N::ShieldedUtils>::gen_shielded_transfer(
-            context, source, target, token, amount, update_ctx,
+            context,
+            source,
+            target,
+            token,
+            amount,
+            memo,
+            note_selection,
+            update_ctx,
         )
         .await;
 
@@ -2798,6 +3218,75 @@ pub async fn build_update_account(
     .map(|tx| (tx, signing_data))
 }
 
+/// Submit a transaction to update the spending policy of an account
+pub async fn build_update_spending_policy(
+    context: &impl Namada,
+    args::TxUpdateSpendingPolicy {
+        tx: tx_args,
+        tx_code_path,
+        addr,
+        limits,
+        allowed_recipients,
+        disallowed_recipients,
+        allowances,
+    }: &args::TxUpdateSpendingPolicy,
+) -> Result<(Tx, SigningTxData)> {
+    let default_signer = Some(addr.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(addr.clone()),
+        default_signer,
+    )
+    .await?;
+    let (fee_amount, _, unshield) = validate_fee_and_gen_unshield(
+        context,
+        tx_args,
+        &signing_data.fee_payer,
+    )
+    .await?;
+
+    let addr = if let Some(account) =
+        rpc::get_account_info(context.client(), addr).await?
+    {
+        account.address
+    } else if tx_args.force {
+        addr.clone()
+    } else {
+        return Err(Error::from(TxSubmitError::LocationDoesNotExist(
+            addr.clone(),
+        )));
+    };
+
+    let mut allowances_map: BTreeMap<_, BTreeMap<_, _>> = BTreeMap::new();
+    for (delegate, token, amount) in allowances {
+        allowances_map
+            .entry(delegate.clone())
+            .or_default()
+            .insert(token.clone(), *amount);
+    }
+    let data = UpdateSpendingPolicy {
+        addr,
+        limits: limits.iter().cloned().collect(),
+        allowed_recipients: allowed_recipients.iter().cloned().collect(),
+        disallowed_recipients: disallowed_recipients.iter().cloned().collect(),
+        allowances: allowances_map,
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        unshield,
+        fee_amount,
+        &signing_data.fee_payer,
+    )
+    .await
+    .map(|tx| (tx, signing_data))
+}
+
 /// Submit a custom transaction
 pub async fn build_custom(
     context: &impl Namada,
@@ -2907,6 +3396,8 @@ pub async fn gen_ibc_shielded_transfer<N: Namada>(
             &args.target,
             &token,
             validated_amount,
+            MemoBytes::empty(),
+            NoteSelection::default(),
             true,
         )
         .await
@@ -3081,6 +3572,60 @@ async fn target_exists_or_err(
     .await
 }
 
+/// Checks that the transfer pays the given payment request and that the
+/// request hasn't expired. Force overrides this.
+async fn check_payment_request(
+    context: &impl Namada,
+    args: &args::TxTransfer,
+    request: &PaymentRequest,
+    amount: token::DenominatedAmount,
+) -> Result<()> {
+    let expired = match request.expiry {
+        Some(expiry) if expiry < DateTimeUtc::now() => {
+            Some(TxSubmitError::PaymentRequestExpired(expiry))
+        }
+        _ => None,
+    };
+    let requested_amount = match request.amount {
+        Some(requested) => Some(
+            validate_amount(
+                context,
+                InputAmount::Unvalidated(requested),
+                &args.token,
+                args.tx.force,
+            )
+            .await?,
+        ),
+        None => None,
+    };
+    let mismatch = if !matches!(
+        &args.target,
+        TransferTarget::PaymentAddress(pa) if *pa == request.address
+    ) {
+        Some("target")
+    } else if request
+        .token
+        .as_ref()
+        .map_or(false, |token| *token != args.token)
+    {
+        Some("token")
+    } else if requested_amount
+        .map_or(false, |requested| requested.amount() != amount.amount())
+    {
+        Some("amount")
+    } else {
+        None
+    };
+    match expired.or(mismatch.map(TxSubmitError::PaymentRequestMismatch)) {
+        Some(err) if args.tx.force => {
+            edisplay_line!(context.io(), "{}", err);
+            Ok(())
+        }
+        Some(err) => Err(Error::from(err)),
+        None => Ok(()),
+    }
+}
+
 enum CheckBalance {
     Balance(token::Amount),
     Query(storage::Key),
@@ -0,0 +1,378 @@
+//! Wallet address book: metadata of known counterparties and its import and
+//! export in JSON and CSV formats.
+
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use namada_core::address::Address;
+use namada_core::masp::PaymentAddress;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Metadata of a contact in the address book, associated with the alias of a
+/// transparent address or a payment address
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contact {
+    /// Free-form notes about the contact
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Tags for grouping contacts, e.g. "exchange", "validator", "personal"
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub tags: BTreeSet<String>,
+    /// The token that's sent to this contact by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_token: Option<Address>,
+    /// The memo attached to transfers to this contact by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_memo: Option<String>,
+    /// A shielded payment address of the contact
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payment_address: Option<PaymentAddress>,
+}
+
+impl Contact {
+    /// Add a tag to the contact. Tags are case-insensitive.
+    pub fn add_tag(&mut self, tag: impl AsRef<str>) {
+        let tag = tag.as_ref().trim().to_lowercase();
+        if !tag.is_empty() {
+            self.tags.insert(tag);
+        }
+    }
+
+    /// Check if the contact has the given tag
+    pub fn has_tag(&self, tag: impl AsRef<str>) -> bool {
+        self.tags.contains(&tag.as_ref().trim().to_lowercase())
+    }
+
+    /// Check if the contact's metadata matches the given lower-case search
+    /// query
+    pub fn matches(&self, query: &str) -> bool {
+        let contains = |s: &str| s.to_lowercase().contains(query);
+        self.note.as_deref().map(contains).unwrap_or_default()
+            || self
+                .default_memo
+                .as_deref()
+                .map(contains)
+                .unwrap_or_default()
+            || self.tags.iter().any(|tag| tag.contains(query))
+            || self
+                .payment_address
+                .map(|pa| contains(&pa.to_string()))
+                .unwrap_or_default()
+    }
+}
+
+/// A flattened entry of the address book used for its import and export
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressBookEntry {
+    /// The alias of the entry
+    pub alias: String,
+    /// The transparent address of the entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+    /// The contact's metadata
+    #[serde(flatten)]
+    pub contact: Contact,
+}
+
+/// The file formats of the address book import and export
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AddressBookFormat {
+    /// A JSON array of entries
+    Json,
+    /// Comma-separated values with a header row. Tags are separated by
+    /// semicolons.
+    Csv,
+}
+
+impl Display for AddressBookFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::Csv => write!(f, "csv"),
+        }
+    }
+}
+
+impl FromStr for AddressBookFormat {
+    type Err = AddressBookError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(AddressBookError::UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+/// Errors of the address book import and export
+#[derive(Error, Debug)]
+pub enum AddressBookError {
+    /// Unknown file format
+    #[error("Unknown address book format {0}, expected json or csv")]
+    UnknownFormat(String),
+    /// JSON encoding or decoding error
+    #[error("Invalid JSON address book: {0}")]
+    Json(#[from] serde_json::Error),
+    /// CSV decoding error
+    #[error("Invalid CSV address book at line {0}: {1}")]
+    Csv(usize, String),
+    /// An entry has neither a transparent nor a payment address
+    #[error("The address book entry \"{0}\" has no address")]
+    MissingAddress(String),
+}
+
+/// The columns of the CSV address book, in order
+const CSV_HEADER: [&str; 7] = [
+    "alias",
+    "address",
+    "payment_address",
+    "tags",
+    "default_token",
+    "default_memo",
+    "note",
+];
+
+/// The separator of tags within the CSV tags column
+const CSV_TAG_SEPARATOR: char = ';';
+
+/// Encode the address book entries in the given format
+pub fn encode(
+    entries: &[AddressBookEntry],
+    format: AddressBookFormat,
+) -> Result<String, AddressBookError> {
+    match format {
+        AddressBookFormat::Json => Ok(serde_json::to_string_pretty(entries)?),
+        AddressBookFormat::Csv => Ok(encode_csv(entries)),
+    }
+}
+
+/// Decode the address book entries from the given format
+pub fn decode(
+    data: &str,
+    format: AddressBookFormat,
+) -> Result<Vec<AddressBookEntry>, AddressBookError> {
+    let entries: Vec<AddressBookEntry> = match format {
+        AddressBookFormat::Json => serde_json::from_str(data)?,
+        AddressBookFormat::Csv => decode_csv(data)?,
+    };
+    for entry in &entries {
+        if entry.address.is_none() && entry.contact.payment_address.is_none() {
+            return Err(AddressBookError::MissingAddress(entry.alias.clone()));
+        }
+    }
+    Ok(entries)
+}
+
+fn encode_csv(entries: &[AddressBookEntry]) -> String {
+    let mut out = CSV_HEADER.join(",");
+    out.push('\n');
+    for entry in entries {
+        let contact = &entry.contact;
+        let tags = contact
+            .tags
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(&CSV_TAG_SEPARATOR.to_string());
+        let fields = [
+            entry.alias.clone(),
+            entry
+                .address
+                .as_ref()
+                .map(Address::encode)
+                .unwrap_or_default(),
+            contact
+                .payment_address
+                .map(|pa| pa.to_string())
+                .unwrap_or_default(),
+            tags,
+            contact
+                .default_token
+                .as_ref()
+                .map(Address::encode)
+                .unwrap_or_default(),
+            contact.default_memo.clone().unwrap_or_default(),
+            contact.note.clone().unwrap_or_default(),
+        ];
+        let row = fields
+            .iter()
+            .map(|field| escape_csv_field(field))
+            .collect::<Vec<_>>()
+            .join(",");
+        out.push_str(&row);
+        out.push('\n');
+    }
+    out
+}
+
+fn decode_csv(data: &str) -> Result<Vec<AddressBookEntry>, AddressBookError> {
+    let mut records = parse_csv(data)?.into_iter();
+    let header = match records.next() {
+        Some((_, header)) => header,
+        None => return Ok(vec![]),
+    };
+    // Find the index of every known column, allowing any order
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|col| col.trim().eq_ignore_ascii_case(name))
+    };
+    let alias_col = column("alias").ok_or_else(|| {
+        AddressBookError::Csv(1, "Missing alias column".into())
+    })?;
+    let cols = CSV_HEADER.map(column);
+
+    let mut entries = vec![];
+    for (line, record) in records {
+        let field = |idx: Option<usize>| {
+            idx.and_then(|idx| record.get(idx))
+                .map(|field| field.trim())
+                .filter(|field| !field.is_empty())
+        };
+        let parse_addr = |idx: Option<usize>| {
+            field(idx)
+                .map(|addr| {
+                    Address::decode(addr).map_err(|err| {
+                        AddressBookError::Csv(line, err.to_string())
+                    })
+                })
+                .transpose()
+        };
+        let Some(alias) = field(Some(alias_col)) else {
+            continue;
+        };
+        let mut contact = Contact {
+            note: field(cols[6]).map(str::to_string),
+            default_token: parse_addr(cols[4])?,
+            default_memo: field(cols[5]).map(str::to_string),
+            payment_address: field(cols[2])
+                .map(|pa| {
+                    PaymentAddress::from_str(pa).map_err(|err| {
+                        AddressBookError::Csv(line, err.to_string())
+                    })
+                })
+                .transpose()?,
+            ..Contact::default()
+        };
+        for tag in field(cols[3]).unwrap_or_default().split(CSV_TAG_SEPARATOR) {
+            contact.add_tag(tag);
+        }
+        entries.push(AddressBookEntry {
+            alias: alias.to_string(),
+            address: parse_addr(cols[1])?,
+            contact,
+        });
+    }
+    Ok(entries)
+}
+
+/// Quote a CSV field if it contains a separator, a quote or a line break
+pub(crate) fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Parse CSV records, returning each with its starting line number. Quoted
+/// fields may contain separators, escaped quotes and line breaks.
+fn parse_csv(
+    data: &str,
+) -> Result<Vec<(usize, Vec<String>)>, AddressBookError> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = data.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|field| !field.is_empty()) {
+                    records.push((record_line, std::mem::take(&mut record)));
+                } else {
+                    record.clear();
+                }
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(AddressBookError::Csv(
+            record_line,
+            "Unterminated quoted field".into(),
+        ));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((record_line, record));
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::address::testing::{established_address_1, nam};
+
+    use super::*;
+
+    #[test]
+    fn test_address_book_csv_roundtrip() {
+        let mut contact = Contact {
+            note: Some("Hot wallet, \"main\" desk\nsecond line".into()),
+            default_token: Some(nam()),
+            default_memo: Some("invoice 42, Q3".into()),
+            ..Contact::default()
+        };
+        contact.add_tag("Exchange");
+        contact.add_tag("personal");
+        let entries = vec![AddressBookEntry {
+            alias: "exchange-1".into(),
+            address: Some(established_address_1()),
+            contact,
+        }];
+
+        let encoded = encode(&entries, AddressBookFormat::Csv).unwrap();
+        let decoded = decode(&encoded, AddressBookFormat::Csv).unwrap();
+        assert_eq!(decoded, entries);
+
+        let encoded = encode(&entries, AddressBookFormat::Json).unwrap();
+        let decoded = decode(&encoded, AddressBookFormat::Json).unwrap();
+        assert_eq!(decoded, entries);
+    }
+
+    #[test]
+    fn test_address_book_entry_without_address_rejected() {
+        let data = "alias,tags\nnobody,exchange\n";
+        assert!(matches!(
+            decode(data, AddressBookFormat::Csv),
+            Err(AddressBookError::MissingAddress(_))
+        ));
+    }
+}
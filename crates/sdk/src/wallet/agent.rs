@@ -0,0 +1,202 @@
+//! Client of the wallet agent.
+//!
+//! The agent is a local process, similar to `ssh-agent`, that keeps
+//! decrypted wallet keys in memory for a limited time, so that they don't
+//! have to be unlocked with a password by every command. It listens on a Unix
+//! domain socket given by the [`AGENT_SOCKET_ENV`] environment variable and
+//! answers newline-delimited JSON requests. Keys are looked up by their
+//! encrypted form, so the agent serves any wallet holding the same keys.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
+
+/// The environment variable with the path of the wallet agent's socket
+pub const AGENT_SOCKET_ENV: &str = "NAMADA_WALLET_AGENT_SOCKET";
+
+/// The read and write timeout of the connections to the agent
+const AGENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum AgentError {
+    #[error("Wallet agent socket {0} error: {1}")]
+    Io(PathBuf, io::Error),
+    #[error("Invalid wallet agent message: {0}")]
+    Encoding(#[from] serde_json::Error),
+    #[error("The wallet agent failed: {0}")]
+    Agent(String),
+    #[error("Unexpected response from the wallet agent")]
+    UnexpectedResponse,
+}
+
+/// A key handed to the agent for unlocking
+#[derive(Clone, Serialize, Deserialize)]
+pub struct UnlockedKey {
+    /// The alias of the key in the wallet that unlocked it
+    pub alias: String,
+    /// The hex encoding of the encrypted key
+    pub encrypted: String,
+    /// The hex encoding of the decrypted key
+    pub decrypted: String,
+}
+
+impl std::fmt::Debug for UnlockedKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnlockedKey")
+            .field("alias", &self.alias)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for UnlockedKey {
+    fn drop(&mut self) {
+        self.decrypted.zeroize();
+    }
+}
+
+/// A key held by the agent, as listed to its clients
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UnlockedKeyInfo {
+    /// The alias of the key in the wallet that unlocked it
+    pub alias: String,
+    /// The remaining time until the key is locked again
+    pub expires_in: Duration,
+}
+
+/// Requests served by the wallet agent
+#[derive(Debug, Serialize, Deserialize)]
+pub enum AgentRequest {
+    /// Get the decrypted form of an encrypted key, if it's unlocked
+    Get {
+        /// The hex encoding of the encrypted key
+        encrypted: String,
+    },
+    /// Unlock keys for the given time, or the agent's default time if not
+    /// given
+    Unlock {
+        /// The keys to unlock
+        keys: Vec<UnlockedKey>,
+        /// For how long the keys stay unlocked
+        lifetime: Option<Duration>,
+    },
+    /// Lock all the unlocked keys
+    Lock,
+    /// List the unlocked keys
+    List,
+}
+
+/// Responses of the wallet agent
+#[derive(Debug, Serialize, Deserialize)]
+pub enum AgentResponse {
+    /// The decrypted key, if it's unlocked
+    Key(Option<String>),
+    /// The unlocked keys
+    Keys(Vec<UnlockedKeyInfo>),
+    /// The request succeeded
+    Done,
+    /// The request failed
+    Error(String),
+}
+
+/// A client of the wallet agent
+#[derive(Debug, Clone)]
+pub struct AgentClient {
+    socket: PathBuf,
+}
+
+impl AgentClient {
+    /// Create a client of the agent listening on the given socket
+    pub fn new(socket: impl AsRef<Path>) -> Self {
+        Self {
+            socket: socket.as_ref().to_owned(),
+        }
+    }
+
+    /// Create a client of the agent whose socket is given by the
+    /// [`AGENT_SOCKET_ENV`] environment variable, if it's set
+    pub fn from_env() -> Option<Self> {
+        std::env::var_os(AGENT_SOCKET_ENV)
+            .filter(|socket| !socket.is_empty())
+            .map(Self::new)
+    }
+
+    /// The path of the agent's socket
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+
+    /// Get the decrypted form of the given encrypted key if the agent holds
+    /// it unlocked
+    pub fn get_key(
+        &self,
+        encrypted: &str,
+    ) -> Result<Option<Zeroizing<String>>, AgentError> {
+        match self.request(&AgentRequest::Get {
+            encrypted: encrypted.to_string(),
+        })? {
+            AgentResponse::Key(key) => Ok(key.map(Zeroizing::new)),
+            _ => Err(AgentError::UnexpectedResponse),
+        }
+    }
+
+    /// Unlock the given keys for the given time, or for the agent's default
+    /// time if not given
+    pub fn unlock(
+        &self,
+        keys: Vec<UnlockedKey>,
+        lifetime: Option<Duration>,
+    ) -> Result<(), AgentError> {
+        match self.request(&AgentRequest::Unlock { keys, lifetime })? {
+            AgentResponse::Done => Ok(()),
+            _ => Err(AgentError::UnexpectedResponse),
+        }
+    }
+
+    /// Lock all the keys held by the agent
+    pub fn lock(&self) -> Result<(), AgentError> {
+        match self.request(&AgentRequest::Lock)? {
+            AgentResponse::Done => Ok(()),
+            _ => Err(AgentError::UnexpectedResponse),
+        }
+    }
+
+    /// List the keys held by the agent
+    pub fn list(&self) -> Result<Vec<UnlockedKeyInfo>, AgentError> {
+        match self.request(&AgentRequest::List)? {
+            AgentResponse::Keys(keys) => Ok(keys),
+            _ => Err(AgentError::UnexpectedResponse),
+        }
+    }
+
+    /// Send a request on a new connection and read its response
+    fn request(
+        &self,
+        request: &AgentRequest,
+    ) -> Result<AgentResponse, AgentError> {
+        let io_err = |err| AgentError::Io(self.socket.clone(), err);
+        let mut stream = UnixStream::connect(&self.socket).map_err(io_err)?;
+        stream
+            .set_read_timeout(Some(AGENT_TIMEOUT))
+            .map_err(io_err)?;
+        stream
+            .set_write_timeout(Some(AGENT_TIMEOUT))
+            .map_err(io_err)?;
+        let mut message = Zeroizing::new(serde_json::to_vec(request)?);
+        message.push(b'\n');
+        stream.write_all(&message).map_err(io_err)?;
+        let mut line = Zeroizing::new(String::new());
+        BufReader::new(stream)
+            .read_line(&mut line)
+            .map_err(io_err)?;
+        match serde_json::from_str(&line)? {
+            AgentResponse::Error(err) => Err(AgentError::Agent(err)),
+            response => Ok(response),
+        }
+    }
+}
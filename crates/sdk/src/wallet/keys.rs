@@ -178,6 +178,13 @@ where
         match self {
             StoredKeypair::Encrypted(encrypted_keypair) => {
                 if decrypt {
+                    if password.is_none() {
+                        if let Some(key) =
+                            encrypted_keypair.find_unlocked::<U>()
+                        {
+                            return Ok(key);
+                        }
+                    }
                     let password =
                         password.unwrap_or_else(|| U::read_password(false));
                     let key = encrypted_keypair.decrypt(password)?;
@@ -190,6 +197,26 @@ where
         }
     }
 
+    /// Decrypt the keypair for unlocking it in a wallet agent. Returns the hex
+    /// encodings of the encrypted and the decrypted keypair, or `None` if the
+    /// keypair isn't encrypted.
+    pub fn decrypt_for_agent(
+        &self,
+        password: Zeroizing<String>,
+    ) -> Result<Option<(String, Zeroizing<String>)>, DecryptionError> {
+        match self {
+            StoredKeypair::Encrypted(encrypted_keypair) => {
+                let keypair = encrypted_keypair.decrypt(password)?;
+                let data = Zeroizing::new(keypair.serialize_to_vec());
+                Ok(Some((
+                    encrypted_keypair.to_string(),
+                    Zeroizing::new(HEXLOWER.encode(&data)),
+                )))
+            }
+            StoredKeypair::Raw(_) => Ok(None),
+        }
+    }
+
     /// Indicates whether this key has been encrypted or not
     pub fn is_encrypted(&self) -> bool {
         match self {
@@ -238,6 +265,13 @@ impl<T: BorshSerialize + BorshDeserialize> EncryptedKeypair<T> {
         T::try_from_slice(&decrypted_data)
             .map_err(|_| DecryptionError::DeserializingError)
     }
+
+    /// Find the keypair among the keys unlocked by a wallet agent
+    pub fn find_unlocked<U: WalletIo>(&self) -> Option<T> {
+        let unlocked = U::read_unlocked_key(&self.to_string())?;
+        let data = Zeroizing::new(HEXLOWER.decode(unlocked.as_bytes()).ok()?);
+        T::try_from_slice(&data).ok()
+    }
 }
 
 /// Keypair encryption salt
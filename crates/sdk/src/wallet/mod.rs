@@ -1,4 +1,7 @@
 //! Provides functionality for managing keys and addresses for a user
+pub mod address_book;
+#[cfg(all(feature = "std", unix))]
+pub mod agent;
 pub mod alias;
 mod derivation_path;
 mod keys;
@@ -9,6 +12,7 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Display;
 use std::str::FromStr;
 
+use address_book::{AddressBookError, AddressBookFormat, Contact};
 use alias::Alias;
 use bip39::{Language, Mnemonic, MnemonicType, Seed};
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -17,6 +21,7 @@ use namada_core::key::*;
 use namada_core::masp::{
     ExtendedSpendingKey, ExtendedViewingKey, PaymentAddress,
 };
+use namada_core::storage::BlockHeight;
 pub use pre_genesis::gen_key_to_store;
 use rand::CryptoRng;
 use rand_core::RngCore;
@@ -54,6 +59,12 @@ pub trait WalletIo: Sized + Clone {
         panic!("attempted to prompt for password in non-interactive mode");
     }
 
+    /// Read a key unlocked by a wallet agent, given the hex encoding of the
+    /// encrypted key. Returns the hex encoding of the decrypted key.
+    fn read_unlocked_key(_encrypted: &str) -> Option<Zeroizing<String>> {
+        None
+    }
+
     /// Read an alias from the file/env/stdin.
     fn read_alias(_prompt_msg: &str) -> String {
         panic!("attempted to prompt for alias in non-interactive mode");
@@ -379,6 +390,39 @@ impl<U> Wallet<U> {
         }
     }
 
+    /// Find the address book metadata of the given alias
+    pub fn find_contact(&self, alias: impl AsRef<str>) -> Option<&Contact> {
+        self.store.find_contact(alias)
+    }
+
+    /// Search the address book for the aliases whose alias, address, payment
+    /// address or metadata contain the given query, case-insensitively.
+    pub fn search_contacts(&self, query: impl AsRef<str>) -> Vec<String> {
+        self.store
+            .search_contacts(query)
+            .into_iter()
+            .map(Into::into)
+            .collect()
+    }
+
+    /// Set the address book metadata of an alias of an address or a payment
+    /// address. Returns `false` if the alias isn't known.
+    pub fn set_contact(
+        &mut self,
+        alias: impl AsRef<str>,
+        contact: Contact,
+    ) -> bool {
+        self.store.set_contact(alias.into(), contact)
+    }
+
+    /// Export the address book in the given format
+    pub fn export_address_book(
+        &self,
+        format: AddressBookFormat,
+    ) -> Result<String, AddressBookError> {
+        address_book::encode(&self.store.get_address_book(), format)
+    }
+
     /// Find the viewing key with the given alias in the wallet and return it
     pub fn find_viewing_key(
         &self,
@@ -916,11 +960,21 @@ impl<U: WalletIo> Wallet<U> {
     {
         match stored_key {
             StoredKeypair::Encrypted(encrypted) => {
-                let password =
-                    password.unwrap_or_else(|| U::read_password(false));
-                let key = encrypted
-                    .decrypt(password)
-                    .map_err(FindKeyError::KeyDecryptionError)?;
+                // Without a password, try the keys unlocked by an agent
+                let unlocked = password
+                    .is_none()
+                    .then(|| encrypted.find_unlocked::<U>())
+                    .flatten();
+                let key = match unlocked {
+                    Some(key) => key,
+                    None => {
+                        let password =
+                            password.unwrap_or_else(|| U::read_password(false));
+                        encrypted
+                            .decrypt(password)
+                            .map_err(FindKeyError::KeyDecryptionError)?
+                    }
+                };
                 decrypted_key_cache.insert(alias.clone(), key);
                 decrypted_key_cache
                     .get(&alias)
@@ -998,6 +1052,21 @@ impl<U: WalletIo> Wallet<U> {
             .map(Into::into)
     }
 
+    /// Find the birthday height of the viewing key with the given alias
+    pub fn find_birthday(&self, alias: impl AsRef<str>) -> Option<BlockHeight> {
+        self.store.find_birthday(alias)
+    }
+
+    /// Set the birthday height of the viewing key, or spending key, with the
+    /// given alias. Returns `false` if the alias isn't known.
+    pub fn set_birthday(
+        &mut self,
+        alias: impl AsRef<str>,
+        birthday: BlockHeight,
+    ) -> bool {
+        self.store.set_birthday(alias.into(), birthday)
+    }
+
     /// Insert a viewing key into the wallet under the given alias
     pub fn insert_viewing_key(
         &mut self,
@@ -1048,6 +1117,23 @@ impl<U: WalletIo> Wallet<U> {
             .map(Into::into)
     }
 
+    /// Import an address book in the given format. Returns the aliases of the
+    /// imported entries.
+    pub fn import_address_book(
+        &mut self,
+        data: &str,
+        format: AddressBookFormat,
+        force_alias: bool,
+    ) -> Result<Vec<String>, AddressBookError> {
+        let entries = address_book::decode(data, format)?;
+        Ok(self
+            .store
+            .insert_address_book::<U>(entries, force_alias)
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
     /// Extend this wallet from another wallet (typically pre-genesis).
     /// Note that this method ignores `store.validator_data` if any.
     pub fn extend(&mut self, wallet: Self) {
@@ -12,9 +12,11 @@ use namada_core::key::*;
 use namada_core::masp::{
     ExtendedSpendingKey, ExtendedViewingKey, PaymentAddress,
 };
+use namada_core::storage::BlockHeight;
 use serde::{Deserialize, Serialize};
 use zeroize::Zeroizing;
 
+use super::address_book::{AddressBookEntry, Contact};
 use super::alias::{self, Alias};
 use super::derivation_path::DerivationPath;
 use super::pre_genesis;
@@ -79,6 +81,13 @@ pub struct Store {
     pub(crate) validator_data: Option<ValidatorData>,
     /// Namada address vp type
     address_vp_types: BTreeMap<AddressVpType, HashSet<Address>>,
+    /// Address book metadata of known addresses and payment addresses
+    #[serde(default)]
+    contacts: BTreeMap<Alias, Contact>,
+    /// Birthday heights of known viewing keys, before which they have no
+    /// notes
+    #[serde(default)]
+    birthdays: BTreeMap<Alias, BlockHeight>,
 }
 
 /// Grouping of addresses by validity predicate.
@@ -243,6 +252,65 @@ impl Store {
         &self.addresses
     }
 
+    /// Find the address book metadata of the given alias
+    pub fn find_contact(&self, alias: impl AsRef<str>) -> Option<&Contact> {
+        self.contacts.get(&alias.into())
+    }
+
+    /// Get the address book metadata of all contacts by their alias.
+    pub fn get_contacts(&self) -> &BTreeMap<Alias, Contact> {
+        &self.contacts
+    }
+
+    /// Search the address book for the aliases whose alias, address, payment
+    /// address or metadata contain the given query, case-insensitively.
+    pub fn search_contacts(&self, query: impl AsRef<str>) -> Vec<&Alias> {
+        let query = query.as_ref().to_lowercase();
+        let addresses = self.addresses.iter().map(|(alias, addr)| {
+            (alias, addr.encode().to_lowercase().contains(&query))
+        });
+        let payment_addrs = self.payment_addrs.iter().map(|(alias, pa)| {
+            (alias, pa.to_string().to_lowercase().contains(&query))
+        });
+        addresses
+            .chain(payment_addrs)
+            .filter(|(alias, addr_matches)| {
+                *addr_matches
+                    || alias.normalize().contains(&query)
+                    || self
+                        .contacts
+                        .get(*alias)
+                        .map(|contact| contact.matches(&query))
+                        .unwrap_or_default()
+            })
+            .map(|(alias, _)| alias)
+            .collect()
+    }
+
+    /// Get the entries of the address book, i.e. all the known addresses and
+    /// payment addresses with their metadata.
+    pub fn get_address_book(&self) -> Vec<AddressBookEntry> {
+        let addresses = self.addresses.iter().map(|(alias, addr)| {
+            let contact = self.contacts.get(alias).cloned().unwrap_or_default();
+            AddressBookEntry {
+                alias: alias.normalize(),
+                address: Some(addr.clone()),
+                contact,
+            }
+        });
+        let payment_addrs = self.payment_addrs.iter().map(|(alias, pa)| {
+            let mut contact =
+                self.contacts.get(alias).cloned().unwrap_or_default();
+            contact.payment_address = Some(*pa);
+            AddressBookEntry {
+                alias: alias.normalize(),
+                address: None,
+                contact,
+            }
+        });
+        addresses.chain(payment_addrs).collect()
+    }
+
     /// Get all known payment addresses by their alias.
     pub fn get_payment_addrs(&self) -> &BiBTreeMap<Alias, PaymentAddress> {
         &self.payment_addrs
@@ -260,6 +328,16 @@ impl Store {
         &self.spend_keys
     }
 
+    /// Find the birthday height of the viewing key with the given alias
+    pub fn find_birthday(&self, alias: impl AsRef<str>) -> Option<BlockHeight> {
+        self.birthdays.get(&alias.into()).copied()
+    }
+
+    /// Get the birthday heights of the viewing keys by their alias.
+    pub fn get_birthdays(&self) -> &BTreeMap<Alias, BlockHeight> {
+        &self.birthdays
+    }
+
     /// Add validator data to the store
     pub fn add_validator_data(
         &mut self,
@@ -563,6 +641,76 @@ impl Store {
         Some(alias)
     }
 
+    /// Set the birthday height of the viewing key, or spending key, with the
+    /// given alias. Returns `false` if the alias isn't known.
+    pub fn set_birthday(
+        &mut self,
+        alias: Alias,
+        birthday: BlockHeight,
+    ) -> bool {
+        if !self.view_keys.contains_key(&alias) {
+            return false;
+        }
+        self.birthdays.insert(alias, birthday);
+        true
+    }
+
+    /// Set the address book metadata of an alias of an address or a payment
+    /// address. Returns `false` if the alias isn't known.
+    pub fn set_contact(&mut self, alias: Alias, contact: Contact) -> bool {
+        if !self.addresses.contains_left(&alias)
+            && !self.payment_addrs.contains_left(&alias)
+        {
+            return false;
+        }
+        self.contacts.insert(alias, contact);
+        true
+    }
+
+    /// Insert the entries of an address book. Every entry's alias is assigned
+    /// to its transparent address, or to its payment address if it has no
+    /// transparent address, with the same confirmation as
+    /// [`Store::insert_address`]. Returns the aliases of the inserted entries.
+    pub fn insert_address_book<U: WalletIo>(
+        &mut self,
+        entries: Vec<AddressBookEntry>,
+        force: bool,
+    ) -> Vec<Alias> {
+        let mut inserted = vec![];
+        for AddressBookEntry {
+            alias,
+            address,
+            mut contact,
+        } in entries
+        {
+            let alias: Alias = alias.into();
+            let alias = match (address, contact.payment_address) {
+                // Only update the metadata of an already known address
+                (Some(address), _)
+                    if self.addresses.get_by_left(&alias) == Some(&address) =>
+                {
+                    Some(alias)
+                }
+                (Some(address), _) => {
+                    self.insert_address::<U>(alias, address, force)
+                }
+                (None, Some(payment_addr)) => {
+                    // The payment address is stored in its own map
+                    contact.payment_address = None;
+                    self.insert_payment_addr::<U>(alias, payment_addr, force)
+                }
+                (None, None) => None,
+            };
+            if let Some(alias) = alias {
+                if contact != Contact::default() {
+                    self.contacts.insert(alias.clone(), contact);
+                }
+                inserted.push(alias);
+            }
+        }
+        inserted
+    }
+
     /// Check if any map of the wallet contains the given alias
     pub fn contains_alias(&self, alias: &Alias) -> bool {
         self.payment_addrs.contains_left(alias)
@@ -585,6 +733,8 @@ impl Store {
         self.pkhs.retain(|_key, val| val != alias);
         self.public_keys.remove(alias);
         self.derivation_paths.remove(alias);
+        self.contacts.remove(alias);
+        self.birthdays.remove(alias);
     }
 
     /// Extend this store from another store (typically pre-genesis).
@@ -601,6 +751,8 @@ impl Store {
             pkhs,
             validator_data: _,
             address_vp_types,
+            contacts,
+            birthdays,
         } = self;
         view_keys.extend(store.view_keys);
         spend_keys.extend(store.spend_keys);
@@ -611,6 +763,8 @@ impl Store {
         addresses.extend(store.addresses);
         pkhs.extend(store.pkhs);
         address_vp_types.extend(store.address_vp_types);
+        contacts.extend(store.contacts);
+        birthdays.extend(store.birthdays);
     }
 
     /// Extend this store from pre-genesis validator wallet.
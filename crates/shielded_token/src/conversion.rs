@@ -1,7 +1,9 @@
 //! MASP rewards conversions
 
 use namada_core::address::{Address, MASP};
+use namada_core::borsh::{BorshDeserialize, BorshSerialize};
 use namada_core::dec::Dec;
+use namada_core::storage::Epoch;
 use namada_core::uint::Uint;
 use namada_parameters as parameters;
 use namada_storage::{StorageRead, StorageWrite};
@@ -21,11 +23,11 @@ use crate::WithConversionState;
 /// Compute the precision of MASP rewards for the given token. This function
 /// must be a non-zero constant for a given token.
 pub fn calculate_masp_rewards_precision<S>(
-    storage: &mut S,
+    storage: &S,
     addr: &Address,
 ) -> namada_storage::Result<(u128, Denomination)>
 where
-    S: StorageWrite + StorageRead,
+    S: StorageRead,
 {
     let denomination =
         read_denom(storage, addr)?.expect("failed to read token denomination");
@@ -42,18 +44,44 @@ where
     ))
 }
 
-/// Compute the MASP rewards by applying the PD-controller to the genesis
-/// parameters and the last inflation and last locked rewards ratio values.
-pub fn calculate_masp_rewards<S>(
-    storage: &mut S,
+/// The estimated MASP rewards of a token in an epoch
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct MaspRewardEstimate {
+    /// The epoch that the estimate is for
+    pub epoch: Epoch,
+    /// The raw amount of the native token rewarded for every `precision` raw
+    /// units of the token held in the shielded pool
+    pub reward: u128,
+    /// The precision of the reward
+    pub precision: u128,
+    /// The native token inflation that rewards the whole shielded pool of the
+    /// token
+    pub inflation: Amount,
+}
+
+impl MaspRewardEstimate {
+    /// The reward rate per epoch, if it can be represented
+    pub fn reward_rate(&self) -> Option<Dec> {
+        let reward = Dec::try_from(self.reward).ok()?;
+        let precision = Dec::try_from(self.precision).ok()?;
+        reward.trunc_div(&precision)
+    }
+
+    /// The estimated reward of the given amount of the token held in the
+    /// shielded pool
+    pub fn reward_for(&self, amount: Amount) -> Amount {
+        (amount * (self.reward, self.precision)).0
+    }
+}
+
+/// Read the state of the PD-controller of the MASP rewards of the given token
+fn read_masp_rewards_controller<S>(
+    storage: &S,
     token: &Address,
-) -> namada_storage::Result<((u128, u128), Denomination)>
+) -> namada_storage::Result<ShieldedRewardsController>
 where
-    S: StorageWrite + StorageRead,
+    S: StorageRead,
 {
-    let (precision, denomination) =
-        calculate_masp_rewards_precision(storage, token)?;
-
     let masp_addr = MASP;
 
     // Query the storage for information -------------------------
@@ -100,7 +128,7 @@ where
         .expect("locked ratio target should properly decode");
 
     // Creating the PD controller for handing out tokens
-    let controller = ShieldedRewardsController {
+    Ok(ShieldedRewardsController {
         locked_tokens: total_tokens_in_masp.raw_amount(),
         total_native_tokens: total_native_tokens.raw_amount(),
         locked_tokens_target: target_locked_amount.raw_amount(),
@@ -110,11 +138,17 @@ where
         p_gain_nom: kp_gain_nom,
         d_gain_nom: kd_gain_nom,
         epochs_per_year,
-    };
-
-    let ShieldedValsToUpdate { inflation } =
-        ShieldedRewardsController::run(controller);
+    })
+}
 
+/// Make the inflation of the given token discrete in the note format, and
+/// get the inflation amount that it corresponds to.
+fn noterize_inflation(
+    token: &Address,
+    inflation: Uint,
+    total_tokens_in_masp: Uint,
+    precision: u128,
+) -> (u128, Amount) {
     // inflation-per-token = inflation / locked tokens = n/PRECISION
     // ∴ n = (inflation * PRECISION) / locked tokens
     // Since we must put the notes in a compatible format with the
@@ -123,10 +157,7 @@ where
         0u128
     } else {
         inflation
-            .checked_mul_div(
-                Uint::from(precision),
-                total_tokens_in_masp.raw_amount(),
-            )
+            .checked_mul_div(Uint::from(precision), total_tokens_in_masp)
             .and_then(|x| x.0.try_into().ok())
             .unwrap_or_else(|| {
                 tracing::warn!(
@@ -139,29 +170,42 @@ where
             })
     };
     let inflation_amount = Amount::from_uint(
-        (total_tokens_in_masp.raw_amount() / precision)
-            * Uint::from(noterized_inflation),
+        (total_tokens_in_masp / precision) * Uint::from(noterized_inflation),
         0,
     )
     .unwrap();
+    (noterized_inflation, inflation_amount)
+}
+
+/// Compute the MASP rewards by applying the PD-controller to the genesis
+/// parameters and the last inflation and last locked rewards ratio values.
+pub fn calculate_masp_rewards<S>(
+    storage: &mut S,
+    token: &Address,
+) -> namada_storage::Result<((u128, u128), Denomination)>
+where
+    S: StorageWrite + StorageRead,
+{
+    let (precision, denomination) =
+        calculate_masp_rewards_precision(storage, token)?;
+
+    let controller = read_masp_rewards_controller(storage, token)?;
+    tracing::debug!("Controller, call: {:?}", controller);
+    let total_tokens_in_masp = Amount::from_uint(controller.locked_tokens, 0)
+        .expect("the locked amount should be an amount");
+
+    let ShieldedValsToUpdate { inflation } =
+        ShieldedRewardsController::run(controller);
+
+    let (noterized_inflation, inflation_amount) = noterize_inflation(
+        token,
+        inflation,
+        total_tokens_in_masp.raw_amount(),
+        precision,
+    );
     let denom_amount = DenominatedAmount::new(inflation_amount, denomination);
     tracing::info!("MASP inflation for {token} is {denom_amount}");
 
-    tracing::debug!(
-        "Controller, call: total_in_masp {:?}, total_native_tokens {:?}, \
-         locked_target_amount {:?}, last_locked_amount {:?}, max_reward_rate \
-         {:?}, last_inflation {:?}, kp_gain_nom {:?}, kd_gain_nom {:?}, \
-         epochs_per_year {:?}",
-        total_tokens_in_masp,
-        total_native_tokens,
-        target_locked_amount,
-        last_locked_amount,
-        max_reward_rate,
-        last_inflation,
-        kp_gain_nom,
-        kd_gain_nom,
-        epochs_per_year,
-    );
     tracing::debug!("Token address: {:?}", token);
     tracing::debug!("inflation from the pd controller {:?}", inflation);
     tracing::debug!("total in the masp {:?}", total_tokens_in_masp);
@@ -179,6 +223,63 @@ where
     Ok(((noterized_inflation, precision), denomination))
 }
 
+/// Estimate the MASP rewards of the given token in the current epoch and in
+/// the given number of epochs after it. The current rewards are those that
+/// were set at the start of the epoch. The projection runs the PD-controller
+/// ahead assuming that the locked amount and the native token supply stay
+/// the same, so it gets less accurate the further it looks. It looks at most
+/// a year ahead.
+pub fn estimate_masp_rewards<S>(
+    storage: &S,
+    token: &Address,
+    epochs: u64,
+) -> namada_storage::Result<Vec<MaspRewardEstimate>>
+where
+    S: StorageRead,
+{
+    let (precision, _) = calculate_masp_rewards_precision(storage, token)?;
+    let mut controller = read_masp_rewards_controller(storage, token)?;
+    let mut epoch = storage.get_block_epoch()?;
+    let epochs = std::cmp::min(epochs, controller.epochs_per_year);
+
+    // The rewards of the current epoch were computed from the locked amount
+    // at its start
+    let (reward, _) = noterize_inflation(
+        token,
+        controller.last_inflation_amount,
+        controller.locked_tokens_last,
+        precision,
+    );
+    let mut estimates = vec![MaspRewardEstimate {
+        epoch,
+        reward,
+        precision,
+        inflation: Amount::from_uint(controller.last_inflation_amount, 0)
+            .expect("the last inflation should be an amount"),
+    }];
+    for _ in 0..epochs {
+        epoch = epoch.next();
+        let ShieldedValsToUpdate { inflation } = controller.clone().run();
+        let (reward, inflation_amount) = noterize_inflation(
+            token,
+            inflation,
+            controller.locked_tokens,
+            precision,
+        );
+        estimates.push(MaspRewardEstimate {
+            epoch,
+            reward,
+            precision,
+            inflation: inflation_amount,
+        });
+        // Carry the controller state over to the next epoch, as
+        // calculate_masp_rewards would
+        controller.last_inflation_amount = inflation_amount.raw_amount();
+        controller.locked_tokens_last = controller.locked_tokens;
+    }
+    Ok(estimates)
+}
+
 // This is only enabled when "wasm-runtime" is on, because we're using rayon
 #[cfg(not(any(feature = "multicore", test)))]
 /// Update the MASP's allowed conversions
@@ -560,6 +661,41 @@ mod tests {
     ) {
         const ROUNDS: usize = 10;
 
+        let mut s = init_storage(initial_balance, masp_locked_ratio);
+
+        for i in 0..ROUNDS {
+            println!("Round {i}");
+            update_allowed_conversions(&mut s).unwrap();
+            println!();
+            println!();
+        }
+    }
+
+    /// Check that the projected MASP rewards of the next epochs match the
+    /// rewards that are then computed, while the locked amount is unchanged
+    #[test]
+    fn test_estimate_masp_rewards() {
+        const EPOCHS: u64 = 5;
+
+        let mut s = init_storage(
+            Amount::native_whole(1_000_000),
+            Dec::from_str("0.1").unwrap(),
+        );
+        let token = address::testing::btc();
+        let estimates = estimate_masp_rewards(&s, &token, EPOCHS).unwrap();
+        assert_eq!(estimates.len(), EPOCHS as usize + 1);
+        let start = s.get_block_epoch().unwrap();
+        for (offset, estimate) in estimates.iter().skip(1).enumerate() {
+            assert_eq!(estimate.epoch, start + offset as u64 + 1);
+            let (reward, _) = calculate_masp_rewards(&mut s, &token).unwrap();
+            assert_eq!((estimate.reward, estimate.precision), reward);
+        }
+    }
+
+    fn init_storage(
+        initial_balance: Amount,
+        masp_locked_ratio: Dec,
+    ) -> TestStorage {
         let mut s = TestStorage::default();
         let params = Parameters {
             max_tx_bytes: 1024 * 1024,
@@ -620,13 +756,7 @@ mod tests {
                     .insert(alias.to_string(), token_addr.clone());
             }
         }
-
-        for i in 0..ROUNDS {
-            println!("Round {i}");
-            update_allowed_conversions(&mut s).unwrap();
-            println!();
-            println!();
-        }
+        s
     }
 
     pub fn tokens() -> HashMap<Address, (&'static str, Denomination)> {
@@ -2614,3 +2614,89 @@ fn dynamic_assets() -> Result<()> {
 
     Ok(())
 }
+
+/// Test syncing the shielded context from a local index of the MASP txs
+/// instead of the node.
+#[test]
+fn masp_sync_from_index() -> Result<()> {
+    // This address doesn't matter for tests. But an argument is required.
+    let validator_one_rpc = "http://127.0.0.1:26567";
+    // Download the shielded pool parameters before starting node
+    let _ = FsShieldedUtils::new(PathBuf::new());
+    let (mut node, _services) = setup::setup()?;
+    // Wait till epoch boundary
+    node.next_epoch();
+    // Send 1 BTC from Albert to PA
+    run(
+        &node,
+        Bin::Client,
+        vec![
+            "transfer",
+            "--source",
+            ALBERT,
+            "--target",
+            AA_PAYMENT_ADDRESS,
+            "--token",
+            BTC,
+            "--amount",
+            "1",
+            "--node",
+            validator_one_rpc,
+        ],
+    )?;
+    node.assert_success();
+
+    // Index the MASP txs
+    let tempdir = tempfile::tempdir().unwrap();
+    let index_path = tempdir.path().join("masp-txs.dat");
+    let index_path = index_path.to_str().unwrap();
+    run(
+        &node,
+        Bin::Client,
+        vec![
+            "shielded-index",
+            "--masp-txs-file",
+            index_path,
+            "--node",
+            validator_one_rpc,
+        ],
+    )?;
+    node.assert_success();
+
+    // sync the shielded context from the index
+    run(
+        &node,
+        Bin::Client,
+        vec![
+            "shielded-sync",
+            "--viewing-keys",
+            AA_VIEWING_KEY,
+            "--masp-txs-file",
+            index_path,
+            "--node",
+            validator_one_rpc,
+        ],
+    )?;
+    node.assert_success();
+
+    // Assert BTC balance at VK(A) is 1
+    let captured = CapturedOutput::of(|| {
+        run(
+            &node,
+            Bin::Client,
+            vec![
+                "balance",
+                "--owner",
+                AA_VIEWING_KEY,
+                "--token",
+                BTC,
+                "--node",
+                validator_one_rpc,
+            ],
+        )
+    });
+    assert!(captured.result.is_ok());
+    assert!(captured.contains("btc: 1"));
+
+    Ok(())
+}
@@ -341,6 +341,7 @@ impl TxSentinel {
 #[cfg(test)]
 mod test_process_tx {
     use assert_matches::assert_matches;
+    use namada_core::account::AccountPublicKeysMap;
     use namada_core::address::testing::nam;
     use namada_core::key::*;
     use namada_core::storage::Epoch;
@@ -424,6 +425,77 @@ mod test_process_tx {
         }
     }
 
+    /// Test that signatures made by Ethereum wallets over the EIP-712
+    /// encoding of the header are verified against the signer's keys
+    #[test]
+    fn test_eth_signed_raw_tx() {
+        use rand::prelude::ThreadRng;
+        use rand::thread_rng;
+
+        let mut rng: ThreadRng = thread_rng();
+        let keypair: common::SecretKey =
+            secp256k1::SigScheme::generate(&mut rng)
+                .try_to_sk()
+                .unwrap();
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.set_code(Code::new("wasm code".as_bytes().to_owned(), None));
+        tx.set_data(Data::new("transaction data".as_bytes().to_owned()));
+        tx.sign_raw_eth(vec![keypair.clone()]);
+
+        tx.verify_signatures(
+            &[tx.raw_header_hash()],
+            AccountPublicKeysMap::from_iter([keypair.ref_to()]),
+            &None,
+            1,
+            None,
+            || Ok(()),
+        )
+        .expect("Test failed");
+
+        // Wallets sign over the typed data of the tx header hash
+        assert_eq!(
+            tx.eth_signable_hash(),
+            SignableEthTypedData::as_signable(&tx.raw_header_hash())
+        );
+
+        // The same signatures supplied externally must also be accepted
+        let signature =
+            common::SigScheme::sign(&keypair, tx.eth_signable_hash());
+        let mut other_tx = tx.clone();
+        other_tx
+            .sections
+            .retain(|section| section.signature().is_none());
+        other_tx.add_eth_signatures(vec![(keypair.ref_to(), signature)]);
+        other_tx
+            .verify_signatures(
+                &[other_tx.raw_header_hash()],
+                AccountPublicKeysMap::from_iter([keypair.ref_to()]),
+                &None,
+                1,
+                None,
+                || Ok(()),
+            )
+            .expect("Test failed");
+
+        // An ed25519 key cannot produce Ethereum signatures
+        let ed25519_keypair = gen_keypair();
+        let mut bad_tx = other_tx.clone();
+        bad_tx
+            .sections
+            .retain(|section| section.signature().is_none());
+        bad_tx.sign_raw_eth(vec![ed25519_keypair.clone()]);
+        assert!(bad_tx
+            .verify_signatures(
+                &[bad_tx.raw_header_hash()],
+                AccountPublicKeysMap::from_iter([ed25519_keypair.ref_to()]),
+                &None,
+                1,
+                None,
+                || Ok(()),
+            )
+            .is_err());
+    }
+
     /// Test that process_tx correctly identifies a wrapper tx with some
     /// data and extracts the signed data.
     #[test]
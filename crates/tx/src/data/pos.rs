@@ -4,6 +4,7 @@ use namada_core::address::Address;
 use namada_core::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_core::dec::Dec;
 use namada_core::key::{common, secp256k1};
+use namada_core::storage::Epoch;
 use namada_core::token;
 use serde::{Deserialize, Serialize};
 
@@ -117,6 +118,77 @@ pub struct ClaimRewards {
     pub source: Option<Address>,
 }
 
+/// A tx data type to enable or disable the automatic restaking of the rewards
+/// of a bond.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct Restake {
+    /// Validator address
+    pub validator: Address,
+    /// Source address of the bond. For self-bonds, the validator is also the
+    /// source
+    pub source: Option<Address>,
+    /// Number of epochs between two restakes of the rewards, or `None` to
+    /// disable the restaking
+    pub interval: Option<u64>,
+}
+
+/// A liquid bond of tokens to a validator that mints the share tokens of the
+/// validator's liquid bond.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct LiquidBond {
+    /// Validator address
+    pub validator: Address,
+    /// The amount of tokens
+    pub amount: token::Amount,
+    /// Source address of the tokens and owner of the minted shares
+    pub source: Address,
+}
+
+/// A redemption of the share tokens of a validator's liquid bond for an
+/// unbond.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct RedeemShares {
+    /// Validator address
+    pub validator: Address,
+    /// The amount of share tokens to redeem
+    pub shares: token::Amount,
+    /// Owner of the shares and of the resulting unbond
+    pub owner: Address,
+}
+
 /// A redelegation of bonded tokens from one validator to another.
 #[derive(
     Debug,
@@ -161,6 +233,29 @@ pub struct CommissionChange {
     pub new_rate: Dec,
 }
 
+/// A change to the validator commission rate from a given epoch, which cannot
+/// be before the pipeline epoch.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct ScheduledCommissionChange {
+    /// Validator address
+    pub validator: Address,
+    /// The new commission rate
+    pub new_rate: Dec,
+    /// The epoch from which the new rate takes effect
+    pub effective_epoch: Epoch,
+}
+
 /// A change to the validator metadata.
 #[derive(
     Debug,
@@ -217,6 +312,7 @@ pub mod tests {
     use namada_core::address::testing::arb_non_internal_address;
     use namada_core::dec::testing::arb_dec;
     use namada_core::key::testing::{arb_common_pk, arb_pk};
+    use namada_core::storage::testing::arb_epoch;
     use namada_core::token::testing::arb_amount;
     use proptest::{option, prop_compose};
 
@@ -263,6 +359,21 @@ pub mod tests {
         }
     }
 
+    prop_compose! {
+        /// Generate an arbitrary scheduled commission change
+        pub fn arb_scheduled_commission_change()(
+            validator in arb_non_internal_address(),
+            new_rate in arb_dec(),
+            effective_epoch in arb_epoch(),
+        ) -> ScheduledCommissionChange {
+            ScheduledCommissionChange {
+                validator,
+                new_rate,
+                effective_epoch,
+            }
+        }
+    }
+
     prop_compose! {
         /// Generate an arbitrary metadata change
         pub fn arb_metadata_change()(
@@ -16,6 +16,7 @@ use namada_core::borsh::{
     BorshDeserialize, BorshSchema, BorshSerialize, BorshSerializeExt,
 };
 use namada_core::chain::ChainId;
+use namada_core::keccak::KeccakHash;
 use namada_core::key::*;
 use namada_core::masp::AssetData;
 use namada_core::sign::SignatureIndex;
@@ -362,6 +363,9 @@ pub enum Signer {
     Address(Address),
     /// The public keys that constitute a signer
     PubKeys(Vec<common::PublicKey>),
+    /// The secp256k1 public keys of Ethereum wallets, whose signatures are
+    /// made over the EIP-712 typed data encoding of the signed hash
+    EthPubKeys(Vec<common::PublicKey>),
 }
 
 /// A section representing a multisig over another section
@@ -425,6 +429,32 @@ impl Signature {
         }
     }
 
+    /// Sign the given tx header hash with the given secp256k1 keys, as an
+    /// Ethereum wallet would using EIP-712 typed data, and return a section
+    pub fn new_eth(
+        header_hash: namada_core::hash::Hash,
+        secret_keys: Vec<common::SecretKey>,
+    ) -> Self {
+        let partial = Self {
+            targets: vec![header_hash],
+            signer: Signer::EthPubKeys(
+                secret_keys.iter().map(RefTo::ref_to).collect(),
+            ),
+            signatures: BTreeMap::new(),
+        };
+        let target = SignableEthTypedData::as_signable(&header_hash);
+        let signatures = (0..)
+            .zip(secret_keys.iter())
+            .map(|(index, secret_key)| {
+                (index, common::SigScheme::sign(secret_key, &target))
+            })
+            .collect();
+        Self {
+            signatures,
+            ..partial
+        }
+    }
+
     pub fn total_signatures(&self) -> u8 {
         self.signatures.len() as u8
     }
@@ -451,6 +481,19 @@ impl Signature {
         .get_hash()
     }
 
+    /// Get the EIP-712 digest that Ethereum wallets sign over to produce
+    /// the signatures of this section. The section must target only the
+    /// header of the tx, whose hash is the `txHash` of the typed data (see
+    /// [`SignableEthTypedData`]). Returns `None` for any other targets.
+    pub fn get_eth_signable_hash(&self) -> Option<KeccakHash> {
+        match self.targets.as_slice() {
+            [header_hash] => {
+                Some(SignableEthTypedData::as_signable(header_hash))
+            }
+            _ => None,
+        }
+    }
+
     /// Verify that the signature contained in this section is valid
     pub fn verify_signature<F>(
         &self,
@@ -504,6 +547,41 @@ impl Signature {
                     }
                 }
             }
+            // Verify the Ethereum wallet signatures against the subset of this
+            // section's public keys that are also in the given map
+            Signer::EthPubKeys(pks) => {
+                let eth_hash =
+                    self.get_eth_signable_hash().ok_or_else(|| {
+                        VerifySigError::InvalidSectionSignature(
+                            "Ethereum signatures must target only the tx \
+                             header."
+                                .to_string(),
+                        )
+                    })?;
+                for (idx, pk) in pks.iter().enumerate() {
+                    if let Some(map_idx) =
+                        public_keys_index_map.get_index_from_public_key(pk)
+                    {
+                        // Ethereum wallets only hold secp256k1 keys
+                        if !matches!(pk, common::PublicKey::Secp256k1(_)) {
+                            return Err(
+                                VerifySigError::InvalidSectionSignature(
+                                    "Ethereum signer key is not secp256k1."
+                                        .to_string(),
+                                ),
+                            );
+                        }
+                        consume_verify_sig_gas()?;
+                        common::SigScheme::verify_signature(
+                            pk,
+                            &eth_hash,
+                            &self.signatures[&(idx as u8)],
+                        )?;
+                        verified_pks.insert(map_idx);
+                        verifications += 1;
+                    }
+                }
+            }
         }
         Ok(verifications)
     }
@@ -1265,6 +1343,18 @@ impl Tx {
         .map_err(|_| VerifySigError::InvalidWrapperSignature)
     }
 
+    /// Check if any signature section of this tx lists the given public key
+    /// among its signers. The signatures themselves are not verified.
+    pub fn has_signer_pk(&self, public_key: &common::PublicKey) -> bool {
+        self.sections.iter().any(|section| match section {
+            Section::Signature(Signature {
+                signer: Signer::PubKeys(pks) | Signer::EthPubKeys(pks),
+                ..
+            }) => pks.contains(public_key),
+            _ => false,
+        })
+    }
+
     pub fn compute_section_signature(
         &self,
         secret_keys: &[common::SecretKey],
@@ -1290,7 +1380,7 @@ impl Tx {
                     });
                 }
             }
-            Signer::PubKeys(pub_keys) => {
+            Signer::PubKeys(pub_keys) | Signer::EthPubKeys(pub_keys) => {
                 for (idx, signature) in section.signatures {
                     signatures.push(SignatureIndex {
                         pubkey: pub_keys[idx as usize].clone(),
@@ -1525,6 +1615,45 @@ impl Tx {
         self
     }
 
+    /// Add signing keys to the tx builder, signing over the header as an
+    /// Ethereum wallet would using EIP-712 typed data
+    pub fn sign_raw_eth(
+        &mut self,
+        keypairs: Vec<common::SecretKey>,
+    ) -> &mut Self {
+        // The inner tx signer signs the Decrypted version of the Header
+        let header_hash = self.raw_header_hash();
+        self.protocol_filter();
+
+        self.add_section(Section::Signature(Signature::new_eth(
+            header_hash,
+            keypairs,
+        )));
+        self
+    }
+
+    /// Add signatures produced by Ethereum wallets over the digest returned
+    /// by [`Tx::eth_signable_hash`]
+    pub fn add_eth_signatures(
+        &mut self,
+        signatures: Vec<(common::PublicKey, common::Signature)>,
+    ) -> &mut Self {
+        self.protocol_filter();
+        let (pks, sigs): (Vec<_>, Vec<_>) = signatures.into_iter().unzip();
+        self.add_section(Section::Signature(Signature {
+            targets: vec![self.raw_header_hash()],
+            signer: Signer::EthPubKeys(pks),
+            signatures: (0..).zip(sigs).collect(),
+        }));
+        self
+    }
+
+    /// Get the EIP-712 digest that an Ethereum wallet must sign over in
+    /// order to authorize this transaction
+    pub fn eth_signable_hash(&self) -> KeccakHash {
+        SignableEthTypedData::as_signable(&self.raw_header_hash())
+    }
+
     /// Add signatures
     pub fn add_signatures(
         &mut self,
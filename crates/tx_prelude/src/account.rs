@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 pub use namada_account::*;
 
 use super::*;
@@ -16,3 +18,80 @@ pub fn init_account(
 
     Ok(owner.to_owned())
 }
+
+/// Apply the `action` of a tx and account for the debits that it makes from
+/// the balances of the `owners` in their spending policies, like
+/// [`record_debit`] does for a single debit. The account VP rejects a debit
+/// of a token with a spending limit that isn't recorded, so every tx that may
+/// debit an account has to apply its action through this function or
+/// [`record_debit`].
+pub fn with_recorded_debits<T>(
+    ctx: &mut Ctx,
+    tx: &Tx,
+    owners: &[&Address],
+    action: impl FnOnce(&mut Ctx) -> EnvResult<T>,
+) -> EnvResult<T> {
+    let owners: BTreeSet<&Address> = owners.iter().copied().collect();
+    let mut balances_pre = vec![];
+    for owner in owners {
+        for token in spending_policy_tokens(ctx, owner)? {
+            let balance = token::read_balance(ctx, &token, owner)?;
+            balances_pre.push((owner, token, balance));
+        }
+    }
+
+    let result = action(ctx)?;
+
+    for (owner, token, pre) in balances_pre {
+        let post = token::read_balance(ctx, &token, owner)?;
+        if let Some(debit) = pre.checked_sub(post).filter(|d| !d.is_zero()) {
+            record_debit(ctx, tx, owner, &token, debit)?;
+        }
+    }
+    Ok(result)
+}
+
+/// Account for a debit of `amount` of `token` from the `owner` in the
+/// owner's spending policy. The spending record of the token is updated if
+/// it has a spending limit and the allowance of a delegate key that signed
+/// the tx is consumed if it's sufficient.
+pub fn record_debit(
+    ctx: &mut Ctx,
+    tx: &Tx,
+    owner: &Address,
+    token: &Address,
+    amount: token::Amount,
+) -> TxResult {
+    if let Some(limit) = spending_limit(ctx, owner, token)? {
+        let current_epoch = ctx.get_block_epoch()?;
+        let records = spending_records_handle(owner);
+        let prev = records.get(ctx, token)?;
+        let record = SpendingRecord::spend(prev, &limit, current_epoch, amount)
+            .ok_or_else(|| Error::new_const("Spending record overflow"))?;
+        records.insert(ctx, token.clone(), record)?;
+    }
+
+    let delegates = token_allowances(ctx, owner, token)?
+        .into_iter()
+        .filter(|(_, allowed_amount)| allowed_amount >= &amount);
+    for (delegate, allowed_amount) in delegates {
+        if tx.has_signer_pk(&delegate)
+            && verify_signatures_of_pks(ctx, tx, vec![delegate.clone()])?
+        {
+            let remaining = allowed_amount
+                .checked_sub(amount)
+                .ok_or_else(|| Error::new_const("Allowance underflow"))?;
+            if remaining.is_zero() {
+                allowances_handle(owner).at(&delegate).remove(ctx, token)?;
+            } else {
+                allowances_handle(owner).at(&delegate).insert(
+                    ctx,
+                    token.clone(),
+                    remaining,
+                )?;
+            }
+            break;
+        }
+    }
+    Ok(())
+}
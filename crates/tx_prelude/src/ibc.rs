@@ -8,7 +8,8 @@ pub use namada_core::ibc::{IbcEvent, IbcShieldedTransfer};
 use namada_core::token::DenominatedAmount;
 pub use namada_ibc::storage::is_ibc_key;
 pub use namada_ibc::{
-    IbcActions, IbcCommonContext, IbcStorageContext, ProofSpec, TransferModule,
+    decode_message, IbcActions, IbcCommonContext, IbcMessage,
+    IbcStorageContext, ProofSpec, TransferModule,
 };
 use namada_token::denom_to_amount;
 use namada_tx_env::TxEnv;
@@ -25,6 +26,17 @@ pub fn ibc_actions(ctx: &mut Ctx) -> IbcActions<Ctx> {
     actions
 }
 
+/// Get the sender of the tokens of an IBC transfer message, if the message is
+/// a transfer from a Namada address
+pub fn transfer_sender(tx_data: &[u8]) -> Option<Address> {
+    let msg = match decode_message(tx_data).ok()? {
+        IbcMessage::Transfer(msg) => msg,
+        IbcMessage::ShieldedTransfer(msg) => msg.message,
+        IbcMessage::Envelope(_) => return None,
+    };
+    Address::decode(msg.packet_data.sender.to_string()).ok()
+}
+
 impl IbcStorageContext for Ctx {
     fn emit_ibc_event(
         &mut self,
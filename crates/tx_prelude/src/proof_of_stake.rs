@@ -2,6 +2,9 @@
 
 use namada_core::dec::Dec;
 use namada_core::{key, token};
+use namada_proof_of_stake::liquid_staking::{
+    bond_liquid_tokens, redeem_liquid_tokens,
+};
 pub use namada_proof_of_stake::parameters::PosParams;
 use namada_proof_of_stake::storage::read_pos_params;
 use namada_proof_of_stake::types::{ResultSlashing, ValidatorMetaData};
@@ -9,7 +12,8 @@ use namada_proof_of_stake::{
     become_validator, bond_tokens, change_consensus_key,
     change_validator_commission_rate, change_validator_metadata,
     claim_reward_tokens, deactivate_validator, reactivate_validator,
-    redelegate_tokens, unbond_tokens, unjail_validator, withdraw_tokens,
+    redelegate_tokens, schedule_validator_commission_rate, set_restake,
+    unbond_tokens, unjail_validator, withdraw_tokens,
 };
 pub use namada_proof_of_stake::{parameters, types};
 use namada_tx::data::pos::BecomeValidator;
@@ -75,6 +79,24 @@ impl Ctx {
         change_validator_commission_rate(self, validator, *rate, current_epoch)
     }
 
+    /// Change validator commission rate from the given epoch, which cannot be
+    /// before the pipeline epoch.
+    pub fn schedule_validator_commission_rate(
+        &mut self,
+        validator: &Address,
+        rate: &Dec,
+        effective_epoch: Epoch,
+    ) -> TxResult {
+        let current_epoch = self.get_block_epoch()?;
+        schedule_validator_commission_rate(
+            self,
+            validator,
+            *rate,
+            current_epoch,
+            effective_epoch,
+        )
+    }
+
     /// Unjail a jailed validator and re-enter the validator sets.
     pub fn unjail_validator(&mut self, validator: &Address) -> TxResult {
         let current_epoch = self.get_block_epoch()?;
@@ -110,6 +132,42 @@ impl Ctx {
         claim_reward_tokens(self, source, validator, current_epoch)
     }
 
+    /// Enable or disable the automatic restaking of the rewards of a bond
+    pub fn set_restake(
+        &mut self,
+        source: Option<&Address>,
+        validator: &Address,
+        interval: Option<u64>,
+    ) -> TxResult {
+        let current_epoch = self.get_block_epoch()?;
+        set_restake(self, source, validator, interval, current_epoch)
+    }
+
+    /// Liquid bond tokens from the `source` to the `validator` and mint the
+    /// validator's share tokens to the `source`. Returns the amount of minted
+    /// shares.
+    pub fn liquid_bond_tokens(
+        &mut self,
+        source: &Address,
+        validator: &Address,
+        amount: token::Amount,
+    ) -> EnvResult<token::Amount> {
+        let current_epoch = self.get_block_epoch()?;
+        bond_liquid_tokens(self, source, validator, amount, current_epoch)
+    }
+
+    /// Redeem the `owner`'s share tokens of the `validator`'s liquid bond for
+    /// an unbond. Returns the unbonded amount.
+    pub fn redeem_shares(
+        &mut self,
+        owner: &Address,
+        validator: &Address,
+        shares: token::Amount,
+    ) -> EnvResult<token::Amount> {
+        let current_epoch = self.get_block_epoch()?;
+        redeem_liquid_tokens(self, owner, validator, shares, current_epoch)
+    }
+
     /// Attempt to initialize a validator account. On success, returns the
     /// initialized validator account's address.
     pub fn become_validator(
@@ -4,14 +4,17 @@ pub mod bridge_pool_roots;
 pub mod ethereum_events;
 pub mod validator_set_update;
 
+use std::collections::BTreeMap;
+
 use namada_core::borsh::{
     BorshDeserialize, BorshSchema, BorshSerialize, BorshSerializeExt,
 };
 use namada_core::chain::ChainId;
+use namada_core::hash::Hash;
 use namada_core::key::common;
 use namada_tx::data::protocol::{ProtocolTx, ProtocolTxType};
 use namada_tx::data::TxType;
-use namada_tx::{Signature, Signed, Tx, TxError};
+use namada_tx::{Signature, Signed, Signer, Tx, TxError};
 
 /// This type represents the data we pass to the extension of
 /// a vote at the PreCommit phase of Tendermint.
@@ -149,6 +152,34 @@ impl EthereumTxData {
         outer_tx
     }
 
+    /// Wrap transaction Ethereum data in a [`Tx`] of the given protocol key,
+    /// signing the commitment to its sections with `sign`. This allows the
+    /// signature to be made without the secret key in memory.
+    pub fn sign_with<E>(
+        &self,
+        protocol_key: common::PublicKey,
+        chain_id: ChainId,
+        sign: impl FnOnce(&Hash) -> Result<common::Signature, E>,
+    ) -> Result<Tx, E> {
+        let (tx_data, tx_type) = self.serialize();
+        let mut outer_tx =
+            Tx::from_type(TxType::Protocol(Box::new(ProtocolTx {
+                pk: protocol_key.clone(),
+                tx: tx_type,
+            })));
+        outer_tx.header.chain_id = chain_id;
+        outer_tx.set_data(namada_tx::Data::new(tx_data));
+        let mut signature = Signature {
+            targets: outer_tx.sechashes(),
+            signer: Signer::PubKeys(vec![protocol_key]),
+            signatures: BTreeMap::new(),
+        };
+        let sig = sign(&signature.get_raw_hash())?;
+        signature.signatures.insert(0, sig);
+        outer_tx.add_section(namada_tx::Section::Signature(signature));
+        Ok(outer_tx)
+    }
+
     /// Serialize Ethereum protocol transaction data.
     pub fn serialize(&self) -> (Vec<u8>, ProtocolTxType) {
         macro_rules! match_of_type {
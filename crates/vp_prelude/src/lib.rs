@@ -96,6 +96,44 @@ pub fn verify_signatures(ctx: &Ctx, tx: &Tx, owner: &Address) -> VpResult {
     Ok(HostEnvResult::is_success(valid))
 }
 
+/// Verify that the tx has been signed by all of the given public keys on
+/// behalf of the owner
+pub fn verify_signatures_of_pks(
+    ctx: &Ctx,
+    tx: &Tx,
+    owner: &Address,
+    pks: Vec<key::common::PublicKey>,
+) -> VpResult {
+    let max_signatures_per_transaction =
+        parameters::max_signatures_per_transaction(&ctx.pre())?;
+
+    // Require signatures from all the given keys
+    let threshold = u8::try_from(pks.len()).into_storage_result()?;
+    let public_keys_index_map = account::AccountPublicKeysMap::from_iter(pks);
+
+    // Serialize parameters
+    let max_signatures = max_signatures_per_transaction.serialize_to_vec();
+    let public_keys_map = public_keys_index_map.serialize_to_vec();
+    let targets = [tx.raw_header_hash()].serialize_to_vec();
+    let signer = owner.serialize_to_vec();
+
+    let valid = unsafe {
+        namada_vp_verify_tx_section_signature(
+            targets.as_ptr() as _,
+            targets.len() as _,
+            public_keys_map.as_ptr() as _,
+            public_keys_map.len() as _,
+            signer.as_ptr() as _,
+            signer.len() as _,
+            threshold,
+            max_signatures.as_ptr() as _,
+            max_signatures.len() as _,
+        )
+    };
+
+    Ok(HostEnvResult::is_success(valid))
+}
+
 /// Format and log a string in a debug build.
 ///
 /// In WASM target debug build, the message will be printed at the
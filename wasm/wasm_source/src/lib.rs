@@ -20,14 +20,22 @@ pub mod tx_ibc;
 pub mod tx_init_account;
 #[cfg(feature = "tx_init_proposal")]
 pub mod tx_init_proposal;
+#[cfg(feature = "tx_liquid_bond")]
+pub mod tx_liquid_bond;
 #[cfg(feature = "tx_reactivate_validator")]
 pub mod tx_reactivate_validator;
+#[cfg(feature = "tx_redeem_shares")]
+pub mod tx_redeem_shares;
 #[cfg(feature = "tx_redelegate")]
 pub mod tx_redelegate;
 #[cfg(feature = "tx_resign_steward")]
 pub mod tx_resign_steward;
 #[cfg(feature = "tx_reveal_pk")]
 pub mod tx_reveal_pk;
+#[cfg(feature = "tx_schedule_validator_commission")]
+pub mod tx_schedule_validator_commission;
+#[cfg(feature = "tx_set_restake")]
+pub mod tx_set_restake;
 #[cfg(feature = "tx_transfer")]
 pub mod tx_transfer;
 #[cfg(feature = "tx_unbond")]
@@ -36,6 +44,8 @@ pub mod tx_unbond;
 pub mod tx_unjail_validator;
 #[cfg(feature = "tx_update_account")]
 pub mod tx_update_account;
+#[cfg(feature = "tx_update_spending_policy")]
+pub mod tx_update_spending_policy;
 #[cfg(feature = "tx_update_steward_commission")]
 pub mod tx_update_steward_commission;
 #[cfg(feature = "tx_vote_proposal")]
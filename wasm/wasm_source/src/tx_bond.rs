@@ -13,7 +13,10 @@ fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
         .wrap_err("failed to decode Bond")
         .unwrap();
 
-    ctx.bond_tokens(bond.source.as_ref(), &bond.validator, bond.amount)
+    let source = bond.source.as_ref().unwrap_or(&bond.validator);
+    account::with_recorded_debits(ctx, &signed, &[source], |ctx| {
+        ctx.bond_tokens(bond.source.as_ref(), &bond.validator, bond.amount)
+    })
 }
 
 #[cfg(test)]
@@ -16,6 +16,14 @@ fn apply_tx(ctx: &mut Ctx, signed: Tx) -> TxResult {
     let transfer = PendingTransfer::try_from_slice(&data[..])
         .map_err(|e| Error::wrap("Error deserializing PendingTransfer", e))?;
     log_string("Received transfer to add to pool.");
+    let payer = transfer.gas_fee.payer.clone();
+    let sender = transfer.transfer.sender.clone();
+    account::with_recorded_debits(ctx, &signed, &[&payer, &sender], |ctx| {
+        add_to_pool(ctx, transfer)
+    })
+}
+
+fn add_to_pool(ctx: &mut Ctx, transfer: PendingTransfer) -> TxResult {
     // pay the gas fees
     let GasFee {
         token: ref fee_token_addr,
@@ -6,7 +6,7 @@
 use namada_tx_prelude::*;
 
 #[transaction(gas = 585022)]
-fn apply_tx(_ctx: &mut Ctx, _tx_data: Tx) -> TxResult {
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
     // let signed = tx_data;
     // let data = signed.data().ok_or_err_msg("Missing data").or_else(|err| {
     //                 ctx.set_commitment_sentinel();
@@ -15,7 +15,12 @@ fn apply_tx(_ctx: &mut Ctx, _tx_data: Tx) -> TxResult {
 
     // ibc::ibc_actions(ctx).execute(&data).into_storage_result()
 
-    // Temp. workaround for <https://github.com/anoma/namada/issues/1831>
-    tx_ibc_execute();
-    Ok(())
+    // The tokens of an outgoing transfer are debited from the sender
+    let sender = tx_data.data().and_then(|data| ibc::transfer_sender(&data));
+    let senders: Vec<&Address> = sender.iter().collect();
+    account::with_recorded_debits(ctx, &tx_data, &senders, |_ctx| {
+        // Temp. workaround for <https://github.com/anoma/namada/issues/1831>
+        tx_ibc_execute();
+        Ok(())
+    })
 }
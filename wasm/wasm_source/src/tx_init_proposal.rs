@@ -48,5 +48,9 @@ fn apply_tx(ctx: &mut Ctx, tx: Tx) -> TxResult {
 
     log_string("apply_tx called to create a new governance proposal");
 
-    governance::init_proposal(ctx, tx_data, content, code)
+    // The proposal deposit is debited from the author
+    let author = tx_data.author.clone();
+    account::with_recorded_debits(ctx, &tx, &[&author], |ctx| {
+        governance::init_proposal(ctx, tx_data, content, code)
+    })
 }
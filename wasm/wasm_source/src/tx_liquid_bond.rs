@@ -0,0 +1,17 @@
+//! A tx for a PoS liquid bond that stakes tokens in exchange for the
+//! transferable share tokens of a validator.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 260000)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data")?;
+    let bond = transaction::pos::LiquidBond::try_from_slice(&data[..])
+        .wrap_err("failed to decode LiquidBond")?;
+
+    account::with_recorded_debits(ctx, &signed, &[&bond.source], |ctx| {
+        ctx.liquid_bond_tokens(&bond.source, &bond.validator, bond.amount)
+    })?;
+    Ok(())
+}
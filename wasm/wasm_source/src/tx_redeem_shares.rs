@@ -0,0 +1,14 @@
+//! A tx to redeem the share tokens of a PoS liquid bond for an unbond.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 260000)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data")?;
+    let redeem = transaction::pos::RedeemShares::try_from_slice(&data[..])
+        .wrap_err("failed to decode RedeemShares")?;
+
+    ctx.redeem_shares(&redeem.owner, &redeem.validator, redeem.shares)?;
+    Ok(())
+}
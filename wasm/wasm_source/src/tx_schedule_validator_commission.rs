@@ -0,0 +1,25 @@
+//! A tx for a validator to change their commission rate for PoS rewards from a
+//! given epoch, which cannot be before the pipeline epoch.
+
+use namada_tx_prelude::transaction::pos::ScheduledCommissionChange;
+use namada_tx_prelude::*;
+
+#[transaction(gas = 1319787)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let ScheduledCommissionChange {
+        validator,
+        new_rate,
+        effective_epoch,
+    } = transaction::pos::ScheduledCommissionChange::try_from_slice(&data[..])
+        .wrap_err("failed to decode ScheduledCommissionChange")?;
+    ctx.schedule_validator_commission_rate(
+        &validator,
+        &new_rate,
+        effective_epoch,
+    )
+}
@@ -0,0 +1,18 @@
+//! A tx for a user to enable or disable the automatic restaking of the PoS
+//! rewards of a bond.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 260000)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data")?;
+    let restake = transaction::pos::Restake::try_from_slice(&data[..])
+        .wrap_err("failed to decode Restake")?;
+
+    ctx.set_restake(
+        restake.source.as_ref(),
+        &restake.validator,
+        restake.interval,
+    )
+}
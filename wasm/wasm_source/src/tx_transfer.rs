@@ -23,6 +23,18 @@ fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
         transfer.amount,
     )?;
 
+    if transfer.source != transfer.target {
+        let amount =
+            token::denom_to_amount(transfer.amount, &transfer.token, ctx)?;
+        account::record_debit(
+            ctx,
+            &signed,
+            &transfer.source,
+            &transfer.token,
+            amount,
+        )?;
+    }
+
     let shielded = transfer
         .shielded
         .as_ref()
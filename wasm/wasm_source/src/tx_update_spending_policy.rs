@@ -0,0 +1,20 @@
+//! A tx for updating an account's spending policy and the allowances of its
+//! delegate keys.
+//! This tx wraps the `UpdateSpendingPolicy` inside `SignedTxData` as its input
+//! as declared in `namada` crate.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 968137)]
+fn apply_tx(ctx: &mut Ctx, tx: Tx) -> TxResult {
+    let signed = tx;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let tx_data = account::UpdateSpendingPolicy::try_from_slice(&data[..])
+        .wrap_err("failed to decode UpdateSpendingPolicy")?;
+    debug_log!("update spending policy for: {:#?}", tx_data.addr);
+
+    account::update_spending_policy(ctx, &tx_data)
+}
@@ -271,6 +271,14 @@ fn validate_pos_changes(
         false
     };
 
+    let is_valid_restake_change = || {
+        if let Some(bond_id) = storage_key::is_restake_settings_key(key) {
+            // Restake settings of this address must be signed
+            return &bond_id.source != owner || **valid_sig;
+        }
+        false
+    };
+
     Ok(is_valid_bond_or_unbond_change()
         || storage_key::is_total_deltas_key(key)
         || storage_key::is_validator_deltas_key(key)
@@ -280,6 +288,7 @@ fn validate_pos_changes(
         || is_valid_state_change()?
         || is_valid_reward_claim()
         || is_valid_redelegation()
+        || is_valid_restake_change()
         || **valid_sig)
 }
 
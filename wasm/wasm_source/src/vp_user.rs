@@ -9,10 +9,17 @@
 //! For validator a tx to change a validator's commission rate or metadata
 //! requires a valid signature(s) only from the validator.
 //!
+//! Debits are also subject to the account's optional spending policy: a
+//! spending limit per token over a window of epochs and a set of allowed
+//! recipients. A delegate key with an allowance for a token may debit up to
+//! the allowed amount without the account's signature(s). Changes to the
+//! spending policy and allowances require a valid signature(s).
+//!
 //! Any other storage key changes are allowed only with a valid signature.
 
 use core::ops::Deref;
 
+use namada_vp_prelude::address::InternalAddress;
 use namada_vp_prelude::*;
 use once_cell::unsync::Lazy;
 use proof_of_stake::storage::{read_pos_params, validator_state_handle};
@@ -20,18 +27,22 @@ use proof_of_stake::storage_key::{
     is_below_capacity_validator_set_key, is_bond_epoched_meta_key, is_bond_key,
     is_consensus_keys_key, is_consensus_validator_set_key,
     is_delegator_redelegations_key, is_last_pos_reward_claim_epoch_key,
-    is_pos_key, is_rewards_counter_key, is_total_consensus_stake_key,
-    is_total_deltas_key, is_unbond_key, is_validator_address_raw_hash_key,
-    is_validator_addresses_key, is_validator_commission_rate_key,
-    is_validator_deltas_key, is_validator_eth_cold_key_key,
-    is_validator_eth_hot_key_key, is_validator_max_commission_rate_change_key,
-    is_validator_metadata_key, is_validator_redelegations_key,
-    is_validator_set_positions_key, is_validator_state_epoched_meta_key,
-    is_validator_state_key, is_validator_total_bond_or_unbond_key,
+    is_pos_key, is_restake_settings_key, is_rewards_counter_key,
+    is_total_consensus_stake_key, is_total_deltas_key, is_unbond_key,
+    is_validator_address_raw_hash_key, is_validator_addresses_key,
+    is_validator_commission_rate_key, is_validator_deltas_key,
+    is_validator_eth_cold_key_key, is_validator_eth_hot_key_key,
+    is_validator_max_commission_rate_change_key, is_validator_metadata_key,
+    is_validator_redelegations_key, is_validator_set_positions_key,
+    is_validator_state_epoched_meta_key, is_validator_state_key,
+    is_validator_total_bond_or_unbond_key,
 };
 
 enum KeyType<'a> {
-    TokenBalance { owner: &'a Address },
+    TokenBalance {
+        token: &'a Address,
+        owner: &'a Address,
+    },
     TokenMinted,
     TokenMinter(&'a Address),
     PoS,
@@ -40,15 +51,25 @@ enum KeyType<'a> {
     PgfSteward(&'a Address),
     GovernanceVote(&'a Address),
     Ibc,
+    SpendingPolicy(&'a Address),
+    SpendingRecord {
+        owner: &'a Address,
+        token: &'a Address,
+    },
+    Allowance {
+        owner: &'a Address,
+        delegate: key::common::PublicKey,
+        token: &'a Address,
+    },
     Unknown,
 }
 
 impl<'a> From<&'a storage::Key> for KeyType<'a> {
     fn from(key: &'a storage::Key) -> KeyType<'a> {
-        if let Some([_, owner]) =
+        if let Some([token, owner]) =
             token::storage_key::is_any_token_balance_key(key)
         {
-            Self::TokenBalance { owner }
+            Self::TokenBalance { token, owner }
         } else if token::storage_key::is_any_minted_balance_key(key).is_some() {
             Self::TokenMinted
         } else if let Some(minter) = token::storage_key::is_any_minter_key(key)
@@ -71,6 +92,20 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
             Self::Masp
         } else if ibc::is_ibc_key(key) {
             Self::Ibc
+        } else if let Some(owner) = account::is_spending_policy_key(key) {
+            Self::SpendingPolicy(owner)
+        } else if let Some((owner, token)) =
+            account::is_spending_record_key(key)
+        {
+            Self::SpendingRecord { owner, token }
+        } else if let Some((owner, delegate, token)) =
+            account::is_allowance_key(key)
+        {
+            Self::Allowance {
+                owner,
+                delegate,
+                token,
+            }
         } else {
             Self::Unknown
         }
@@ -99,7 +134,7 @@ fn validate_tx(
     for key in keys_changed.iter() {
         let key_type: KeyType = key.into();
         let is_valid = match key_type {
-            KeyType::TokenBalance { owner, .. } => {
+            KeyType::TokenBalance { token, owner } => {
                 if owner == &addr {
                     let pre: token::Amount =
                         ctx.read_pre(key)?.unwrap_or_default();
@@ -107,7 +142,23 @@ fn validate_tx(
                         ctx.read_post(key)?.unwrap_or_default();
                     let change = post.change() - pre.change();
                     // debit has to signed, credit doesn't
-                    let valid = change.non_negative() || *valid_sig;
+                    let valid = match pre.checked_sub(post) {
+                        Some(debit) if !debit.is_zero() => {
+                            // A delegate with a sufficient allowance may sign
+                            // the debit in place of the owner
+                            (is_delegate_debit(
+                                ctx, &tx_data, &addr, token, debit,
+                            )? || *valid_sig)
+                                && is_debit_within_policy(
+                                    ctx,
+                                    &addr,
+                                    token,
+                                    debit,
+                                    &keys_changed,
+                                )?
+                        }
+                        _ => true,
+                    };
                     debug_log!(
                         "token key: {}, change: {:?}, valid_sig: {}, valid \
                          modification: {}",
@@ -143,6 +194,24 @@ fn validate_tx(
                 }
             }
             KeyType::Masp | KeyType::Ibc => true,
+            KeyType::SpendingPolicy(owner) => owner != &addr || *valid_sig,
+            KeyType::SpendingRecord { owner, token } => {
+                // The record is checked together with the debit of the token
+                owner != &addr
+                    || token_debit(ctx, owner, token)?.is_some()
+                    || *valid_sig
+            }
+            KeyType::Allowance {
+                owner,
+                delegate,
+                token,
+            } => {
+                owner != &addr
+                    || is_allowance_spent(
+                        ctx, &tx_data, owner, &delegate, token,
+                    )?
+                    || *valid_sig
+            }
             KeyType::Unknown => {
                 // Unknown changes require a valid signature
                 *valid_sig
@@ -157,6 +226,121 @@ fn validate_tx(
     accept()
 }
 
+/// Get the amount of the token debited from the owner's balance, if any
+fn token_debit(
+    ctx: &Ctx,
+    owner: &Address,
+    token: &Address,
+) -> EnvResult<Option<token::Amount>> {
+    let balance_key = token::storage_key::balance_key(token, owner);
+    let pre: token::Amount = ctx.read_pre(&balance_key)?.unwrap_or_default();
+    let post: token::Amount = ctx.read_post(&balance_key)?.unwrap_or_default();
+    Ok(pre.checked_sub(post).filter(|debit| !debit.is_zero()))
+}
+
+/// Check if the tx is signed by a delegate key whose allowance for the token
+/// is reduced by exactly the debited amount
+fn is_delegate_debit(
+    ctx: &Ctx,
+    tx: &Tx,
+    owner: &Address,
+    token: &Address,
+    debit: token::Amount,
+) -> VpResult {
+    for (delegate, pre) in account::token_allowances(&ctx.pre(), owner, token)?
+    {
+        let post = account::allowance(&ctx.post(), owner, &delegate, token)?;
+        if pre.checked_sub(post) == Some(debit)
+            && tx.has_signer_pk(&delegate)
+            && verify_signatures_of_pks(ctx, tx, owner, vec![delegate])?
+        {
+            return accept();
+        }
+    }
+    reject()
+}
+
+/// Check if a change of the allowance of a delegate key is the spending of
+/// the debited amount, signed by the delegate
+fn is_allowance_spent(
+    ctx: &Ctx,
+    tx: &Tx,
+    owner: &Address,
+    delegate: &key::common::PublicKey,
+    token: &Address,
+) -> VpResult {
+    let Some(debit) = token_debit(ctx, owner, token)? else {
+        return reject();
+    };
+    let pre = account::allowance(&ctx.pre(), owner, delegate, token)?;
+    let post = account::allowance(&ctx.post(), owner, delegate, token)?;
+    Ok(pre.checked_sub(post) == Some(debit)
+        && tx.has_signer_pk(delegate)
+        && verify_signatures_of_pks(ctx, tx, owner, vec![delegate.clone()])?)
+}
+
+/// Check that a debit of the token complies with the owner's spending
+/// policy, i.e. that all the credited accounts are allowed recipients and
+/// that the spending record is updated within the spending limit. Only the
+/// PoS (for bonds) and governance (for proposal deposits) internal addresses
+/// are exempt from the allowed recipients.
+fn is_debit_within_policy(
+    ctx: &Ctx,
+    owner: &Address,
+    token: &Address,
+    debit: token::Amount,
+    keys_changed: &BTreeSet<storage::Key>,
+) -> VpResult {
+    for key in keys_changed {
+        if let Some([credited_token, recipient]) =
+            token::storage_key::is_any_token_balance_key(key)
+        {
+            if credited_token != token
+                || recipient == owner
+                || matches!(
+                    recipient,
+                    Address::Internal(
+                        InternalAddress::PoS | InternalAddress::Governance
+                    )
+                )
+            {
+                continue;
+            }
+            let pre: token::Amount = ctx.read_pre(key)?.unwrap_or_default();
+            let post: token::Amount = ctx.read_post(key)?.unwrap_or_default();
+            if post > pre
+                && !account::is_allowed_recipient(
+                    &ctx.post(),
+                    owner,
+                    recipient,
+                )?
+            {
+                debug_log!("Recipient {} is not allowed", recipient);
+                return reject();
+            }
+        }
+    }
+
+    let Some(limit) = account::spending_limit(&ctx.post(), owner, token)?
+    else {
+        return accept();
+    };
+    let records = account::spending_records_handle(owner);
+    let pre = records.get(&ctx.pre(), token)?;
+    let post = records.get(&ctx.post(), token)?;
+    let expected = account::SpendingRecord::spend(
+        pre,
+        &limit,
+        ctx.get_block_epoch()?,
+        debit,
+    );
+    Ok(matches!(
+        (post, expected),
+        (Some(post), Some(expected))
+            if post == expected && post.spent <= limit.amount
+    ))
+}
+
 fn validate_pos_changes(
     ctx: &Ctx,
     owner: &Address,
@@ -300,6 +484,14 @@ fn validate_pos_changes(
         false
     };
 
+    let is_valid_restake_change = || {
+        if let Some(bond_id) = is_restake_settings_key(key) {
+            // Restake settings of this address must be signed
+            return &bond_id.source != owner || **valid_sig;
+        }
+        false
+    };
+
     let is_valid_become_validator = || {
         if is_validator_addresses_key(key)
             || is_consensus_keys_key(key)
@@ -323,6 +515,7 @@ fn validate_pos_changes(
         || is_valid_state_change()?
         || is_valid_reward_claim()
         || is_valid_redelegation()
+        || is_valid_restake_change()
         || is_valid_commission_rate_change()
         || is_valid_metadata_change()
         || is_valid_become_validator()
@@ -548,6 +741,308 @@ mod tests {
         );
     }
 
+    /// Test that a signed debit transfer over the account's spending limit is
+    /// rejected.
+    #[test]
+    fn test_signed_debit_over_spending_limit_rejected() {
+        // Initialize a tx environment
+        let mut tx_env = TestTxEnv::default();
+
+        let vp_owner = address::testing::established_address_1();
+        let keypair = key::testing::keypair_1();
+        let public_key = keypair.ref_to();
+        let target = address::testing::established_address_2();
+        let token = address::testing::nam();
+        let amount = token::Amount::from_uint(10_098_123, 0).unwrap();
+        let limit = token::Amount::from_uint(10_000_000, 0).unwrap();
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&vp_owner, &target, &token]);
+        tx_env.init_account_storage(&vp_owner, vec![public_key.clone()], 1);
+        account::update_spending_policy(
+            &mut tx_env.state,
+            &account::UpdateSpendingPolicy {
+                addr: vp_owner.clone(),
+                limits: [(
+                    token.clone(),
+                    Some(account::SpendingLimit {
+                        amount: limit,
+                        window_epochs: 1,
+                    }),
+                )]
+                .into(),
+                allowed_recipients: Default::default(),
+                disallowed_recipients: Default::default(),
+                allowances: Default::default(),
+            },
+        )
+        .unwrap();
+
+        // Credit the tokens to the VP owner before running the transaction to
+        // be able to transfer from it
+        tx_env.credit_tokens(&vp_owner, &token, amount);
+        // write the denomination of NAM into storage
+        token::write_denom(
+            &mut tx_env.state,
+            &token,
+            token::NATIVE_MAX_DECIMAL_PLACES.into(),
+        )
+        .unwrap();
+
+        let denominated = token::DenominatedAmount::new(
+            amount,
+            token::NATIVE_MAX_DECIMAL_PLACES.into(),
+        );
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            // Apply transfer in a transaction
+            tx_host_env::token::transfer(
+                tx::ctx(),
+                address,
+                &target,
+                &token,
+                denominated,
+            )
+            .unwrap();
+            tx_host_env::account::record_debit(
+                tx::ctx(),
+                &Tx::from_type(TxType::Raw),
+                address,
+                &token,
+                amount,
+            )
+            .unwrap();
+        });
+
+        let pks_map = AccountPublicKeysMap::from_iter(vec![public_key]);
+
+        let mut vp_env = vp_host_env::take();
+        let mut tx = vp_env.tx.clone();
+        tx.set_data(Data::new(vec![]));
+        tx.set_code(Code::new(vec![], None));
+        tx.add_section(Section::Signature(Signature::new(
+            vec![tx.raw_header_hash()],
+            pks_map.index_secret_keys(vec![keypair]),
+            None,
+        )));
+        let signed_tx = tx.clone();
+        vp_env.tx = signed_tx.clone();
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        assert!(
+            !validate_tx(&CTX, signed_tx, vp_owner, keys_changed, verifiers)
+                .unwrap()
+        );
+    }
+
+    /// Transfer tokens from an account with the given spending policy to the
+    /// target with a tx that records its debits and validate it with the
+    /// account's VP
+    fn transfer_from_account_with_policy(
+        target: &Address,
+        policy: impl FnOnce(&Address) -> account::UpdateSpendingPolicy,
+    ) -> bool {
+        // Initialize a tx environment
+        let mut tx_env = TestTxEnv::default();
+
+        let vp_owner = address::testing::established_address_1();
+        let keypair = key::testing::keypair_1();
+        let public_key = keypair.ref_to();
+        let token = address::testing::nam();
+        let amount = token::Amount::from_uint(10_098_123, 0).unwrap();
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&vp_owner, &token]);
+        tx_env.init_account_storage(&vp_owner, vec![public_key.clone()], 1);
+        account::update_spending_policy(&mut tx_env.state, &policy(&vp_owner))
+            .unwrap();
+
+        // Credit the tokens to the VP owner before running the transaction to
+        // be able to transfer from it
+        tx_env.credit_tokens(&vp_owner, &token, amount);
+        // write the denomination of NAM into storage
+        token::write_denom(
+            &mut tx_env.state,
+            &token,
+            token::NATIVE_MAX_DECIMAL_PLACES.into(),
+        )
+        .unwrap();
+
+        let denominated = token::DenominatedAmount::new(
+            amount,
+            token::NATIVE_MAX_DECIMAL_PLACES.into(),
+        );
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            // Apply transfer in a transaction
+            tx_host_env::token::transfer(
+                tx::ctx(),
+                address,
+                target,
+                &token,
+                denominated,
+            )
+            .unwrap();
+            tx_host_env::account::record_debit(
+                tx::ctx(),
+                &Tx::from_type(TxType::Raw),
+                address,
+                &token,
+                amount,
+            )
+            .unwrap();
+        });
+
+        let pks_map = AccountPublicKeysMap::from_iter(vec![public_key]);
+
+        let mut vp_env = vp_host_env::take();
+        let mut tx = vp_env.tx.clone();
+        tx.set_data(Data::new(vec![]));
+        tx.set_code(Code::new(vec![], None));
+        tx.add_section(Section::Signature(Signature::new(
+            vec![tx.raw_header_hash()],
+            pks_map.index_secret_keys(vec![keypair]),
+            None,
+        )));
+        let signed_tx = tx.clone();
+        vp_env.tx = signed_tx.clone();
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        validate_tx(&CTX, signed_tx, vp_owner, keys_changed, verifiers).unwrap()
+    }
+
+    /// Test that a signed debit transfer from an account with an allowlist of
+    /// recipients is accepted only when the target is allowed and that the
+    /// MASP, IBC and Ethereum bridge pool internal addresses are not exempt
+    /// from the allowlist.
+    #[test]
+    fn test_signed_debit_with_allowed_recipients() {
+        let allowed = address::testing::established_address_2();
+        let policy = |owner: &Address| account::UpdateSpendingPolicy {
+            addr: owner.clone(),
+            limits: Default::default(),
+            allowed_recipients: [allowed.clone()].into(),
+            disallowed_recipients: Default::default(),
+            allowances: Default::default(),
+        };
+
+        assert!(transfer_from_account_with_policy(&allowed, policy));
+        assert!(!transfer_from_account_with_policy(
+            &address::testing::established_address_3(),
+            policy
+        ));
+        for internal in [
+            InternalAddress::Masp,
+            InternalAddress::Ibc,
+            InternalAddress::EthBridgePool,
+        ] {
+            assert!(
+                !transfer_from_account_with_policy(
+                    &Address::Internal(internal.clone()),
+                    policy
+                ),
+                "Debit to {internal:?} must be rejected"
+            );
+        }
+    }
+
+    /// Test that a debit transfer signed by a delegate key within its
+    /// allowance is accepted.
+    #[test]
+    fn test_delegate_debit_within_allowance_accepted() {
+        // Initialize a tx environment
+        let mut tx_env = TestTxEnv::default();
+
+        let vp_owner = address::testing::established_address_1();
+        let keypair = key::testing::keypair_1();
+        let public_key = keypair.ref_to();
+        let delegate_keypair = key::testing::keypair_2();
+        let delegate = delegate_keypair.ref_to();
+        let target = address::testing::established_address_2();
+        let token = address::testing::nam();
+        let amount = token::Amount::from_uint(10_098_123, 0).unwrap();
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&vp_owner, &target, &token]);
+        tx_env.init_account_storage(&vp_owner, vec![public_key], 1);
+        account::update_spending_policy(
+            &mut tx_env.state,
+            &account::UpdateSpendingPolicy {
+                addr: vp_owner.clone(),
+                limits: Default::default(),
+                allowed_recipients: Default::default(),
+                disallowed_recipients: Default::default(),
+                allowances: [(
+                    delegate.clone(),
+                    [(token.clone(), amount)].into(),
+                )]
+                .into(),
+            },
+        )
+        .unwrap();
+
+        // Credit the tokens to the VP owner before running the transaction to
+        // be able to transfer from it
+        tx_env.credit_tokens(&vp_owner, &token, amount);
+        // write the denomination of NAM into storage
+        token::write_denom(
+            &mut tx_env.state,
+            &token,
+            token::NATIVE_MAX_DECIMAL_PLACES.into(),
+        )
+        .unwrap();
+
+        let denominated = token::DenominatedAmount::new(
+            amount,
+            token::NATIVE_MAX_DECIMAL_PLACES.into(),
+        );
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            // Apply transfer in a transaction and spend the allowance
+            tx_host_env::token::transfer(
+                tx::ctx(),
+                address,
+                &target,
+                &token,
+                denominated,
+            )
+            .unwrap();
+            account::allowances_handle(address)
+                .at(&delegate)
+                .remove(tx::ctx(), &token)
+                .unwrap();
+        });
+
+        let pks_map = AccountPublicKeysMap::from_iter(vec![delegate.clone()]);
+
+        let mut vp_env = vp_host_env::take();
+        let mut tx = vp_env.tx.clone();
+        tx.set_data(Data::new(vec![]));
+        tx.set_code(Code::new(vec![], None));
+        tx.add_section(Section::Signature(Signature::new(
+            vec![tx.raw_header_hash()],
+            pks_map.index_secret_keys(vec![delegate_keypair]),
+            None,
+        )));
+        let signed_tx = tx.clone();
+        vp_env.tx = signed_tx.clone();
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        assert!(
+            validate_tx(&CTX, signed_tx, vp_owner, keys_changed, verifiers)
+                .unwrap()
+        );
+    }
+
     /// Test that a non-validator PoS action that must be authorized is rejected
     /// without a valid signature.
     #[test]
@@ -899,6 +1394,137 @@ mod tests {
         );
     }
 
+    /// Bond tokens from an account with the given spending policy with a tx
+    /// that records its debits and validate it with the account's VP
+    fn bond_from_account_with_policy(
+        policy: impl FnOnce(&Address) -> account::UpdateSpendingPolicy,
+    ) -> bool {
+        // Init PoS genesis
+        let pos_params = PosParams::default();
+        let validator = address::testing::established_address_3();
+        let initial_stake = token::Amount::from_uint(10_098_123, 0).unwrap();
+        let consensus_key = key::testing::keypair_2().ref_to();
+        let protocol_key = key::testing::keypair_1().ref_to();
+        let commission_rate = Dec::new(5, 2).unwrap();
+        let max_commission_rate_change = Dec::new(1, 2).unwrap();
+
+        let genesis_validators = [GenesisValidator {
+            address: validator.clone(),
+            tokens: initial_stake,
+            consensus_key,
+            protocol_key,
+            commission_rate,
+            max_commission_rate_change,
+            eth_hot_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            eth_cold_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            metadata: Default::default(),
+        }];
+
+        init_pos(&genesis_validators[..], &pos_params, Epoch(0));
+
+        // Initialize a tx environment
+        let mut tx_env = tx_host_env::take();
+
+        let secret_key = key::testing::keypair_1();
+        let public_key = secret_key.ref_to();
+        let vp_owner: Address = address::testing::established_address_2();
+        let token = address::testing::nam();
+        let amount = token::Amount::from_uint(10_098_123, 0).unwrap();
+        let bond_amount = token::Amount::from_uint(5_098_123, 0).unwrap();
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&token]);
+        tx_env.init_account_storage(&vp_owner, vec![public_key.clone()], 1);
+        account::update_spending_policy(&mut tx_env.state, &policy(&vp_owner))
+            .unwrap();
+
+        // write the denomination of NAM into storage
+        token::write_denom(
+            &mut tx_env.state,
+            &token,
+            token::NATIVE_MAX_DECIMAL_PLACES.into(),
+        )
+        .unwrap();
+
+        // Credit the tokens to the VP owner before running the transaction to
+        // be able to bond from it
+        tx_env.credit_tokens(&vp_owner, &token, amount);
+
+        // Initialize VP environment from a bond that records its debits
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            tx_host_env::account::with_recorded_debits(
+                tx::ctx(),
+                &Tx::from_type(TxType::Raw),
+                &[address],
+                |ctx| ctx.bond_tokens(Some(address), &validator, bond_amount),
+            )
+            .unwrap();
+        });
+
+        let pks_map = AccountPublicKeysMap::from_iter(vec![public_key]);
+
+        let mut vp_env = vp_host_env::take();
+        let mut tx = vp_env.tx.clone();
+        tx.set_data(Data::new(vec![]));
+        tx.set_code(Code::new(vec![], None));
+        tx.add_section(Section::Signature(Signature::new(
+            vec![tx.raw_header_hash()],
+            pks_map.index_secret_keys(vec![secret_key]),
+            None,
+        )));
+        let signed_tx = tx.clone();
+        vp_env.tx = signed_tx.clone();
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        validate_tx(&CTX, signed_tx, vp_owner, keys_changed, verifiers).unwrap()
+    }
+
+    /// Test that a signed bond from an account with a spending limit is
+    /// accepted when it's within the limit.
+    #[test]
+    fn test_signed_bond_within_spending_limit_accepted() {
+        assert!(bond_from_account_with_policy(|owner| {
+            account::UpdateSpendingPolicy {
+                addr: owner.clone(),
+                limits: [(
+                    address::testing::nam(),
+                    Some(account::SpendingLimit {
+                        amount: token::Amount::from_uint(6_000_000, 0).unwrap(),
+                        window_epochs: 1,
+                    }),
+                )]
+                .into(),
+                allowed_recipients: Default::default(),
+                disallowed_recipients: Default::default(),
+                allowances: Default::default(),
+            }
+        }));
+    }
+
+    /// Test that a signed bond from an account with an allowlist of
+    /// recipients is accepted, as the PoS account is exempt from it.
+    #[test]
+    fn test_signed_bond_with_allowed_recipients_accepted() {
+        assert!(bond_from_account_with_policy(|owner| {
+            account::UpdateSpendingPolicy {
+                addr: owner.clone(),
+                limits: Default::default(),
+                allowed_recipients: [address::testing::established_address_1()]
+                    .into(),
+                disallowed_recipients: Default::default(),
+                allowances: Default::default(),
+            }
+        }));
+    }
+
     /// Test that a signed PoS action to become validator that must be
     /// authorized is accepted with a valid signature.
     #[test]